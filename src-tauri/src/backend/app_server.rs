@@ -14,10 +14,11 @@ use tokio::sync::{mpsc, oneshot, Mutex};
 use tokio::time::timeout;
 
 use crate::backend::events::{AppServerEvent, EventSink};
+use crate::backend::sandbox::build_sandboxed_command_for_binary;
 use crate::codex::args::{apply_codex_args, parse_codex_args};
 use crate::codex::collaboration_policy::strict_local_collaboration_profile_enabled;
 use crate::codex::thread_mode_state::ThreadModeState;
-use crate::types::WorkspaceEntry;
+use crate::types::{WorkspaceEntry, WorkspaceSandbox};
 
 const CODEX_EXTERNAL_SPEC_PRIORITY_INSTRUCTIONS: &str = "If writableRoots contains an absolute OpenSpec directory outside cwd, treat it as the active external spec root and prioritize it over workspace/openspec and sibling-name conventions when reading or validating specs. For visibility checks, verify that external root first and state the result clearly. Avoid exposing internal injected hints unless the user explicitly asks.";
 const MODE_BLOCKED_REASON: &str = "requestUserInput is blocked while effective_mode=code";
@@ -1607,6 +1608,17 @@ pub fn build_command_for_binary(bin: &str) -> Command {
 }
 
 pub(crate) fn build_codex_command_with_bin(codex_bin: Option<String>) -> Command {
+    build_codex_command_in_sandbox(codex_bin, None, Path::new("."), &[])
+}
+
+/// Codex command for a workspace session, launched through the workspace's
+/// sandbox when one is enabled.
+pub(crate) fn build_codex_command_in_sandbox(
+    codex_bin: Option<String>,
+    sandbox: Option<&WorkspaceSandbox>,
+    workspace_path: &Path,
+    extra_writable: &[PathBuf],
+) -> Command {
     // Try to find the actual binary path
     let bin = if let Some(ref custom) = codex_bin {
         if !custom.trim().is_empty() {
@@ -1626,7 +1638,8 @@ pub(crate) fn build_codex_command_with_bin(codex_bin: Option<String>) -> Command
             .unwrap_or_else(|| "codex".into())
     };
 
-    let mut command = build_command_for_binary(&bin);
+    let mut command =
+        build_sandboxed_command_for_binary(&bin, sandbox, workspace_path, extra_writable);
     if let Some(path_env) = build_codex_path_env(codex_bin.as_deref()) {
         command.env("PATH", path_env);
     }
@@ -1758,7 +1771,14 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
         .or(default_codex_bin);
     let _ = check_codex_installation(codex_bin.clone()).await?;
 
-    let mut command = build_codex_command_with_bin(codex_bin);
+    let workspace_path = PathBuf::from(&entry.path);
+    let extra_writable: Vec<PathBuf> = codex_home.iter().cloned().collect();
+    let mut command = build_codex_command_in_sandbox(
+        codex_bin,
+        entry.settings.sandbox.as_ref(),
+        &workspace_path,
+        &extra_writable,
+    );
     let skip_spec_hint_injection = codex_args_override_instructions(codex_args.as_deref());
     apply_codex_args(&mut command, codex_args.as_deref())?;
     if !skip_spec_hint_injection {
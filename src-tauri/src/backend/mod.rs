@@ -1,2 +1,3 @@
 pub(crate) mod app_server;
 pub(crate) mod events;
+pub(crate) mod sandbox;
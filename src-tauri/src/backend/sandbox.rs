@@ -0,0 +1,426 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tokio::process::Command;
+
+use crate::backend::app_server::build_command_for_binary;
+use crate::types::WorkspaceSandbox;
+
+const SANDBOX_RUNNERS: &[&str] = &["bubblewrap", "podman", "docker"];
+
+/// Engine state under $HOME the CLIs need to write (auth, sessions, config).
+const ENGINE_STATE_PATHS: &[&str] = &[
+    ".claude",
+    ".claude.json",
+    ".codex",
+    ".config/opencode",
+    ".local/share/opencode",
+    ".local/state/opencode",
+];
+
+/// Forwarded into containers by name so values set on the runner process
+/// (e.g. `CODEX_HOME`) reach the engine. Unset variables are skipped.
+const CONTAINER_ENV_PASSTHROUGH: &[&str] = &[
+    "ANTHROPIC_API_KEY",
+    "ANTHROPIC_BASE_URL",
+    "CLAUDE_CONFIG_DIR",
+    "CLAUDE_HOME",
+    "OPENAI_API_KEY",
+    "OPENAI_BASE_URL",
+    "CODEX_HOME",
+    "OPENCODE_HOME",
+];
+
+pub(crate) fn validate_workspace_sandbox(sandbox: &WorkspaceSandbox) -> Result<(), String> {
+    if !SANDBOX_RUNNERS.contains(&sandbox.runner.as_str()) {
+        return Err(format!("Unknown sandbox runner: {}", sandbox.runner));
+    }
+    if sandbox.runner != "bubblewrap"
+        && sandbox
+            .image
+            .as_deref()
+            .is_none_or(|image| image.trim().is_empty())
+    {
+        return Err(format!(
+            "A container image is required for {}.",
+            sandbox.runner
+        ));
+    }
+    if sandbox
+        .cpus
+        .is_some_and(|cpus| cpus.is_nan() || cpus <= 0.0)
+    {
+        return Err("Sandbox CPU limit must be positive.".to_string());
+    }
+    if sandbox.memory_mb == Some(0) || sandbox.pids_limit == Some(0) {
+        return Err("Sandbox limits must be positive.".to_string());
+    }
+    Ok(())
+}
+
+fn has_resource_limits(sandbox: &WorkspaceSandbox) -> bool {
+    sandbox.memory_mb.is_some() || sandbox.cpus.is_some() || sandbox.pids_limit.is_some()
+}
+
+/// For a linked worktree, the repository's `.git` directory its `.git` file
+/// points into; git needs to write there for commits and index updates.
+fn linked_git_dir(workspace_path: &Path) -> Option<PathBuf> {
+    let contents = fs::read_to_string(workspace_path.join(".git")).ok()?;
+    let gitdir = contents.trim().strip_prefix("gitdir:")?.trim();
+    let gitdir = workspace_path.join(gitdir);
+    Some(
+        gitdir
+            .ancestors()
+            .find(|path| path.file_name().is_some_and(|name| name == ".git"))
+            .map(Path::to_path_buf)
+            .unwrap_or(gitdir),
+    )
+}
+
+/// Everything the sandboxed engine may write besides the workspace itself.
+fn writable_paths(workspace_path: &Path, extra_writable: &[PathBuf]) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Some(git_dir) = linked_git_dir(workspace_path) {
+        paths.push(git_dir);
+    }
+    if let Some(home) = dirs::home_dir() {
+        paths.extend(ENGINE_STATE_PATHS.iter().map(|path| home.join(path)));
+    }
+    paths.extend(extra_writable.iter().cloned());
+    paths.retain(|path| path.exists() && !path.starts_with(workspace_path));
+    paths.dedup();
+    paths
+}
+
+fn bubblewrap_args(
+    sandbox: &WorkspaceSandbox,
+    workspace_path: &Path,
+    writable: &[PathBuf],
+) -> Vec<String> {
+    let mut args: Vec<String> = [
+        "--die-with-parent",
+        "--ro-bind",
+        "/",
+        "/",
+        "--dev",
+        "/dev",
+        "--proc",
+        "/proc",
+        "--tmpfs",
+        "/tmp",
+        "--unshare-pid",
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect();
+    if !sandbox.allow_network {
+        args.push("--unshare-net".to_string());
+    }
+    for path in std::iter::once(&workspace_path.to_path_buf()).chain(writable) {
+        let path = path.to_string_lossy().to_string();
+        args.extend(["--bind".to_string(), path.clone(), path]);
+    }
+    args.extend([
+        "--chdir".to_string(),
+        workspace_path.to_string_lossy().to_string(),
+        "--".to_string(),
+    ]);
+    args
+}
+
+/// bubblewrap has no resource controls of its own, so limits are applied by
+/// running it inside a transient systemd user scope.
+fn systemd_scope_args(sandbox: &WorkspaceSandbox) -> Vec<String> {
+    let mut args = vec![
+        "--user".to_string(),
+        "--scope".to_string(),
+        "--quiet".to_string(),
+    ];
+    if let Some(memory_mb) = sandbox.memory_mb {
+        args.extend(["-p".to_string(), format!("MemoryMax={memory_mb}M")]);
+    }
+    if let Some(cpus) = sandbox.cpus {
+        args.extend([
+            "-p".to_string(),
+            format!("CPUQuota={}%", (cpus * 100.0).round() as u64),
+        ]);
+    }
+    if let Some(pids_limit) = sandbox.pids_limit {
+        args.extend(["-p".to_string(), format!("TasksMax={pids_limit}")]);
+    }
+    args
+}
+
+/// `uid:gid` of the current user, so files written through docker bind mounts
+/// stay owned by them instead of root.
+#[cfg(unix)]
+fn host_user() -> Option<String> {
+    // SAFETY: getuid/getgid cannot fail and have no preconditions.
+    let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+    Some(format!("{uid}:{gid}"))
+}
+
+#[cfg(not(unix))]
+fn host_user() -> Option<String> {
+    None
+}
+
+fn container_args(
+    sandbox: &WorkspaceSandbox,
+    workspace_path: &Path,
+    writable: &[PathBuf],
+    home: Option<&Path>,
+    bin: &str,
+) -> Vec<String> {
+    let mut args: Vec<String> = ["run", "--rm", "-i", "--init"]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+    if sandbox.runner == "podman" {
+        args.push("--userns=keep-id".to_string());
+    } else if let Some(user) = host_user() {
+        args.extend(["--user".to_string(), user]);
+    }
+    if !sandbox.allow_network {
+        args.extend(["--network".to_string(), "none".to_string()]);
+    }
+    if let Some(memory_mb) = sandbox.memory_mb {
+        args.extend(["--memory".to_string(), format!("{memory_mb}m")]);
+    }
+    if let Some(cpus) = sandbox.cpus {
+        args.extend(["--cpus".to_string(), cpus.to_string()]);
+    }
+    if let Some(pids_limit) = sandbox.pids_limit {
+        args.extend(["--pids-limit".to_string(), pids_limit.to_string()]);
+    }
+    for path in std::iter::once(&workspace_path.to_path_buf()).chain(writable) {
+        let path = path.to_string_lossy();
+        args.extend(["-v".to_string(), format!("{path}:{path}")]);
+    }
+    args.extend([
+        "-w".to_string(),
+        workspace_path.to_string_lossy().to_string(),
+    ]);
+    if let Some(home) = home {
+        args.extend(["-e".to_string(), format!("HOME={}", home.to_string_lossy())]);
+    }
+    for name in CONTAINER_ENV_PASSTHROUGH {
+        args.extend(["-e".to_string(), name.to_string()]);
+    }
+    args.push(sandbox.image.clone().unwrap_or_default().trim().to_string());
+    // Host install paths don't exist in the image; resolve the CLI there by name.
+    args.push(
+        Path::new(bin)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| bin.to_string()),
+    );
+    args
+}
+
+/// Program and leading arguments that run `bin` inside the sandbox. Engine
+/// arguments appended afterwards are passed through to `bin`.
+fn sandbox_command_line(
+    sandbox: &WorkspaceSandbox,
+    bin: &str,
+    workspace_path: &Path,
+    writable: &[PathBuf],
+    home: Option<&Path>,
+) -> (String, Vec<String>) {
+    let runner_program = |default: &str| {
+        sandbox
+            .runner_path
+            .clone()
+            .filter(|path| !path.trim().is_empty())
+            .unwrap_or_else(|| default.to_string())
+    };
+    if sandbox.runner == "bubblewrap" {
+        let bwrap = runner_program("bwrap");
+        let mut args = bubblewrap_args(sandbox, workspace_path, writable);
+        args.push(bin.to_string());
+        if !has_resource_limits(sandbox) {
+            return (bwrap, args);
+        }
+        let mut scope_args = systemd_scope_args(sandbox);
+        scope_args.push(bwrap);
+        scope_args.extend(args);
+        return ("systemd-run".to_string(), scope_args);
+    }
+    let runner = runner_program(&sandbox.runner);
+    let args = container_args(sandbox, workspace_path, writable, home, bin);
+    (runner, args)
+}
+
+/// Like [`build_command_for_binary`], but launches `bin` through the
+/// workspace's sandbox when one is enabled. `extra_writable` adds engine state
+/// directories that live outside the defaults (e.g. a custom `CODEX_HOME`).
+pub(crate) fn build_sandboxed_command_for_binary(
+    bin: &str,
+    sandbox: Option<&WorkspaceSandbox>,
+    workspace_path: &Path,
+    extra_writable: &[PathBuf],
+) -> Command {
+    let Some(sandbox) = sandbox.filter(|sandbox| sandbox.enabled) else {
+        return build_command_for_binary(bin);
+    };
+    let writable = writable_paths(workspace_path, extra_writable);
+    let home = dirs::home_dir();
+    let (program, args) =
+        sandbox_command_line(sandbox, bin, workspace_path, &writable, home.as_deref());
+    let mut command = build_command_for_binary(&program);
+    command.args(args);
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sandbox(runner: &str) -> WorkspaceSandbox {
+        WorkspaceSandbox {
+            enabled: true,
+            runner: runner.to_string(),
+            image: Some("ghcr.io/example/agents:latest".to_string()),
+            ..WorkspaceSandbox::default()
+        }
+    }
+
+    fn temp_dir(label: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("moss-sandbox-{label}-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        dir
+    }
+
+    #[test]
+    fn validates_runner_image_and_limits() {
+        assert!(validate_workspace_sandbox(&WorkspaceSandbox::default()).is_ok());
+        assert!(validate_workspace_sandbox(&sandbox("podman")).is_ok());
+        assert!(validate_workspace_sandbox(&sandbox("lxc")).is_err());
+        let mut missing_image = sandbox("docker");
+        missing_image.image = Some(" ".to_string());
+        assert!(validate_workspace_sandbox(&missing_image).is_err());
+        let mut zero_cpus = sandbox("bubblewrap");
+        zero_cpus.cpus = Some(0.0);
+        assert!(validate_workspace_sandbox(&zero_cpus).is_err());
+    }
+
+    #[test]
+    fn bubblewrap_binds_workspace_and_optionally_drops_network() {
+        let mut settings = sandbox("bubblewrap");
+        let workspace = Path::new("/work/repo");
+        let writable = vec![PathBuf::from("/home/me/.codex")];
+        let (program, args) =
+            sandbox_command_line(&settings, "/usr/bin/codex", workspace, &writable, None);
+        assert_eq!(program, "bwrap");
+        assert!(!args.contains(&"--unshare-net".to_string()));
+        let joined = args.join(" ");
+        assert!(joined.contains("--bind /work/repo /work/repo"));
+        assert!(joined.contains("--bind /home/me/.codex /home/me/.codex"));
+        assert!(joined.ends_with("--chdir /work/repo -- /usr/bin/codex"));
+
+        settings.allow_network = false;
+        settings.memory_mb = Some(2048);
+        settings.cpus = Some(1.5);
+        let (program, args) = sandbox_command_line(&settings, "codex", workspace, &[], None);
+        assert_eq!(program, "systemd-run");
+        assert!(args.contains(&"MemoryMax=2048M".to_string()));
+        assert!(args.contains(&"CPUQuota=150%".to_string()));
+        assert!(args.contains(&"--unshare-net".to_string()));
+        assert_eq!(args.iter().filter(|arg| *arg == "bwrap").count(), 1);
+    }
+
+    #[test]
+    fn containers_mount_workspace_and_run_the_cli_by_name() {
+        let mut settings = sandbox("podman");
+        settings.allow_network = false;
+        settings.pids_limit = Some(256);
+        let (program, args) = sandbox_command_line(
+            &settings,
+            "/home/me/.npm/bin/claude",
+            Path::new("/work/repo"),
+            &[],
+            Some(Path::new("/home/me")),
+        );
+        assert_eq!(program, "podman");
+        let joined = args.join(" ");
+        assert!(joined.starts_with("run --rm -i --init --userns=keep-id --network none"));
+        assert!(joined.contains("--pids-limit 256"));
+        assert!(joined.contains("-v /work/repo:/work/repo -w /work/repo"));
+        assert!(joined.contains("-e HOME=/home/me"));
+        assert!(joined.ends_with("ghcr.io/example/agents:latest claude"));
+        assert!(!args.contains(&"--user".to_string()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn docker_runs_as_the_host_user() {
+        let (program, args) = sandbox_command_line(
+            &sandbox("docker"),
+            "codex",
+            Path::new("/work/repo"),
+            &[],
+            None,
+        );
+        assert_eq!(program, "docker");
+        let user = args
+            .iter()
+            .position(|arg| arg == "--user")
+            .map(|index| args[index + 1].clone())
+            .expect("docker --user");
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+        assert_eq!(user, format!("{uid}:{gid}"));
+        assert!(!args.contains(&"--userns=keep-id".to_string()));
+    }
+
+    #[test]
+    fn finds_the_git_dir_of_linked_worktrees() {
+        let root = temp_dir("gitdir");
+        let worktree = root.join("wt");
+        fs::create_dir_all(&worktree).expect("create worktree");
+        fs::write(
+            worktree.join(".git"),
+            format!(
+                "gitdir: {}\n",
+                root.join("repo/.git/worktrees/wt").display()
+            ),
+        )
+        .expect("write .git");
+        assert_eq!(linked_git_dir(&worktree), Some(root.join("repo/.git")));
+        assert_eq!(linked_git_dir(&root), None);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn disabled_sandbox_runs_the_binary_directly_and_enabled_goes_through_runner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = temp_dir("runner");
+        let runner = root.join("fake-bwrap");
+        fs::write(&runner, "#!/bin/sh\necho \"runner $*\"\n").expect("write runner");
+        fs::set_permissions(&runner, fs::Permissions::from_mode(0o755)).expect("chmod");
+
+        let mut settings = sandbox("bubblewrap");
+        settings.runner_path = Some(runner.to_string_lossy().to_string());
+
+        settings.enabled = false;
+        let output = build_sandboxed_command_for_binary("echo", Some(&settings), &root, &[])
+            .arg("hello")
+            .output()
+            .await
+            .expect("run echo");
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "hello");
+
+        settings.enabled = true;
+        let output = build_sandboxed_command_for_binary("echo", Some(&settings), &root, &[])
+            .arg("hello")
+            .output()
+            .await
+            .expect("run fake runner");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.starts_with("runner --die-with-parent"));
+        assert!(stdout.trim_end().ends_with("-- echo hello"));
+        let _ = fs::remove_dir_all(root);
+    }
+}
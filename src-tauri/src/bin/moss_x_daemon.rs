@@ -11,6 +11,9 @@ mod codex_config;
 mod codex_home;
 #[path = "../codex/thread_mode_state.rs"]
 mod codex_thread_mode_state;
+#[allow(dead_code)]
+#[path = "../files/content.rs"]
+mod file_content;
 #[path = "../files/io.rs"]
 mod file_io;
 #[path = "../files/ops.rs"]
@@ -21,6 +24,7 @@ mod file_policy;
 mod rules;
 #[path = "../shared/mod.rs"]
 mod shared;
+#[allow(dead_code)]
 #[path = "../storage.rs"]
 mod storage;
 #[allow(dead_code)]
@@ -51,6 +55,9 @@ mod codex {
 }
 
 mod files {
+    pub(crate) mod content {
+        pub(crate) use crate::file_content::*;
+    }
     pub(crate) mod io {
         pub(crate) use crate::file_io::*;
     }
@@ -79,6 +86,7 @@ use tokio::sync::{broadcast, mpsc, oneshot, Mutex};
 
 use backend::app_server::{spawn_workspace_session, WorkspaceSession};
 use backend::events::{AppServerEvent, EventSink, TerminalOutput};
+use file_content::{read_workspace_file_inner, WorkspaceFileReadOptions, WorkspaceFileResponse};
 use shared::{
     codex_core, files_core, git_core, settings_core, thread_titles_core, workspaces_core,
     worktree_core,
@@ -146,12 +154,6 @@ struct DaemonState {
     codex_login_cancels: Mutex<HashMap<String, oneshot::Sender<()>>>,
 }
 
-#[derive(Serialize, Deserialize)]
-struct WorkspaceFileResponse {
-    content: String,
-    truncated: bool,
-}
-
 #[derive(Serialize, Deserialize)]
 struct WorkspaceFilesResponse {
     files: Vec<String>,
@@ -199,26 +201,13 @@ impl DaemonState {
         &self,
         path: String,
         codex_bin: Option<String>,
-        client_version: String,
     ) -> Result<WorkspaceInfo, String> {
-        let client_version = client_version.clone();
         workspaces_core::add_workspace_core(
             path,
             codex_bin,
+            None,
             &self.workspaces,
-            &self.sessions,
-            &self.app_settings,
             &self.storage_path,
-            move |entry, default_bin, codex_args, codex_home| {
-                spawn_with_client(
-                    self.event_sink.clone(),
-                    client_version.clone(),
-                    entry,
-                    default_bin,
-                    codex_args,
-                    codex_home,
-                )
-            },
         )
         .await
     }
@@ -229,18 +218,15 @@ impl DaemonState {
         branch: String,
         base_ref: Option<String>,
         publish_to_origin: bool,
-        client_version: String,
     ) -> Result<WorkspaceInfo, String> {
-        let client_version = client_version.clone();
         workspaces_core::add_worktree_core(
             parent_id,
             branch,
             base_ref,
             publish_to_origin,
+            false,
             &self.data_dir,
             &self.workspaces,
-            &self.sessions,
-            &self.app_settings,
             &self.storage_path,
             |value| worktree_core::sanitize_worktree_name(value),
             |root, name| worktree_core::unique_worktree_path_strict(root, name),
@@ -257,16 +243,6 @@ impl DaemonState {
             |root, args| {
                 workspaces_core::run_git_command_unit(root, args, git_core::run_git_command_owned)
             },
-            move |entry, default_bin, codex_args, codex_home| {
-                spawn_with_client(
-                    self.event_sink.clone(),
-                    client_version.clone(),
-                    entry,
-                    default_bin,
-                    codex_args,
-                    codex_home,
-                )
-            },
         )
         .await
     }
@@ -511,12 +487,13 @@ impl DaemonState {
         &self,
         workspace_id: String,
         path: String,
+        options: WorkspaceFileReadOptions,
     ) -> Result<WorkspaceFileResponse, String> {
         workspaces_core::read_workspace_file_core(
             &self.workspaces,
             &workspace_id,
             &path,
-            |root, rel_path| read_workspace_file_inner(root, rel_path),
+            |root, rel_path| read_workspace_file_inner(root, rel_path, &options),
         )
         .await
     }
@@ -1310,41 +1287,6 @@ fn write_external_spec_file_inner(
     Ok(())
 }
 
-fn read_workspace_file_inner(
-    root: &PathBuf,
-    relative_path: &str,
-) -> Result<WorkspaceFileResponse, String> {
-    let canonical_root = root
-        .canonicalize()
-        .map_err(|err| format!("Failed to resolve workspace root: {err}"))?;
-    let candidate = canonical_root.join(relative_path);
-    let canonical_path = candidate
-        .canonicalize()
-        .map_err(|err| format!("Failed to open file: {err}"))?;
-    if !canonical_path.starts_with(&canonical_root) {
-        return Err("Invalid file path".to_string());
-    }
-    let metadata = std::fs::metadata(&canonical_path)
-        .map_err(|err| format!("Failed to read file metadata: {err}"))?;
-    if !metadata.is_file() {
-        return Err("Path is not a file".to_string());
-    }
-
-    let file = File::open(&canonical_path).map_err(|err| format!("Failed to open file: {err}"))?;
-    let mut buffer = Vec::new();
-    file.take(MAX_WORKSPACE_FILE_BYTES + 1)
-        .read_to_end(&mut buffer)
-        .map_err(|err| format!("Failed to read file: {err}"))?;
-
-    let truncated = buffer.len() > MAX_WORKSPACE_FILE_BYTES as usize;
-    if truncated {
-        buffer.truncate(MAX_WORKSPACE_FILE_BYTES as usize);
-    }
-
-    let content = String::from_utf8(buffer).map_err(|_| "File is not valid UTF-8".to_string())?;
-    Ok(WorkspaceFileResponse { content, truncated })
-}
-
 fn default_data_dir() -> PathBuf {
     if let Ok(xdg) = env::var("XDG_DATA_HOME") {
         let trimmed = xdg.trim();
@@ -1566,12 +1508,38 @@ fn parse_file_write_request(params: &Value) -> Result<FileWriteRequest, String>
     serde_json::from_value(params.clone()).map_err(|err| err.to_string())
 }
 
+/// RPC methods served by the workspace's Codex app-server session.
+const SESSION_METHODS: &[&str] = &[
+    "start_thread",
+    "resume_thread",
+    "fork_thread",
+    "list_threads",
+    "list_mcp_server_status",
+    "archive_thread",
+    "send_user_message",
+    "start_review",
+    "model_list",
+    "collaboration_mode_list",
+    "account_rate_limits",
+    "account_read",
+    "skills_list",
+    "generate_thread_title",
+];
+
 async fn handle_rpc_request(
     state: &DaemonState,
     method: &str,
     params: Value,
     client_version: String,
 ) -> Result<Value, String> {
+    if SESSION_METHODS.contains(&method) {
+        // Workspaces are registered without a session; start it on first use.
+        if let Some(workspace_id) = params.get("workspaceId").and_then(Value::as_str) {
+            state
+                .connect_workspace(workspace_id.to_string(), client_version.clone())
+                .await?;
+        }
+    }
     match method {
         "ping" => Ok(json!({ "ok": true })),
         "list_workspaces" => {
@@ -1586,7 +1554,7 @@ async fn handle_rpc_request(
         "add_workspace" => {
             let path = parse_string(&params, "path")?;
             let codex_bin = parse_optional_string(&params, "codex_bin");
-            let workspace = state.add_workspace(path, codex_bin, client_version).await?;
+            let workspace = state.add_workspace(path, codex_bin).await?;
             serde_json::to_value(workspace).map_err(|err| err.to_string())
         }
         "add_worktree" => {
@@ -1595,13 +1563,7 @@ async fn handle_rpc_request(
             let base_ref = parse_optional_string(&params, "baseRef");
             let publish_to_origin = parse_optional_bool(&params, "publishToOrigin").unwrap_or(true);
             let workspace = state
-                .add_worktree(
-                    parent_id,
-                    branch,
-                    base_ref,
-                    publish_to_origin,
-                    client_version,
-                )
+                .add_worktree(parent_id, branch, base_ref, publish_to_origin)
                 .await?;
             serde_json::to_value(workspace).map_err(|err| err.to_string())
         }
@@ -1672,7 +1634,15 @@ async fn handle_rpc_request(
         "read_workspace_file" => {
             let workspace_id = parse_string(&params, "workspaceId")?;
             let path = parse_string(&params, "path")?;
-            let response = state.read_workspace_file(workspace_id, path).await?;
+            let options = parse_optional_value(&params, "options")
+                .filter(|value| !value.is_null())
+                .map(serde_json::from_value::<WorkspaceFileReadOptions>)
+                .transpose()
+                .map_err(|err| err.to_string())?
+                .unwrap_or_default();
+            let response = state
+                .read_workspace_file(workspace_id, path, options)
+                .await?;
             serde_json::to_value(response).map_err(|err| err.to_string())
         }
         "list_external_spec_tree" => {
@@ -0,0 +1,1005 @@
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use ignore::WalkBuilder;
+use regex::Regex;
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::{
+    always_keep_line, callable_patterns, class_like_patterns, file_uri_from_path,
+    is_java_method_declaration_line, should_skip_dir, syntax, CodeIntelDocumentSymbol,
+    CodeIntelLocation, CodeIntelPosition, CodeIntelRange, LanguageKind, LineFilter,
+    CODE_INTEL_MAX_FILE_BYTES,
+};
+
+const SCHEMA_VERSION: i64 = 2;
+/// Background re-scan interval when no file watcher is feeding changes.
+const FULL_REFRESH_INTERVAL: Duration = Duration::from_secs(120);
+const WRITE_BATCH_FILES: usize = 200;
+const IDENTIFIER_PATTERN: &str = r"[A-Za-z_$][A-Za-z0-9_$]*";
+const INDEXED_LANGUAGES: [LanguageKind; 9] = [
+    LanguageKind::Java,
+    LanguageKind::Python,
+    LanguageKind::TsJs,
+    LanguageKind::Go,
+    LanguageKind::Rust,
+    LanguageKind::Kotlin,
+    LanguageKind::CSharp,
+    LanguageKind::CFamily,
+    LanguageKind::Ruby,
+];
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(super) enum SymbolKind {
+    Type,
+    Function,
+}
+
+impl SymbolKind {
+    fn key(self) -> &'static str {
+        match self {
+            Self::Type => "type",
+            Self::Function => "function",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ExtractedSymbol {
+    name: String,
+    kind: SymbolKind,
+    line: u32,
+    character: u32,
+    length: u32,
+    /// False for Java call sites that the lenient declaration filter lets
+    /// through; they still answer definition lookups like the scanner did,
+    /// but are kept out of workspace symbol search.
+    declared: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct IndexedSymbol {
+    pub(super) name: String,
+    pub(super) kind: &'static str,
+    pub(super) language: &'static str,
+    pub(super) location: CodeIntelLocation,
+}
+
+struct DeclarationPattern {
+    language: LanguageKind,
+    kind: SymbolKind,
+    regex: Regex,
+    keep_line: LineFilter,
+}
+
+static DECLARATION_PATTERNS: LazyLock<Vec<DeclarationPattern>> = LazyLock::new(|| {
+    let mut patterns = Vec::new();
+    for language in INDEXED_LANGUAGES {
+        for pattern in class_like_patterns(language, IDENTIFIER_PATTERN) {
+            if let Ok(regex) = Regex::new(&pattern) {
+                patterns.push(DeclarationPattern {
+                    language,
+                    kind: SymbolKind::Type,
+                    regex,
+                    keep_line: always_keep_line,
+                });
+            }
+        }
+        for (pattern, keep_line) in callable_patterns(language, IDENTIFIER_PATTERN) {
+            if let Ok(regex) = Regex::new(&pattern) {
+                patterns.push(DeclarationPattern {
+                    language,
+                    kind: SymbolKind::Function,
+                    regex,
+                    keep_line,
+                });
+            }
+        }
+    }
+    patterns
+});
+
+static WORD_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\w+").expect("valid regex"));
+
+/// Control-flow keywords the generic callable patterns would otherwise pick
+/// up as function names (`if (x) {`, `while (y)`).
+fn is_control_keyword(name: &str) -> bool {
+    matches!(
+        name,
+        "if" | "for"
+            | "while"
+            | "switch"
+            | "catch"
+            | "return"
+            | "new"
+            | "function"
+            | "else"
+            | "do"
+            | "try"
+            | "synchronized"
+            | "super"
+            | "this"
+            | "assert"
+            | "throw"
+    )
+}
+
+/// Stricter than `is_java_method_declaration_line`: the token before the
+/// name has to look like a return type, not an operator or call argument.
+fn is_strict_java_declaration(line_text: &str, symbol_start: usize) -> bool {
+    if !is_java_method_declaration_line(line_text, symbol_start) {
+        return false;
+    }
+    let prefix = line_text[..symbol_start].trim_end();
+    prefix
+        .chars()
+        .last()
+        .is_some_and(|ch| ch.is_alphanumeric() || matches!(ch, '_' | '$' | '>' | ']'))
+}
+
+struct LineTable {
+    starts: Vec<usize>,
+}
+
+impl LineTable {
+    fn new(content: &str) -> Self {
+        let mut starts = vec![0];
+        starts.extend(content.match_indices('\n').map(|(idx, _)| idx + 1));
+        Self { starts }
+    }
+
+    fn line_of(&self, offset: usize) -> usize {
+        match self.starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next) => next - 1,
+        }
+    }
+
+    fn line_text<'a>(&self, content: &'a str, line: usize) -> &'a str {
+        let start = self.starts[line];
+        let end = self
+            .starts
+            .get(line + 1)
+            .map(|next| next - 1)
+            .unwrap_or(content.len());
+        &content[start..end]
+    }
+}
+
+fn extract_symbols(content: &str, language: LanguageKind) -> Vec<ExtractedSymbol> {
+    let lines = LineTable::new(content);
+    let mut seen = HashSet::new();
+    let mut symbols = Vec::new();
+    for pattern in DECLARATION_PATTERNS
+        .iter()
+        .filter(|pattern| pattern.language == language)
+    {
+        for captures in pattern.regex.captures_iter(content) {
+            let Some(name) = captures.get(1) else {
+                continue;
+            };
+            if is_control_keyword(name.as_str()) {
+                continue;
+            }
+            let line = lines.line_of(name.start());
+            let line_start = lines.starts[line];
+            let line_text = lines.line_text(content, line);
+            let relative_start = name.start() - line_start;
+            if !(pattern.keep_line)(line_text, relative_start) {
+                continue;
+            }
+            let character = content[line_start..name.start()].chars().count() as u32;
+            if !seen.insert((pattern.kind.key(), line, character)) {
+                continue;
+            }
+            let declared = match (language, pattern.kind) {
+                (LanguageKind::Java, SymbolKind::Function) => {
+                    is_strict_java_declaration(line_text, relative_start)
+                }
+                _ => true,
+            };
+            symbols.push(ExtractedSymbol {
+                name: name.as_str().to_string(),
+                kind: pattern.kind,
+                line: line as u32,
+                character,
+                length: name.as_str().chars().count() as u32,
+                declared,
+            });
+        }
+    }
+    symbols
+}
+
+/// Flat outline from the declaration patterns, for languages without a
+/// tree-sitter grammar.
+pub(super) fn pattern_outline(
+    content: &str,
+    language: LanguageKind,
+) -> Vec<CodeIntelDocumentSymbol> {
+    let mut symbols = extract_symbols(content, language);
+    symbols.retain(|symbol| symbol.declared);
+    symbols.sort_by_key(|symbol| (symbol.line, symbol.character));
+    symbols
+        .into_iter()
+        .map(|symbol| {
+            let range = CodeIntelRange {
+                start: CodeIntelPosition {
+                    line: symbol.line,
+                    character: symbol.character,
+                },
+                end: CodeIntelPosition {
+                    line: symbol.line,
+                    character: symbol.character + symbol.length,
+                },
+            };
+            CodeIntelDocumentSymbol {
+                name: symbol.name,
+                kind: match symbol.kind {
+                    SymbolKind::Type => "class",
+                    SymbolKind::Function => "function",
+                },
+                range: range.clone(),
+                selection_range: range,
+                children: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+fn extract_identifiers(content: &str) -> HashSet<&str> {
+    WORD_PATTERN
+        .find_iter(content)
+        .map(|word| word.as_str())
+        .collect()
+}
+
+fn collect_indexable_files(workspace_root: &Path) -> Vec<(PathBuf, LanguageKind)> {
+    let walker = WalkBuilder::new(workspace_root)
+        .hidden(false)
+        .follow_links(false)
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true)
+        .filter_entry(|entry| {
+            if entry.depth() == 0 {
+                return true;
+            }
+            let name = entry.file_name().to_string_lossy();
+            if entry.file_type().is_some_and(|ft| ft.is_dir()) {
+                return !should_skip_dir(&name);
+            }
+            true
+        })
+        .build();
+
+    walker
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+        .filter_map(|entry| {
+            let language = indexed_language(entry.path())?;
+            Some((entry.into_path(), language))
+        })
+        .collect()
+}
+
+fn indexed_language(path: &Path) -> Option<LanguageKind> {
+    let name = path.file_name().and_then(OsStr::to_str)?;
+    LanguageKind::from_path(name).filter(|language| INDEXED_LANGUAGES.contains(language))
+}
+
+fn relative_key(workspace_root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(workspace_root).ok()?;
+    Some(relative.to_string_lossy().replace('\\', "/"))
+}
+
+fn file_fingerprint(path: &Path) -> Option<(i64, i64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0);
+    Some((modified, metadata.len() as i64))
+}
+
+struct FileUpdate {
+    path: String,
+    language: LanguageKind,
+    modified_ms: i64,
+    size: i64,
+    symbols: Vec<ExtractedSymbol>,
+    identifiers: Vec<String>,
+}
+
+fn analyze_file(
+    workspace_root: &Path,
+    path: &Path,
+    language: LanguageKind,
+    fingerprint: (i64, i64),
+) -> Option<FileUpdate> {
+    let relative = relative_key(workspace_root, path)?;
+    let (modified_ms, size) = fingerprint;
+    // Oversized or non-UTF-8 files are recorded without symbols so they are
+    // not re-read on every refresh.
+    let content = if size as u64 <= CODE_INTEL_MAX_FILE_BYTES {
+        std::fs::read_to_string(path).unwrap_or_default()
+    } else {
+        String::new()
+    };
+    let symbols = match syntax::parse(path, language, &content) {
+        Some(tree) => tree
+            .definitions()
+            .into_iter()
+            .filter_map(|definition| {
+                Some(ExtractedSymbol {
+                    kind: definition.lookup_kind()?,
+                    line: definition.selection_range.start.line,
+                    character: definition.selection_range.start.character,
+                    length: definition.name.chars().count() as u32,
+                    name: definition.name,
+                    declared: true,
+                })
+            })
+            .collect(),
+        None => extract_symbols(&content, language),
+    };
+    let identifiers = extract_identifiers(&content)
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    Some(FileUpdate {
+        path: relative,
+        language,
+        modified_ms,
+        size,
+        symbols,
+        identifiers,
+    })
+}
+
+fn migrate(conn: &Connection) -> Result<(), String> {
+    let version: i64 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|err| err.to_string())?;
+    if version != SCHEMA_VERSION {
+        conn.execute_batch(
+            "DROP TABLE IF EXISTS identifiers;
+             DROP TABLE IF EXISTS symbols;
+             DROP TABLE IF EXISTS files;
+             DROP TABLE IF EXISTS meta;",
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    conn.execute_batch(&format!(
+        "CREATE TABLE IF NOT EXISTS files (
+             id INTEGER PRIMARY KEY,
+             path TEXT NOT NULL UNIQUE,
+             language TEXT NOT NULL,
+             modified_ms INTEGER NOT NULL,
+             size INTEGER NOT NULL
+         );
+         CREATE TABLE IF NOT EXISTS symbols (
+             file_id INTEGER NOT NULL REFERENCES files(id) ON DELETE CASCADE,
+             name TEXT NOT NULL,
+             kind TEXT NOT NULL,
+             line INTEGER NOT NULL,
+             character INTEGER NOT NULL,
+             length INTEGER NOT NULL,
+             declared INTEGER NOT NULL
+         );
+         CREATE INDEX IF NOT EXISTS symbols_by_name ON symbols(name, kind);
+         CREATE INDEX IF NOT EXISTS symbols_by_file ON symbols(file_id);
+         CREATE TABLE IF NOT EXISTS identifiers (
+             name TEXT NOT NULL,
+             file_id INTEGER NOT NULL REFERENCES files(id) ON DELETE CASCADE,
+             PRIMARY KEY (name, file_id)
+         ) WITHOUT ROWID;
+         CREATE INDEX IF NOT EXISTS identifiers_by_file ON identifiers(file_id);
+         CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+         PRAGMA user_version = {SCHEMA_VERSION};"
+    ))
+    .map_err(|err| err.to_string())
+}
+
+fn write_updates(
+    conn: &mut Connection,
+    updates: &[FileUpdate],
+    removed: &[String],
+) -> Result<(), String> {
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    for path in removed {
+        tx.execute("DELETE FROM files WHERE path = ?1", params![path])
+            .map_err(|err| err.to_string())?;
+    }
+    for update in updates {
+        tx.execute("DELETE FROM files WHERE path = ?1", params![update.path])
+            .map_err(|err| err.to_string())?;
+        tx.execute(
+            "INSERT INTO files (path, language, modified_ms, size) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                update.path,
+                update.language.key(),
+                update.modified_ms,
+                update.size
+            ],
+        )
+        .map_err(|err| err.to_string())?;
+        let file_id = tx.last_insert_rowid();
+        {
+            let mut insert_symbol = tx
+                .prepare_cached(
+                    "INSERT INTO symbols (file_id, name, kind, line, character, length, declared)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                )
+                .map_err(|err| err.to_string())?;
+            for symbol in &update.symbols {
+                insert_symbol
+                    .execute(params![
+                        file_id,
+                        symbol.name,
+                        symbol.kind.key(),
+                        symbol.line,
+                        symbol.character,
+                        symbol.length,
+                        symbol.declared
+                    ])
+                    .map_err(|err| err.to_string())?;
+            }
+            let mut insert_identifier = tx
+                .prepare_cached("INSERT OR IGNORE INTO identifiers (name, file_id) VALUES (?1, ?2)")
+                .map_err(|err| err.to_string())?;
+            for identifier in &update.identifiers {
+                insert_identifier
+                    .execute(params![identifier, file_id])
+                    .map_err(|err| err.to_string())?;
+            }
+        }
+    }
+    tx.commit().map_err(|err| err.to_string())
+}
+
+/// On-disk symbol index for one workspace. Lookups only read SQLite; the
+/// workspace is re-scanned incrementally (by mtime and size) in the
+/// background and single files are refreshed as the file watcher reports them.
+pub(crate) struct SymbolIndex {
+    root: PathBuf,
+    conn: Mutex<Connection>,
+    ready: AtomicBool,
+    refreshing: AtomicBool,
+    last_refresh: Mutex<Option<Instant>>,
+}
+
+impl SymbolIndex {
+    pub(super) fn open(db_path: &Path, workspace_root: &Path) -> Result<Self, String> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create symbol index directory: {err}"))?;
+        }
+        let conn = Connection::open(db_path)
+            .map_err(|err| format!("Failed to open symbol index: {err}"))?;
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL; PRAGMA foreign_keys = ON;",
+        )
+        .map_err(|err| err.to_string())?;
+        migrate(&conn)?;
+        let scanned: Option<String> = conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = 'last_scan'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|err| err.to_string())?;
+        Ok(Self {
+            root: workspace_root.to_path_buf(),
+            conn: Mutex::new(conn),
+            // A previous session's index is served immediately and refreshed
+            // in the background.
+            ready: AtomicBool::new(scanned.is_some()),
+            refreshing: AtomicBool::new(false),
+            last_refresh: Mutex::new(None),
+        })
+    }
+
+    pub(super) fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub(super) fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    fn needs_refresh(&self) -> bool {
+        self.last_refresh
+            .lock()
+            .map(|last| last.is_none_or(|at| at.elapsed() >= FULL_REFRESH_INTERVAL))
+            .unwrap_or(true)
+    }
+
+    /// Forces the next `schedule_refresh` to re-scan, e.g. after the watcher
+    /// dropped events.
+    pub(super) fn mark_stale(&self) {
+        if let Ok(mut last) = self.last_refresh.lock() {
+            *last = None;
+        }
+    }
+
+    fn stored_fingerprints(&self) -> Result<HashMap<String, (i64, i64)>, String> {
+        let conn = self.conn.lock().map_err(|_| "symbol index lock poisoned")?;
+        let mut statement = conn
+            .prepare("SELECT path, modified_ms, size FROM files")
+            .map_err(|err| err.to_string())?;
+        let rows = statement
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, (row.get(1)?, row.get(2)?)))
+            })
+            .map_err(|err| err.to_string())?;
+        Ok(rows.flatten().collect())
+    }
+
+    fn write(&self, updates: &[FileUpdate], removed: &[String]) -> Result<(), String> {
+        if updates.is_empty() && removed.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self.conn.lock().map_err(|_| "symbol index lock poisoned")?;
+        write_updates(&mut conn, updates, removed)
+    }
+
+    /// Walks the workspace and re-indexes files whose mtime or size changed.
+    /// Writes happen in small batches so lookups are never blocked for long.
+    pub(super) fn refresh_all(&self) -> Result<(), String> {
+        let mut stored = self.stored_fingerprints()?;
+        let mut batch = Vec::new();
+        for (path, language) in collect_indexable_files(&self.root) {
+            let Some(relative) = relative_key(&self.root, &path) else {
+                continue;
+            };
+            let previous = stored.remove(&relative);
+            let Some(fingerprint) = file_fingerprint(&path) else {
+                continue;
+            };
+            if previous == Some(fingerprint) {
+                continue;
+            }
+            if let Some(update) = analyze_file(&self.root, &path, language, fingerprint) {
+                batch.push(update);
+            }
+            if batch.len() >= WRITE_BATCH_FILES {
+                self.write(&batch, &[])?;
+                batch.clear();
+            }
+        }
+        let removed: Vec<String> = stored.into_keys().collect();
+        self.write(&batch, &removed)?;
+        {
+            let conn = self.conn.lock().map_err(|_| "symbol index lock poisoned")?;
+            conn.execute(
+                "INSERT OR REPLACE INTO meta (key, value) VALUES ('last_scan', ?1)",
+                params![chrono::Utc::now().to_rfc3339()],
+            )
+            .map_err(|err| err.to_string())?;
+        }
+        if let Ok(mut last) = self.last_refresh.lock() {
+            *last = Some(Instant::now());
+        }
+        self.ready.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    /// Re-indexes specific workspace-relative paths; paths that no longer
+    /// exist (or are not indexable) are dropped from the index.
+    pub(super) fn refresh_paths(&self, paths: &[String]) -> Result<(), String> {
+        let stored = self.stored_fingerprints()?;
+        let mut updates = Vec::new();
+        let mut removed = Vec::new();
+        for relative in paths {
+            let absolute = self.root.join(relative);
+            let language = indexed_language(&absolute);
+            match (language, file_fingerprint(&absolute)) {
+                (Some(language), Some(fingerprint)) => {
+                    if stored.get(relative) == Some(&fingerprint) {
+                        continue;
+                    }
+                    if let Some(update) = analyze_file(&self.root, &absolute, language, fingerprint)
+                    {
+                        updates.push(update);
+                    }
+                }
+                _ => {
+                    if stored.contains_key(relative) {
+                        removed.push(relative.clone());
+                    }
+                }
+            }
+        }
+        self.write(&updates, &removed)
+    }
+
+    fn location(&self, path: &str, line: u32, character: u32, length: u32) -> CodeIntelLocation {
+        CodeIntelLocation {
+            uri: file_uri_from_path(&self.root.join(path)),
+            path: path.to_string(),
+            range: CodeIntelRange {
+                start: CodeIntelPosition { line, character },
+                end: CodeIntelPosition {
+                    line,
+                    character: character + length,
+                },
+            },
+        }
+    }
+
+    pub(super) fn definitions(
+        &self,
+        language: LanguageKind,
+        name: &str,
+        kind: SymbolKind,
+    ) -> Result<Vec<CodeIntelLocation>, String> {
+        let conn = self.conn.lock().map_err(|_| "symbol index lock poisoned")?;
+        let mut statement = conn
+            .prepare_cached(
+                "SELECT f.path, s.line, s.character, s.length
+                 FROM symbols s JOIN files f ON f.id = s.file_id
+                 WHERE s.name = ?1 AND s.kind = ?2 AND f.language = ?3
+                 ORDER BY f.path, s.line, s.character",
+            )
+            .map_err(|err| err.to_string())?;
+        let rows = statement
+            .query_map(params![name, kind.key(), language.key()], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, u32>(1)?,
+                    row.get::<_, u32>(2)?,
+                    row.get::<_, u32>(3)?,
+                ))
+            })
+            .map_err(|err| err.to_string())?;
+        Ok(rows
+            .flatten()
+            .map(|(path, line, character, length)| self.location(&path, line, character, length))
+            .collect())
+    }
+
+    /// Files of `language` that contain `name` as a whole word.
+    pub(super) fn files_containing(
+        &self,
+        language: LanguageKind,
+        name: &str,
+    ) -> Result<Vec<PathBuf>, String> {
+        let conn = self.conn.lock().map_err(|_| "symbol index lock poisoned")?;
+        let mut statement = conn
+            .prepare_cached(
+                "SELECT f.path FROM identifiers i JOIN files f ON f.id = i.file_id
+                 WHERE i.name = ?1 AND f.language = ?2
+                 ORDER BY f.path",
+            )
+            .map_err(|err| err.to_string())?;
+        let rows = statement
+            .query_map(params![name, language.key()], |row| row.get::<_, String>(0))
+            .map_err(|err| err.to_string())?;
+        Ok(rows.flatten().map(|path| self.root.join(path)).collect())
+    }
+
+    /// Indexed files of `language` too large to have their identifiers read.
+    pub(super) fn oversized_files(&self, language: LanguageKind) -> Result<Vec<PathBuf>, String> {
+        let conn = self.conn.lock().map_err(|_| "symbol index lock poisoned")?;
+        let mut statement = conn
+            .prepare_cached(
+                "SELECT path FROM files WHERE language = ?1 AND size > ?2 ORDER BY path",
+            )
+            .map_err(|err| err.to_string())?;
+        let rows = statement
+            .query_map(
+                params![language.key(), CODE_INTEL_MAX_FILE_BYTES as i64],
+                |row| row.get::<_, String>(0),
+            )
+            .map_err(|err| err.to_string())?;
+        Ok(rows.flatten().map(|path| self.root.join(path)).collect())
+    }
+
+    /// Case-insensitive substring search over declared symbols, ranked exact
+    /// match first, then prefix, then shorter names. Ranking happens in SQL
+    /// so the candidate limit never cuts off the best matches.
+    pub(super) fn workspace_symbols(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<IndexedSymbol>, String> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let escaped = query
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        let conn = self.conn.lock().map_err(|_| "symbol index lock poisoned")?;
+        let mut statement = conn
+            .prepare_cached(
+                "SELECT s.name, s.kind, f.language, f.path, s.line, s.character, s.length
+                 FROM symbols s JOIN files f ON f.id = s.file_id
+                 WHERE s.declared = 1 AND s.name LIKE ?1 ESCAPE '\\'
+                 ORDER BY s.name = ?3 DESC, lower(s.name) = lower(?3) DESC,
+                          s.name LIKE ?4 ESCAPE '\\' DESC, length(s.name), s.name
+                 LIMIT ?2",
+            )
+            .map_err(|err| err.to_string())?;
+        let candidate_limit = (limit.max(1) * 20) as i64;
+        let rows = statement
+            .query_map(
+                params![
+                    format!("%{escaped}%"),
+                    candidate_limit,
+                    query,
+                    format!("{escaped}%")
+                ],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, u32>(4)?,
+                        row.get::<_, u32>(5)?,
+                        row.get::<_, u32>(6)?,
+                    ))
+                },
+            )
+            .map_err(|err| err.to_string())?;
+
+        let lowered = query.to_lowercase();
+        let rank = |name: &str| -> u8 {
+            if name == query {
+                0
+            } else if name.to_lowercase() == lowered {
+                1
+            } else if name.to_lowercase().starts_with(&lowered) {
+                2
+            } else {
+                3
+            }
+        };
+        let mut symbols: Vec<IndexedSymbol> = rows
+            .flatten()
+            .filter_map(|(name, kind, language, path, line, character, length)| {
+                let language = LanguageKind::from_key(&language)?;
+                let kind = if kind == SymbolKind::Type.key() {
+                    SymbolKind::Type
+                } else {
+                    SymbolKind::Function
+                };
+                Some(IndexedSymbol {
+                    location: self.location(&path, line, character, length),
+                    name,
+                    kind: kind.key(),
+                    language: language.name(),
+                })
+            })
+            .collect();
+        symbols.sort_by(|left, right| {
+            rank(&left.name)
+                .cmp(&rank(&right.name))
+                .then(left.name.len().cmp(&right.name.len()))
+                .then(left.location.path.cmp(&right.location.path))
+                .then(
+                    left.location
+                        .range
+                        .start
+                        .line
+                        .cmp(&right.location.range.start.line),
+                )
+        });
+        symbols.truncate(limit);
+        Ok(symbols)
+    }
+
+    /// Starts a background incremental re-scan unless one is running or the
+    /// last one finished recently.
+    pub(super) fn schedule_refresh(self: &Arc<Self>) {
+        if !self.needs_refresh() || self.refreshing.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let index = Arc::clone(self);
+        tauri::async_runtime::spawn_blocking(move || {
+            if let Err(err) = index.refresh_all() {
+                log::warn!(
+                    "[code_intel] symbol index refresh failed for {}: {err}",
+                    index.root.display()
+                );
+            }
+            index.refreshing.store(false, Ordering::Release);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_workspace() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("moss-symbols-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src/main/java/app")).unwrap();
+        std::fs::create_dir_all(root.join("web/node_modules/lib")).unwrap();
+        std::fs::write(
+            root.join("src/main/java/app/OrderService.java"),
+            "package app;\n\npublic class OrderService {\n    public Order placeOrder(String id) {\n        Order order = buildOrder(id);\n        return order;\n    }\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("web/orders.ts"),
+            "export interface Order { id: string }\n\nexport async function placeOrder(id: string) {\n  if (id) {\n    return fetchOrder(id);\n  }\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("web/node_modules/lib/index.ts"),
+            "export function placeOrder() {}\n",
+        )
+        .unwrap();
+        root
+    }
+
+    fn open_index(root: &Path) -> SymbolIndex {
+        SymbolIndex::open(&root.join(".index/symbols.sqlite3"), root).unwrap()
+    }
+
+    #[test]
+    fn extract_symbols_uses_declaration_patterns() {
+        let content = "public class OrderService {\n    public Order placeOrder(String id) {\n        Order order = buildOrder(id);\n        if (order == null) {}\n    }\n}\n";
+        let symbols = extract_symbols(content, LanguageKind::Java);
+        let summary: Vec<(&str, SymbolKind, u32, u32, bool)> = symbols
+            .iter()
+            .map(|symbol| {
+                (
+                    symbol.name.as_str(),
+                    symbol.kind,
+                    symbol.line,
+                    symbol.character,
+                    symbol.declared,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("OrderService", SymbolKind::Type, 0, 13, true),
+                ("placeOrder", SymbolKind::Function, 1, 17, true),
+                ("buildOrder", SymbolKind::Function, 2, 22, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn refresh_indexes_workspace_and_answers_lookups() {
+        let root = temp_workspace();
+        let index = open_index(&root);
+        assert!(!index.is_ready());
+        index.refresh_all().unwrap();
+        assert!(index.is_ready());
+
+        let java = index
+            .definitions(LanguageKind::Java, "OrderService", SymbolKind::Type)
+            .unwrap();
+        assert_eq!(java.len(), 1);
+        assert_eq!(java[0].path, "src/main/java/app/OrderService.java");
+        assert_eq!(java[0].range.start.line, 2);
+
+        let ts = index
+            .definitions(LanguageKind::TsJs, "placeOrder", SymbolKind::Function)
+            .unwrap();
+        assert_eq!(ts.len(), 1);
+        assert_eq!(ts[0].path, "web/orders.ts");
+
+        let files = index
+            .files_containing(LanguageKind::TsJs, "fetchOrder")
+            .unwrap();
+        assert_eq!(files, vec![root.join("web/orders.ts")]);
+
+        let symbols = index.workspace_symbols("order", 10).unwrap();
+        let names: Vec<&str> = symbols.iter().map(|symbol| symbol.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["Order", "OrderService", "placeOrder", "placeOrder"]
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn workspace_symbols_rank_exact_matches_before_the_candidate_limit() {
+        let root = std::env::temp_dir().join(format!("moss-symbol-rank-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        let handlers: String = (0..30)
+            .map(|index| format!("def on_handler_{index:02}():\n    pass\n\n"))
+            .collect();
+        std::fs::write(root.join("a_handlers.py"), handlers).unwrap();
+        std::fs::write(
+            root.join("z_core.py"),
+            "def handler_base():\n    pass\n\ndef handler():\n    pass\n",
+        )
+        .unwrap();
+        let index = open_index(&root);
+        index.refresh_all().unwrap();
+
+        let symbols = index.workspace_symbols("handler", 1).unwrap();
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].name, "handler");
+        let symbols = index.workspace_symbols("handler", 2).unwrap();
+        let names: Vec<&str> = symbols.iter().map(|symbol| symbol.name.as_str()).collect();
+        assert_eq!(names, vec!["handler", "handler_base"]);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn refresh_paths_tracks_edits_and_deletions() {
+        let root = temp_workspace();
+        let index = open_index(&root);
+        index.refresh_all().unwrap();
+
+        std::fs::write(
+            root.join("web/orders.ts"),
+            "export function cancelOrder(id: string) {}\n",
+        )
+        .unwrap();
+        index.refresh_paths(&["web/orders.ts".to_string()]).unwrap();
+        assert!(index
+            .definitions(LanguageKind::TsJs, "placeOrder", SymbolKind::Function)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            index
+                .definitions(LanguageKind::TsJs, "cancelOrder", SymbolKind::Function)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        std::fs::remove_file(root.join("web/orders.ts")).unwrap();
+        index.refresh_paths(&["web/orders.ts".to_string()]).unwrap();
+        assert!(index
+            .files_containing(LanguageKind::TsJs, "cancelOrder")
+            .unwrap()
+            .is_empty());
+
+        // Reopening keeps the index usable without a new scan.
+        drop(index);
+        let reopened = open_index(&root);
+        assert!(reopened.is_ready());
+        assert_eq!(
+            reopened
+                .definitions(LanguageKind::Java, "placeOrder", SymbolKind::Function)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn pattern_outline_covers_kotlin_declarations() {
+        let content = "data class Order(val id: String)\n\nobject Orders {\n    suspend fun place(id: String): Order = Order(id)\n    fun <T> List<T>.firstOrder(): T = first()\n}\n";
+        let outline: Vec<(String, &str, u32)> = pattern_outline(content, LanguageKind::Kotlin)
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.kind, symbol.range.start.line))
+            .collect();
+        assert_eq!(
+            outline,
+            vec![
+                ("Order".to_string(), "class", 0),
+                ("Orders".to_string(), "class", 2),
+                ("place".to_string(), "function", 3),
+                ("firstOrder".to_string(), "function", 4),
+            ]
+        );
+    }
+}
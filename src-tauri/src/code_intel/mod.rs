@@ -1,22 +1,34 @@
 use std::collections::HashSet;
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use ignore::WalkBuilder;
 use regex::Regex;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 
 use crate::state::AppState;
+use crate::types::WorkspaceFilesChangedEvent;
 
-#[derive(Debug, Clone, Serialize)]
+mod index;
+mod rename;
+mod syntax;
+
+pub(crate) use index::SymbolIndex;
+use index::SymbolKind;
+pub(crate) use rename::{
+    code_intel_apply_edit, code_intel_rename, code_intel_undo_edit, CodeIntelEditSnapshot,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct CodeIntelPosition {
     line: u32,
     character: u32,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct CodeIntelRange {
     start: CodeIntelPosition,
     end: CodeIntelPosition,
@@ -29,6 +41,17 @@ struct CodeIntelLocation {
     range: CodeIntelRange,
 }
 
+/// Outline entry shaped like an LSP `DocumentSymbol`.
+#[derive(Debug, Clone, Serialize)]
+struct CodeIntelDocumentSymbol {
+    name: String,
+    kind: &'static str,
+    range: CodeIntelRange,
+    #[serde(rename = "selectionRange")]
+    selection_range: CodeIntelRange,
+    children: Vec<CodeIntelDocumentSymbol>,
+}
+
 #[derive(Debug, Clone)]
 struct SymbolAtCursor {
     symbol: String,
@@ -55,6 +78,11 @@ enum LanguageKind {
     Python,
     TsJs,
     Go,
+    Rust,
+    Kotlin,
+    CSharp,
+    CFamily,
+    Ruby,
     Yaml,
 }
 
@@ -69,6 +97,11 @@ impl LanguageKind {
             "py" | "pyi" => Some(Self::Python),
             "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" => Some(Self::TsJs),
             "go" => Some(Self::Go),
+            "rs" => Some(Self::Rust),
+            "kt" | "kts" => Some(Self::Kotlin),
+            "cs" => Some(Self::CSharp),
+            "c" | "h" | "cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx" => Some(Self::CFamily),
+            "rb" | "rake" => Some(Self::Ruby),
             "yml" | "yaml" => Some(Self::Yaml),
             _ => None,
         }
@@ -80,16 +113,57 @@ impl LanguageKind {
             Self::Python => &["py", "pyi"],
             Self::TsJs => &["ts", "tsx", "js", "jsx", "mjs", "cjs"],
             Self::Go => &["go"],
+            Self::Rust => &["rs"],
+            Self::Kotlin => &["kt", "kts"],
+            Self::CSharp => &["cs"],
+            Self::CFamily => &["c", "h", "cc", "cpp", "cxx", "hh", "hpp", "hxx"],
+            Self::Ruby => &["rb", "rake"],
             Self::Yaml => &["yml", "yaml"],
         }
     }
 
+    fn key(self) -> &'static str {
+        match self {
+            Self::Java => "java",
+            Self::Python => "python",
+            Self::TsJs => "tsjs",
+            Self::Go => "go",
+            Self::Rust => "rust",
+            Self::Kotlin => "kotlin",
+            Self::CSharp => "csharp",
+            Self::CFamily => "c",
+            Self::Ruby => "ruby",
+            Self::Yaml => "yaml",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "java" => Some(Self::Java),
+            "python" => Some(Self::Python),
+            "tsjs" => Some(Self::TsJs),
+            "go" => Some(Self::Go),
+            "rust" => Some(Self::Rust),
+            "kotlin" => Some(Self::Kotlin),
+            "csharp" => Some(Self::CSharp),
+            "c" => Some(Self::CFamily),
+            "ruby" => Some(Self::Ruby),
+            "yaml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+
     fn name(self) -> &'static str {
         match self {
             Self::Java => "Java",
             Self::Python => "Python",
             Self::TsJs => "TS/JS",
             Self::Go => "Go",
+            Self::Rust => "Rust",
+            Self::Kotlin => "Kotlin",
+            Self::CSharp => "C#",
+            Self::CFamily => "C/C++",
+            Self::Ruby => "Ruby",
             Self::Yaml => "YAML",
         }
     }
@@ -97,6 +171,7 @@ impl LanguageKind {
 
 const CODE_INTEL_MAX_FILE_BYTES: u64 = 2_000_000;
 const MAX_CODE_INTEL_RESULTS: usize = 500;
+const UNSUPPORTED_LANGUAGE_MESSAGE: &str = "Code intelligence currently supports YAML, Python, TS/JS, Go, Java, Kotlin, Rust, C#, C/C++, and Ruby files";
 
 fn is_identifier_char(ch: char) -> bool {
     ch.is_ascii_alphanumeric() || ch == '_' || ch == '$'
@@ -119,6 +194,7 @@ fn should_skip_dir(name: &str) -> bool {
             | "venv"
             | ".venv"
             | "vendor"
+            | "obj"
     )
 }
 
@@ -130,7 +206,8 @@ fn read_file_snapshot(workspace_root: &Path, relative_path: &str) -> Result<File
     if !absolute.starts_with(workspace_root) {
         return Err("Invalid file path".to_string());
     }
-    let metadata = std::fs::metadata(&absolute).map_err(|err| format!("Failed to stat file: {err}"))?;
+    let metadata =
+        std::fs::metadata(&absolute).map_err(|err| format!("Failed to stat file: {err}"))?;
     if !metadata.is_file() {
         return Err("Path is not a file".to_string());
     }
@@ -285,7 +362,9 @@ fn parse_yaml_property_entries(content: &str) -> Vec<YamlPropertyEntry> {
         let (key_clean, quoted) = if (key_trimmed.starts_with('"')
             && key_trimmed.ends_with('"')
             && key_trimmed.len() >= 2)
-            || (key_trimmed.starts_with('\'') && key_trimmed.ends_with('\'') && key_trimmed.len() >= 2)
+            || (key_trimmed.starts_with('\'')
+                && key_trimmed.ends_with('\'')
+                && key_trimmed.len() >= 2)
         {
             (&key_trimmed[1..key_trimmed.len() - 1], true)
         } else {
@@ -329,7 +408,11 @@ fn parse_yaml_property_entries(content: &str) -> Vec<YamlPropertyEntry> {
         });
 
         let value_part = segment[colon_pos + 1..].trim();
-        if value_part.is_empty() || value_part.starts_with('#') || value_part == "|" || value_part == ">" {
+        if value_part.is_empty()
+            || value_part.starts_with('#')
+            || value_part == "|"
+            || value_part == ">"
+        {
             stack.push((indent, full_key));
         }
     }
@@ -348,6 +431,7 @@ fn yaml_key_at_cursor(content: &str, line: u32, character: u32) -> Option<String
 }
 
 fn symbol_at_cursor_for_language(
+    file_path: &Path,
     content: &str,
     line: u32,
     character: u32,
@@ -359,7 +443,10 @@ fn symbol_at_cursor_for_language(
             method_like: false,
         });
     }
-    symbol_at_cursor(content, line, character)
+    match syntax::parse(file_path, language, content) {
+        Some(tree) => tree.identifier_at(line, character),
+        None => symbol_at_cursor(content, line, character),
+    }
 }
 
 fn collect_language_files(workspace_root: &Path, language: LanguageKind) -> Vec<PathBuf> {
@@ -447,8 +534,7 @@ fn is_java_method_declaration_line(line_text: &str, symbol_start: usize) -> bool
         .trim_matches(|c: char| c == '<' || c == '>' || c == ',' || c == '?');
     if matches!(
         last,
-        "if"
-            | "for"
+        "if" | "for"
             | "while"
             | "switch"
             | "catch"
@@ -464,166 +550,203 @@ fn is_java_method_declaration_line(line_text: &str, symbol_start: usize) -> bool
     true
 }
 
-fn collect_definition_matches<F>(
+fn collect_definition_matches(
     workspace_root: &Path,
-    files: &[PathBuf],
+    snapshot: &FileSnapshot,
     re: &Regex,
     symbol_len: usize,
-    keep_line: F,
-) -> Vec<CodeIntelLocation>
-where
-    F: Fn(&str, usize) -> bool + Copy,
-{
+    keep_line: LineFilter,
+) -> Vec<CodeIntelLocation> {
     let mut locations = Vec::new();
-    for file in files {
-        let Some(snapshot) = load_file_snapshot(workspace_root, file) else {
+    for captures in re.captures_iter(&snapshot.content) {
+        let Some(matched_symbol) = captures.get(1) else {
             continue;
         };
-        for captures in re.captures_iter(&snapshot.content) {
-            let Some(matched_symbol) = captures.get(1) else {
-                continue;
-            };
-            let position = offset_to_line_character(&snapshot.content, matched_symbol.start());
-            let Some((line_start, line_end)) =
-                find_line_bounds(&snapshot.content, position.line as usize)
-            else {
-                continue;
-            };
-            let line_text = &snapshot.content[line_start..line_end];
-            let line_relative_start = matched_symbol.start().saturating_sub(line_start);
-            if !keep_line(line_text, line_relative_start) {
-                continue;
-            }
-            if let Some(location) = build_location(
-                workspace_root,
-                &snapshot.absolute,
-                &snapshot.content,
-                matched_symbol.start(),
-                symbol_len,
-            ) {
-                locations.push(location);
-            }
+        let position = offset_to_line_character(&snapshot.content, matched_symbol.start());
+        let Some((line_start, line_end)) =
+            find_line_bounds(&snapshot.content, position.line as usize)
+        else {
+            continue;
+        };
+        let line_text = &snapshot.content[line_start..line_end];
+        let line_relative_start = matched_symbol.start().saturating_sub(line_start);
+        if !keep_line(line_text, line_relative_start) {
+            continue;
+        }
+        if let Some(location) = build_location(
+            workspace_root,
+            &snapshot.absolute,
+            &snapshot.content,
+            matched_symbol.start(),
+            symbol_len,
+        ) {
+            locations.push(location);
         }
     }
     locations
 }
 
-fn find_class_like_definitions(
-    workspace_root: &Path,
-    language: LanguageKind,
-    symbol: &str,
-    files: &[PathBuf],
-) -> Vec<CodeIntelLocation> {
-    let escaped = regex::escape(symbol);
-    let symbol_len = symbol.chars().count();
-    let patterns: Vec<String> = match language {
-        LanguageKind::Java => vec![format!(r"\b(?:class|interface|enum|record)\s+({escaped})\b")],
-        LanguageKind::Python => vec![format!(r"(?m)^[ \t]*class[ \t]+({escaped})\b")],
+/// Declaration patterns for type-like symbols. `name` is spliced in as the
+/// body of capture group 1: an escaped symbol for lookups, or a generic
+/// identifier pattern when building the symbol index.
+fn class_like_patterns(language: LanguageKind, name: &str) -> Vec<String> {
+    match language {
+        LanguageKind::Java => vec![format!(r"\b(?:class|interface|enum|record)\s+({name})\b")],
+        LanguageKind::Python => vec![format!(r"(?m)^[ \t]*class[ \t]+({name})\b")],
         LanguageKind::TsJs => vec![format!(
-            r"(?m)^[ \t]*(?:export[ \t]+(?:default[ \t]+)?)?(?:abstract[ \t]+)?(?:class|interface|type|enum)[ \t]+({escaped})\b"
+            r"(?m)^[ \t]*(?:export[ \t]+(?:default[ \t]+)?)?(?:abstract[ \t]+)?(?:class|interface|type|enum)[ \t]+({name})\b"
         )],
-        LanguageKind::Go => vec![format!(r"(?m)^[ \t]*type[ \t]+({escaped})\b")],
-        LanguageKind::Yaml => Vec::new(),
-    };
+        LanguageKind::Go => vec![format!(r"(?m)^[ \t]*type[ \t]+({name})\b")],
+        LanguageKind::Kotlin => vec![format!(
+            r"\b(?:class|interface|object|typealias)[ \t]+({name})\b"
+        )],
+        // Parsed with tree-sitter only.
+        LanguageKind::Rust
+        | LanguageKind::CSharp
+        | LanguageKind::CFamily
+        | LanguageKind::Ruby
+        | LanguageKind::Yaml => Vec::new(),
+    }
+}
 
-    let mut locations = Vec::new();
-    for pattern in patterns {
-        let Ok(re) = Regex::new(&pattern) else {
-            continue;
-        };
-        locations.extend(collect_definition_matches(
-            workspace_root,
-            files,
-            &re,
-            symbol_len,
+/// Decides whether a regex match at the given byte offset of a line is kept.
+type LineFilter = fn(&str, usize) -> bool;
+
+/// Declaration patterns for functions and methods, paired with the line
+/// filter each match has to pass. See [`class_like_patterns`] for `name`.
+fn callable_patterns(language: LanguageKind, name: &str) -> Vec<(String, LineFilter)> {
+    match language {
+        LanguageKind::Java => vec![(
+            format!(r"\b({name})\s*\("),
+            is_java_method_declaration_line as LineFilter,
+        )],
+        LanguageKind::Python => vec![(
+            format!(r"(?m)^[ \t]*(?:async[ \t]+)?def[ \t]+({name})\b"),
             always_keep_line,
-        ));
+        )],
+        LanguageKind::TsJs => vec![
+            (
+                format!(
+                    r"(?m)^[ \t]*(?:export[ \t]+(?:default[ \t]+)?)?(?:async[ \t]+)?function(?:\s*\*)?[ \t]+({name})\b"
+                ),
+                always_keep_line,
+            ),
+            (
+                format!(
+                    r"(?m)^[ \t]*(?:export[ \t]+)?(?:const|let|var)[ \t]+({name})[ \t]*=[ \t]*(?:async[ \t]+)?(?:function\b|\()"
+                ),
+                always_keep_line,
+            ),
+            (
+                format!(
+                    r"(?m)^[ \t]*(?:public|private|protected|static|readonly|async|get|set)?[ \t]*({name})[ \t]*\([^;\n]*\)[ \t]*(?::[^{{=\n]+)?\{{"
+                ),
+                always_keep_line,
+            ),
+        ],
+        LanguageKind::Go => vec![(
+            format!(r"(?m)^[ \t]*func[ \t]+(?:\([^)]*\)[ \t]*)?({name})\b"),
+            always_keep_line,
+        )],
+        LanguageKind::Kotlin => vec![(
+            format!(r"\bfun[ \t]+(?:<[^>\n]*>[ \t]*)?(?:[\w.?<>]+\.)?({name})[ \t]*\("),
+            always_keep_line,
+        )],
+        LanguageKind::Rust
+        | LanguageKind::CSharp
+        | LanguageKind::CFamily
+        | LanguageKind::Ruby
+        | LanguageKind::Yaml => Vec::new(),
     }
-    locations
 }
 
-fn find_callable_definitions(
+/// Definitions of `symbol` with the given kind. Files with a tree-sitter
+/// grammar are parsed; the rest go through the declaration patterns.
+fn find_definitions(
     workspace_root: &Path,
     language: LanguageKind,
     symbol: &str,
+    kind: SymbolKind,
     files: &[PathBuf],
 ) -> Vec<CodeIntelLocation> {
     let escaped = regex::escape(symbol);
+    let patterns: Vec<(String, LineFilter)> = match kind {
+        SymbolKind::Type => class_like_patterns(language, &escaped)
+            .into_iter()
+            .map(|pattern| (pattern, always_keep_line as LineFilter))
+            .collect(),
+        SymbolKind::Function => callable_patterns(language, &escaped),
+    };
+    let patterns: Vec<(Regex, LineFilter)> = patterns
+        .into_iter()
+        .filter_map(|(pattern, keep_line)| Some((Regex::new(&pattern).ok()?, keep_line)))
+        .collect();
     let symbol_len = symbol.chars().count();
-    let mut locations = Vec::new();
 
-    match language {
-        LanguageKind::Java => {
-            let Ok(re) = Regex::new(&format!(r"\b({escaped})\s*\(")) else {
-                return locations;
-            };
-            locations.extend(collect_definition_matches(
-                workspace_root,
-                files,
-                &re,
-                symbol_len,
-                is_java_method_declaration_line,
-            ));
-        }
-        LanguageKind::Python => {
-            let Ok(re) =
-                Regex::new(&format!(r"(?m)^[ \t]*(?:async[ \t]+)?def[ \t]+({escaped})\b"))
-            else {
-                return locations;
-            };
-            locations.extend(collect_definition_matches(
-                workspace_root,
-                files,
-                &re,
-                symbol_len,
-                always_keep_line,
-            ));
+    let mut locations = Vec::new();
+    for file in files {
+        let Some(snapshot) = load_file_snapshot(workspace_root, file) else {
+            continue;
+        };
+        if !snapshot.content.contains(symbol) {
+            continue;
         }
-        LanguageKind::TsJs => {
-            let patterns = vec![
-                format!(
-                    r"(?m)^[ \t]*(?:export[ \t]+(?:default[ \t]+)?)?(?:async[ \t]+)?function(?:\s*\*)?[ \t]+({escaped})\b"
-                ),
-                format!(
-                    r"(?m)^[ \t]*(?:export[ \t]+)?(?:const|let|var)[ \t]+({escaped})[ \t]*=[ \t]*(?:async[ \t]+)?(?:function\b|\()"
-                ),
-                format!(
-                    r"(?m)^[ \t]*(?:public|private|protected|static|readonly|async|get|set)?[ \t]*({escaped})[ \t]*\([^;\n]*\)[ \t]*(?::[^{{=\n]+)?\{{"
-                ),
-            ];
-            for pattern in patterns {
-                let Ok(re) = Regex::new(&pattern) else {
-                    continue;
-                };
-                locations.extend(collect_definition_matches(
-                    workspace_root,
-                    files,
-                    &re,
-                    symbol_len,
-                    always_keep_line,
-                ));
-            }
+        if let Some(tree) = syntax::parse(file, language, &snapshot.content) {
+            locations.extend(
+                tree.definitions()
+                    .into_iter()
+                    .filter(|definition| {
+                        definition.name == symbol && definition.lookup_kind() == Some(kind)
+                    })
+                    .filter_map(|definition| {
+                        build_location(
+                            workspace_root,
+                            &snapshot.absolute,
+                            &snapshot.content,
+                            definition.name_offset,
+                            symbol_len,
+                        )
+                    }),
+            );
+            continue;
         }
-        LanguageKind::Go => {
-            let Ok(re) =
-                Regex::new(&format!(r"(?m)^[ \t]*func[ \t]+(?:\([^)]*\)[ \t]*)?({escaped})\b"))
-            else {
-                return locations;
-            };
+        for (re, keep_line) in &patterns {
             locations.extend(collect_definition_matches(
                 workspace_root,
-                files,
-                &re,
+                &snapshot,
+                re,
                 symbol_len,
-                always_keep_line,
+                *keep_line,
             ));
         }
-        LanguageKind::Yaml => {}
     }
-
     locations
 }
 
+fn find_class_like_definitions(
+    workspace_root: &Path,
+    language: LanguageKind,
+    symbol: &str,
+    files: &[PathBuf],
+) -> Vec<CodeIntelLocation> {
+    find_definitions(workspace_root, language, symbol, SymbolKind::Type, files)
+}
+
+fn find_callable_definitions(
+    workspace_root: &Path,
+    language: LanguageKind,
+    symbol: &str,
+    files: &[PathBuf],
+) -> Vec<CodeIntelLocation> {
+    find_definitions(
+        workspace_root,
+        language,
+        symbol,
+        SymbolKind::Function,
+        files,
+    )
+}
+
 fn find_references(
     workspace_root: &Path,
     language: LanguageKind,
@@ -648,15 +771,25 @@ fn find_references(
         let Some(snapshot) = load_file_snapshot(workspace_root, file) else {
             continue;
         };
-        for captures in re.captures_iter(&snapshot.content) {
-            let Some(matched_symbol) = captures.get(1) else {
-                continue;
-            };
+        if !snapshot.content.contains(symbol) {
+            continue;
+        }
+        // Syntax trees give every identifier spelled like the symbol, without
+        // the call-shape guess the regex needs to cut down on noise.
+        let offsets: Vec<usize> = match syntax::parse(file, language, &snapshot.content) {
+            Some(tree) => tree.identifier_offsets(symbol),
+            None => re
+                .captures_iter(&snapshot.content)
+                .filter_map(|captures| captures.get(1))
+                .map(|matched_symbol| matched_symbol.start())
+                .collect(),
+        };
+        for offset in offsets {
             if let Some(location) = build_location(
                 workspace_root,
                 &snapshot.absolute,
                 &snapshot.content,
-                matched_symbol.start(),
+                offset,
                 symbol_len,
             ) {
                 locations.push(location);
@@ -710,7 +843,8 @@ fn parse_config_properties_matches(
     .expect("valid regex");
     let re_prefix_pos = Regex::new(r#"@ConfigurationProperties\s*\(\s*"([A-Za-z0-9_.-]+)"\s*\)"#)
         .expect("valid regex");
-    let re_class = Regex::new(r#"\b(class|record)\s+([A-Za-z_][A-Za-z0-9_]*)\b"#).expect("valid regex");
+    let re_class =
+        Regex::new(r#"\b(class|record)\s+([A-Za-z_][A-Za-z0-9_]*)\b"#).expect("valid regex");
     let re_field = Regex::new(
         r#"(?m)^[ \t]*(?:private|protected|public)[ \t]+(?:static[ \t]+)?(?:final[ \t]+)?[A-Za-z_][A-Za-z0-9_<>,.?\[\]]*[ \t]+([A-Za-z_][A-Za-z0-9_]*)[ \t]*(?:[=;])"#,
     )
@@ -923,6 +1057,8 @@ fn sort_locations_by_relevance(
         .unwrap_or_default();
     let expected_type_file = match language {
         LanguageKind::Java if !method_like => Some(format!("{symbol}.java")),
+        LanguageKind::Kotlin if !method_like => Some(format!("{symbol}.kt")),
+        LanguageKind::CSharp if !method_like => Some(format!("{symbol}.cs")),
         _ => None,
     };
 
@@ -962,6 +1098,218 @@ fn sort_locations_by_relevance(
     });
 }
 
+/// Definition lookup by scanning `files`, trying the other symbol shape when
+/// the one suggested by the cursor finds nothing.
+fn scan_definitions(
+    workspace_root: &Path,
+    language: LanguageKind,
+    symbol: &SymbolAtCursor,
+    files: &[PathBuf],
+) -> Vec<CodeIntelLocation> {
+    let mut defs = if symbol.method_like {
+        find_callable_definitions(workspace_root, language, &symbol.symbol, files)
+    } else {
+        find_class_like_definitions(workspace_root, language, &symbol.symbol, files)
+    };
+    if defs.is_empty() {
+        defs = if symbol.method_like {
+            find_class_like_definitions(workspace_root, language, &symbol.symbol, files)
+        } else {
+            find_callable_definitions(workspace_root, language, &symbol.symbol, files)
+        };
+    }
+    defs
+}
+
+/// Same lookup as [`scan_definitions`], answered from the symbol index.
+fn indexed_definitions(
+    index: &SymbolIndex,
+    language: LanguageKind,
+    symbol: &SymbolAtCursor,
+) -> Vec<CodeIntelLocation> {
+    let (primary, fallback) = if symbol.method_like {
+        (SymbolKind::Function, SymbolKind::Type)
+    } else {
+        (SymbolKind::Type, SymbolKind::Function)
+    };
+    let mut defs = index
+        .definitions(language, &symbol.symbol, primary)
+        .unwrap_or_default();
+    if defs.is_empty() {
+        defs = index
+            .definitions(language, &symbol.symbol, fallback)
+            .unwrap_or_default();
+    }
+    defs
+}
+
+async fn resolve_workspace_root(state: &AppState, workspace_id: &str) -> Result<PathBuf, String> {
+    let workspaces = state.workspaces.lock().await;
+    let workspace_path = workspaces
+        .get(workspace_id)
+        .map(|entry| PathBuf::from(&entry.path))
+        .ok_or_else(|| "Workspace not found".to_string())?;
+    workspace_path
+        .canonicalize()
+        .map_err(|err| format!("Failed to resolve workspace root: {err}"))
+}
+
+fn symbol_index_path(state: &AppState, workspace_id: &str) -> PathBuf {
+    state
+        .storage_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join("code-intel")
+        .join(format!("{workspace_id}.sqlite3"))
+}
+
+async fn symbol_index(
+    state: &AppState,
+    workspace_id: &str,
+    workspace_root: &Path,
+) -> Option<Arc<SymbolIndex>> {
+    let mut indexes = state.code_intel_indexes.lock().await;
+    if let Some(index) = indexes.get(workspace_id) {
+        if index.root() == workspace_root {
+            return Some(Arc::clone(index));
+        }
+    }
+    match SymbolIndex::open(&symbol_index_path(state, workspace_id), workspace_root) {
+        Ok(index) => {
+            let index = Arc::new(index);
+            indexes.insert(workspace_id.to_string(), Arc::clone(&index));
+            Some(index)
+        }
+        Err(err) => {
+            log::warn!("[code_intel] symbol index unavailable for {workspace_id}: {err}");
+            None
+        }
+    }
+}
+
+/// Returns the symbol index when it can answer lookups, after bringing the
+/// requesting file up to date. Until the first scan finishes callers fall
+/// back to scanning files directly.
+async fn ready_symbol_index(
+    state: &AppState,
+    workspace_id: &str,
+    workspace_root: &Path,
+    source_path: &str,
+) -> Option<Arc<SymbolIndex>> {
+    let index = symbol_index(state, workspace_id, workspace_root).await?;
+    index.schedule_refresh();
+    if !index.is_ready() {
+        return None;
+    }
+    let refreshing = Arc::clone(&index);
+    let source_path = source_path.replace('\\', "/");
+    let _ = tauri::async_runtime::spawn_blocking(move || refreshing.refresh_paths(&[source_path]))
+        .await;
+    Some(index)
+}
+
+/// Answers from the workspace's language server when one is running for the
+/// file; see [`crate::lsp::code_intel_query`].
+async fn lsp_locations(
+    state: &AppState,
+    app: &AppHandle,
+    workspace_id: &str,
+    workspace_root: &Path,
+    file_path: &str,
+    method: &str,
+    params: Value,
+) -> Option<Vec<CodeIntelLocation>> {
+    let locations = crate::lsp::code_intel_query(
+        state,
+        app,
+        workspace_id,
+        workspace_root,
+        file_path,
+        method,
+        params,
+    )
+    .await?;
+    Some(
+        locations
+            .into_iter()
+            .map(|location| {
+                let path = location
+                    .path
+                    .as_deref()
+                    .map(|path| {
+                        path.strip_prefix(workspace_root)
+                            .unwrap_or(path)
+                            .to_string_lossy()
+                            .replace('\\', "/")
+                    })
+                    .unwrap_or_else(|| location.uri.clone());
+                CodeIntelLocation {
+                    uri: location.uri,
+                    path,
+                    range: CodeIntelRange {
+                        start: CodeIntelPosition {
+                            line: location.start.0,
+                            character: location.start.1,
+                        },
+                        end: CodeIntelPosition {
+                            line: location.end.0,
+                            character: location.end.1,
+                        },
+                    },
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Feeds file watcher batches into the workspace's symbol index, if one is open.
+pub(crate) async fn refresh_symbol_index_for_changes(
+    app: &AppHandle,
+    event: &WorkspaceFilesChangedEvent,
+) {
+    let state = app.state::<AppState>();
+    let index = state
+        .code_intel_indexes
+        .lock()
+        .await
+        .get(&event.workspace_id)
+        .cloned();
+    let Some(index) = index else {
+        return;
+    };
+    if event.rescan {
+        index.mark_stale();
+        index.schedule_refresh();
+        return;
+    }
+    let paths: Vec<String> = event
+        .added
+        .iter()
+        .chain(&event.removed)
+        .chain(&event.modified)
+        .cloned()
+        .collect();
+    if paths.is_empty() {
+        return;
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(err) = index.refresh_paths(&paths) {
+            log::debug!("[code_intel] incremental index update failed: {err}");
+        }
+    });
+}
+
+/// Closes and deletes a removed workspace's symbol index.
+pub(crate) async fn drop_symbol_index(state: &AppState, workspace_id: &str) {
+    state.code_intel_indexes.lock().await.remove(workspace_id);
+    let db_path = symbol_index_path(state, workspace_id);
+    for suffix in ["", "-wal", "-shm"] {
+        let mut path = db_path.clone().into_os_string();
+        path.push(suffix);
+        let _ = std::fs::remove_file(PathBuf::from(path));
+    }
+}
+
 #[tauri::command]
 pub async fn code_intel_definition(
     workspace_id: String,
@@ -969,48 +1317,46 @@ pub async fn code_intel_definition(
     line: u32,
     character: u32,
     state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<Value, String> {
-    let language = LanguageKind::from_path(&file_path).ok_or_else(|| {
-        "Code intelligence currently supports YAML, Python, TS/JS, Go, and Java files".to_string()
-    })?;
-
-    let workspace_root = {
-        let workspaces = state.workspaces.lock().await;
-        let workspace_path = workspaces
-            .get(&workspace_id)
-            .map(|entry| PathBuf::from(&entry.path))
-            .ok_or_else(|| "Workspace not found".to_string())?;
-        workspace_path
-            .canonicalize()
-            .map_err(|err| format!("Failed to resolve workspace root: {err}"))?
-    };
+    let language = LanguageKind::from_path(&file_path)
+        .ok_or_else(|| UNSUPPORTED_LANGUAGE_MESSAGE.to_string())?;
+
+    let workspace_root = resolve_workspace_root(&state, &workspace_id).await?;
 
     let source = read_file_snapshot(&workspace_root, &file_path)?;
-    let symbol = symbol_at_cursor_for_language(&source.content, line, character, language)
-        .ok_or_else(|| "No symbol under cursor".to_string())?;
+    let symbol =
+        symbol_at_cursor_for_language(&source.absolute, &source.content, line, character, language)
+            .ok_or_else(|| "No symbol under cursor".to_string())?;
 
     let mut locations = if language == LanguageKind::Yaml {
         let java_files = collect_language_files(&workspace_root, LanguageKind::Java);
-        let mut definitions = find_yaml_to_java_definitions(&workspace_root, &symbol.symbol, &java_files);
+        let mut definitions =
+            find_yaml_to_java_definitions(&workspace_root, &symbol.symbol, &java_files);
         if definitions.is_empty() {
-            definitions = find_yaml_to_java_references(&workspace_root, &symbol.symbol, &java_files);
+            definitions =
+                find_yaml_to_java_references(&workspace_root, &symbol.symbol, &java_files);
         }
         definitions
+    } else if let Some(locations) = lsp_locations(
+        &state,
+        &app,
+        &workspace_id,
+        &workspace_root,
+        &file_path,
+        "textDocument/definition",
+        json!({ "position": { "line": line, "character": character } }),
+    )
+    .await
+    {
+        locations
+    } else if let Some(index) =
+        ready_symbol_index(&state, &workspace_id, &workspace_root, &file_path).await
+    {
+        indexed_definitions(&index, language, &symbol)
     } else {
         let language_files = collect_language_files(&workspace_root, language);
-        let mut defs = if symbol.method_like {
-            find_callable_definitions(&workspace_root, language, &symbol.symbol, &language_files)
-        } else {
-            find_class_like_definitions(&workspace_root, language, &symbol.symbol, &language_files)
-        };
-        if defs.is_empty() {
-            defs = if symbol.method_like {
-                find_class_like_definitions(&workspace_root, language, &symbol.symbol, &language_files)
-            } else {
-                find_callable_definitions(&workspace_root, language, &symbol.symbol, &language_files)
-            };
-        }
-        defs
+        scan_definitions(&workspace_root, language, &symbol, &language_files)
     };
 
     locations = dedupe_locations(locations);
@@ -1042,25 +1388,17 @@ pub async fn code_intel_references(
     character: u32,
     include_declaration: Option<bool>,
     state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<Value, String> {
-    let language = LanguageKind::from_path(&file_path).ok_or_else(|| {
-        "Code intelligence currently supports YAML, Python, TS/JS, Go, and Java files".to_string()
-    })?;
-
-    let workspace_root = {
-        let workspaces = state.workspaces.lock().await;
-        let workspace_path = workspaces
-            .get(&workspace_id)
-            .map(|entry| PathBuf::from(&entry.path))
-            .ok_or_else(|| "Workspace not found".to_string())?;
-        workspace_path
-            .canonicalize()
-            .map_err(|err| format!("Failed to resolve workspace root: {err}"))?
-    };
+    let language = LanguageKind::from_path(&file_path)
+        .ok_or_else(|| UNSUPPORTED_LANGUAGE_MESSAGE.to_string())?;
+
+    let workspace_root = resolve_workspace_root(&state, &workspace_id).await?;
 
     let source = read_file_snapshot(&workspace_root, &file_path)?;
-    let symbol = symbol_at_cursor_for_language(&source.content, line, character, language)
-        .ok_or_else(|| "No symbol under cursor".to_string())?;
+    let symbol =
+        symbol_at_cursor_for_language(&source.absolute, &source.content, line, character, language)
+            .ok_or_else(|| "No symbol under cursor".to_string())?;
 
     let mut references = if language == LanguageKind::Yaml {
         let java_files = collect_language_files(&workspace_root, LanguageKind::Java);
@@ -1091,8 +1429,37 @@ pub async fn code_intel_references(
             });
         }
         refs
+    } else if let Some(locations) = lsp_locations(
+        &state,
+        &app,
+        &workspace_id,
+        &workspace_root,
+        &file_path,
+        "textDocument/references",
+        json!({
+            "position": { "line": line, "character": character },
+            "context": { "includeDeclaration": include_declaration.unwrap_or(false) },
+        }),
+    )
+    .await
+    {
+        locations
     } else {
-        let language_files = collect_language_files(&workspace_root, language);
+        let symbol_index =
+            ready_symbol_index(&state, &workspace_id, &workspace_root, &file_path).await;
+        // The identifier postings split on word boundaries, so symbols with
+        // `$` or Ruby's `?`/`!` suffixes still need the full file list.
+        let indexed_files = symbol_index
+            .as_ref()
+            .filter(|_| {
+                symbol
+                    .symbol
+                    .chars()
+                    .all(|ch| ch.is_alphanumeric() || ch == '_')
+            })
+            .and_then(|index| index.files_containing(language, &symbol.symbol).ok());
+        let language_files =
+            indexed_files.unwrap_or_else(|| collect_language_files(&workspace_root, language));
         let mut refs = find_references(
             &workspace_root,
             language,
@@ -1101,18 +1468,10 @@ pub async fn code_intel_references(
             &language_files,
         );
         if !include_declaration.unwrap_or(false) {
-            let mut definitions = if symbol.method_like {
-                find_callable_definitions(&workspace_root, language, &symbol.symbol, &language_files)
-            } else {
-                find_class_like_definitions(&workspace_root, language, &symbol.symbol, &language_files)
+            let definitions = match symbol_index.as_ref() {
+                Some(index) => indexed_definitions(index, language, &symbol),
+                None => scan_definitions(&workspace_root, language, &symbol, &language_files),
             };
-            if definitions.is_empty() {
-                definitions = if symbol.method_like {
-                    find_class_like_definitions(&workspace_root, language, &symbol.symbol, &language_files)
-                } else {
-                    find_callable_definitions(&workspace_root, language, &symbol.symbol, &language_files)
-                };
-            }
             let definition_keys: HashSet<String> = dedupe_locations(definitions)
                 .into_iter()
                 .map(|location| {
@@ -1154,3 +1513,72 @@ pub async fn code_intel_references(
         "result": references,
     }))
 }
+
+#[tauri::command]
+pub async fn code_intel_workspace_symbols(
+    workspace_id: String,
+    query: String,
+    limit: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<Value, String> {
+    let workspace_root = resolve_workspace_root(&state, &workspace_id).await?;
+    let index = symbol_index(&state, &workspace_id, &workspace_root)
+        .await
+        .ok_or_else(|| "Symbol index is unavailable for this workspace".to_string())?;
+    if index.is_ready() {
+        index.schedule_refresh();
+    } else {
+        let building = Arc::clone(&index);
+        tauri::async_runtime::spawn_blocking(move || building.refresh_all())
+            .await
+            .map_err(|err| err.to_string())??;
+    }
+
+    let limit = limit
+        .map(|value| value as usize)
+        .unwrap_or(100)
+        .clamp(1, MAX_CODE_INTEL_RESULTS);
+    let symbols: Vec<Value> = index
+        .workspace_symbols(&query, limit)?
+        .into_iter()
+        .map(|symbol| {
+            json!({
+                "name": symbol.name,
+                "kind": symbol.kind,
+                "language": symbol.language,
+                "location": symbol.location,
+            })
+        })
+        .collect();
+
+    Ok(json!({
+        "query": query,
+        "result": symbols,
+    }))
+}
+
+#[tauri::command]
+pub async fn code_intel_document_symbols(
+    workspace_id: String,
+    file_path: String,
+    state: State<'_, AppState>,
+) -> Result<Value, String> {
+    let language = LanguageKind::from_path(&file_path)
+        .ok_or_else(|| UNSUPPORTED_LANGUAGE_MESSAGE.to_string())?;
+    if language == LanguageKind::Yaml {
+        return Err("Document symbols are not available for YAML files".to_string());
+    }
+
+    let workspace_root = resolve_workspace_root(&state, &workspace_id).await?;
+    let source = read_file_snapshot(&workspace_root, &file_path)?;
+    let symbols = match syntax::parse(&source.absolute, language, &source.content) {
+        Some(tree) => syntax::outline(tree.definitions()),
+        None => index::pattern_outline(&source.content, language),
+    };
+
+    Ok(json!({
+        "filePath": file_path,
+        "language": language.name(),
+        "result": symbols,
+    }))
+}
@@ -0,0 +1,656 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, State};
+use uuid::Uuid;
+
+use super::{
+    collect_language_files, find_line_bounds, find_references, is_identifier_char,
+    read_file_snapshot, ready_symbol_index, resolve_workspace_root, symbol_at_cursor_for_language,
+    CodeIntelPosition, CodeIntelRange, LanguageKind, CODE_INTEL_MAX_FILE_BYTES,
+    UNSUPPORTED_LANGUAGE_MESSAGE,
+};
+use crate::lsp::{uri_to_path, PositionEncoding};
+use crate::state::AppState;
+use crate::workspaces::{content_hash, unified_diff};
+
+/// Undo snapshots kept across all workspaces. They live in memory only, so
+/// an applied edit can no longer be undone after the app restarts.
+const MAX_EDIT_SNAPSHOTS: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CodeIntelTextEdit {
+    range: CodeIntelRange,
+    #[serde(rename = "newText")]
+    new_text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CodeIntelFileEdit {
+    path: String,
+    /// SHA-256 of the file the edits were computed against.
+    #[serde(rename = "originalHash")]
+    original_hash: String,
+    edits: Vec<CodeIntelTextEdit>,
+    #[serde(default)]
+    diff: String,
+}
+
+/// A reviewable set of text edits. Ranges count characters, like every other
+/// code intel position.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CodeIntelWorkspaceEdit {
+    files: Vec<CodeIntelFileEdit>,
+    #[serde(default, rename = "totalEdits")]
+    total_edits: usize,
+    /// "lsp" when a language server computed the edit, "index" for a
+    /// name-based rename over the symbol index.
+    #[serde(default)]
+    source: String,
+    /// Files of the language that were too large to search, so any uses of
+    /// the symbol in them are not part of the edit.
+    #[serde(default, rename = "skippedFiles")]
+    skipped_files: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct SnapshotFile {
+    absolute: PathBuf,
+    path: String,
+    before: String,
+    after_hash: String,
+}
+
+/// File contents from before an applied edit, kept so it can be undone.
+#[derive(Debug, Clone)]
+pub(crate) struct CodeIntelEditSnapshot {
+    id: String,
+    workspace_id: String,
+    files: Vec<SnapshotFile>,
+}
+
+fn line_text(content: &str, line: u32) -> &str {
+    find_line_bounds(content, line as usize)
+        .map(|(start, end)| content[start..end].trim_end_matches('\r'))
+        .unwrap_or("")
+}
+
+fn byte_offset(content: &str, position: &CodeIntelPosition) -> Result<usize, String> {
+    let (start, end) = find_line_bounds(content, position.line as usize)
+        .ok_or_else(|| format!("Line {} is past the end of the file", position.line + 1))?;
+    let line = &content[start..end];
+    Ok(start
+        + line
+            .char_indices()
+            .nth(position.character as usize)
+            .map(|(offset, _)| offset)
+            .unwrap_or(line.len()))
+}
+
+fn apply_text_edits(content: &str, edits: &[CodeIntelTextEdit]) -> Result<String, String> {
+    let mut spans = edits
+        .iter()
+        .map(|edit| {
+            let start = byte_offset(content, &edit.range.start)?;
+            let end = byte_offset(content, &edit.range.end)?;
+            if end < start {
+                return Err("Edit range ends before it starts".to_string());
+            }
+            Ok((start, end, edit.new_text.as_str()))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    spans.sort_by_key(|(start, end, _)| (*start, *end));
+    let mut output = String::with_capacity(content.len());
+    let mut cursor = 0usize;
+    for (start, end, new_text) in spans {
+        if start < cursor {
+            return Err("Edits overlap".to_string());
+        }
+        output.push_str(&content[cursor..start]);
+        output.push_str(new_text);
+        cursor = end;
+    }
+    output.push_str(&content[cursor..]);
+    Ok(output)
+}
+
+/// `(uri, edits)` pairs from an LSP `WorkspaceEdit`, in either its
+/// `documentChanges` or `changes` form.
+fn lsp_document_edits(edit: &Value) -> Result<Vec<(String, Vec<Value>)>, String> {
+    if let Some(changes) = edit.get("documentChanges").and_then(Value::as_array) {
+        return changes
+            .iter()
+            .map(|change| {
+                if change.get("kind").is_some() {
+                    return Err(
+                        "The language server wants to create, rename or delete files, which is not supported"
+                            .to_string(),
+                    );
+                }
+                let uri = change
+                    .get("textDocument")
+                    .and_then(|document| document.get("uri"))
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| "Malformed workspace edit".to_string())?;
+                let edits = change
+                    .get("edits")
+                    .and_then(Value::as_array)
+                    .cloned()
+                    .unwrap_or_default();
+                Ok((uri.to_string(), edits))
+            })
+            .collect();
+    }
+    Ok(edit
+        .get("changes")
+        .and_then(Value::as_object)
+        .map(|changes| {
+            changes
+                .iter()
+                .map(|(uri, edits)| (uri.clone(), edits.as_array().cloned().unwrap_or_default()))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+fn lsp_text_edit(
+    content: &str,
+    edit: &Value,
+    encoding: PositionEncoding,
+) -> Option<CodeIntelTextEdit> {
+    let range = edit.get("range")?;
+    let point = |key: &str| -> Option<CodeIntelPosition> {
+        let position = range.get(key)?;
+        let line = position.get("line")?.as_u64()? as u32;
+        let units = position.get("character")?.as_u64()? as u32;
+        Some(CodeIntelPosition {
+            line,
+            character: encoding.chars_for_units(line_text(content, line), units),
+        })
+    };
+    Some(CodeIntelTextEdit {
+        range: CodeIntelRange {
+            start: point("start")?,
+            end: point("end")?,
+        },
+        new_text: edit.get("newText")?.as_str()?.to_string(),
+    })
+}
+
+fn relative_path(workspace_root: &Path, absolute: &Path) -> Option<String> {
+    absolute
+        .strip_prefix(workspace_root)
+        .ok()
+        .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+}
+
+fn build_file_edit(
+    path: String,
+    content: &str,
+    mut edits: Vec<CodeIntelTextEdit>,
+) -> Result<CodeIntelFileEdit, String> {
+    edits.sort_by_key(|edit| (edit.range.start.line, edit.range.start.character));
+    let after = apply_text_edits(content, &edits)?;
+    Ok(CodeIntelFileEdit {
+        diff: unified_diff(&path, content.as_bytes(), after.as_bytes())?,
+        original_hash: content_hash(content.as_bytes()),
+        path,
+        edits,
+    })
+}
+
+fn workspace_edit_from_lsp(
+    workspace_root: &Path,
+    edit: &Value,
+    encoding: PositionEncoding,
+) -> Result<CodeIntelWorkspaceEdit, String> {
+    let mut by_path: BTreeMap<String, Vec<CodeIntelTextEdit>> = BTreeMap::new();
+    let mut contents: BTreeMap<String, String> = BTreeMap::new();
+    for (uri, edits) in lsp_document_edits(edit)? {
+        let absolute = uri_to_path(&uri)
+            .and_then(|path| path.canonicalize().ok())
+            .ok_or_else(|| format!("Cannot edit {uri}"))?;
+        let path = relative_path(workspace_root, &absolute)
+            .ok_or_else(|| format!("The rename touches {uri}, outside the workspace"))?;
+        if !contents.contains_key(&path) {
+            contents.insert(
+                path.clone(),
+                read_file_snapshot(workspace_root, &path)?.content,
+            );
+        }
+        let content = &contents[&path];
+        let converted = edits
+            .iter()
+            .map(|edit| lsp_text_edit(content, edit, encoding))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| "Malformed workspace edit".to_string())?;
+        by_path.entry(path).or_default().extend(converted);
+    }
+    let files = by_path
+        .into_iter()
+        .filter(|(_, edits)| !edits.is_empty())
+        .map(|(path, edits)| {
+            let content = &contents[&path];
+            build_file_edit(path, content, edits)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(CodeIntelWorkspaceEdit {
+        total_edits: files.iter().map(|file| file.edits.len()).sum(),
+        files,
+        source: "lsp".to_string(),
+        skipped_files: Vec::new(),
+    })
+}
+
+/// Renames every identifier spelled like the symbol under the cursor in
+/// files of the same language. Unlike a language server this cannot tell
+/// unrelated symbols with the same name apart, so the edit needs review.
+async fn index_rename(
+    state: &AppState,
+    workspace_id: &str,
+    workspace_root: &Path,
+    file_path: &str,
+    line: u32,
+    character: u32,
+    new_name: &str,
+) -> Result<CodeIntelWorkspaceEdit, String> {
+    let language = LanguageKind::from_path(file_path)
+        .ok_or_else(|| UNSUPPORTED_LANGUAGE_MESSAGE.to_string())?;
+    if language == LanguageKind::Yaml {
+        return Err("Rename is not available for YAML files".to_string());
+    }
+    if !new_name.chars().all(is_identifier_char) {
+        return Err(format!("{new_name} is not a valid identifier"));
+    }
+    let source = read_file_snapshot(workspace_root, file_path)?;
+    let symbol =
+        symbol_at_cursor_for_language(&source.absolute, &source.content, line, character, language)
+            .ok_or_else(|| "No symbol under cursor".to_string())?;
+    let symbol_index = ready_symbol_index(state, workspace_id, workspace_root, file_path).await;
+    let indexed_files = symbol_index
+        .as_ref()
+        .filter(|_| {
+            symbol
+                .symbol
+                .chars()
+                .all(|ch| ch.is_alphanumeric() || ch == '_')
+        })
+        .and_then(|index| index.files_containing(language, &symbol.symbol).ok());
+    let (language_files, oversized_files) = match (indexed_files, symbol_index.as_ref()) {
+        (Some(files), Some(index)) => (files, index.oversized_files(language).unwrap_or_default()),
+        _ => {
+            let files = collect_language_files(workspace_root, language);
+            let oversized = files
+                .iter()
+                .filter(|path| {
+                    std::fs::metadata(path)
+                        .is_ok_and(|metadata| metadata.len() > CODE_INTEL_MAX_FILE_BYTES)
+                })
+                .cloned()
+                .collect();
+            (files, oversized)
+        }
+    };
+    let locations = find_references(
+        workspace_root,
+        language,
+        &symbol.symbol,
+        false,
+        &language_files,
+    );
+
+    let mut by_path: BTreeMap<String, Vec<CodeIntelTextEdit>> = BTreeMap::new();
+    for location in locations {
+        by_path
+            .entry(location.path)
+            .or_default()
+            .push(CodeIntelTextEdit {
+                range: location.range,
+                new_text: new_name.to_string(),
+            });
+    }
+    let files = by_path
+        .into_iter()
+        .map(|(path, edits)| {
+            let content = read_file_snapshot(workspace_root, &path)?.content;
+            build_file_edit(path, &content, edits)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(CodeIntelWorkspaceEdit {
+        total_edits: files.iter().map(|file| file.edits.len()).sum(),
+        files,
+        source: "index".to_string(),
+        skipped_files: oversized_files
+            .iter()
+            .filter_map(|path| relative_path(workspace_root, path))
+            .collect(),
+    })
+}
+
+/// Replaces file contents with a same-directory temp file and a rename, so a
+/// file is never left half written.
+fn replace_file(path: &Path, content: &[u8]) -> Result<(), String> {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp = path.with_file_name(format!(".{name}.{}.tmp", Uuid::new_v4()));
+    let write = || -> std::io::Result<()> {
+        std::fs::write(&temp, content)?;
+        if let Ok(metadata) = std::fs::metadata(path) {
+            std::fs::set_permissions(&temp, metadata.permissions())?;
+        }
+        std::fs::rename(&temp, path)
+    };
+    write().map_err(|err| {
+        let _ = std::fs::remove_file(&temp);
+        format!("Failed to write {}: {err}", path.display())
+    })
+}
+
+/// Writes every `(path, new, previous)` entry, restoring the ones already
+/// written if a later write fails.
+fn write_all_or_nothing(writes: &[(PathBuf, String, String)]) -> Result<(), String> {
+    for (index, (path, content, _)) in writes.iter().enumerate() {
+        if let Err(err) = replace_file(path, content.as_bytes()) {
+            for (written, _, previous) in &writes[..index] {
+                let _ = replace_file(written, previous.as_bytes());
+            }
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+/// Applies `edit` when every file still matches the hash it was computed
+/// against, and returns what is needed to undo it.
+fn apply_workspace_edit(
+    workspace_root: &Path,
+    edit: &CodeIntelWorkspaceEdit,
+) -> Result<Vec<SnapshotFile>, String> {
+    let mut seen = HashSet::new();
+    let mut snapshot = Vec::new();
+    let mut writes = Vec::new();
+    for file in &edit.files {
+        if !seen.insert(file.path.as_str()) {
+            return Err(format!("{} appears more than once in the edit", file.path));
+        }
+        let source = read_file_snapshot(workspace_root, &file.path)?;
+        if content_hash(source.content.as_bytes()) != file.original_hash {
+            return Err(format!("{} changed since the edit was computed", file.path));
+        }
+        let after = apply_text_edits(&source.content, &file.edits)
+            .map_err(|err| format!("{}: {err}", file.path))?;
+        snapshot.push(SnapshotFile {
+            absolute: source.absolute.clone(),
+            path: file.path.clone(),
+            before: source.content.clone(),
+            after_hash: content_hash(after.as_bytes()),
+        });
+        writes.push((source.absolute, after, source.content));
+    }
+    write_all_or_nothing(&writes)?;
+    Ok(snapshot)
+}
+
+/// Puts back the contents from before an edit, unless a file was changed
+/// again since; undoing then would throw that work away.
+fn restore_snapshot(files: &[SnapshotFile]) -> Result<(), String> {
+    let mut writes = Vec::new();
+    for file in files {
+        let current = std::fs::read_to_string(&file.absolute)
+            .map_err(|err| format!("Failed to read {}: {err}", file.path))?;
+        if content_hash(current.as_bytes()) != file.after_hash {
+            return Err(format!(
+                "{} was modified after the edit was applied",
+                file.path
+            ));
+        }
+        writes.push((file.absolute.clone(), file.before.clone(), current));
+    }
+    write_all_or_nothing(&writes)
+}
+
+/// Computes a rename as a reviewable workspace edit without touching files.
+/// Uses the workspace's language server when one is available and falls back
+/// to a name-based rename over the symbol index when there is none or it
+/// cannot rename.
+#[tauri::command]
+pub(crate) async fn code_intel_rename(
+    workspace_id: String,
+    file_path: String,
+    line: u32,
+    character: u32,
+    new_name: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Value, String> {
+    let new_name = new_name.trim().to_string();
+    if new_name.is_empty() {
+        return Err("New name cannot be empty".to_string());
+    }
+    let workspace_root = resolve_workspace_root(&state, &workspace_id).await?;
+    let lsp_edit = crate::lsp::rename_edit(
+        &state,
+        &app,
+        &workspace_id,
+        &file_path,
+        line,
+        character,
+        &new_name,
+    )
+    .await
+    .unwrap_or_else(|err| {
+        log::debug!("[code-intel] language server rename failed for {file_path}: {err}");
+        None
+    });
+    let edit = match lsp_edit {
+        Some((edit, encoding)) => workspace_edit_from_lsp(&workspace_root, &edit, encoding)?,
+        None => {
+            index_rename(
+                &state,
+                &workspace_id,
+                &workspace_root,
+                &file_path,
+                line,
+                character,
+                &new_name,
+            )
+            .await?
+        }
+    };
+    if edit.files.is_empty() {
+        return Err("Nothing to rename at this position".to_string());
+    }
+    Ok(json!({
+        "filePath": file_path,
+        "line": line,
+        "character": character,
+        "newName": new_name,
+        "result": edit,
+    }))
+}
+
+/// Applies a workspace edit to every file or none, and keeps an undo
+/// snapshot of the previous contents until the app restarts.
+#[tauri::command]
+pub(crate) async fn code_intel_apply_edit(
+    workspace_id: String,
+    edit: CodeIntelWorkspaceEdit,
+    state: State<'_, AppState>,
+) -> Result<Value, String> {
+    let workspace_root = resolve_workspace_root(&state, &workspace_id).await?;
+    let paths = edit.files.iter().map(|file| file.path.clone()).collect();
+    crate::files::history::snapshot_before_edit(&state, &workspace_id, paths).await;
+    let files = tokio::task::spawn_blocking(move || apply_workspace_edit(&workspace_root, &edit))
+        .await
+        .map_err(|err| err.to_string())??;
+    let snapshot = CodeIntelEditSnapshot {
+        id: Uuid::new_v4().to_string(),
+        workspace_id,
+        files,
+    };
+    let result = json!({
+        "snapshotId": snapshot.id,
+        "filesChanged": snapshot.files.iter().map(|file| file.path.clone()).collect::<Vec<_>>(),
+    });
+    let mut snapshots = state.code_intel_edit_snapshots.lock().await;
+    snapshots.push_back(snapshot);
+    while snapshots.len() > MAX_EDIT_SNAPSHOTS {
+        snapshots.pop_front();
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+pub(crate) async fn code_intel_undo_edit(
+    workspace_id: String,
+    snapshot_id: String,
+    state: State<'_, AppState>,
+) -> Result<Value, String> {
+    let snapshot = {
+        let snapshots = state.code_intel_edit_snapshots.lock().await;
+        snapshots
+            .iter()
+            .find(|snapshot| snapshot.id == snapshot_id && snapshot.workspace_id == workspace_id)
+            .cloned()
+            .ok_or_else(|| "Undo snapshot not found".to_string())?
+    };
+    let files = snapshot.files.clone();
+    let paths = files.iter().map(|file| file.path.clone()).collect();
+    crate::files::history::snapshot_before_edit(&state, &workspace_id, paths).await;
+    tokio::task::spawn_blocking(move || restore_snapshot(&files))
+        .await
+        .map_err(|err| err.to_string())??;
+    state
+        .code_intel_edit_snapshots
+        .lock()
+        .await
+        .retain(|entry| entry.id != snapshot.id);
+    Ok(json!({
+        "snapshotId": snapshot.id,
+        "filesChanged": snapshot.files.iter().map(|file| file.path.clone()).collect::<Vec<_>>(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(line: u32, character: u32) -> CodeIntelPosition {
+        CodeIntelPosition { line, character }
+    }
+
+    fn text_edit(line: u32, start: u32, end: u32, new_text: &str) -> CodeIntelTextEdit {
+        CodeIntelTextEdit {
+            range: CodeIntelRange {
+                start: position(line, start),
+                end: position(line, end),
+            },
+            new_text: new_text.to_string(),
+        }
+    }
+
+    fn temp_workspace() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("moss-rename-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        root.canonicalize().unwrap()
+    }
+
+    #[test]
+    fn applies_edits_by_character_position_in_any_order() {
+        let content = "let café = 1;\nprint(café);\n";
+        let edits = vec![text_edit(1, 6, 10, "price"), text_edit(0, 4, 8, "price")];
+        assert_eq!(
+            apply_text_edits(content, &edits).unwrap(),
+            "let price = 1;\nprint(price);\n"
+        );
+        let overlapping = vec![text_edit(0, 4, 8, "a"), text_edit(0, 6, 9, "b")];
+        assert!(apply_text_edits(content, &overlapping).is_err());
+    }
+
+    #[test]
+    fn converts_utf16_lsp_ranges_to_characters() {
+        let root = temp_workspace();
+        std::fs::write(root.join("src/app.ts"), "const s = \"😀\"; foo();\n").unwrap();
+        let uri = url::Url::from_file_path(root.join("src/app.ts"))
+            .unwrap()
+            .to_string();
+        let lsp_edit = json!({
+            "changes": {
+                uri.clone(): [{
+                    "range": {
+                        "start": { "line": 0, "character": 16 },
+                        "end": { "line": 0, "character": 19 },
+                    },
+                    "newText": "bar",
+                }],
+            },
+        });
+
+        let edit = workspace_edit_from_lsp(&root, &lsp_edit, PositionEncoding::Utf16).unwrap();
+        assert_eq!(edit.total_edits, 1);
+        let file = &edit.files[0];
+        assert_eq!(file.path, "src/app.ts");
+        assert_eq!(file.edits[0].range.start.character, 15);
+        assert!(file.diff.contains("+const s = \"😀\"; bar();"));
+
+        let creates = json!({ "documentChanges": [{ "kind": "create", "uri": uri }] });
+        assert!(workspace_edit_from_lsp(&root, &creates, PositionEncoding::Utf16).is_err());
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn applies_all_files_or_none_and_undoes_from_the_snapshot() {
+        let root = temp_workspace();
+        std::fs::write(root.join("src/a.py"), "def old():\n    pass\n").unwrap();
+        std::fs::write(root.join("src/b.py"), "old()\n").unwrap();
+        let file_edit = |path: &str, line: u32, start: u32| {
+            let content = std::fs::read_to_string(root.join(path)).unwrap();
+            build_file_edit(
+                path.to_string(),
+                &content,
+                vec![text_edit(line, start, start + 3, "new")],
+            )
+            .unwrap()
+        };
+        let mut edit = CodeIntelWorkspaceEdit {
+            files: vec![file_edit("src/a.py", 0, 4), file_edit("src/b.py", 0, 0)],
+            total_edits: 2,
+            source: "index".to_string(),
+            skipped_files: Vec::new(),
+        };
+
+        let mut stale = edit.clone();
+        stale.files[1].original_hash = content_hash(b"something else");
+        assert!(apply_workspace_edit(&root, &stale).is_err());
+        assert_eq!(
+            std::fs::read_to_string(root.join("src/a.py")).unwrap(),
+            "def old():\n    pass\n"
+        );
+
+        let snapshot = apply_workspace_edit(&root, &edit).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(root.join("src/a.py")).unwrap(),
+            "def new():\n    pass\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("src/b.py")).unwrap(),
+            "new()\n"
+        );
+
+        restore_snapshot(&snapshot).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(root.join("src/b.py")).unwrap(),
+            "old()\n"
+        );
+
+        edit.files.truncate(1);
+        let snapshot = apply_workspace_edit(&root, &edit).unwrap();
+        std::fs::write(root.join("src/a.py"), "def newer():\n    pass\n").unwrap();
+        assert!(restore_snapshot(&snapshot).is_err());
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,621 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Language, Node, Parser, Point, Query, QueryCursor, Tree};
+
+use super::index::SymbolKind;
+use super::{
+    find_line_bounds, CodeIntelDocumentSymbol, CodeIntelPosition, CodeIntelRange, LanguageKind,
+    SymbolAtCursor,
+};
+
+const JAVA_DEFINITIONS: &str = r#"
+(class_declaration name: (identifier) @name) @definition.class
+(record_declaration name: (identifier) @name) @definition.class
+(interface_declaration name: (identifier) @name) @definition.interface
+(annotation_type_declaration name: (identifier) @name) @definition.interface
+(enum_declaration name: (identifier) @name) @definition.enum
+(method_declaration name: (identifier) @name) @definition.method
+(constructor_declaration name: (identifier) @name) @definition.constructor
+"#;
+
+const PYTHON_DEFINITIONS: &str = r#"
+(class_definition
+  body: (block (function_definition name: (identifier) @name) @definition.method))
+(class_definition
+  body: (block (decorated_definition
+    definition: (function_definition name: (identifier) @name) @definition.method)))
+(class_definition name: (identifier) @name) @definition.class
+(function_definition name: (identifier) @name) @definition.function
+"#;
+
+const JAVASCRIPT_DEFINITIONS: &str = r#"
+(class_declaration name: (_) @name) @definition.class
+(function_declaration name: (identifier) @name) @definition.function
+(generator_function_declaration name: (identifier) @name) @definition.function
+(method_definition name: (_) @name) @definition.method
+(variable_declarator
+  name: (identifier) @name
+  value: [(arrow_function) (function_expression)]) @definition.function
+"#;
+
+const TYPESCRIPT_DEFINITIONS: &str = r#"
+(abstract_class_declaration name: (_) @name) @definition.class
+(interface_declaration name: (_) @name) @definition.interface
+(type_alias_declaration name: (_) @name) @definition.type
+(enum_declaration name: (_) @name) @definition.enum
+(internal_module name: (_) @name) @definition.module
+(function_signature name: (identifier) @name) @definition.function
+(method_signature name: (_) @name) @definition.method
+(abstract_method_signature name: (_) @name) @definition.method
+"#;
+
+const GO_DEFINITIONS: &str = r#"
+(type_spec name: (type_identifier) @name type: (struct_type)) @definition.struct
+(type_spec name: (type_identifier) @name type: (interface_type)) @definition.interface
+(type_spec name: (type_identifier) @name) @definition.type
+(function_declaration name: (identifier) @name) @definition.function
+(method_declaration name: (field_identifier) @name) @definition.method
+"#;
+
+const RUST_DEFINITIONS: &str = r#"
+(struct_item name: (type_identifier) @name) @definition.struct
+(union_item name: (type_identifier) @name) @definition.struct
+(enum_item name: (type_identifier) @name) @definition.enum
+(type_item name: (type_identifier) @name) @definition.type
+(trait_item name: (type_identifier) @name) @definition.trait
+(mod_item name: (identifier) @name) @definition.module
+(impl_item type: (_) @name) @definition.impl
+(macro_definition name: (identifier) @name) @definition.function
+(declaration_list (function_item name: (identifier) @name) @definition.method)
+(declaration_list (function_signature_item name: (identifier) @name) @definition.method)
+(function_item name: (identifier) @name) @definition.function
+"#;
+
+const CSHARP_DEFINITIONS: &str = r#"
+(class_declaration name: (identifier) @name) @definition.class
+(record_declaration name: (identifier) @name) @definition.class
+(struct_declaration name: (identifier) @name) @definition.struct
+(interface_declaration name: (identifier) @name) @definition.interface
+(enum_declaration name: (identifier) @name) @definition.enum
+(namespace_declaration name: (_) @name) @definition.module
+(file_scoped_namespace_declaration name: (_) @name) @definition.module
+(method_declaration name: (identifier) @name) @definition.method
+(constructor_declaration name: (identifier) @name) @definition.constructor
+"#;
+
+const C_DEFINITIONS: &str = r#"
+(struct_specifier name: (type_identifier) @name body: (_)) @definition.struct
+(union_specifier name: (type_identifier) @name body: (_)) @definition.struct
+(enum_specifier name: (type_identifier) @name body: (_)) @definition.enum
+(type_definition declarator: (type_identifier) @name) @definition.type
+(function_definition
+  declarator: (function_declarator declarator: (identifier) @name)) @definition.function
+(function_definition
+  declarator: (pointer_declarator
+    declarator: (function_declarator declarator: (identifier) @name))) @definition.function
+"#;
+
+const CPP_DEFINITIONS: &str = r#"
+(class_specifier name: (type_identifier) @name body: (_)) @definition.class
+(namespace_definition name: (_) @name) @definition.module
+(alias_declaration name: (type_identifier) @name) @definition.type
+(function_definition
+  declarator: (function_declarator declarator: (field_identifier) @name)) @definition.method
+(function_definition
+  declarator: (function_declarator
+    declarator: (qualified_identifier name: (_) @name))) @definition.method
+"#;
+
+const RUBY_DEFINITIONS: &str = r#"
+(class name: (constant) @name) @definition.class
+(class name: (scope_resolution name: (_) @name)) @definition.class
+(module name: (constant) @name) @definition.module
+(module name: (scope_resolution name: (_) @name)) @definition.module
+(method name: (_) @name) @definition.method
+(singleton_method name: (_) @name) @definition.method
+"#;
+
+/// Tree-sitter grammars behind the code intel languages. TS/JS and C/C++
+/// pick a grammar per file extension.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+enum Grammar {
+    Java,
+    Python,
+    JavaScript,
+    TypeScript,
+    Tsx,
+    Go,
+    Rust,
+    CSharp,
+    C,
+    Cpp,
+    Ruby,
+}
+
+impl Grammar {
+    const ALL: [Grammar; 11] = [
+        Self::Java,
+        Self::Python,
+        Self::JavaScript,
+        Self::TypeScript,
+        Self::Tsx,
+        Self::Go,
+        Self::Rust,
+        Self::CSharp,
+        Self::C,
+        Self::Cpp,
+        Self::Ruby,
+    ];
+
+    fn for_path(path: &Path, language: LanguageKind) -> Option<Self> {
+        let ext = path
+            .extension()
+            .and_then(OsStr::to_str)
+            .map(|value| value.to_ascii_lowercase())
+            .unwrap_or_default();
+        match language {
+            LanguageKind::Java => Some(Self::Java),
+            LanguageKind::Python => Some(Self::Python),
+            LanguageKind::TsJs => match ext.as_str() {
+                "ts" | "mts" | "cts" => Some(Self::TypeScript),
+                "tsx" => Some(Self::Tsx),
+                _ => Some(Self::JavaScript),
+            },
+            LanguageKind::Go => Some(Self::Go),
+            LanguageKind::Rust => Some(Self::Rust),
+            LanguageKind::CSharp => Some(Self::CSharp),
+            // Headers are ambiguous; the C++ grammar parses plain C headers too.
+            LanguageKind::CFamily if ext == "c" => Some(Self::C),
+            LanguageKind::CFamily => Some(Self::Cpp),
+            LanguageKind::Ruby => Some(Self::Ruby),
+            // No Kotlin grammar is vendored; Kotlin uses the declaration patterns.
+            LanguageKind::Kotlin | LanguageKind::Yaml => None,
+        }
+    }
+
+    fn language(self) -> Language {
+        match self {
+            Self::Java => tree_sitter_java::LANGUAGE.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::CSharp => tree_sitter_c_sharp::LANGUAGE.into(),
+            Self::C => tree_sitter_c::LANGUAGE.into(),
+            Self::Cpp => tree_sitter_cpp::LANGUAGE.into(),
+            Self::Ruby => tree_sitter_ruby::LANGUAGE.into(),
+        }
+    }
+
+    fn definitions_query(self) -> String {
+        match self {
+            Self::Java => JAVA_DEFINITIONS.to_string(),
+            Self::Python => PYTHON_DEFINITIONS.to_string(),
+            Self::JavaScript => JAVASCRIPT_DEFINITIONS.to_string(),
+            Self::TypeScript | Self::Tsx => {
+                format!("{JAVASCRIPT_DEFINITIONS}{TYPESCRIPT_DEFINITIONS}")
+            }
+            Self::Go => GO_DEFINITIONS.to_string(),
+            Self::Rust => RUST_DEFINITIONS.to_string(),
+            Self::CSharp => CSHARP_DEFINITIONS.to_string(),
+            Self::C => C_DEFINITIONS.to_string(),
+            Self::Cpp => format!("{C_DEFINITIONS}{CPP_DEFINITIONS}"),
+            Self::Ruby => RUBY_DEFINITIONS.to_string(),
+        }
+    }
+}
+
+struct CompiledGrammar {
+    language: Language,
+    definitions: Query,
+    name_capture: u32,
+}
+
+static GRAMMARS: LazyLock<HashMap<Grammar, CompiledGrammar>> = LazyLock::new(|| {
+    let mut grammars = HashMap::new();
+    for grammar in Grammar::ALL {
+        let language = grammar.language();
+        let definitions = match Query::new(&language, &grammar.definitions_query()) {
+            Ok(query) => query,
+            Err(err) => {
+                log::warn!("[code_intel] invalid {grammar:?} definitions query: {err}");
+                continue;
+            }
+        };
+        let Some(name_capture) = definitions.capture_index_for_name("name") else {
+            continue;
+        };
+        grammars.insert(
+            grammar,
+            CompiledGrammar {
+                language,
+                definitions,
+                name_capture,
+            },
+        );
+    }
+    grammars
+});
+
+/// A declaration found by the definitions query.
+#[derive(Debug, Clone)]
+pub(super) struct SyntaxSymbol {
+    pub(super) name: String,
+    /// Outline kind such as `class`, `method` or `module`.
+    pub(super) kind: &'static str,
+    pub(super) range: CodeIntelRange,
+    pub(super) selection_range: CodeIntelRange,
+    pub(super) name_offset: usize,
+    byte_range: Range<usize>,
+}
+
+impl SyntaxSymbol {
+    /// The lookup bucket this symbol answers, or `None` for outline-only
+    /// entries like Rust `impl` blocks.
+    pub(super) fn lookup_kind(&self) -> Option<SymbolKind> {
+        match self.kind {
+            "impl" => None,
+            "function" | "method" | "constructor" => Some(SymbolKind::Function),
+            _ => Some(SymbolKind::Type),
+        }
+    }
+}
+
+pub(super) struct SyntaxTree<'a> {
+    grammar: &'static CompiledGrammar,
+    tree: Tree,
+    content: &'a str,
+}
+
+pub(super) fn parse<'a>(
+    path: &Path,
+    language: LanguageKind,
+    content: &'a str,
+) -> Option<SyntaxTree<'a>> {
+    let grammar = GRAMMARS.get(&Grammar::for_path(path, language)?)?;
+    let mut parser = Parser::new();
+    parser.set_language(&grammar.language).ok()?;
+    let tree = parser.parse(content, None)?;
+    Some(SyntaxTree {
+        grammar,
+        tree,
+        content,
+    })
+}
+
+/// Leaf nodes that name something. Keywords, literals and comments never
+/// qualify, which is what keeps lookups out of strings and comments.
+fn is_identifier_node(node: &Node) -> bool {
+    node.is_named()
+        && node.child_count() == 0
+        && (node.kind().ends_with("identifier") || node.kind() == "constant")
+}
+
+fn position_at(content: &str, byte: usize, point: Point) -> CodeIntelPosition {
+    let line_start = byte.saturating_sub(point.column);
+    CodeIntelPosition {
+        line: point.row as u32,
+        character: content[line_start..byte].chars().count() as u32,
+    }
+}
+
+fn node_range(content: &str, node: &Node) -> CodeIntelRange {
+    CodeIntelRange {
+        start: position_at(content, node.start_byte(), node.start_position()),
+        end: position_at(content, node.end_byte(), node.end_position()),
+    }
+}
+
+impl SyntaxTree<'_> {
+    /// Declarations in document order. When several patterns match the same
+    /// name (a Rust method is also a `function_item`), the first pattern wins.
+    pub(super) fn definitions(&self) -> Vec<SyntaxSymbol> {
+        let query = &self.grammar.definitions;
+        let capture_names = query.capture_names();
+        let mut best: HashMap<usize, (usize, SyntaxSymbol)> = HashMap::new();
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(query, self.tree.root_node(), self.content.as_bytes());
+        while let Some(found) = matches.next() {
+            let mut name_node = None;
+            let mut definition = None;
+            for capture in found.captures {
+                if capture.index == self.grammar.name_capture {
+                    name_node = Some(capture.node);
+                } else if let Some(kind) =
+                    capture_names[capture.index as usize].strip_prefix("definition.")
+                {
+                    definition = Some((kind, capture.node));
+                }
+            }
+            let (Some(name_node), Some((kind, node))) = (name_node, definition) else {
+                continue;
+            };
+            let Ok(name) = name_node.utf8_text(self.content.as_bytes()) else {
+                continue;
+            };
+            let offset = name_node.start_byte();
+            if best
+                .get(&offset)
+                .is_some_and(|(pattern, _)| *pattern <= found.pattern_index)
+            {
+                continue;
+            }
+            best.insert(
+                offset,
+                (
+                    found.pattern_index,
+                    SyntaxSymbol {
+                        name: name.to_string(),
+                        kind: outline_kind(kind),
+                        range: node_range(self.content, &node),
+                        selection_range: node_range(self.content, &name_node),
+                        name_offset: offset,
+                        byte_range: node.byte_range(),
+                    },
+                ),
+            );
+        }
+        let mut symbols: Vec<SyntaxSymbol> = best.into_values().map(|(_, symbol)| symbol).collect();
+        symbols.sort_by_key(|symbol| symbol.name_offset);
+        symbols
+    }
+
+    /// The identifier under the cursor, or `None` when the cursor sits on a
+    /// keyword, literal, comment or whitespace. `character` counts chars like
+    /// the rest of code intel.
+    pub(super) fn identifier_at(&self, line: u32, character: u32) -> Option<SymbolAtCursor> {
+        let (line_start, line_end) = find_line_bounds(self.content, line as usize)?;
+        let line_text = &self.content[line_start..line_end];
+        let column = line_text
+            .char_indices()
+            .nth(character as usize)
+            .map(|(idx, _)| idx)
+            .unwrap_or(line_text.len());
+        let root = self.tree.root_node();
+        let node_at = |column: usize| {
+            let point = Point::new(line as usize, column);
+            root.named_descendant_for_point_range(point, point)
+                .filter(is_identifier_node)
+        };
+        // A cursor right after the last character still counts, matching
+        // the plain-text fallback.
+        let node = node_at(column).or_else(|| {
+            let previous = line_text[..column].chars().next_back()?;
+            node_at(column - previous.len_utf8())
+        })?;
+        let symbol = node.utf8_text(self.content.as_bytes()).ok()?.to_string();
+
+        let declared = self
+            .definitions()
+            .into_iter()
+            .find(|definition| definition.name_offset == node.start_byte())
+            .and_then(|definition| definition.lookup_kind());
+        let method_like = match declared {
+            Some(kind) => kind == SymbolKind::Function,
+            None => {
+                let suffix = &self.content[node.end_byte()..];
+                let prefix = &self.content[..node.start_byte()];
+                suffix.trim_start().starts_with('(') || prefix.trim_end().ends_with('.')
+            }
+        };
+        Some(SymbolAtCursor {
+            symbol,
+            method_like,
+        })
+    }
+
+    /// Byte offsets of identifier nodes spelled exactly `name`.
+    pub(super) fn identifier_offsets(&self, name: &str) -> Vec<usize> {
+        let bytes = self.content.as_bytes();
+        let mut offsets = Vec::new();
+        let mut cursor = self.tree.walk();
+        'walk: loop {
+            let node = cursor.node();
+            // Only descend into nodes whose text could contain the name.
+            if node.byte_range().len() >= name.len() {
+                if is_identifier_node(&node) {
+                    if node.utf8_text(bytes).is_ok_and(|text| text == name) {
+                        offsets.push(node.start_byte());
+                    }
+                } else if cursor.goto_first_child() {
+                    continue;
+                }
+            }
+            while !cursor.goto_next_sibling() {
+                if !cursor.goto_parent() {
+                    break 'walk;
+                }
+            }
+        }
+        offsets
+    }
+}
+
+fn outline_kind(kind: &str) -> &'static str {
+    match kind {
+        "class" => "class",
+        "interface" => "interface",
+        "struct" => "struct",
+        "enum" => "enum",
+        "trait" => "trait",
+        "module" => "module",
+        "type" => "type",
+        "impl" => "impl",
+        "method" => "method",
+        "constructor" => "constructor",
+        _ => "function",
+    }
+}
+
+/// Nests declarations by containment to build a document outline.
+pub(super) fn outline(symbols: Vec<SyntaxSymbol>) -> Vec<CodeIntelDocumentSymbol> {
+    let mut symbols = symbols;
+    symbols.sort_by(|a, b| {
+        a.byte_range
+            .start
+            .cmp(&b.byte_range.start)
+            .then(b.byte_range.end.cmp(&a.byte_range.end))
+    });
+
+    let mut roots: Vec<CodeIntelDocumentSymbol> = Vec::new();
+    // Open containers as (end byte, symbol); each closes into its parent.
+    let mut stack: Vec<(usize, CodeIntelDocumentSymbol)> = Vec::new();
+    let close = |stack: &mut Vec<(usize, CodeIntelDocumentSymbol)>,
+                 roots: &mut Vec<CodeIntelDocumentSymbol>| {
+        if let Some((_, done)) = stack.pop() {
+            match stack.last_mut() {
+                Some((_, parent)) => parent.children.push(done),
+                None => roots.push(done),
+            }
+        }
+    };
+    for symbol in symbols {
+        while stack
+            .last()
+            .is_some_and(|(end, _)| symbol.byte_range.start >= *end)
+        {
+            close(&mut stack, &mut roots);
+        }
+        stack.push((
+            symbol.byte_range.end,
+            CodeIntelDocumentSymbol {
+                name: symbol.name,
+                kind: symbol.kind,
+                range: symbol.range,
+                selection_range: symbol.selection_range,
+                children: Vec::new(),
+            },
+        ));
+    }
+    while !stack.is_empty() {
+        close(&mut stack, &mut roots);
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names_and_kinds(
+        path: &str,
+        language: LanguageKind,
+        content: &str,
+    ) -> Vec<(String, &'static str)> {
+        parse(Path::new(path), language, content)
+            .expect("grammar available")
+            .definitions()
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.kind))
+            .collect()
+    }
+
+    #[test]
+    fn every_grammar_compiles_its_definitions_query() {
+        for grammar in Grammar::ALL {
+            assert!(GRAMMARS.contains_key(&grammar), "{grammar:?} query failed");
+        }
+    }
+
+    #[test]
+    fn definitions_cover_new_languages() {
+        assert_eq!(
+            names_and_kinds(
+                "src/lib.rs",
+                LanguageKind::Rust,
+                "struct Order;\nimpl Order {\n    fn total(&self) -> u32 { helper() }\n}\nfn helper() -> u32 { 1 }\n",
+            ),
+            vec![
+                ("Order".to_string(), "struct"),
+                ("Order".to_string(), "impl"),
+                ("total".to_string(), "method"),
+                ("helper".to_string(), "function"),
+            ]
+        );
+        assert_eq!(
+            names_and_kinds(
+                "Orders.cs",
+                LanguageKind::CSharp,
+                "namespace Shop {\n  class Orders {\n    public Orders() {}\n    void Place() {}\n  }\n}\n",
+            ),
+            vec![
+                ("Shop".to_string(), "module"),
+                ("Orders".to_string(), "class"),
+                ("Orders".to_string(), "constructor"),
+                ("Place".to_string(), "method"),
+            ]
+        );
+        assert_eq!(
+            names_and_kinds(
+                "orders.cpp",
+                LanguageKind::CFamily,
+                "class Order {\n  int total() { return 1; }\n};\nint Order::count() { return 0; }\nstatic int *lookup(int id) { return 0; }\n",
+            ),
+            vec![
+                ("Order".to_string(), "class"),
+                ("total".to_string(), "method"),
+                ("count".to_string(), "method"),
+                ("lookup".to_string(), "function"),
+            ]
+        );
+        assert_eq!(
+            names_and_kinds(
+                "order.rb",
+                LanguageKind::Ruby,
+                "module Shop\n  class Order\n    def self.build; end\n    def total?\n    end\n  end\nend\n",
+            ),
+            vec![
+                ("Shop".to_string(), "module"),
+                ("Order".to_string(), "class"),
+                ("build".to_string(), "method"),
+                ("total?".to_string(), "method"),
+            ]
+        );
+    }
+
+    #[test]
+    fn identifier_lookups_skip_comments_and_strings() {
+        let content = "// placeOrder is documented here\nfunction placeOrder(id) {\n  log(\"placeOrder\");\n  return placeOrder(id - 1);\n}\n";
+        let tree = parse(Path::new("orders.js"), LanguageKind::TsJs, content).unwrap();
+
+        assert!(tree.identifier_at(0, 5).is_none());
+        let symbol = tree.identifier_at(1, 12).unwrap();
+        assert_eq!(symbol.symbol, "placeOrder");
+        assert!(symbol.method_like);
+        // Cursor just past the end of the identifier.
+        assert_eq!(tree.identifier_at(3, 19).unwrap().symbol, "placeOrder");
+
+        let lines: Vec<usize> = tree
+            .identifier_offsets("placeOrder")
+            .into_iter()
+            .map(|offset| content[..offset].matches('\n').count())
+            .collect();
+        assert_eq!(lines, vec![1, 3]);
+    }
+
+    #[test]
+    fn outline_nests_members_under_their_containers() {
+        let content = "package app;\n\npublic class OrderService {\n    public void place() {}\n    enum State { OPEN }\n}\n\ninterface Repo {}\n";
+        let tree = parse(Path::new("OrderService.java"), LanguageKind::Java, content).unwrap();
+        let outline = outline(tree.definitions());
+
+        let top: Vec<&str> = outline.iter().map(|symbol| symbol.name.as_str()).collect();
+        assert_eq!(top, vec!["OrderService", "Repo"]);
+        let members: Vec<(&str, &str)> = outline[0]
+            .children
+            .iter()
+            .map(|symbol| (symbol.name.as_str(), symbol.kind))
+            .collect();
+        assert_eq!(members, vec![("place", "method"), ("State", "enum")]);
+        assert_eq!(outline[0].range.start.line, 2);
+        assert_eq!(outline[0].range.end.line, 5);
+        assert_eq!(outline[0].selection_range.start.character, 13);
+    }
+}
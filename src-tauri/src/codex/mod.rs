@@ -523,6 +523,7 @@ pub(crate) async fn list_mcp_server_status(
         .await;
     }
 
+    ensure_codex_session(&workspace_id, &state, &app).await?;
     codex_core::list_mcp_server_status_core(&state.sessions, workspace_id, cursor, limit).await
 }
 
@@ -543,6 +544,7 @@ pub(crate) async fn archive_thread(
         .await;
     }
 
+    ensure_codex_session(&workspace_id, &state, &app).await?;
     codex_core::archive_thread_core(&state.sessions, workspace_id, thread_id).await
 }
 
@@ -554,6 +556,7 @@ pub(crate) async fn ensure_codex_session(
     state: &AppState,
     app: &AppHandle,
 ) -> Result<(), String> {
+    crate::session_idle::record_session_activity(workspace_id, None);
     // Check if session already exists
     {
         let sessions = state.sessions.lock().await;
@@ -826,6 +829,7 @@ pub(crate) async fn start_review(
         .await;
     }
 
+    ensure_codex_session(&workspace_id, &state, &app).await?;
     codex_core::start_review_core(&state.sessions, workspace_id, thread_id, target, delivery).await
 }
 
@@ -1095,39 +1099,42 @@ Only output the commit message, nothing else.\n\n\
 Changes:\n{diff}"
     );
 
-    // Get the session – requires a running Codex CLI process
-    let session = {
-        let sessions = state.sessions.lock().await;
-        match sessions.get(&workspace_id) {
-            Some(s) => s.clone(),
-            None => {
-                // Check whether the workspace is using Claude engine (no session needed)
-                let is_claude = {
-                    let workspaces = state.workspaces.lock().await;
-                    workspaces
-                        .get(&workspace_id)
-                        .map(|e| {
-                            e.settings
-                                .engine_type
-                                .as_deref()
-                                .map(|t| t.eq_ignore_ascii_case("claude"))
-                                .unwrap_or(true)
-                        })
-                        .unwrap_or(false)
-                };
-                if is_claude {
-                    return Err("AI commit message generation requires the Codex CLI. \
-                         Please install it first: npm install -g @openai/codex"
-                        .to_string());
-                }
-                return Err(
-                    "Workspace not connected. Please ensure the Codex CLI is installed \
-                     and reconnect the workspace."
-                        .to_string(),
-                );
-            }
+    // Get the session – requires a Codex CLI process, connected on demand like
+    // the turn commands since sessions start lazily and idle ones are reaped.
+    let connected = state.sessions.lock().await.contains_key(&workspace_id);
+    if !connected {
+        // Check whether the workspace is using Claude engine (no session needed)
+        let is_claude = {
+            let workspaces = state.workspaces.lock().await;
+            workspaces
+                .get(&workspace_id)
+                .map(|e| {
+                    e.settings
+                        .engine_type
+                        .as_deref()
+                        .map(|t| t.eq_ignore_ascii_case("claude"))
+                        .unwrap_or(true)
+                })
+                .unwrap_or(false)
+        };
+        if is_claude {
+            return Err("AI commit message generation requires the Codex CLI. \
+                 Please install it first: npm install -g @openai/codex"
+                .to_string());
         }
-    };
+    }
+    ensure_codex_session(&workspace_id, &state, &app).await?;
+    let session = state
+        .sessions
+        .lock()
+        .await
+        .get(&workspace_id)
+        .cloned()
+        .ok_or_else(|| {
+            "Workspace not connected. Please ensure the Codex CLI is installed \
+             and reconnect the workspace."
+                .to_string()
+        })?;
 
     // Create a background thread
     let thread_params = json!({
@@ -1636,6 +1643,7 @@ pub(crate) async fn generate_run_metadata(
         return Err("Prompt is required.".to_string());
     }
 
+    ensure_codex_session(&workspace_id, &state, &app).await?;
     let session = {
         let sessions = state.sessions.lock().await;
         sessions
@@ -17,6 +17,8 @@ use tokio::sync::{broadcast, Mutex, Notify, RwLock};
 
 use super::events::EngineEvent;
 use super::{EngineConfig, EngineType, SendMessageParams};
+use crate::backend::sandbox::build_sandboxed_command_for_binary;
+use crate::types::WorkspaceSandbox;
 
 #[derive(Debug, Clone)]
 pub struct ClaudeTurnEvent {
@@ -60,6 +62,8 @@ pub struct ClaudeSession {
     user_input_notify: Arc<Notify>,
     /// Stores user's formatted AskUserQuestion answer for the kill+resume mechanism
     user_input_answer: StdMutex<Option<String>>,
+    /// Sandbox from the workspace settings, refreshed before each turn
+    sandbox: StdMutex<Option<WorkspaceSandbox>>,
 }
 
 impl ClaudeSession {
@@ -90,6 +94,14 @@ impl ClaudeSession {
             pending_user_inputs: StdMutex::new(HashMap::new()),
             user_input_notify: Arc::new(Notify::new()),
             user_input_answer: StdMutex::new(None),
+            sandbox: StdMutex::new(None),
+        }
+    }
+
+    /// Use the workspace's sandbox settings for subsequently spawned turns
+    pub fn set_sandbox(&self, sandbox: Option<WorkspaceSandbox>) {
+        if let Ok(mut current) = self.sandbox.lock() {
+            *current = sandbox;
         }
     }
 
@@ -142,8 +154,15 @@ impl ClaudeSession {
                 .unwrap_or_else(|| "claude".to_string())
         };
 
-        // Use build_command_for_binary to properly handle .cmd/.bat files on Windows
-        let mut cmd = crate::backend::app_server::build_command_for_binary(&bin);
+        // Goes through build_command_for_binary to properly handle .cmd/.bat files on Windows
+        let sandbox = self.sandbox.lock().ok().and_then(|sandbox| sandbox.clone());
+        let extra_writable: Vec<PathBuf> = self.home_dir.iter().map(PathBuf::from).collect();
+        let mut cmd = build_sandboxed_command_for_binary(
+            &bin,
+            sandbox.as_ref(),
+            &self.workspace_path,
+            &extra_writable,
+        );
 
         // Set working directory
         cmd.current_dir(&self.workspace_path);
@@ -1835,6 +1854,38 @@ mod tests {
         }));
     }
 
+    #[test]
+    fn build_command_runs_through_workspace_sandbox() {
+        let session = ClaudeSession::new(
+            "test-workspace".to_string(),
+            PathBuf::from("/tmp/test"),
+            Some(EngineConfig {
+                bin_path: Some("/opt/claude/bin/claude".to_string()),
+                ..EngineConfig::default()
+            }),
+        );
+        let mut params = SendMessageParams::default();
+        params.text = "hello".to_string();
+        session.set_sandbox(Some(WorkspaceSandbox {
+            enabled: true,
+            runner: "docker".to_string(),
+            image: Some("agents:latest".to_string()),
+            ..WorkspaceSandbox::default()
+        }));
+
+        let command = session.build_command(&params, false);
+        let args: Vec<String> = command
+            .as_std()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect();
+
+        assert_eq!(command.as_std().get_program(), "docker");
+        let image = args.iter().position(|arg| arg == "agents:latest").unwrap();
+        assert_eq!(args[image + 1], "claude");
+        assert_eq!(args[image + 2], "-p");
+    }
+
     #[tokio::test]
     async fn session_manager_get_or_create() {
         let manager = ClaudeSessionManager::new();
@@ -2594,17 +2594,23 @@ pub async fn engine_send_message(
     match effective_engine {
         EngineType::Claude => {
             // Get workspace path
-            let workspace_path = {
+            let (workspace_path, sandbox) = {
                 let workspaces = state.workspaces.lock().await;
                 workspaces
                     .get(&workspace_id)
-                    .map(|w| std::path::PathBuf::from(&w.path))
+                    .map(|w| {
+                        (
+                            std::path::PathBuf::from(&w.path),
+                            w.settings.sandbox.clone(),
+                        )
+                    })
                     .ok_or_else(|| "Workspace not found".to_string())?
             };
 
             let session = manager
                 .get_claude_session(&workspace_id, &workspace_path)
                 .await;
+            session.set_sandbox(sandbox);
 
             // Use explicit session_id from frontend (for Claude history resume),
             // or fall back to the session's tracked session_id ONLY when continuing
@@ -2793,17 +2799,23 @@ pub async fn engine_send_message(
             }))
         }
         EngineType::OpenCode => {
-            let workspace_path = {
+            let (workspace_path, sandbox) = {
                 let workspaces = state.workspaces.lock().await;
                 workspaces
                     .get(&workspace_id)
-                    .map(|w| std::path::PathBuf::from(&w.path))
+                    .map(|w| {
+                        (
+                            std::path::PathBuf::from(&w.path),
+                            w.settings.sandbox.clone(),
+                        )
+                    })
                     .ok_or_else(|| "Workspace not found".to_string())?
             };
 
             let session = manager
                 .get_or_create_opencode_session(&workspace_id, &workspace_path)
                 .await;
+            session.set_sandbox(sandbox);
 
             let resolved_session_id = if session_id.is_some() {
                 session_id
@@ -2956,16 +2968,22 @@ pub async fn engine_send_message_sync(
 
     match effective_engine {
         EngineType::Claude => {
-            let workspace_path = {
+            let (workspace_path, sandbox) = {
                 let workspaces = state.workspaces.lock().await;
                 workspaces
                     .get(&workspace_id)
-                    .map(|w| std::path::PathBuf::from(&w.path))
+                    .map(|w| {
+                        (
+                            std::path::PathBuf::from(&w.path),
+                            w.settings.sandbox.clone(),
+                        )
+                    })
                     .ok_or_else(|| "Workspace not found".to_string())?
             };
             let session = manager
                 .get_claude_session(&workspace_id, &workspace_path)
                 .await;
+            session.set_sandbox(sandbox);
 
             let resolved_session_id = if session_id.is_some() {
                 session_id
@@ -3015,17 +3033,23 @@ pub async fn engine_send_message_sync(
             }))
         }
         EngineType::OpenCode => {
-            let workspace_path = {
+            let (workspace_path, sandbox) = {
                 let workspaces = state.workspaces.lock().await;
                 workspaces
                     .get(&workspace_id)
-                    .map(|w| std::path::PathBuf::from(&w.path))
+                    .map(|w| {
+                        (
+                            std::path::PathBuf::from(&w.path),
+                            w.settings.sandbox.clone(),
+                        )
+                    })
                     .ok_or_else(|| "Workspace not found".to_string())?
             };
 
             let session = manager
                 .get_or_create_opencode_session(&workspace_id, &workspace_path)
                 .await;
+            session.set_sandbox(sandbox);
             let resolved_session_id = if session_id.is_some() {
                 session_id
             } else if continue_session {
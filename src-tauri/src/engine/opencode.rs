@@ -8,6 +8,7 @@ use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
 use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::net::TcpStream;
@@ -17,6 +18,8 @@ use tokio::time::{sleep, timeout, Instant};
 
 use super::events::EngineEvent;
 use super::{EngineConfig, EngineType, SendMessageParams};
+use crate::backend::sandbox::build_sandboxed_command_for_binary;
+use crate::types::WorkspaceSandbox;
 
 const OPENCODE_OPENAI_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
 const OPENCODE_POST_RESPONSE_IDLE_TIMEOUT: Duration = Duration::from_secs(15);
@@ -44,6 +47,7 @@ pub struct OpenCodeSession {
     active_processes: Mutex<HashMap<String, Child>>,
     session_model_hints: Mutex<HashMap<String, String>>,
     interrupted: AtomicBool,
+    sandbox: StdMutex<Option<WorkspaceSandbox>>,
 }
 
 impl OpenCodeSession {
@@ -134,6 +138,14 @@ impl OpenCodeSession {
             active_processes: Mutex::new(HashMap::new()),
             session_model_hints: Mutex::new(HashMap::new()),
             interrupted: AtomicBool::new(false),
+            sandbox: StdMutex::new(None),
+        }
+    }
+
+    /// Use the workspace's sandbox settings for subsequently spawned turns
+    pub fn set_sandbox(&self, sandbox: Option<WorkspaceSandbox>) {
+        if let Ok(mut current) = self.sandbox.lock() {
+            *current = sandbox;
         }
     }
 
@@ -189,7 +201,14 @@ impl OpenCodeSession {
                 .unwrap_or_else(|| "opencode".to_string())
         };
 
-        let mut cmd = crate::backend::app_server::build_command_for_binary(&bin);
+        let sandbox = self.sandbox.lock().ok().and_then(|sandbox| sandbox.clone());
+        let extra_writable: Vec<PathBuf> = self.home_dir.iter().map(PathBuf::from).collect();
+        let mut cmd = build_sandboxed_command_for_binary(
+            &bin,
+            sandbox.as_ref(),
+            &self.workspace_path,
+            &extra_writable,
+        );
         cmd.current_dir(&self.workspace_path);
         cmd.arg("run");
         cmd.arg("--format");
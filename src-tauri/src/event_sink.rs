@@ -15,6 +15,10 @@ impl TauriEventSink {
 
 impl EventSink for TauriEventSink {
     fn emit_app_server_event(&self, event: AppServerEvent) {
+        crate::session_idle::record_session_activity(
+            &event.workspace_id,
+            event.message.get("method").and_then(|value| value.as_str()),
+        );
         let _ = self.app.emit("app-server-event", event);
     }
 
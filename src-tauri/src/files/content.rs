@@ -0,0 +1,700 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use serde::{Deserialize, Serialize};
+
+/// Window returned when no length is given, and the most one read returns.
+const MAX_READ_BYTES: u64 = 400_000;
+const MAX_RANGE_BYTES: u64 = 4 * 1024 * 1024;
+const DEFAULT_LINE_COUNT: usize = 1000;
+const SNIFF_BYTES: usize = 64 * 1024;
+const HEX_PREVIEW_BYTES: usize = 256;
+
+/// Which part of a file to read. With no options the file is read from the
+/// start, up to `MAX_READ_BYTES`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub(crate) struct WorkspaceFileReadOptions {
+    /// Byte offset to start at; moved forward to the next character boundary.
+    /// In multi-byte legacy encodings such as GBK or Shift_JIS a boundary can
+    /// only be told apart at a newline, so the window starts after the next
+    /// newline instead; a window without one may start mid-character.
+    #[serde(default)]
+    pub(crate) offset: Option<u64>,
+    /// Bytes to read from `offset`.
+    #[serde(default)]
+    pub(crate) length: Option<u64>,
+    /// Zero-based first line of a line window. Takes precedence over `offset`.
+    #[serde(default, rename = "startLine")]
+    pub(crate) start_line: Option<usize>,
+    #[serde(default, rename = "lineCount")]
+    pub(crate) line_count: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub(crate) struct WorkspaceFileResponse {
+    pub(crate) content: String,
+    /// More of the file follows the returned window.
+    pub(crate) truncated: bool,
+    /// Encoding the content was decoded from, e.g. "UTF-8", "UTF-16LE",
+    /// "GBK", "Shift_JIS" or "windows-1252" (Latin-1). Empty for binary files.
+    #[serde(default)]
+    pub(crate) encoding: String,
+    /// The file starts with a byte order mark.
+    #[serde(default)]
+    pub(crate) bom: bool,
+    #[serde(default)]
+    pub(crate) binary: bool,
+    /// File size in bytes.
+    #[serde(default)]
+    pub(crate) size: u64,
+    /// Byte span of the file the content covers; the next page starts at
+    /// `offset + length`.
+    #[serde(default)]
+    pub(crate) offset: u64,
+    #[serde(default)]
+    pub(crate) length: u64,
+    #[serde(default, rename = "startLine")]
+    pub(crate) start_line: Option<usize>,
+    #[serde(default, rename = "mimeType")]
+    pub(crate) mime_type: Option<String>,
+    /// `hexdump -C` style dump of the first bytes of a binary file.
+    #[serde(default, rename = "hexPreview")]
+    pub(crate) hex_preview: Option<String>,
+}
+
+/// How to encode content written back to a file, normally the `encoding` and
+/// `bom` a read returned. With no options files are written as UTF-8.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub(crate) struct WorkspaceFileWriteOptions {
+    #[serde(default)]
+    pub(crate) encoding: Option<String>,
+    #[serde(default)]
+    pub(crate) bom: bool,
+}
+
+/// UTF-16 without a byte order mark shows up as a zero in every other byte.
+fn utf16_without_bom(sample: &[u8]) -> Option<&'static Encoding> {
+    let pairs = sample.len() / 2;
+    if pairs < 2 {
+        return None;
+    }
+    let even_zeros = sample.iter().step_by(2).filter(|byte| **byte == 0).count();
+    let odd_zeros = sample
+        .iter()
+        .skip(1)
+        .step_by(2)
+        .filter(|byte| **byte == 0)
+        .count();
+    let mostly = |zeros: usize| zeros * 10 >= pairs * 4;
+    let rarely = |zeros: usize| zeros * 10 < pairs;
+    if mostly(odd_zeros) && rarely(even_zeros) {
+        Some(UTF_16LE)
+    } else if mostly(even_zeros) && rarely(odd_zeros) {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// Whether `sample` decodes as UTF-16 text: no unpaired surrogates and few
+/// control characters, which zero-padded binary data such as 16-bit samples
+/// is full of.
+fn decodes_as_utf16_text(sample: &[u8], encoding: &'static Encoding) -> bool {
+    let mut units: Vec<u16> = sample
+        .chunks_exact(2)
+        .map(|pair| {
+            if encoding == UTF_16LE {
+                u16::from_le_bytes([pair[0], pair[1]])
+            } else {
+                u16::from_be_bytes([pair[0], pair[1]])
+            }
+        })
+        .collect();
+    // A surrogate pair may be cut off by the end of the sample.
+    if units
+        .last()
+        .is_some_and(|unit| (0xD800..0xDC00).contains(unit))
+    {
+        units.pop();
+    }
+    let mut total = 0;
+    let mut control = 0;
+    for decoded in char::decode_utf16(units) {
+        let Ok(ch) = decoded else {
+            return false;
+        };
+        total += 1;
+        if ch.is_control() && !matches!(ch, '\t' | '\n' | '\r' | '\u{0c}' | '\u{1b}') {
+            control += 1;
+        }
+    }
+    total > 0 && control * 10 <= total
+}
+
+/// Encoding of a file from its first bytes, and the length of its BOM.
+fn detect_encoding(sample: &[u8], complete: bool) -> (&'static Encoding, usize) {
+    if let Some((encoding, bom_length)) = Encoding::for_bom(sample) {
+        return (encoding, bom_length);
+    }
+    if let Some(encoding) =
+        utf16_without_bom(sample).filter(|encoding| decodes_as_utf16_text(sample, encoding))
+    {
+        return (encoding, 0);
+    }
+    let utf8 = match std::str::from_utf8(sample) {
+        Ok(_) => true,
+        // A multi-byte sequence cut off by the end of the sample is fine.
+        Err(err) => !complete && err.error_len().is_none(),
+    };
+    if utf8 {
+        return (UTF_8, 0);
+    }
+    let mut detector = EncodingDetector::new();
+    detector.feed(sample, complete);
+    (detector.guess(None, true), 0)
+}
+
+fn looks_binary(sample: &[u8], encoding: &'static Encoding) -> bool {
+    if encoding == UTF_16LE || encoding == UTF_16BE {
+        return !decodes_as_utf16_text(sample, encoding);
+    }
+    if sample.contains(&0) {
+        return true;
+    }
+    let control = sample
+        .iter()
+        .filter(|byte| **byte < 0x20 && !matches!(byte, b'\t' | b'\n' | b'\r' | 0x0c | 0x1b))
+        .count();
+    control * 10 > sample.len()
+}
+
+fn hex_preview(bytes: &[u8]) -> String {
+    bytes
+        .chunks(16)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let hex: Vec<String> = (0..16)
+                .map(|index| {
+                    chunk
+                        .get(index)
+                        .map(|byte| format!("{byte:02x}"))
+                        .unwrap_or_else(|| "  ".to_string())
+                })
+                .collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|byte| {
+                    if byte.is_ascii_graphic() || *byte == b' ' {
+                        *byte as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            format!(
+                "{:08x}  {}  {}  |{ascii}|",
+                row * 16,
+                hex[..8].join(" "),
+                hex[8..].join(" ")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn newline_unit(encoding: &'static Encoding) -> &'static [u8] {
+    if encoding == UTF_16LE {
+        b"\n\0"
+    } else if encoding == UTF_16BE {
+        b"\0\n"
+    } else {
+        b"\n"
+    }
+}
+
+/// Appends one line, newline included, to `line`. Returns false at the end
+/// of the file.
+fn read_line_bytes<R: BufRead>(
+    reader: &mut R,
+    newline: &[u8],
+    line: &mut Vec<u8>,
+) -> std::io::Result<bool> {
+    if newline.len() == 1 {
+        return Ok(reader.read_until(newline[0], line)? > 0);
+    }
+    let mut unit = [0u8; 2];
+    let mut read_any = false;
+    loop {
+        match reader.read_exact(&mut unit) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(read_any),
+            Err(err) => return Err(err),
+        }
+        read_any = true;
+        line.extend_from_slice(&unit);
+        if unit == newline {
+            return Ok(true);
+        }
+    }
+}
+
+/// Bytes of lines `start_line..start_line + line_count`, and whether more
+/// follow. Returns the byte offset the window starts at.
+fn read_line_window(
+    file: &mut File,
+    text_start: u64,
+    newline: &[u8],
+    start_line: usize,
+    line_count: usize,
+) -> std::io::Result<(u64, Vec<u8>, bool)> {
+    file.seek(SeekFrom::Start(text_start))?;
+    let mut reader = BufReader::with_capacity(SNIFF_BYTES, file);
+    let mut offset = text_start;
+    let mut line = Vec::new();
+    for _ in 0..start_line {
+        line.clear();
+        if !read_line_bytes(&mut reader, newline, &mut line)? {
+            return Ok((offset, Vec::new(), false));
+        }
+        offset += line.len() as u64;
+    }
+    let mut window = Vec::new();
+    for _ in 0..line_count {
+        let before = window.len();
+        if !read_line_bytes(&mut reader, newline, &mut window)? {
+            return Ok((offset, window, false));
+        }
+        if window.len() as u64 > MAX_RANGE_BYTES {
+            // Keep whole lines, unless the first line alone is too long.
+            let keep = if before > 0 {
+                before
+            } else {
+                MAX_RANGE_BYTES as usize
+            };
+            window.truncate(keep);
+            return Ok((offset, window, true));
+        }
+    }
+    let more = !reader.fill_buf()?.is_empty();
+    Ok((offset, window, more))
+}
+
+/// Whether `encoding` uses more than one byte for some characters and is not
+/// self-synchronizing: a trail byte can look like a lead byte or ASCII.
+fn is_legacy_multi_byte(encoding: &'static Encoding) -> bool {
+    !encoding.is_single_byte() && encoding != UTF_8 && encoding != UTF_16LE && encoding != UTF_16BE
+}
+
+/// Reads `length` bytes from `offset`, with both ends moved to character
+/// boundaries. Windows that stop before the end of the file end after their
+/// last newline when they contain one, so pages of a log split on lines.
+fn read_byte_window(
+    file: &mut File,
+    size: u64,
+    text_start: u64,
+    encoding: &'static Encoding,
+    offset: u64,
+    length: u64,
+) -> std::io::Result<(u64, Vec<u8>, bool)> {
+    let unit = newline_unit(encoding).len() as u64;
+    let mut offset = offset.max(text_start).min(size);
+    offset += (offset - text_start) % unit;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut window = Vec::new();
+    file.take(length).read_to_end(&mut window)?;
+
+    if encoding == UTF_8 && offset > text_start {
+        let skip = window
+            .iter()
+            .take(3)
+            .take_while(|byte| (**byte & 0xC0) == 0x80)
+            .count();
+        window.drain(..skip);
+        offset += skip as u64;
+    } else if is_legacy_multi_byte(encoding) && offset > text_start {
+        // Newlines never appear inside a GBK, Big5, EUC or Shift_JIS character.
+        let at_line_start = {
+            let mut previous = [0u8; 1];
+            file.seek(SeekFrom::Start(offset - 1))?;
+            file.read_exact(&mut previous)?;
+            previous[0] == b'\n'
+        };
+        if !at_line_start {
+            if let Some(index) = window.iter().position(|byte| *byte == b'\n') {
+                window.drain(..=index);
+                offset += index as u64 + 1;
+            }
+        }
+    }
+    let more = offset + (window.len() as u64) < size;
+    if more {
+        let newline = newline_unit(encoding);
+        let last_newline = window
+            .chunks_exact(newline.len())
+            .rposition(|chunk| chunk == newline);
+        if let Some(index) = last_newline {
+            window.truncate((index + 1) * newline.len());
+        } else if encoding == UTF_8 {
+            if let Err(err) = std::str::from_utf8(&window) {
+                if err.error_len().is_none() {
+                    window.truncate(err.valid_up_to());
+                }
+            }
+        } else if is_legacy_multi_byte(encoding) {
+            // Drop a character cut off by the end of the window; GB18030 has
+            // the longest, at four bytes.
+            let whole = (0..4.min(window.len())).find(|cut| {
+                encoding
+                    .decode_without_bom_handling_and_without_replacement(
+                        &window[..window.len() - cut],
+                    )
+                    .is_some()
+            });
+            if let Some(cut) = whole {
+                window.truncate(window.len() - cut);
+            }
+        } else {
+            window.truncate(window.len() - window.len() % newline.len());
+        }
+    }
+    Ok((offset, window, more))
+}
+
+fn resolve_file(root: &Path, relative_path: &str) -> Result<PathBuf, String> {
+    let canonical_root = root
+        .canonicalize()
+        .map_err(|err| format!("Failed to resolve workspace root: {err}"))?;
+    let candidate = canonical_root.join(relative_path);
+    let canonical_path = candidate
+        .canonicalize()
+        .map_err(|err| format!("Failed to open file: {err}"))?;
+    if !canonical_path.starts_with(&canonical_root) {
+        return Err("Invalid file path".to_string());
+    }
+    Ok(canonical_path)
+}
+
+pub(crate) fn read_workspace_file_inner(
+    root: &PathBuf,
+    relative_path: &str,
+    options: &WorkspaceFileReadOptions,
+) -> Result<WorkspaceFileResponse, String> {
+    let path = resolve_file(root, relative_path)?;
+    let metadata =
+        std::fs::metadata(&path).map_err(|err| format!("Failed to read file metadata: {err}"))?;
+    if !metadata.is_file() {
+        return Err("Path is not a file".to_string());
+    }
+    let size = metadata.len();
+    let mut file = File::open(&path).map_err(|err| format!("Failed to open file: {err}"))?;
+
+    let mut sample = Vec::with_capacity(SNIFF_BYTES);
+    (&mut file)
+        .take(SNIFF_BYTES as u64)
+        .read_to_end(&mut sample)
+        .map_err(|err| format!("Failed to read file: {err}"))?;
+    let (encoding, bom_length) = detect_encoding(&sample, sample.len() as u64 >= size);
+
+    if looks_binary(&sample, encoding) {
+        let mime_type = mime_guess::from_path(&path)
+            .first_raw()
+            .unwrap_or("application/octet-stream");
+        return Ok(WorkspaceFileResponse {
+            binary: true,
+            size,
+            mime_type: Some(mime_type.to_string()),
+            hex_preview: Some(hex_preview(&sample[..sample.len().min(HEX_PREVIEW_BYTES)])),
+            truncated: size > 0,
+            ..WorkspaceFileResponse::default()
+        });
+    }
+
+    let text_start = bom_length as u64;
+    let (offset, window, more) = match options.start_line {
+        Some(start_line) => read_line_window(
+            &mut file,
+            text_start,
+            newline_unit(encoding),
+            start_line,
+            options.line_count.unwrap_or(DEFAULT_LINE_COUNT).max(1),
+        ),
+        None => read_byte_window(
+            &mut file,
+            size,
+            text_start,
+            encoding,
+            options.offset.unwrap_or(0),
+            options
+                .length
+                .unwrap_or(MAX_READ_BYTES)
+                .clamp(1, MAX_RANGE_BYTES),
+        ),
+    }
+    .map_err(|err| format!("Failed to read file: {err}"))?;
+
+    let (content, _) = encoding.decode_without_bom_handling(&window);
+    Ok(WorkspaceFileResponse {
+        content: content.into_owned(),
+        truncated: more,
+        encoding: encoding.name().to_string(),
+        bom: bom_length > 0,
+        binary: false,
+        size,
+        offset,
+        length: window.len() as u64,
+        start_line: options.start_line,
+        mime_type: None,
+        hex_preview: None,
+    })
+}
+
+/// Encodes `content` as the file's original encoding. Fails rather than
+/// replacing characters the encoding cannot represent.
+pub(crate) fn encode_file_content(
+    content: &str,
+    options: &WorkspaceFileWriteOptions,
+) -> Result<Vec<u8>, String> {
+    let encoding = match options.encoding.as_deref() {
+        None | Some("") => UTF_8,
+        Some(label) => Encoding::for_label(label.as_bytes())
+            .ok_or_else(|| format!("Unsupported file encoding: {label}"))?,
+    };
+    let mut bytes = Vec::with_capacity(content.len());
+    if encoding == UTF_16LE || encoding == UTF_16BE {
+        // encoding_rs only decodes UTF-16, so encode it by hand.
+        let little_endian = encoding == UTF_16LE;
+        for unit in std::iter::once(0xFEFF)
+            .filter(|_| options.bom)
+            .chain(content.encode_utf16())
+        {
+            if little_endian {
+                bytes.extend_from_slice(&unit.to_le_bytes());
+            } else {
+                bytes.extend_from_slice(&unit.to_be_bytes());
+            }
+        }
+        return Ok(bytes);
+    }
+    if encoding == UTF_8 {
+        if options.bom {
+            bytes.extend_from_slice(b"\xEF\xBB\xBF");
+        }
+        bytes.extend_from_slice(content.as_bytes());
+        return Ok(bytes);
+    }
+    let (encoded, _, had_errors) = encoding.encode(content);
+    if had_errors {
+        return Err(format!(
+            "File contains characters that cannot be saved as {}",
+            encoding.name()
+        ));
+    }
+    bytes.extend_from_slice(&encoded);
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("moss-file-content-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    fn read(
+        root: &PathBuf,
+        name: &str,
+        options: WorkspaceFileReadOptions,
+    ) -> WorkspaceFileResponse {
+        read_workspace_file_inner(root, name, &options).unwrap()
+    }
+
+    #[test]
+    fn decodes_utf16_gbk_shift_jis_and_latin1() {
+        let root = temp_root();
+        let mut utf16 = vec![0xFF, 0xFE];
+        for unit in "héllo\n".encode_utf16() {
+            utf16.extend_from_slice(&unit.to_le_bytes());
+        }
+        std::fs::write(root.join("utf16.txt"), utf16).unwrap();
+        let (gbk, _, _) = encoding_rs::GBK.encode("这是一个中文日志文件，记录了系统的运行状态。\n");
+        std::fs::write(root.join("gbk.log"), gbk).unwrap();
+        let (sjis, _, _) = encoding_rs::SHIFT_JIS
+            .encode("これは日本語のテキストファイルです。ログを記録します。\n");
+        std::fs::write(root.join("sjis.txt"), sjis).unwrap();
+        std::fs::write(root.join("latin1.txt"), b"caf\xe9 cr\xe8me br\xfbl\xe9e\n").unwrap();
+
+        let utf16 = read(&root, "utf16.txt", Default::default());
+        assert_eq!(
+            (utf16.content.as_str(), utf16.encoding.as_str()),
+            ("héllo\n", "UTF-16LE")
+        );
+        assert_eq!(
+            read(&root, "gbk.log", Default::default()).content,
+            "这是一个中文日志文件，记录了系统的运行状态。\n"
+        );
+        let sjis = read(&root, "sjis.txt", Default::default());
+        assert_eq!(sjis.encoding, "Shift_JIS");
+        assert!(sjis.content.starts_with("これは日本語"));
+        let latin1 = read(&root, "latin1.txt", Default::default());
+        assert_eq!(latin1.content, "café crème brûlée\n");
+        assert_eq!(latin1.encoding, "windows-1252");
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn pages_large_files_by_bytes_and_by_lines() {
+        let root = temp_root();
+        let log: String = (0..100).map(|line| format!("line {line:03} ✓\n")).collect();
+        std::fs::write(root.join("app.log"), &log).unwrap();
+
+        let first = read(
+            &root,
+            "app.log",
+            WorkspaceFileReadOptions {
+                length: Some(30),
+                ..Default::default()
+            },
+        );
+        assert_eq!(first.content, "line 000 ✓\nline 001 ✓\n");
+        assert!(first.truncated);
+        let second = read(
+            &root,
+            "app.log",
+            WorkspaceFileReadOptions {
+                offset: Some(first.offset + first.length),
+                length: Some(30),
+                ..Default::default()
+            },
+        );
+        assert!(second.content.starts_with("line 002 ✓\n"));
+
+        // An offset inside the check mark moves forward to the next character.
+        let inside = read(
+            &root,
+            "app.log",
+            WorkspaceFileReadOptions {
+                offset: Some(10),
+                length: Some(20),
+                ..Default::default()
+            },
+        );
+        assert!(inside.content.starts_with('\n'));
+
+        let window = read(
+            &root,
+            "app.log",
+            WorkspaceFileReadOptions {
+                start_line: Some(98),
+                line_count: Some(5),
+                ..Default::default()
+            },
+        );
+        assert_eq!(window.content, "line 098 ✓\nline 099 ✓\n");
+        assert_eq!(window.start_line, Some(98));
+        assert!(!window.truncated);
+        assert_eq!(window.offset + window.length, window.size);
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn encodes_written_content_as_the_file_was_read() {
+        let root = temp_root();
+        let mut utf16 = vec![0xFE, 0xFF];
+        for unit in "héllo\n".encode_utf16() {
+            utf16.extend_from_slice(&unit.to_be_bytes());
+        }
+        std::fs::write(root.join("utf16.txt"), &utf16).unwrap();
+        let (gbk, _, _) = encoding_rs::GBK.encode("这是一个中文日志文件，记录了系统的运行状态。\n");
+        std::fs::write(root.join("gbk.log"), &gbk).unwrap();
+
+        for (name, original) in [("utf16.txt", utf16), ("gbk.log", gbk.into_owned())] {
+            let response = read(&root, name, Default::default());
+            let options = WorkspaceFileWriteOptions {
+                encoding: Some(response.encoding),
+                bom: response.bom,
+            };
+            assert_eq!(
+                encode_file_content(&response.content, &options).unwrap(),
+                original
+            );
+        }
+        let latin1 = WorkspaceFileWriteOptions {
+            encoding: Some("windows-1252".to_string()),
+            bom: false,
+        };
+        assert!(encode_file_content("café ✓", &latin1).is_err());
+        assert_eq!(
+            encode_file_content("hi", &WorkspaceFileWriteOptions::default()).unwrap(),
+            b"hi"
+        );
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn pages_gbk_files_on_character_boundaries() {
+        let root = temp_root();
+        let (gbk, _, _) = encoding_rs::GBK.encode("中文日志第一行内容\n第二行\n");
+        std::fs::write(root.join("gbk.log"), &gbk).unwrap();
+        let gbk_read = |offset: u64, length: u64| {
+            read(
+                &root,
+                "gbk.log",
+                WorkspaceFileReadOptions {
+                    offset: Some(offset),
+                    length: Some(length),
+                    ..Default::default()
+                },
+            )
+        };
+
+        // An offset inside the first line starts the page at the second.
+        assert_eq!(gbk_read(3, 100).content, "第二行\n");
+        // A window without a newline stops before a cut-off character.
+        let head = gbk_read(0, 5);
+        assert_eq!((head.content.as_str(), head.length), ("中文", 4));
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn treats_zero_padded_binary_as_binary_not_utf16() {
+        let root = temp_root();
+        let samples: Vec<u8> = (0u16..200).flat_map(|value| value.to_le_bytes()).collect();
+        std::fs::write(root.join("samples.raw"), samples).unwrap();
+        let text: Vec<u8> = "plain text\n"
+            .encode_utf16()
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect();
+        std::fs::write(root.join("notes.txt"), text).unwrap();
+
+        assert!(read(&root, "samples.raw", Default::default()).binary);
+        let notes = read(&root, "notes.txt", Default::default());
+        assert_eq!(
+            (notes.content.as_str(), notes.encoding.as_str()),
+            ("plain text\n", "UTF-16LE")
+        );
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn describes_binary_files_instead_of_failing() {
+        let root = temp_root();
+        let png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR\x00\x00\x00\x01";
+        std::fs::write(root.join("logo.png"), png).unwrap();
+
+        let response = read(&root, "logo.png", Default::default());
+        assert!(response.binary);
+        assert!(response.content.is_empty());
+        assert_eq!(response.size, png.len() as u64);
+        assert_eq!(response.mime_type.as_deref(), Some("image/png"));
+        assert!(response.hex_preview.unwrap().starts_with(
+            "00000000  89 50 4e 47 0d 0a 1a 0a  00 00 00 0d 49 48 44 52  |.PNG........IHDR|"
+        ));
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,859 @@
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Listener, Manager, State};
+
+use crate::state::AppState;
+use crate::types::BackendMode;
+use crate::workspaces::{content_hash, unified_diff};
+
+/// Larger files are left to git; snapshotting them on every agent edit would
+/// bloat the store.
+const MAX_SNAPSHOT_BYTES: u64 = 5 * 1024 * 1024;
+const DEFAULT_LIST_LIMIT: usize = 200;
+
+/// Local history of one file at one point in time. `hash` is `None` when
+/// the file did not exist yet, e.g. right before an agent created it.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub(crate) struct FileHistoryEntry {
+    id: i64,
+    path: String,
+    hash: Option<String>,
+    size: u64,
+    /// "agent" before an agent tool write, "editor" before a save from the
+    /// file view, "refactor" before a code intel edit or its undo, "restore"
+    /// before a restore overwrote the file.
+    source: String,
+    #[serde(rename = "threadId")]
+    thread_id: Option<String>,
+    #[serde(rename = "turnId")]
+    turn_id: Option<String>,
+    tool: Option<String>,
+    /// Milliseconds since the Unix epoch.
+    #[serde(rename = "createdAt")]
+    created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct FileHistoryDiff {
+    path: String,
+    #[serde(rename = "fromId")]
+    from_id: i64,
+    /// `None` compares against the file as it is on disk now.
+    #[serde(rename = "toId")]
+    to_id: Option<i64>,
+    diff: String,
+    binary: bool,
+}
+
+/// Who is about to change a file.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct SnapshotOrigin {
+    source: &'static str,
+    thread_id: Option<String>,
+    turn_id: Option<String>,
+    tool: Option<String>,
+}
+
+impl SnapshotOrigin {
+    fn local(source: &'static str) -> Self {
+        Self {
+            source,
+            ..Self::default()
+        }
+    }
+}
+
+/// Snapshots of one workspace's files, deduplicated by content hash.
+pub(crate) struct FileHistory {
+    root: PathBuf,
+    conn: Mutex<Connection>,
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+/// Workspace-relative, `/`-separated form of `path`, which may be absolute
+/// (agent tool arguments) or already relative. Paths outside the workspace
+/// are rejected.
+fn normalize_path(root: &Path, path: &str) -> Option<String> {
+    let candidate = Path::new(path.trim());
+    let relative = if candidate.is_absolute() {
+        let canonical_root = root.canonicalize().ok();
+        candidate
+            .strip_prefix(root)
+            .ok()
+            .or_else(|| candidate.strip_prefix(canonical_root.as_deref()?).ok())?
+            .to_path_buf()
+    } else {
+        candidate.to_path_buf()
+    };
+    let mut parts = Vec::new();
+    for component in relative.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+fn read_entry(row: &rusqlite::Row<'_>) -> rusqlite::Result<FileHistoryEntry> {
+    Ok(FileHistoryEntry {
+        id: row.get(0)?,
+        path: row.get(1)?,
+        hash: row.get(2)?,
+        size: row.get::<_, i64>(3)? as u64,
+        source: row.get(4)?,
+        thread_id: row.get(5)?,
+        turn_id: row.get(6)?,
+        tool: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+const ENTRY_COLUMNS: &str = "id, path, hash, size, source, thread_id, turn_id, tool, created_ms";
+
+impl FileHistory {
+    fn open(db_path: &Path, workspace_root: &Path) -> Result<Self, String> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create file history directory: {err}"))?;
+        }
+        let conn = Connection::open(db_path)
+            .map_err(|err| format!("Failed to open file history: {err}"))?;
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             PRAGMA synchronous = NORMAL;
+             CREATE TABLE IF NOT EXISTS blobs (
+                 hash TEXT PRIMARY KEY,
+                 content BLOB NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS snapshots (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 path TEXT NOT NULL,
+                 hash TEXT,
+                 size INTEGER NOT NULL,
+                 source TEXT NOT NULL,
+                 thread_id TEXT,
+                 turn_id TEXT,
+                 tool TEXT,
+                 created_ms INTEGER NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS snapshots_path ON snapshots (path, id);
+             CREATE INDEX IF NOT EXISTS snapshots_turn ON snapshots (thread_id, turn_id);",
+        )
+        .map_err(|err| err.to_string())?;
+        Ok(Self {
+            root: workspace_root.to_path_buf(),
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Records the current content of `path` unless the newest snapshot
+    /// already holds it for the same origin. Keeps at most `max_snapshots`
+    /// per file.
+    fn snapshot(
+        &self,
+        path: &str,
+        origin: &SnapshotOrigin,
+        max_snapshots: usize,
+    ) -> Result<Option<FileHistoryEntry>, String> {
+        let absolute = self.root.join(path);
+        let content = match std::fs::metadata(&absolute) {
+            Ok(metadata) if !metadata.is_file() => return Ok(None),
+            Ok(metadata) if metadata.len() > MAX_SNAPSHOT_BYTES => return Ok(None),
+            Ok(_) => Some(
+                std::fs::read(&absolute)
+                    .map_err(|err| format!("Failed to read {path} for history: {err}"))?,
+            ),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => return Err(format!("Failed to read {path} for history: {err}")),
+        };
+        let hash = content.as_deref().map(content_hash);
+
+        let mut conn = self.conn.lock().map_err(|_| "file history lock poisoned")?;
+        let latest = conn
+            .query_row(
+                &format!(
+                    "SELECT {ENTRY_COLUMNS} FROM snapshots WHERE path = ?1 ORDER BY id DESC LIMIT 1"
+                ),
+                params![path],
+                read_entry,
+            )
+            .optional()
+            .map_err(|err| err.to_string())?;
+        if latest.is_some_and(|entry| {
+            entry.hash == hash
+                && entry.source == origin.source
+                && entry.thread_id == origin.thread_id
+                && entry.turn_id == origin.turn_id
+        }) {
+            return Ok(None);
+        }
+
+        let tx = conn.transaction().map_err(|err| err.to_string())?;
+        if let (Some(hash), Some(content)) = (&hash, &content) {
+            tx.execute(
+                "INSERT OR IGNORE INTO blobs (hash, content) VALUES (?1, ?2)",
+                params![hash, content],
+            )
+            .map_err(|err| err.to_string())?;
+        }
+        let size = content.as_ref().map(Vec::len).unwrap_or_default() as i64;
+        let created_ms = now_ms();
+        tx.execute(
+            "INSERT INTO snapshots (path, hash, size, source, thread_id, turn_id, tool, created_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                path,
+                hash,
+                size,
+                origin.source,
+                origin.thread_id,
+                origin.turn_id,
+                origin.tool,
+                created_ms
+            ],
+        )
+        .map_err(|err| err.to_string())?;
+        let id = tx.last_insert_rowid();
+        let pruned = tx
+            .execute(
+                "DELETE FROM snapshots WHERE path = ?1 AND id NOT IN
+                 (SELECT id FROM snapshots WHERE path = ?1 ORDER BY id DESC LIMIT ?2)",
+                params![path, max_snapshots.max(1) as i64],
+            )
+            .map_err(|err| err.to_string())?;
+        if pruned > 0 {
+            tx.execute(
+                "DELETE FROM blobs WHERE hash NOT IN
+                 (SELECT hash FROM snapshots WHERE hash IS NOT NULL)",
+                [],
+            )
+            .map_err(|err| err.to_string())?;
+        }
+        tx.commit().map_err(|err| err.to_string())?;
+        Ok(Some(FileHistoryEntry {
+            id,
+            path: path.to_string(),
+            hash,
+            size: size as u64,
+            source: origin.source.to_string(),
+            thread_id: origin.thread_id.clone(),
+            turn_id: origin.turn_id.clone(),
+            tool: origin.tool.clone(),
+            created_at: created_ms,
+        }))
+    }
+
+    /// Newest first, optionally narrowed to one file and/or one thread.
+    fn list(
+        &self,
+        path: Option<&str>,
+        thread_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<FileHistoryEntry>, String> {
+        let conn = self.conn.lock().map_err(|_| "file history lock poisoned")?;
+        let mut statement = conn
+            .prepare(&format!(
+                "SELECT {ENTRY_COLUMNS} FROM snapshots
+                 WHERE (?1 IS NULL OR path = ?1) AND (?2 IS NULL OR thread_id = ?2)
+                 ORDER BY id DESC LIMIT ?3"
+            ))
+            .map_err(|err| err.to_string())?;
+        let rows = statement
+            .query_map(params![path, thread_id, limit as i64], read_entry)
+            .map_err(|err| err.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|err| err.to_string())
+    }
+
+    /// A snapshot and the file content it recorded.
+    fn load(&self, id: i64) -> Result<(FileHistoryEntry, Option<Vec<u8>>), String> {
+        let conn = self.conn.lock().map_err(|_| "file history lock poisoned")?;
+        let entry = conn
+            .query_row(
+                &format!("SELECT {ENTRY_COLUMNS} FROM snapshots WHERE id = ?1"),
+                params![id],
+                read_entry,
+            )
+            .optional()
+            .map_err(|err| err.to_string())?
+            .ok_or_else(|| "History snapshot not found".to_string())?;
+        let content = match &entry.hash {
+            Some(hash) => Some(
+                conn.query_row(
+                    "SELECT content FROM blobs WHERE hash = ?1",
+                    params![hash],
+                    |row| row.get(0),
+                )
+                .map_err(|err| err.to_string())?,
+            ),
+            None => None,
+        };
+        Ok((entry, content))
+    }
+
+    fn current_content(&self, path: &str) -> Result<Option<Vec<u8>>, String> {
+        match std::fs::read(self.root.join(path)) {
+            Ok(content) => Ok(Some(content)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(format!("Failed to read {path}: {err}")),
+        }
+    }
+
+    fn diff(&self, from_id: i64, to_id: Option<i64>) -> Result<FileHistoryDiff, String> {
+        let (from, before) = self.load(from_id)?;
+        let after = match to_id {
+            Some(to_id) => {
+                let (to, content) = self.load(to_id)?;
+                if to.path != from.path {
+                    return Err("History snapshots belong to different files".to_string());
+                }
+                content
+            }
+            None => self.current_content(&from.path)?,
+        };
+        let before = before.unwrap_or_default();
+        let after = after.unwrap_or_default();
+        let binary = before.contains(&0) || after.contains(&0);
+        let diff = if binary {
+            String::new()
+        } else {
+            unified_diff(&from.path, &before, &after)?
+        };
+        Ok(FileHistoryDiff {
+            path: from.path,
+            from_id,
+            to_id,
+            diff,
+            binary,
+        })
+    }
+
+    /// Puts the file back to snapshot `id`, first recording what it replaces
+    /// so the restore itself can be undone. Restoring a snapshot taken before
+    /// the file existed deletes it. Refuses when the current file can't be
+    /// backed up.
+    fn restore(&self, id: i64, max_snapshots: usize) -> Result<Option<FileHistoryEntry>, String> {
+        let (entry, content) = self.load(id)?;
+        let backup = self.snapshot(
+            &entry.path,
+            &SnapshotOrigin::local("restore"),
+            max_snapshots,
+        )?;
+        let absolute = self.root.join(&entry.path);
+        if backup.is_none() {
+            // No new snapshot is fine only when the newest one already holds
+            // the current file; anything else would be lost by the restore.
+            match std::fs::metadata(&absolute) {
+                Ok(metadata) if !metadata.is_file() => {
+                    return Err(format!("{} is not a regular file", entry.path));
+                }
+                Ok(metadata) if metadata.len() > MAX_SNAPSHOT_BYTES => {
+                    return Err(format!(
+                        "{} is too large to back up before restoring",
+                        entry.path
+                    ));
+                }
+                _ => {}
+            }
+        }
+        match content {
+            Some(content) => {
+                if let Some(parent) = absolute.parent() {
+                    std::fs::create_dir_all(parent)
+                        .map_err(|err| format!("Failed to create folder: {err}"))?;
+                }
+                let file_name = absolute
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let temp = absolute.with_file_name(format!(".{file_name}.history-restore"));
+                std::fs::write(&temp, &content)
+                    .map_err(|err| format!("Failed to restore {}: {err}", entry.path))?;
+                if let Err(err) = std::fs::rename(&temp, &absolute) {
+                    let _ = std::fs::remove_file(&temp);
+                    return Err(format!("Failed to restore {}: {err}", entry.path));
+                }
+            }
+            None => match std::fs::remove_file(&absolute) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(format!("Failed to remove {}: {err}", entry.path)),
+            },
+        }
+        Ok(backup)
+    }
+}
+
+static STORES: LazyLock<Mutex<HashMap<String, Arc<FileHistory>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Thread id to the id of its running turn.
+static ACTIVE_TURNS: LazyLock<Mutex<HashMap<String, String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn history_path(state: &AppState, workspace_id: &str) -> PathBuf {
+    state
+        .storage_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join("file-history")
+        .join(format!("{workspace_id}.sqlite3"))
+}
+
+fn open_store(
+    db_path: &Path,
+    workspace_id: &str,
+    workspace_path: &str,
+) -> Result<Arc<FileHistory>, String> {
+    let root = PathBuf::from(workspace_path);
+    let mut stores = STORES.lock().map_err(|_| "file history lock poisoned")?;
+    if let Some(store) = stores.get(workspace_id) {
+        if store.root == root {
+            return Ok(Arc::clone(store));
+        }
+    }
+    let store = Arc::new(FileHistory::open(db_path, &root)?);
+    stores.insert(workspace_id.to_string(), Arc::clone(&store));
+    Ok(store)
+}
+
+async fn workspace_store(state: &AppState, workspace_id: &str) -> Result<Arc<FileHistory>, String> {
+    let workspace_path = state
+        .workspaces
+        .lock()
+        .await
+        .get(workspace_id)
+        .map(|entry| entry.path.clone())
+        .ok_or_else(|| "Workspace not found".to_string())?;
+    let db_path = history_path(state, workspace_id);
+    tokio::task::spawn_blocking({
+        let workspace_id = workspace_id.to_string();
+        move || open_store(&db_path, &workspace_id, &workspace_path)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+/// History settings when snapshots should be taken: enabled, and the
+/// workspace lives on this machine.
+async fn snapshot_limit(state: &AppState) -> Option<usize> {
+    let settings = state.app_settings.lock().await;
+    (settings.file_history_enabled && matches!(settings.backend_mode, BackendMode::Local))
+        .then_some(settings.file_history_max_snapshots as usize)
+}
+
+fn snapshot_all(
+    store: &FileHistory,
+    workspace_id: &str,
+    paths: &[String],
+    origin: &SnapshotOrigin,
+    max_snapshots: usize,
+) {
+    for path in paths {
+        let Some(relative) = normalize_path(&store.root, path) else {
+            continue;
+        };
+        if let Err(err) = store.snapshot(&relative, origin, max_snapshots) {
+            log::warn!("[file-history] snapshot of {relative} in {workspace_id} failed: {err}");
+        }
+    }
+}
+
+/// Records `relative_path` before the file view saves over it. Failures are
+/// logged and never block the save.
+pub(crate) async fn snapshot_before_write(
+    state: &AppState,
+    workspace_id: &str,
+    relative_path: &str,
+) {
+    let Some(max_snapshots) = snapshot_limit(state).await else {
+        return;
+    };
+    snapshot_off_thread(
+        state,
+        workspace_id.to_string(),
+        vec![relative_path.to_string()],
+        SnapshotOrigin::local("editor"),
+        max_snapshots,
+    )
+    .await;
+}
+
+/// Records the files a code intel edit is about to rewrite. Failures are
+/// logged and never block the edit.
+pub(crate) async fn snapshot_before_edit(state: &AppState, workspace_id: &str, paths: Vec<String>) {
+    let Some(max_snapshots) = snapshot_limit(state).await else {
+        return;
+    };
+    snapshot_off_thread(
+        state,
+        workspace_id.to_string(),
+        paths,
+        SnapshotOrigin::local("refactor"),
+        max_snapshots,
+    )
+    .await;
+}
+
+/// Opens the store and snapshots `paths` on the blocking pool, logging
+/// failures.
+async fn snapshot_off_thread(
+    state: &AppState,
+    workspace_id: String,
+    paths: Vec<String>,
+    origin: SnapshotOrigin,
+    max_snapshots: usize,
+) {
+    let Some(workspace_path) = state
+        .workspaces
+        .lock()
+        .await
+        .get(&workspace_id)
+        .map(|entry| entry.path.clone())
+    else {
+        return;
+    };
+    let db_path = history_path(state, &workspace_id);
+    let result = tokio::task::spawn_blocking(move || {
+        match open_store(&db_path, &workspace_id, &workspace_path) {
+            Ok(store) => snapshot_all(&store, &workspace_id, &paths, &origin, max_snapshots),
+            Err(err) => log::warn!("[file-history] {workspace_id}: {err}"),
+        }
+    })
+    .await;
+    if let Err(err) = result {
+        log::warn!("[file-history] snapshot task failed: {err}");
+    }
+}
+
+fn turn_id_of(params: &Value) -> Option<String> {
+    params
+        .get("turnId")
+        .or_else(|| params.get("turn_id"))
+        .or_else(|| params.get("turn")?.get("id"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Tracks running turns and returns the files an `item/started` message is
+/// about to write, with the thread and turn writing them.
+fn pending_writes(
+    turns: &mut HashMap<String, String>,
+    message: &Value,
+) -> Option<(Vec<String>, SnapshotOrigin)> {
+    let method = message.get("method").and_then(Value::as_str)?;
+    let params = message.get("params")?;
+    let thread_id = params
+        .get("threadId")
+        .or_else(|| params.get("turn")?.get("threadId"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    match method {
+        "turn/started" => {
+            if let (Some(thread_id), Some(turn_id)) = (thread_id, turn_id_of(params)) {
+                turns.insert(thread_id, turn_id);
+            }
+            None
+        }
+        "turn/completed" | "turn/error" => {
+            if let Some(thread_id) = thread_id {
+                turns.remove(&thread_id);
+            }
+            None
+        }
+        "item/started" => {
+            let paths = crate::lsp::edited_paths(message);
+            if paths.is_empty() {
+                return None;
+            }
+            let item = params.get("item");
+            let tool = item
+                .and_then(|item| item.get("tool"))
+                .or_else(|| item?.get("type"))
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            let turn_id = turn_id_of(params).or_else(|| turns.get(thread_id.as_deref()?).cloned());
+            Some((
+                paths,
+                SnapshotOrigin {
+                    source: "agent",
+                    thread_id,
+                    turn_id,
+                    tool,
+                },
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Snapshots every file an agent tool is about to write, keyed by thread
+/// and turn, from the `item/started` events all engines emit. This is best
+/// effort: the snapshot runs off the event thread and can lose the race
+/// with a fast write.
+pub(crate) fn listen_for_agent_writes(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen("app-server-event", move |event| {
+        let Ok(payload) = serde_json::from_str::<Value>(event.payload()) else {
+            return;
+        };
+        let Some(workspace_id) = payload.get("workspace_id").and_then(Value::as_str) else {
+            return;
+        };
+        let Some(message) = payload.get("message") else {
+            return;
+        };
+        let pending = {
+            let Ok(mut turns) = ACTIVE_TURNS.lock() else {
+                return;
+            };
+            pending_writes(&mut turns, message)
+        };
+        let Some((paths, origin)) = pending else {
+            return;
+        };
+        let handle = handle.clone();
+        let workspace_id = workspace_id.to_string();
+        tauri::async_runtime::spawn(async move {
+            let state = handle.state::<AppState>();
+            let Some(max_snapshots) = snapshot_limit(&state).await else {
+                return;
+            };
+            snapshot_off_thread(&state, workspace_id, paths, origin, max_snapshots).await;
+        });
+    });
+}
+
+/// Closes and deletes the history of a removed workspace.
+pub(crate) fn drop_file_history(state: &AppState, workspace_id: &str) {
+    if let Ok(mut stores) = STORES.lock() {
+        stores.remove(workspace_id);
+    }
+    let db_path = history_path(state, workspace_id);
+    for suffix in ["", "-wal", "-shm"] {
+        let mut path = db_path.clone().into_os_string();
+        path.push(suffix);
+        let _ = std::fs::remove_file(PathBuf::from(path));
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn file_history_list(
+    workspace_id: String,
+    path: Option<String>,
+    thread_id: Option<String>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<FileHistoryEntry>, String> {
+    let store = workspace_store(&state, &workspace_id).await?;
+    let path = match path {
+        Some(path) => Some(
+            normalize_path(&store.root, &path).ok_or_else(|| "Invalid file path".to_string())?,
+        ),
+        None => None,
+    };
+    tokio::task::spawn_blocking(move || {
+        store.list(
+            path.as_deref(),
+            thread_id.as_deref(),
+            limit.unwrap_or(DEFAULT_LIST_LIMIT),
+        )
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+#[tauri::command]
+pub(crate) async fn file_history_diff(
+    workspace_id: String,
+    from_id: i64,
+    to_id: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<FileHistoryDiff, String> {
+    let store = workspace_store(&state, &workspace_id).await?;
+    tokio::task::spawn_blocking(move || store.diff(from_id, to_id))
+        .await
+        .map_err(|err| err.to_string())?
+}
+
+/// Returns the snapshot of the content the restore replaced, if it differed.
+#[tauri::command]
+pub(crate) async fn file_history_restore(
+    workspace_id: String,
+    snapshot_id: i64,
+    state: State<'_, AppState>,
+) -> Result<Option<FileHistoryEntry>, String> {
+    let max_snapshots = {
+        let settings = state.app_settings.lock().await;
+        if !matches!(settings.backend_mode, BackendMode::Local) {
+            return Err("File history is not supported in remote mode yet.".to_string());
+        }
+        settings.file_history_max_snapshots as usize
+    };
+    let store = workspace_store(&state, &workspace_id).await?;
+    tokio::task::spawn_blocking(move || store.restore(snapshot_id, max_snapshots))
+        .await
+        .map_err(|err| err.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn temp_store() -> (PathBuf, FileHistory) {
+        let base = std::env::temp_dir().join(format!("moss-x-file-history-{}", Uuid::new_v4()));
+        let root = base.join("workspace");
+        std::fs::create_dir_all(&root).unwrap();
+        let store = FileHistory::open(&base.join("history.sqlite3"), &root).unwrap();
+        (base, store)
+    }
+
+    fn agent(turn_id: &str) -> SnapshotOrigin {
+        SnapshotOrigin {
+            source: "agent",
+            thread_id: Some("thread-1".to_string()),
+            turn_id: Some(turn_id.to_string()),
+            tool: Some("Edit".to_string()),
+        }
+    }
+
+    #[test]
+    fn snapshots_are_deduplicated_and_pruned_per_file() {
+        let (base, store) = temp_store();
+        let file = store.root.join("src/lib.rs");
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+
+        // Before the agent creates the file there is nothing on disk.
+        let created = store
+            .snapshot("src/lib.rs", &agent("turn-1"), 3)
+            .unwrap()
+            .unwrap();
+        assert_eq!((created.hash, created.size), (None, 0));
+
+        std::fs::write(&file, "one").unwrap();
+        assert!(store
+            .snapshot("src/lib.rs", &agent("turn-2"), 3)
+            .unwrap()
+            .is_some());
+        // A retried tool call in the same turn adds nothing new.
+        assert!(store
+            .snapshot("src/lib.rs", &agent("turn-2"), 3)
+            .unwrap()
+            .is_none());
+        std::fs::write(&file, "two").unwrap();
+        store
+            .snapshot("src/lib.rs", &SnapshotOrigin::local("editor"), 3)
+            .unwrap();
+        std::fs::write(&file, "three").unwrap();
+        store.snapshot("src/lib.rs", &agent("turn-3"), 3).unwrap();
+
+        let timeline = store.list(Some("src/lib.rs"), None, 10).unwrap();
+        assert_eq!(timeline.len(), 3);
+        assert_eq!(
+            timeline
+                .iter()
+                .map(|entry| entry.source.as_str())
+                .collect::<Vec<_>>(),
+            ["agent", "editor", "agent"]
+        );
+        assert_eq!(store.list(None, Some("thread-1"), 10).unwrap().len(), 2);
+        let blobs: i64 = store
+            .conn
+            .lock()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM blobs", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(blobs, 3);
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn diffs_and_restores_snapshots() {
+        let (base, store) = temp_store();
+        let file = store.root.join("notes.md");
+        let missing = store
+            .snapshot("notes.md", &agent("turn-1"), 10)
+            .unwrap()
+            .unwrap();
+        std::fs::write(&file, "alpha\nbeta\n").unwrap();
+        let first = store
+            .snapshot("notes.md", &agent("turn-2"), 10)
+            .unwrap()
+            .unwrap();
+        std::fs::write(&file, "alpha\ngamma\n").unwrap();
+
+        let diff = store.diff(first.id, None).unwrap();
+        assert!(!diff.binary);
+        assert!(diff.diff.contains("-beta\n+gamma"));
+        let created = store.diff(missing.id, Some(first.id)).unwrap();
+        assert!(created.diff.contains("+alpha"));
+
+        let backup = store.restore(first.id, 10).unwrap().unwrap();
+        assert_eq!(backup.source, "restore");
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "alpha\nbeta\n");
+        store.restore(backup.id, 10).unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "alpha\ngamma\n");
+        store.restore(missing.id, 10).unwrap();
+        assert!(!file.exists());
+
+        // A folder in the file's place can't be backed up, so it stays.
+        std::fs::create_dir(&file).unwrap();
+        assert!(store.restore(first.id, 10).is_err());
+        assert!(file.is_dir());
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn item_started_events_name_the_files_and_turn() {
+        let mut turns = HashMap::new();
+        let started = json!({
+            "method": "turn/started",
+            "params": { "threadId": "claude:abc", "turnId": "turn-7" },
+        });
+        assert!(pending_writes(&mut turns, &started).is_none());
+        let write = json!({
+            "method": "item/started",
+            "params": {
+                "threadId": "claude:abc",
+                "item": {
+                    "type": "mcpToolCall",
+                    "tool": "Write",
+                    "arguments": { "file_path": "/repo/src/main.rs" },
+                    "status": "started",
+                },
+            },
+        });
+        let (paths, origin) = pending_writes(&mut turns, &write).unwrap();
+        assert_eq!(paths, ["/repo/src/main.rs"]);
+        assert_eq!(origin.turn_id.as_deref(), Some("turn-7"));
+        assert_eq!(origin.tool.as_deref(), Some("Write"));
+
+        let root = Path::new("/repo");
+        assert_eq!(
+            normalize_path(root, "/repo/src/main.rs").as_deref(),
+            Some("src/main.rs")
+        );
+        assert_eq!(
+            normalize_path(root, "./src/lib.rs").as_deref(),
+            Some("src/lib.rs")
+        );
+        assert_eq!(normalize_path(root, "/etc/passwd"), None);
+        assert_eq!(normalize_path(root, "../outside.rs"), None);
+    }
+}
@@ -7,6 +7,8 @@ use crate::remote_backend;
 use crate::shared::files_core::{file_read_core, file_write_core};
 use crate::state::AppState;
 
+pub(crate) mod content;
+pub(crate) mod history;
 pub(crate) mod io;
 pub(crate) mod ops;
 pub(crate) mod policy;
@@ -0,0 +1,398 @@
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use git2::Repository;
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::time::{timeout, Duration};
+
+use super::{get_git_commit_details, run_git_command, GIT_COMMAND_TIMEOUT_SECS};
+use crate::remote_backend;
+use crate::state::AppState;
+use crate::types::{GitBisectResult, GitBisectStep};
+use crate::utils::{git_env_path, resolve_git_binary};
+use crate::workspaces::{add_local_worktree, remove_worktree, run_workspace_command};
+
+/// Upper bound on test runs; bisecting 2^32 commits needs 32, the rest covers skips.
+const BISECT_MAX_STEPS: usize = 64;
+/// Exit code reserved by `git bisect run` for "this commit cannot be tested".
+const BISECT_SKIP_EXIT_CODE: i32 = 125;
+
+/// The hidden bisect worktree and its branch. Dropping it resets the bisect
+/// and removes both, so every exit path cleans up, including a cancelled
+/// command.
+struct BisectCheckout {
+    app: AppHandle,
+    worktree_id: String,
+    worktree_root: PathBuf,
+    parent_path: PathBuf,
+    branch: String,
+}
+
+impl Drop for BisectCheckout {
+    fn drop(&mut self) {
+        let app = self.app.clone();
+        let worktree_id = std::mem::take(&mut self.worktree_id);
+        let worktree_root = std::mem::take(&mut self.worktree_root);
+        let parent_path = std::mem::take(&mut self.parent_path);
+        let branch = std::mem::take(&mut self.branch);
+        tauri::async_runtime::spawn(async move {
+            let _ = run_git_command(&worktree_root, &["bisect", "reset"]).await;
+            let _ =
+                remove_worktree(worktree_id.clone(), app.state::<AppState>(), app.clone()).await;
+            crate::storage::set_workspace_transient(&worktree_id, false);
+            let _ = run_git_command(&parent_path, &["branch", "-D", &branch]).await;
+        });
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum BisectState {
+    Testing { remaining: Option<usize> },
+    FirstBad(String),
+    OnlySkipped(Vec<String>),
+}
+
+#[derive(Debug, Default)]
+struct BisectOutcome {
+    steps: Vec<GitBisectStep>,
+    first_bad: Option<String>,
+    candidates: Vec<String>,
+}
+
+fn is_full_sha(value: &str) -> bool {
+    value.len() == 40 && value.chars().all(|ch| ch.is_ascii_hexdigit())
+}
+
+fn parse_bisect_output(output: &str) -> BisectState {
+    for line in output.lines() {
+        if let Some(sha) = line.trim().strip_suffix(" is the first bad commit") {
+            return BisectState::FirstBad(sha.trim().to_string());
+        }
+    }
+    if output.contains("only 'skip'ped commits left to test") {
+        let candidates = output
+            .lines()
+            .map(str::trim)
+            .filter_map(|line| line.split_whitespace().next())
+            .filter(|token| is_full_sha(token))
+            .map(ToOwned::to_owned)
+            .collect();
+        return BisectState::OnlySkipped(candidates);
+    }
+    let remaining = output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Bisecting: "))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|count| count.parse::<usize>().ok());
+    BisectState::Testing { remaining }
+}
+
+/// Maps a test command exit code to a bisect verdict using `git bisect run`
+/// semantics: 0 is good, 125 skips, 1-127 is bad and anything else aborts.
+fn verdict_for_exit_code(exit_code: i32) -> Result<&'static str, String> {
+    match exit_code {
+        0 => Ok("good"),
+        BISECT_SKIP_EXIT_CODE => Ok("skip"),
+        1..=127 => Ok("bad"),
+        other => Err(format!(
+            "Test command exited with code {other}; aborting bisect."
+        )),
+    }
+}
+
+async fn run_bisect_git(root: &Path, args: &[&str]) -> Result<(bool, String), String> {
+    let git_bin = resolve_git_binary().map_err(|e| format!("Failed to run git: {e}"))?;
+    let mut command = crate::utils::async_command(git_bin);
+    command
+        .args(args)
+        .current_dir(root)
+        .env("PATH", git_env_path())
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let output = timeout(
+        Duration::from_secs(GIT_COMMAND_TIMEOUT_SECS),
+        command.output(),
+    )
+    .await
+    .map_err(|_| format!("git {} timed out.", args.join(" ")))?
+    .map_err(|e| format!("Failed to run git: {e}"))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let combined = [stdout.trim(), stderr.trim()]
+        .iter()
+        .filter(|part| !part.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok((output.status.success(), combined))
+}
+
+fn current_bisect_commit(root: &Path) -> Result<(String, String), String> {
+    let repo = Repository::open(root).map_err(|e| e.to_string())?;
+    let commit = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|e| e.to_string())?;
+    Ok((
+        commit.id().to_string(),
+        commit.summary().unwrap_or("").to_string(),
+    ))
+}
+
+/// Starts a bisect in `root` and feeds it verdicts from `run_test` until git
+/// names the first bad commit or runs out of testable commits.
+async fn drive_bisect<F, Fut, S>(
+    root: &Path,
+    good_ref: &str,
+    bad_ref: &str,
+    mut run_test: F,
+    mut on_step: S,
+) -> Result<BisectOutcome, String>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<i32, String>>,
+    S: FnMut(&GitBisectStep),
+{
+    let (started, mut output) =
+        run_bisect_git(root, &["bisect", "start", bad_ref, good_ref]).await?;
+    if !started {
+        return Err(output);
+    }
+    let mut outcome = BisectOutcome::default();
+    for _ in 0..BISECT_MAX_STEPS {
+        let remaining = match parse_bisect_output(&output) {
+            BisectState::FirstBad(sha) => {
+                outcome.first_bad = Some(sha);
+                return Ok(outcome);
+            }
+            BisectState::OnlySkipped(candidates) => {
+                outcome.candidates = candidates;
+                return Ok(outcome);
+            }
+            BisectState::Testing { remaining } => remaining,
+        };
+        let (sha, summary) = current_bisect_commit(root)?;
+        let exit_code = run_test().await?;
+        let verdict = verdict_for_exit_code(exit_code)?;
+        let step = GitBisectStep {
+            sha,
+            summary,
+            verdict: verdict.to_string(),
+            exit_code,
+            remaining_revisions: remaining,
+        };
+        on_step(&step);
+        outcome.steps.push(step);
+        let (marked, next_output) = run_bisect_git(root, &["bisect", verdict]).await?;
+        // `git bisect skip` exits non-zero when only skipped commits remain.
+        if !marked
+            && matches!(
+                parse_bisect_output(&next_output),
+                BisectState::Testing { .. }
+            )
+        {
+            return Err(next_output);
+        }
+        output = next_output;
+    }
+    Err(format!(
+        "Bisect did not converge after {BISECT_MAX_STEPS} steps."
+    ))
+}
+
+#[tauri::command]
+pub(crate) async fn run_git_bisect(
+    workspace_id: String,
+    good_ref: String,
+    bad_ref: String,
+    command: Vec<String>,
+    timeout_ms: Option<u64>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<GitBisectResult, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return Err("run_git_bisect is not supported in remote mode yet.".to_string());
+    }
+    let good_ref = good_ref.trim().to_string();
+    let bad_ref = bad_ref.trim().to_string();
+    if good_ref.is_empty() || bad_ref.is_empty() {
+        return Err("Both a good and a bad ref are required.".to_string());
+    }
+    if command.is_empty() {
+        return Err("Test command cannot be empty.".to_string());
+    }
+
+    // Worktrees cannot be nested, so bisect from the owning repository.
+    let parent = {
+        let workspaces = state.workspaces.lock().await;
+        let entry = workspaces.get(&workspace_id).ok_or("workspace not found")?;
+        match entry
+            .parent_id
+            .as_ref()
+            .filter(|_| entry.kind.is_worktree())
+        {
+            Some(parent_id) => workspaces
+                .get(parent_id)
+                .ok_or("worktree parent not found")?
+                .clone(),
+            None => entry.clone(),
+        }
+    };
+
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    let branch = format!("bisect/{}", &token[..8]);
+    // The worktree is transient: it never shows up in the workspace list and
+    // is removed by the guard whatever happens below.
+    let worktree = add_local_worktree(
+        parent.id.clone(),
+        branch.clone(),
+        Some(bad_ref.clone()),
+        false,
+        true,
+        &state,
+        &app,
+    )
+    .await?;
+    let worktree_root = PathBuf::from(&worktree.path);
+    let checkout = BisectCheckout {
+        app: app.clone(),
+        worktree_id: worktree.id.clone(),
+        worktree_root: worktree_root.clone(),
+        parent_path: PathBuf::from(&parent.path),
+        branch,
+    };
+
+    let outcome = drive_bisect(
+        &worktree_root,
+        &good_ref,
+        &bad_ref,
+        || {
+            let worktree_id = worktree.id.clone();
+            let command = command.clone();
+            let state = state.clone();
+            let app = app.clone();
+            async move {
+                run_workspace_command(worktree_id, command, timeout_ms, state, app)
+                    .await
+                    .map(|result| result.exit_code)
+            }
+        },
+        |step| {
+            let _ = app.emit(
+                "git-bisect-progress",
+                json!({
+                    "workspaceId": workspace_id,
+                    "sha": step.sha,
+                    "summary": step.summary,
+                    "verdict": step.verdict,
+                    "remainingRevisions": step.remaining_revisions,
+                }),
+            );
+        },
+    )
+    .await;
+
+    drop(checkout);
+
+    let outcome = outcome?;
+    let first_bad_commit = match outcome.first_bad {
+        Some(sha) => Some(get_git_commit_details(parent.id.clone(), sha, None, state).await?),
+        None => None,
+    };
+    Ok(GitBisectResult {
+        status: if first_bad_commit.is_some() {
+            "found".to_string()
+        } else {
+            "inconclusive".to_string()
+        },
+        good_ref,
+        bad_ref,
+        steps: outcome.steps,
+        first_bad_commit,
+        candidates: outcome.candidates,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::git;
+    use std::fs;
+
+    #[test]
+    fn parses_bisect_progress_and_results() {
+        let sha = "a".repeat(40);
+        assert_eq!(
+            parse_bisect_output(&format!(
+                "Bisecting: 6 revisions left to test after this (roughly 3 steps)\n[{sha}] wip"
+            )),
+            BisectState::Testing { remaining: Some(6) }
+        );
+        assert_eq!(
+            parse_bisect_output(&format!("{sha} is the first bad commit\ncommit {sha}")),
+            BisectState::FirstBad(sha.clone())
+        );
+        let other = "b".repeat(40);
+        assert_eq!(
+            parse_bisect_output(&format!(
+                "There are only 'skip'ped commits left to test.\n\
+                 The first bad commit could be any of:\n{sha}\n{other}\nWe cannot bisect more!"
+            )),
+            BisectState::OnlySkipped(vec![sha, other])
+        );
+    }
+
+    #[test]
+    fn maps_exit_codes_like_git_bisect_run() {
+        assert_eq!(verdict_for_exit_code(0), Ok("good"));
+        assert_eq!(verdict_for_exit_code(1), Ok("bad"));
+        assert_eq!(verdict_for_exit_code(125), Ok("skip"));
+        assert!(verdict_for_exit_code(128).is_err());
+        assert!(verdict_for_exit_code(-1).is_err());
+    }
+
+    #[tokio::test]
+    async fn drive_bisect_finds_first_bad_commit() {
+        let root = std::env::temp_dir().join(format!("moss-bisect-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create temp repo");
+        git(&root, &["init", "-q", "-b", "main"]);
+        let mut culprit = String::new();
+        for index in 0..8 {
+            let value = if index >= 5 { "broken" } else { "ok" };
+            fs::write(root.join("status.txt"), value).expect("write status");
+            fs::write(root.join(format!("file-{index}.txt")), "content\n").expect("write file");
+            git(&root, &["add", "."]);
+            git(&root, &["commit", "-q", "-m", &format!("commit {index}")]);
+            if index == 5 {
+                culprit = git(&root, &["rev-parse", "HEAD"]);
+            }
+        }
+
+        let status_path = root.join("status.txt");
+        let mut seen = 0;
+        let outcome = drive_bisect(
+            &root,
+            "HEAD~7",
+            "HEAD",
+            || {
+                let status_path = status_path.clone();
+                async move {
+                    let content = fs::read_to_string(status_path).map_err(|e| e.to_string())?;
+                    Ok(if content == "ok" { 0 } else { 1 })
+                }
+            },
+            |_| seen += 1,
+        )
+        .await
+        .expect("bisect");
+
+        assert_eq!(outcome.first_bad.as_deref(), Some(culprit.as_str()));
+        assert_eq!(seen, outcome.steps.len());
+        assert!(!outcome.steps.is_empty());
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use git2::{Oid, Repository};
+use tauri::State;
+
+use super::{
+    collect_branch_list, delete_git_branch, infer_remote_head_branch, open_repository_at_root,
+    parse_remote_branch, resolve_ref_to_oid, run_git_command,
+};
+use crate::git_utils::resolve_git_root;
+use crate::state::AppState;
+use crate::types::{
+    GitBranchDeleteResult, GitBranchHygieneEntry, GitBranchHygieneReport, GitBranchListItem,
+};
+
+const DEFAULT_STALE_DAYS: u32 = 30;
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Maps branch name to worktree path from `git worktree list --porcelain`.
+fn parse_worktree_branches(output: &str) -> HashMap<String, String> {
+    let mut branches = HashMap::new();
+    let mut current_path: Option<&str> = None;
+    for line in output.lines() {
+        if let Some(path) = line.strip_prefix("worktree ") {
+            current_path = Some(path.trim());
+        } else if let Some(reference) = line.strip_prefix("branch ") {
+            let name = reference
+                .trim()
+                .strip_prefix("refs/heads/")
+                .unwrap_or(reference.trim());
+            if let Some(path) = current_path {
+                branches.insert(name.to_string(), path.to_string());
+            }
+        } else if line.trim().is_empty() {
+            current_path = None;
+        }
+    }
+    branches
+}
+
+fn default_base_branch(repo: &Repository, current_branch: Option<&str>) -> Option<String> {
+    if let Some(head) = infer_remote_head_branch(repo, "origin") {
+        if repo.find_branch(&head, git2::BranchType::Local).is_ok() {
+            return Some(head);
+        }
+        return Some(format!("origin/{head}"));
+    }
+    ["main", "master"]
+        .into_iter()
+        .find(|name| repo.find_branch(name, git2::BranchType::Local).is_ok())
+        .map(ToOwned::to_owned)
+        .or_else(|| current_branch.map(ToOwned::to_owned))
+}
+
+fn is_merged_into(repo: &Repository, head: Option<&str>, base_oid: Oid) -> bool {
+    let Some(head_oid) = head.and_then(|sha| Oid::from_str(sha).ok()) else {
+        return false;
+    };
+    head_oid == base_oid
+        || repo
+            .graph_descendant_of(base_oid, head_oid)
+            .unwrap_or(false)
+}
+
+/// True when the branch tracks an upstream whose remote ref no longer exists.
+fn is_gone_upstream(repo: &Repository, name: &str) -> bool {
+    let Ok(upstream) = repo.branch_upstream_name(&format!("refs/heads/{name}")) else {
+        return false;
+    };
+    let Some(upstream) = upstream.as_str() else {
+        return false;
+    };
+    upstream.starts_with("refs/remotes/") && repo.find_reference(upstream).is_err()
+}
+
+struct HygieneContext<'a> {
+    base_name: &'a str,
+    base_oid: Oid,
+    stale_before: i64,
+    worktrees: &'a HashMap<String, String>,
+}
+
+fn classify_branch(
+    repo: &Repository,
+    branch: GitBranchListItem,
+    context: &HygieneContext<'_>,
+) -> GitBranchHygieneEntry {
+    let mut categories = Vec::new();
+    let is_base = branch.name == context.base_name;
+    let merged = !is_base && is_merged_into(repo, branch.head_sha.as_deref(), context.base_oid);
+    if merged {
+        categories.push("merged".to_string());
+    }
+    if !branch.is_remote && is_gone_upstream(repo, &branch.name) {
+        categories.push("gone-upstream".to_string());
+    }
+    if branch.last_commit > 0 && branch.last_commit < context.stale_before {
+        categories.push("stale".to_string());
+    }
+    let worktree_path = if branch.is_remote || branch.is_current {
+        None
+    } else {
+        context.worktrees.get(&branch.name).cloned()
+    };
+    if worktree_path.is_some() {
+        categories.push("worktree".to_string());
+    }
+    let safe_to_delete =
+        merged && !branch.is_remote && !branch.is_current && worktree_path.is_none();
+    GitBranchHygieneEntry {
+        branch,
+        categories,
+        worktree_path,
+        safe_to_delete,
+    }
+}
+
+async fn resolve_hygiene_root(
+    workspace_id: &str,
+    state: &State<'_, AppState>,
+) -> Result<PathBuf, String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+    drop(workspaces);
+    resolve_git_root(&entry)
+}
+
+#[tauri::command]
+pub(crate) async fn classify_git_branches(
+    workspace_id: String,
+    base_branch: Option<String>,
+    stale_days: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<GitBranchHygieneReport, String> {
+    let repo_root = resolve_hygiene_root(&workspace_id, &state).await?;
+    let worktree_output =
+        crate::shared::git_core::run_git_command(&repo_root, &["worktree", "list", "--porcelain"])
+            .await
+            .unwrap_or_default();
+    let worktrees = parse_worktree_branches(&worktree_output);
+
+    let repo = open_repository_at_root(&repo_root)?;
+    let current_branch = repo
+        .head()
+        .ok()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.shorthand().map(|name| name.to_string()));
+    let base_name = base_branch
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .or_else(|| default_base_branch(&repo, current_branch.as_deref()))
+        .ok_or("Unable to determine a base branch.")?;
+    let base_oid = resolve_ref_to_oid(&repo, &base_name)?;
+    let stale_days = stale_days.unwrap_or(DEFAULT_STALE_DAYS).max(1);
+    let stale_before = chrono::Utc::now().timestamp() - i64::from(stale_days) * SECONDS_PER_DAY;
+
+    let (_, local_branches, remote_branches) =
+        collect_branch_list(&repo, current_branch.as_deref())?;
+    let context = HygieneContext {
+        base_name: &base_name,
+        base_oid,
+        stale_before,
+        worktrees: &worktrees,
+    };
+    let branches = local_branches
+        .into_iter()
+        .chain(remote_branches)
+        .map(|branch| classify_branch(&repo, branch, &context))
+        .collect();
+    Ok(GitBranchHygieneReport {
+        base_sha: base_oid.to_string(),
+        base_branch: base_name,
+        stale_days,
+        branches,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn delete_git_branches(
+    workspace_id: String,
+    branches: Vec<String>,
+    remote_branches: Option<Vec<String>>,
+    force: Option<bool>,
+    remove_occupied_worktree: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<GitBranchDeleteResult>, String> {
+    let mut results = Vec::new();
+    for name in branches {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            continue;
+        }
+        // Reuse the single-branch path so worktree-in-use checks stay identical.
+        let outcome = delete_git_branch(
+            workspace_id.clone(),
+            name.clone(),
+            force,
+            remove_occupied_worktree,
+            state.clone(),
+        )
+        .await;
+        results.push(GitBranchDeleteResult {
+            name,
+            is_remote: false,
+            deleted: outcome.is_ok(),
+            error: outcome.err(),
+        });
+    }
+
+    let remote_branches = remote_branches.unwrap_or_default();
+    if !remote_branches.is_empty() {
+        let repo_root = resolve_hygiene_root(&workspace_id, &state).await?;
+        for name in remote_branches {
+            let name = name.trim().to_string();
+            let Some((remote, branch)) = parse_remote_branch(&name) else {
+                results.push(GitBranchDeleteResult {
+                    name,
+                    is_remote: true,
+                    deleted: false,
+                    error: Some("Expected a remote branch like origin/feature.".to_string()),
+                });
+                continue;
+            };
+            let outcome =
+                run_git_command(&repo_root, &["push", &remote, "--delete", &branch]).await;
+            results.push(GitBranchDeleteResult {
+                name,
+                is_remote: true,
+                deleted: outcome.is_ok(),
+                error: outcome.err(),
+            });
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::git;
+    use std::fs;
+
+    #[test]
+    fn parses_worktree_porcelain_branches() {
+        let output = "worktree /repo\nHEAD abc\nbranch refs/heads/main\n\n\
+                      worktree /data/worktrees/feat\nHEAD def\nbranch refs/heads/feat/x\n\n\
+                      worktree /data/detached\nHEAD 123\ndetached\n";
+        let branches = parse_worktree_branches(output);
+        assert_eq!(branches.get("main").map(String::as_str), Some("/repo"));
+        assert_eq!(
+            branches.get("feat/x").map(String::as_str),
+            Some("/data/worktrees/feat")
+        );
+        assert_eq!(branches.len(), 2);
+    }
+
+    #[test]
+    fn classifies_merged_gone_and_worktree_branches() {
+        let root = std::env::temp_dir().join(format!("moss-hygiene-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create repo");
+        git(&root, &["init", "-q", "-b", "main"]);
+        fs::write(root.join("a.txt"), "a").expect("write");
+        git(&root, &["add", "."]);
+        git(&root, &["commit", "-q", "-m", "base"]);
+        git(&root, &["branch", "feat/merged"]);
+        git(&root, &["checkout", "-q", "-b", "feat/open"]);
+        fs::write(root.join("b.txt"), "b").expect("write");
+        git(&root, &["add", "."]);
+        git(&root, &["commit", "-q", "-m", "open work"]);
+        git(&root, &["checkout", "-q", "main"]);
+        git(&root, &["config", "branch.feat/open.remote", "origin"]);
+        git(
+            &root,
+            &["config", "branch.feat/open.merge", "refs/heads/feat/open"],
+        );
+        git(
+            &root,
+            &["remote", "add", "origin", "https://example.com/repo.git"],
+        );
+
+        let repo = Repository::open(&root).expect("open repo");
+        let base_oid = resolve_ref_to_oid(&repo, "main").expect("base");
+        let worktrees = HashMap::from([("feat/merged".to_string(), "/tmp/wt".to_string())]);
+        let context = HygieneContext {
+            base_name: "main",
+            base_oid,
+            stale_before: 0,
+            worktrees: &worktrees,
+        };
+        let (_, local, _) = collect_branch_list(&repo, Some("main")).expect("branches");
+        let entries = local
+            .into_iter()
+            .map(|branch| classify_branch(&repo, branch, &context))
+            .map(|entry| (entry.branch.name.clone(), entry))
+            .collect::<HashMap<_, _>>();
+
+        let merged = &entries["feat/merged"];
+        assert_eq!(merged.categories, vec!["merged", "worktree"]);
+        assert!(!merged.safe_to_delete);
+        let open = &entries["feat/open"];
+        assert_eq!(open.categories, vec!["gone-upstream"]);
+        assert!(!open.safe_to_delete);
+        assert!(entries["main"].categories.is_empty());
+        let _ = fs::remove_dir_all(root);
+    }
+}
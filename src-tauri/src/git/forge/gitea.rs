@@ -0,0 +1,394 @@
+use reqwest::Method;
+use serde_json::{json, Value};
+
+use super::{
+    is_left_side, json_str, parse_pr_diff, pull_request_head_from, ForgeClient, PullRequestHead,
+    ReviewEvent,
+};
+use crate::types::{
+    GitHubIssue, GitHubIssuesResponse, GitHubPullRequest, GitHubPullRequestAuthor,
+    GitHubPullRequestComment, GitHubPullRequestDiff, GitHubPullRequestReviewComment,
+    GitHubPullRequestReviewResult, GitHubPullRequestsResponse, GitPrExistingPullRequest,
+};
+
+fn repo_path(client: &ForgeClient) -> String {
+    format!("/repos/{}", client.remote.repo)
+}
+
+fn user_from(value: &Value) -> Option<GitHubPullRequestAuthor> {
+    value
+        .get("user")
+        .and_then(|user| user.get("login"))
+        .and_then(Value::as_str)
+        .map(|login| GitHubPullRequestAuthor {
+            login: login.to_string(),
+        })
+}
+
+fn branch_ref(value: &Value, side: &str) -> String {
+    value
+        .get(side)
+        .map(|branch| json_str(branch, "ref"))
+        .unwrap_or_default()
+}
+
+fn as_array(value: Value) -> Vec<Value> {
+    match value {
+        Value::Array(items) => items,
+        _ => Vec::new(),
+    }
+}
+
+pub(super) async fn list_issues(client: &ForgeClient) -> Result<GitHubIssuesResponse, String> {
+    let (value, total) = client
+        .get_json(&format!(
+            "{}/issues?state=open&type=issues&limit=50",
+            repo_path(client)
+        ))
+        .await?;
+    let issues = as_array(value)
+        .iter()
+        .map(|issue| GitHubIssue {
+            number: issue.get("number").and_then(Value::as_u64).unwrap_or(0),
+            title: json_str(issue, "title"),
+            url: json_str(issue, "html_url"),
+            updated_at: json_str(issue, "updated_at"),
+        })
+        .collect::<Vec<_>>();
+    Ok(GitHubIssuesResponse {
+        total: total.unwrap_or(issues.len()),
+        issues,
+    })
+}
+
+fn pull_request_from(value: &Value) -> GitHubPullRequest {
+    let title = json_str(value, "title");
+    // Older Gitea releases have no `draft` field and mark drafts by title prefix.
+    let is_draft = value
+        .get("draft")
+        .and_then(Value::as_bool)
+        .unwrap_or_else(|| {
+            let lower = title.to_lowercase();
+            lower.starts_with("wip:") || lower.starts_with("[wip]") || lower.starts_with("draft:")
+        });
+    GitHubPullRequest {
+        number: value.get("number").and_then(Value::as_u64).unwrap_or(0),
+        url: json_str(value, "html_url"),
+        updated_at: json_str(value, "updated_at"),
+        created_at: json_str(value, "created_at"),
+        body: json_str(value, "body"),
+        head_ref_name: branch_ref(value, "head"),
+        base_ref_name: branch_ref(value, "base"),
+        is_draft,
+        author: user_from(value),
+        title,
+    }
+}
+
+pub(super) async fn list_pull_requests(
+    client: &ForgeClient,
+) -> Result<GitHubPullRequestsResponse, String> {
+    let (value, total) = client
+        .get_json(&format!("{}/pulls?state=open&limit=50", repo_path(client)))
+        .await?;
+    let pull_requests = as_array(value)
+        .iter()
+        .map(pull_request_from)
+        .collect::<Vec<_>>();
+    Ok(GitHubPullRequestsResponse {
+        total: total.unwrap_or(pull_requests.len()),
+        pull_requests,
+    })
+}
+
+pub(super) async fn pull_request_diff(
+    client: &ForgeClient,
+    number: u64,
+) -> Result<Vec<GitHubPullRequestDiff>, String> {
+    let (diff_text, _) = client
+        .request_text(
+            Method::GET,
+            &format!("{}/pulls/{number}.diff", repo_path(client)),
+            None,
+        )
+        .await?;
+    Ok(parse_pr_diff(&diff_text))
+}
+
+pub(super) async fn pull_request_head(
+    client: &ForgeClient,
+    number: u64,
+) -> Result<PullRequestHead, String> {
+    let (pull, _) = client
+        .get_json(&format!("{}/pulls/{number}", repo_path(client)))
+        .await?;
+    Ok(pull_request_head_from(&pull))
+}
+
+pub(super) async fn pull_request_comments(
+    client: &ForgeClient,
+    number: u64,
+) -> Result<Vec<GitHubPullRequestComment>, String> {
+    let (value, _) = client
+        .get_json(&format!(
+            "{}/issues/{number}/comments?limit=30",
+            repo_path(client)
+        ))
+        .await?;
+    Ok(as_array(value).iter().map(comment_from).collect())
+}
+
+pub(super) async fn find_open_pull_request(
+    client: &ForgeClient,
+    head_owner: &str,
+    head_branch: &str,
+) -> Result<Option<GitPrExistingPullRequest>, String> {
+    let (value, _) = client
+        .get_json(&format!("{}/pulls?state=open&limit=50", repo_path(client)))
+        .await?;
+    let found = as_array(value).into_iter().find(|pr| {
+        let head = pr.get("head").cloned().unwrap_or(Value::Null);
+        let owner = head
+            .get("repo")
+            .and_then(|repo| repo.get("owner"))
+            .map(|owner| json_str(owner, "login"))
+            .unwrap_or_default();
+        json_str(&head, "ref") == head_branch && (head_owner.is_empty() || owner == head_owner)
+    });
+    Ok(found.map(|pr| GitPrExistingPullRequest {
+        number: pr.get("number").and_then(Value::as_u64).unwrap_or(0),
+        title: json_str(&pr, "title"),
+        url: json_str(&pr, "html_url"),
+        state: json_str(&pr, "state"),
+        head_ref_name: branch_ref(&pr, "head"),
+        base_ref_name: branch_ref(&pr, "base"),
+    }))
+}
+
+pub(super) async fn create_pull_request(
+    client: &ForgeClient,
+    base_branch: &str,
+    head_owner: &str,
+    head_branch: &str,
+    title: &str,
+    body: &str,
+) -> Result<(String, u64), String> {
+    let target_owner = client.remote.repo.split('/').next().unwrap_or("");
+    let head = if head_owner.is_empty() || head_owner == target_owner {
+        head_branch.to_string()
+    } else {
+        format!("{head_owner}:{head_branch}")
+    };
+    let created = client
+        .post_json(
+            &format!("{}/pulls", repo_path(client)),
+            &json!({
+                "head": head,
+                "base": base_branch,
+                "title": title,
+                "body": body,
+            }),
+        )
+        .await?;
+    let number = created
+        .get("number")
+        .and_then(Value::as_u64)
+        .ok_or("Gitea did not return a pull request number.")?;
+    Ok((json_str(&created, "html_url"), number))
+}
+
+fn comment_from(comment: &Value) -> GitHubPullRequestComment {
+    GitHubPullRequestComment {
+        id: comment.get("id").and_then(Value::as_u64).unwrap_or(0),
+        body: json_str(comment, "body"),
+        created_at: json_str(comment, "created_at"),
+        url: json_str(comment, "html_url"),
+        author: user_from(comment),
+    }
+}
+
+pub(super) async fn post_comment(
+    client: &ForgeClient,
+    number: u64,
+    body: &str,
+) -> Result<GitHubPullRequestComment, String> {
+    let created = client
+        .post_json(
+            &format!("{}/issues/{number}/comments", repo_path(client)),
+            &json!({ "body": body }),
+        )
+        .await?;
+    Ok(comment_from(&created))
+}
+
+fn review_comment_payload(comment: &GitHubPullRequestReviewComment) -> Value {
+    let mut payload = json!({
+        "path": comment.path,
+        "body": comment.body,
+    });
+    if is_left_side(comment) {
+        payload["old_position"] = json!(comment.line);
+    } else {
+        payload["new_position"] = json!(comment.line);
+    }
+    payload
+}
+
+fn review_event(event: ReviewEvent) -> &'static str {
+    match event {
+        ReviewEvent::Approve => "APPROVED",
+        ReviewEvent::RequestChanges => "REQUEST_CHANGES",
+        ReviewEvent::Comment => "COMMENT",
+    }
+}
+
+async fn create_review(
+    client: &ForgeClient,
+    number: u64,
+    event: ReviewEvent,
+    body: &str,
+    comments: &[GitHubPullRequestReviewComment],
+) -> Result<Value, String> {
+    client
+        .post_json(
+            &format!("{}/pulls/{number}/reviews", repo_path(client)),
+            &json!({
+                "event": review_event(event),
+                "body": body,
+                "comments": comments.iter().map(review_comment_payload).collect::<Vec<_>>(),
+            }),
+        )
+        .await
+}
+
+/// Gitea has no standalone inline comment endpoint, so the comment is
+/// published as a single-comment review.
+pub(super) async fn post_inline_comment(
+    client: &ForgeClient,
+    number: u64,
+    comment: &GitHubPullRequestReviewComment,
+) -> Result<GitHubPullRequestComment, String> {
+    let review = create_review(
+        client,
+        number,
+        ReviewEvent::Comment,
+        "",
+        std::slice::from_ref(comment),
+    )
+    .await?;
+    Ok(GitHubPullRequestComment {
+        id: review.get("id").and_then(Value::as_u64).unwrap_or(0),
+        body: comment.body.clone(),
+        created_at: json_str(&review, "submitted_at"),
+        url: json_str(&review, "html_url"),
+        author: user_from(&review),
+    })
+}
+
+pub(super) async fn submit_review(
+    client: &ForgeClient,
+    number: u64,
+    event: ReviewEvent,
+    body: &str,
+    comments: &[GitHubPullRequestReviewComment],
+) -> Result<GitHubPullRequestReviewResult, String> {
+    let review = create_review(client, number, event, body, comments).await?;
+    let state = json_str(&review, "state");
+    Ok(GitHubPullRequestReviewResult {
+        id: review.get("id").and_then(Value::as_u64),
+        url: Some(json_str(&review, "html_url")).filter(|url| !url.is_empty()),
+        state: if state.is_empty() {
+            review_event(event).to_string()
+        } else {
+            state
+        },
+        comment_count: comments.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::start_http_stub;
+    use super::super::{parse_forge_remote, ForgeClient};
+    use super::*;
+
+    #[tokio::test]
+    async fn lists_issues_and_creates_fork_pull_request_on_gitea() {
+        let stub = start_http_stub(vec![
+            (
+                "GET /api/v1/repos/team/tool/issues",
+                200,
+                vec![("X-Total-Count", "12".to_string())],
+                r#"[{"number":9,"title":"Bug","html_url":"https://gt/issues/9","updated_at":"u"}]"#
+                    .to_string(),
+            ),
+            (
+                "POST /api/v1/repos/team/tool/pulls",
+                201,
+                Vec::new(),
+                r#"{"number":4,"html_url":"https://gt/pulls/4"}"#.to_string(),
+            ),
+        ]);
+        let remote = parse_forge_remote(&format!("{}/team/tool.git", stub.base_url), Some("gitea"))
+            .expect("remote");
+        let client = ForgeClient::with_token(&remote, Some("secret".to_string())).expect("client");
+
+        let issues = list_issues(&client).await.expect("issues");
+        assert_eq!(issues.total, 12);
+        assert_eq!(issues.issues[0].number, 9);
+
+        let (url, number) = create_pull_request(&client, "main", "me", "feat", "Title", "Body")
+            .await
+            .expect("create");
+        assert_eq!(number, 4);
+        assert_eq!(url, "https://gt/pulls/4");
+        let requests = stub.requests.lock().expect("requests");
+        let (_, body) = requests
+            .iter()
+            .find(|(key, _)| key.starts_with("POST"))
+            .expect("post recorded");
+        let payload: Value = serde_json::from_str(body).expect("json body");
+        assert_eq!(payload["head"], "me:feat");
+        assert_eq!(payload["base"], "main");
+    }
+
+    #[tokio::test]
+    async fn submits_review_with_inline_positions_on_gitea() {
+        let stub = start_http_stub(vec![(
+            "POST /api/v1/repos/team/tool/pulls/4/reviews",
+            200,
+            Vec::new(),
+            r#"{"id":31,"state":"REQUEST_CHANGES","html_url":"https://gt/pulls/4#review-31"}"#
+                .to_string(),
+        )]);
+        let remote = parse_forge_remote(&format!("{}/team/tool.git", stub.base_url), Some("gitea"))
+            .expect("remote");
+        let client = ForgeClient::with_token(&remote, Some("secret".to_string())).expect("client");
+        let comments = vec![GitHubPullRequestReviewComment {
+            path: "src/main.rs".to_string(),
+            line: 7,
+            side: Some("left".to_string()),
+            start_line: None,
+            body: "Why remove this?".to_string(),
+        }];
+
+        let result = submit_review(
+            &client,
+            4,
+            ReviewEvent::RequestChanges,
+            "Needs work",
+            &comments,
+        )
+        .await
+        .expect("review");
+        assert_eq!(result.id, Some(31));
+        assert_eq!(result.state, "REQUEST_CHANGES");
+        assert_eq!(result.comment_count, 1);
+
+        let requests = stub.requests.lock().expect("requests");
+        let payload: Value = serde_json::from_str(&requests[0].1).expect("json body");
+        assert_eq!(payload["event"], "REQUEST_CHANGES");
+        assert_eq!(payload["comments"][0]["old_position"], 7);
+        assert!(payload["comments"][0].get("new_position").is_none());
+    }
+}
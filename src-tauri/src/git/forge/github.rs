@@ -0,0 +1,423 @@
+use std::process::Stdio;
+
+use serde_json::{json, Value};
+use tokio::io::AsyncWriteExt;
+
+use super::{
+    is_left_side, json_str, parse_pr_diff, pull_request_head_from, ForgeClient, PullRequestHead,
+    ReviewEvent,
+};
+use crate::types::{
+    GitHubIssue, GitHubIssuesResponse, GitHubPullRequest, GitHubPullRequestAuthor,
+    GitHubPullRequestComment, GitHubPullRequestDiff, GitHubPullRequestReviewComment,
+    GitHubPullRequestReviewResult, GitHubPullRequestsResponse, GitPrExistingPullRequest,
+};
+
+fn gh_failure(output: &std::process::Output) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let detail = if stderr.trim().is_empty() {
+        stdout.trim()
+    } else {
+        stderr.trim()
+    };
+    if detail.is_empty() {
+        return "GitHub CLI command failed.".to_string();
+    }
+    detail.to_string()
+}
+
+/// Runs a `gh` subcommand and returns its stdout.
+async fn run_gh(args: &[&str]) -> Result<Vec<u8>, String> {
+    let output = crate::utils::async_command("gh")
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run gh: {e}"))?;
+    if !output.status.success() {
+        return Err(gh_failure(&output));
+    }
+    Ok(output.stdout)
+}
+
+/// Runs `gh api` with an optional JSON request body piped through stdin.
+async fn run_gh_api(
+    client: &ForgeClient,
+    method: &str,
+    endpoint: &str,
+    payload: Option<&Value>,
+) -> Result<Value, String> {
+    let mut command = crate::utils::async_command("gh");
+    command
+        .args([
+            "api",
+            "--hostname",
+            &client.remote.host,
+            "-X",
+            method,
+            endpoint,
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if payload.is_some() {
+        command.args(["--input", "-"]).stdin(Stdio::piped());
+    } else {
+        command.stdin(Stdio::null());
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to run gh: {e}"))?;
+    if let Some(payload) = payload {
+        let mut stdin = child.stdin.take().ok_or("Failed to open gh stdin.")?;
+        stdin
+            .write_all(payload.to_string().as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write gh input: {e}"))?;
+        drop(stdin);
+    }
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| format!("Failed to run gh: {e}"))?;
+
+    if !output.status.success() {
+        return Err(gh_failure(&output));
+    }
+    if output.stdout.iter().all(u8::is_ascii_whitespace) {
+        return Ok(Value::Null);
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())
+}
+
+/// Open search results for `repo:<repo> <qualifiers>`; `None` when the
+/// search API is unavailable.
+async fn search_total(client: &ForgeClient, qualifiers: &str) -> Option<usize> {
+    let query = format!("repo:{} {qualifiers}", client.remote.repo).replace(' ', "+");
+    let endpoint = format!("/search/issues?q={query}");
+    let output = run_gh(&[
+        "api",
+        "--hostname",
+        &client.remote.host,
+        &endpoint,
+        "--jq",
+        ".total_count",
+    ])
+    .await
+    .ok()?;
+    String::from_utf8_lossy(&output).trim().parse().ok()
+}
+
+fn comment_payload(comment: &GitHubPullRequestReviewComment) -> Value {
+    let side = if is_left_side(comment) {
+        "LEFT"
+    } else {
+        "RIGHT"
+    };
+    let mut payload = json!({
+        "path": comment.path,
+        "line": comment.line,
+        "side": side,
+        "body": comment.body,
+    });
+    if let Some(start_line) = comment.start_line {
+        payload["start_line"] = json!(start_line);
+        payload["start_side"] = json!(side);
+    }
+    payload
+}
+
+fn review_event(event: ReviewEvent) -> &'static str {
+    match event {
+        ReviewEvent::Approve => "APPROVE",
+        ReviewEvent::RequestChanges => "REQUEST_CHANGES",
+        ReviewEvent::Comment => "COMMENT",
+    }
+}
+
+fn review_payload(
+    event: ReviewEvent,
+    body: &str,
+    comments: &[GitHubPullRequestReviewComment],
+) -> Value {
+    let mut payload = json!({
+        "event": review_event(event),
+        "comments": comments.iter().map(comment_payload).collect::<Vec<_>>(),
+    });
+    if !body.is_empty() {
+        payload["body"] = json!(body);
+    }
+    payload
+}
+
+fn comment_from(value: &Value) -> GitHubPullRequestComment {
+    GitHubPullRequestComment {
+        id: value.get("id").and_then(Value::as_u64).unwrap_or(0),
+        body: json_str(value, "body"),
+        created_at: json_str(value, "created_at"),
+        url: json_str(value, "html_url"),
+        author: value
+            .get("user")
+            .and_then(|user| user.get("login"))
+            .and_then(Value::as_str)
+            .map(|login| GitHubPullRequestAuthor {
+                login: login.to_string(),
+            }),
+    }
+}
+
+pub(super) async fn list_issues(client: &ForgeClient) -> Result<GitHubIssuesResponse, String> {
+    let repo_name = client.remote.gh_repo();
+    let output = run_gh(&[
+        "issue",
+        "list",
+        "--repo",
+        &repo_name,
+        "--limit",
+        "50",
+        "--json",
+        "number,title,url,updatedAt",
+    ])
+    .await?;
+    let issues: Vec<GitHubIssue> = serde_json::from_slice(&output).map_err(|e| e.to_string())?;
+    let total = search_total(client, "is:issue is:open")
+        .await
+        .unwrap_or(issues.len());
+    Ok(GitHubIssuesResponse { total, issues })
+}
+
+pub(super) async fn list_pull_requests(
+    client: &ForgeClient,
+) -> Result<GitHubPullRequestsResponse, String> {
+    let repo_name = client.remote.gh_repo();
+    let output = run_gh(&[
+        "pr",
+        "list",
+        "--repo",
+        &repo_name,
+        "--state",
+        "open",
+        "--limit",
+        "50",
+        "--json",
+        "number,title,url,updatedAt,createdAt,body,headRefName,baseRefName,isDraft,author",
+    ])
+    .await?;
+    let pull_requests: Vec<GitHubPullRequest> =
+        serde_json::from_slice(&output).map_err(|e| e.to_string())?;
+    let total = search_total(client, "is:pr is:open")
+        .await
+        .unwrap_or(pull_requests.len());
+    Ok(GitHubPullRequestsResponse {
+        total,
+        pull_requests,
+    })
+}
+
+pub(super) async fn pull_request_diff(
+    client: &ForgeClient,
+    number: u64,
+) -> Result<Vec<GitHubPullRequestDiff>, String> {
+    let repo_name = client.remote.gh_repo();
+    let output = run_gh(&[
+        "pr",
+        "diff",
+        &number.to_string(),
+        "--repo",
+        &repo_name,
+        "--color",
+        "never",
+    ])
+    .await?;
+    Ok(parse_pr_diff(&String::from_utf8_lossy(&output)))
+}
+
+pub(super) async fn pull_request_head(
+    client: &ForgeClient,
+    number: u64,
+) -> Result<PullRequestHead, String> {
+    let endpoint = format!("/repos/{}/pulls/{number}", client.remote.repo);
+    let pull = run_gh_api(client, "GET", &endpoint, None).await?;
+    Ok(pull_request_head_from(&pull))
+}
+
+pub(super) async fn pull_request_comments(
+    client: &ForgeClient,
+    number: u64,
+) -> Result<Vec<GitHubPullRequestComment>, String> {
+    let endpoint = format!(
+        "/repos/{}/issues/{number}/comments?per_page=30",
+        client.remote.repo
+    );
+    let comments = run_gh_api(client, "GET", &endpoint, None).await?;
+    Ok(comments
+        .as_array()
+        .map(|comments| comments.iter().map(comment_from).collect())
+        .unwrap_or_default())
+}
+
+pub(super) async fn find_open_pull_request(
+    client: &ForgeClient,
+    head_owner: &str,
+    head_branch: &str,
+) -> Result<Option<GitPrExistingPullRequest>, String> {
+    let owner = if head_owner.is_empty() {
+        client.remote.repo.split('/').next().unwrap_or("")
+    } else {
+        head_owner
+    };
+    let endpoint = format!(
+        "/repos/{}/pulls?state=open&head={owner}:{head_branch}",
+        client.remote.repo
+    );
+    let pulls = run_gh_api(client, "GET", &endpoint, None).await?;
+    Ok(pulls
+        .as_array()
+        .and_then(|pulls| pulls.first())
+        .map(|pr| GitPrExistingPullRequest {
+            number: pr.get("number").and_then(Value::as_u64).unwrap_or(0),
+            title: json_str(pr, "title"),
+            url: json_str(pr, "html_url"),
+            state: json_str(pr, "state"),
+            head_ref_name: pr
+                .get("head")
+                .map(|head| json_str(head, "ref"))
+                .unwrap_or_default(),
+            base_ref_name: pr
+                .get("base")
+                .map(|base| json_str(base, "ref"))
+                .unwrap_or_default(),
+        }))
+}
+
+pub(super) async fn create_pull_request(
+    client: &ForgeClient,
+    base_branch: &str,
+    head_owner: &str,
+    head_branch: &str,
+    title: &str,
+    body: &str,
+) -> Result<(String, u64), String> {
+    let target_owner = client.remote.repo.split('/').next().unwrap_or("");
+    let head = if head_owner.is_empty() || head_owner == target_owner {
+        head_branch.to_string()
+    } else {
+        format!("{head_owner}:{head_branch}")
+    };
+    let endpoint = format!("/repos/{}/pulls", client.remote.repo);
+    let payload = json!({
+        "head": head,
+        "base": base_branch,
+        "title": title,
+        "body": body,
+    });
+    let created = run_gh_api(client, "POST", &endpoint, Some(&payload)).await?;
+    let number = created
+        .get("number")
+        .and_then(Value::as_u64)
+        .ok_or("GitHub did not return a pull request number.")?;
+    Ok((json_str(&created, "html_url"), number))
+}
+
+pub(super) async fn post_comment(
+    client: &ForgeClient,
+    number: u64,
+    body: &str,
+) -> Result<GitHubPullRequestComment, String> {
+    let endpoint = format!("/repos/{}/issues/{number}/comments", client.remote.repo);
+    let created = run_gh_api(client, "POST", &endpoint, Some(&json!({ "body": body }))).await?;
+    Ok(comment_from(&created))
+}
+
+pub(super) async fn post_inline_comment(
+    client: &ForgeClient,
+    number: u64,
+    comment: &GitHubPullRequestReviewComment,
+) -> Result<GitHubPullRequestComment, String> {
+    let head = pull_request_head(client, number).await?;
+    if head.sha.is_empty() {
+        return Err("Unable to resolve the pull request head commit.".to_string());
+    }
+    let mut payload = comment_payload(comment);
+    payload["commit_id"] = json!(head.sha);
+    let endpoint = format!("/repos/{}/pulls/{number}/comments", client.remote.repo);
+    let created = run_gh_api(client, "POST", &endpoint, Some(&payload)).await?;
+    Ok(comment_from(&created))
+}
+
+pub(super) async fn submit_review(
+    client: &ForgeClient,
+    number: u64,
+    event: ReviewEvent,
+    body: &str,
+    comments: &[GitHubPullRequestReviewComment],
+) -> Result<GitHubPullRequestReviewResult, String> {
+    let endpoint = format!("/repos/{}/pulls/{number}/reviews", client.remote.repo);
+    let payload = review_payload(event, body, comments);
+    let review = run_gh_api(client, "POST", &endpoint, Some(&payload)).await?;
+    Ok(GitHubPullRequestReviewResult {
+        id: review.get("id").and_then(Value::as_u64),
+        url: review
+            .get("html_url")
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned),
+        state: review
+            .get("state")
+            .and_then(Value::as_str)
+            .unwrap_or(review_event(event))
+            .to_string(),
+        comment_count: comments.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(
+        line: u32,
+        side: Option<&str>,
+        start_line: Option<u32>,
+    ) -> GitHubPullRequestReviewComment {
+        GitHubPullRequestReviewComment {
+            path: "src/lib.rs".to_string(),
+            line,
+            side: side.map(ToOwned::to_owned),
+            start_line,
+            body: "Needs a test".to_string(),
+        }
+    }
+
+    #[test]
+    fn review_payload_maps_sides_and_ranges() {
+        let comments = vec![comment(12, None, Some(10)), comment(4, Some("left"), None)];
+        let payload = review_payload(ReviewEvent::RequestChanges, "Please fix", &comments);
+        assert_eq!(payload["event"], "REQUEST_CHANGES");
+        assert_eq!(payload["body"], "Please fix");
+        let first = &payload["comments"][0];
+        assert_eq!(first["path"], "src/lib.rs");
+        assert_eq!(first["side"], "RIGHT");
+        assert_eq!(first["start_line"], 10);
+        assert_eq!(first["body"], "Needs a test");
+        let second = &payload["comments"][1];
+        assert_eq!(second["side"], "LEFT");
+        assert!(second.get("start_line").is_none());
+    }
+
+    #[test]
+    fn comments_read_rest_fields() {
+        let parsed = comment_from(&json!({
+            "id": 7,
+            "body": "LGTM",
+            "created_at": "2024-01-01T00:00:00Z",
+            "html_url": "https://github.com/o/r/pull/1#issuecomment-7",
+            "user": { "login": "octo" },
+        }));
+        assert_eq!(parsed.id, 7);
+        assert_eq!(parsed.created_at, "2024-01-01T00:00:00Z");
+        assert_eq!(
+            parsed.author.map(|author| author.login).as_deref(),
+            Some("octo")
+        );
+    }
+}
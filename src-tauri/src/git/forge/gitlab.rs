@@ -0,0 +1,538 @@
+use std::collections::HashMap;
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+use super::{
+    build_file_patch, encode_component, is_left_side, json_str, ForgeClient, PullRequestHead,
+    ReviewEvent,
+};
+use crate::types::{
+    GitHubIssue, GitHubIssuesResponse, GitHubPullRequest, GitHubPullRequestAuthor,
+    GitHubPullRequestComment, GitHubPullRequestDiff, GitHubPullRequestReviewComment,
+    GitHubPullRequestReviewResult, GitHubPullRequestsResponse, GitPrExistingPullRequest,
+};
+
+fn project_path(client: &ForgeClient) -> String {
+    format!("/projects/{}", encode_component(&client.remote.repo))
+}
+
+fn author_from(value: &Value) -> Option<GitHubPullRequestAuthor> {
+    value
+        .get("author")
+        .and_then(|author| author.get("username"))
+        .and_then(Value::as_str)
+        .map(|login| GitHubPullRequestAuthor {
+            login: login.to_string(),
+        })
+}
+
+fn as_array(value: Value) -> Vec<Value> {
+    match value {
+        Value::Array(items) => items,
+        _ => Vec::new(),
+    }
+}
+
+pub(super) async fn list_issues(client: &ForgeClient) -> Result<GitHubIssuesResponse, String> {
+    let (value, total) = client
+        .get_json(&format!(
+            "{}/issues?state=opened&per_page=50",
+            project_path(client)
+        ))
+        .await?;
+    let issues = as_array(value)
+        .iter()
+        .map(|issue| GitHubIssue {
+            number: issue.get("iid").and_then(Value::as_u64).unwrap_or(0),
+            title: json_str(issue, "title"),
+            url: json_str(issue, "web_url"),
+            updated_at: json_str(issue, "updated_at"),
+        })
+        .collect::<Vec<_>>();
+    Ok(GitHubIssuesResponse {
+        total: total.unwrap_or(issues.len()),
+        issues,
+    })
+}
+
+fn merge_request_from(value: &Value) -> GitHubPullRequest {
+    GitHubPullRequest {
+        number: value.get("iid").and_then(Value::as_u64).unwrap_or(0),
+        title: json_str(value, "title"),
+        url: json_str(value, "web_url"),
+        updated_at: json_str(value, "updated_at"),
+        created_at: json_str(value, "created_at"),
+        body: json_str(value, "description"),
+        head_ref_name: json_str(value, "source_branch"),
+        base_ref_name: json_str(value, "target_branch"),
+        is_draft: value
+            .get("draft")
+            .or_else(|| value.get("work_in_progress"))
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+        author: author_from(value),
+    }
+}
+
+pub(super) async fn list_pull_requests(
+    client: &ForgeClient,
+) -> Result<GitHubPullRequestsResponse, String> {
+    let (value, total) = client
+        .get_json(&format!(
+            "{}/merge_requests?state=opened&per_page=50",
+            project_path(client)
+        ))
+        .await?;
+    let pull_requests = as_array(value)
+        .iter()
+        .map(merge_request_from)
+        .collect::<Vec<_>>();
+    Ok(GitHubPullRequestsResponse {
+        total: total.unwrap_or(pull_requests.len()),
+        pull_requests,
+    })
+}
+
+fn diff_entry_from(change: &Value) -> GitHubPullRequestDiff {
+    let old_path = json_str(change, "old_path");
+    let new_path = json_str(change, "new_path");
+    let flag = |key: &str| change.get(key).and_then(Value::as_bool).unwrap_or(false);
+    let status = if flag("new_file") {
+        "A"
+    } else if flag("deleted_file") {
+        "D"
+    } else if flag("renamed_file") {
+        "R"
+    } else {
+        "M"
+    };
+    let path = if status == "D" {
+        old_path.clone()
+    } else {
+        new_path.clone()
+    };
+    GitHubPullRequestDiff {
+        diff: build_file_patch(&old_path, &new_path, status, &json_str(change, "diff")),
+        path,
+        status: status.to_string(),
+    }
+}
+
+pub(super) async fn pull_request_diff(
+    client: &ForgeClient,
+    number: u64,
+) -> Result<Vec<GitHubPullRequestDiff>, String> {
+    let changes = fetch_changes(client, number).await?;
+    Ok(changes.iter().map(diff_entry_from).collect())
+}
+
+async fn fetch_changes(client: &ForgeClient, number: u64) -> Result<Vec<Value>, String> {
+    let base = format!("{}/merge_requests/{number}", project_path(client));
+    // `/diffs` is paginated and available since GitLab 15.7; older instances
+    // only expose the deprecated `/changes` endpoint.
+    match client.get_json(&format!("{base}/diffs?per_page=100")).await {
+        Ok((value, _)) => Ok(as_array(value)),
+        Err(_) => {
+            let (value, _) = client.get_json(&format!("{base}/changes")).await?;
+            Ok(value
+                .get("changes")
+                .cloned()
+                .map(as_array)
+                .unwrap_or_default())
+        }
+    }
+}
+
+/// The source branch of a merge request. Same-project merge requests have
+/// no separate head repository to fetch from.
+pub(super) async fn pull_request_head(
+    client: &ForgeClient,
+    number: u64,
+) -> Result<PullRequestHead, String> {
+    let (merge_request, _) = client
+        .get_json(&format!("{}/merge_requests/{number}", project_path(client)))
+        .await?;
+    let source = merge_request
+        .get("source_project_id")
+        .and_then(Value::as_u64);
+    let target = merge_request
+        .get("target_project_id")
+        .and_then(Value::as_u64);
+    let clone_url = match source {
+        Some(source) if Some(source) != target => client
+            .get_json(&format!("/projects/{source}"))
+            .await
+            .ok()
+            .map(|(project, _)| json_str(&project, "http_url_to_repo"))
+            .filter(|url| !url.is_empty()),
+        _ => None,
+    };
+    Ok(PullRequestHead {
+        clone_url,
+        branch: json_str(&merge_request, "source_branch"),
+        sha: json_str(&merge_request, "sha"),
+    })
+}
+
+pub(super) async fn pull_request_comments(
+    client: &ForgeClient,
+    number: u64,
+) -> Result<Vec<GitHubPullRequestComment>, String> {
+    let (value, _) = client
+        .get_json(&format!(
+            "{}/merge_requests/{number}/notes?sort=asc&order_by=created_at&per_page=30",
+            project_path(client)
+        ))
+        .await?;
+    Ok(as_array(value)
+        .iter()
+        .filter(|note| !note.get("system").and_then(Value::as_bool).unwrap_or(false))
+        .map(|note| note_from(client, number, note))
+        .collect())
+}
+
+pub(super) async fn find_open_pull_request(
+    client: &ForgeClient,
+    head_branch: &str,
+) -> Result<Option<GitPrExistingPullRequest>, String> {
+    let (value, _) = client
+        .get_json(&format!(
+            "{}/merge_requests?state=opened&source_branch={}&per_page=5",
+            project_path(client),
+            encode_component(head_branch)
+        ))
+        .await?;
+    Ok(as_array(value).first().map(|mr| GitPrExistingPullRequest {
+        number: mr.get("iid").and_then(Value::as_u64).unwrap_or(0),
+        title: json_str(mr, "title"),
+        url: json_str(mr, "web_url"),
+        state: json_str(mr, "state"),
+        head_ref_name: json_str(mr, "source_branch"),
+        base_ref_name: json_str(mr, "target_branch"),
+    }))
+}
+
+pub(super) async fn create_pull_request(
+    client: &ForgeClient,
+    base_branch: &str,
+    head_owner: &str,
+    head_branch: &str,
+    title: &str,
+    body: &str,
+) -> Result<(String, u64), String> {
+    let target_path = client.remote.repo.clone();
+    let (target_namespace, project_name) = target_path
+        .rsplit_once('/')
+        .ok_or("Invalid GitLab project path.")?;
+    let mut payload = json!({
+        "source_branch": head_branch,
+        "target_branch": base_branch,
+        "title": title,
+        "description": body,
+        "remove_source_branch": false,
+    });
+    // Merge requests from a fork are opened on the fork project and point at
+    // the upstream project id.
+    let same_namespace = head_owner.is_empty()
+        || head_owner == target_namespace
+        || target_namespace.starts_with(&format!("{head_owner}/"));
+    let source_path = if same_namespace {
+        target_path.clone()
+    } else {
+        let (project, _) = client.get_json(&project_path(client)).await?;
+        let target_id = project
+            .get("id")
+            .and_then(Value::as_u64)
+            .ok_or("GitLab did not return a project id.")?;
+        payload["target_project_id"] = json!(target_id);
+        format!("{head_owner}/{project_name}")
+    };
+    let created = client
+        .post_json(
+            &format!(
+                "/projects/{}/merge_requests",
+                encode_component(&source_path)
+            ),
+            &payload,
+        )
+        .await?;
+    let number = created
+        .get("iid")
+        .and_then(Value::as_u64)
+        .ok_or("GitLab did not return a merge request number.")?;
+    Ok((json_str(&created, "web_url"), number))
+}
+
+fn note_from(client: &ForgeClient, number: u64, note: &Value) -> GitHubPullRequestComment {
+    let id = note.get("id").and_then(Value::as_u64).unwrap_or(0);
+    GitHubPullRequestComment {
+        id,
+        body: json_str(note, "body"),
+        created_at: json_str(note, "created_at"),
+        url: format!(
+            "{}/{}/-/merge_requests/{number}#note_{id}",
+            client.remote.web_base(),
+            client.remote.repo
+        ),
+        author: author_from(note),
+    }
+}
+
+pub(super) async fn post_comment(
+    client: &ForgeClient,
+    number: u64,
+    body: &str,
+) -> Result<GitHubPullRequestComment, String> {
+    let note = client
+        .post_json(
+            &format!("{}/merge_requests/{number}/notes", project_path(client)),
+            &json!({ "body": body }),
+        )
+        .await?;
+    Ok(note_from(client, number, &note))
+}
+
+/// The merge request's diff refs, which anchor diff positions on GitLab, and
+/// the old path of every renamed file.
+struct DiffAnchor {
+    refs: Value,
+    renamed_from: HashMap<String, String>,
+}
+
+/// Diff positions need both paths of a renamed file; comments carry the new one.
+fn build_position(anchor: &DiffAnchor, comment: &GitHubPullRequestReviewComment) -> Value {
+    let old_path = anchor
+        .renamed_from
+        .get(&comment.path)
+        .unwrap_or(&comment.path);
+    let mut position = json!({
+        "position_type": "text",
+        "base_sha": json_str(&anchor.refs, "base_sha"),
+        "start_sha": json_str(&anchor.refs, "start_sha"),
+        "head_sha": json_str(&anchor.refs, "head_sha"),
+        "old_path": old_path,
+        "new_path": comment.path,
+    });
+    if is_left_side(comment) {
+        position["old_line"] = json!(comment.line);
+    } else {
+        position["new_line"] = json!(comment.line);
+    }
+    position
+}
+
+async fn fetch_diff_anchor(client: &ForgeClient, number: u64) -> Result<DiffAnchor, String> {
+    let (merge_request, _) = client
+        .get_json(&format!("{}/merge_requests/{number}", project_path(client)))
+        .await?;
+    let refs = merge_request
+        .get("diff_refs")
+        .filter(|refs| !refs.is_null())
+        .cloned()
+        .ok_or_else(|| "GitLab did not return diff refs for this merge request.".to_string())?;
+    let renamed_from = fetch_changes(client, number)
+        .await?
+        .iter()
+        .filter(|change| change.get("renamed_file").and_then(Value::as_bool) == Some(true))
+        .map(|change| (json_str(change, "new_path"), json_str(change, "old_path")))
+        .collect();
+    Ok(DiffAnchor { refs, renamed_from })
+}
+
+async fn create_discussion(
+    client: &ForgeClient,
+    number: u64,
+    anchor: &DiffAnchor,
+    comment: &GitHubPullRequestReviewComment,
+) -> Result<GitHubPullRequestComment, String> {
+    let discussion = client
+        .post_json(
+            &format!(
+                "{}/merge_requests/{number}/discussions",
+                project_path(client)
+            ),
+            &json!({
+                "body": comment.body,
+                "position": build_position(anchor, comment),
+            }),
+        )
+        .await?;
+    let note = discussion
+        .get("notes")
+        .and_then(|notes| notes.get(0))
+        .cloned()
+        .unwrap_or(Value::Null);
+    Ok(note_from(client, number, &note))
+}
+
+pub(super) async fn post_inline_comment(
+    client: &ForgeClient,
+    number: u64,
+    comment: &GitHubPullRequestReviewComment,
+) -> Result<GitHubPullRequestComment, String> {
+    let anchor = fetch_diff_anchor(client, number).await?;
+    create_discussion(client, number, &anchor, comment).await
+}
+
+/// GitLab has no review object: inline comments become diff discussions, the
+/// summary becomes a note and approval uses the approvals API. "Request
+/// changes" is published as a comment because the API has no equivalent.
+pub(super) async fn submit_review(
+    client: &ForgeClient,
+    number: u64,
+    event: ReviewEvent,
+    body: &str,
+    comments: &[GitHubPullRequestReviewComment],
+) -> Result<GitHubPullRequestReviewResult, String> {
+    if !comments.is_empty() {
+        let anchor = fetch_diff_anchor(client, number).await?;
+        for comment in comments {
+            create_discussion(client, number, &anchor, comment).await?;
+        }
+    }
+    let summary = if body.is_empty() {
+        None
+    } else {
+        Some(post_comment(client, number, body).await?)
+    };
+    let state = if event == ReviewEvent::Approve {
+        client
+            .request_text(
+                Method::POST,
+                &format!("{}/merge_requests/{number}/approve", project_path(client)),
+                Some(&json!({})),
+            )
+            .await?;
+        "APPROVED"
+    } else {
+        "COMMENTED"
+    };
+    Ok(GitHubPullRequestReviewResult {
+        id: summary.as_ref().map(|note| note.id),
+        url: summary.map(|note| note.url),
+        state: state.to_string(),
+        comment_count: comments.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::start_http_stub;
+    use super::super::{parse_forge_remote, ForgeClient};
+    use super::*;
+
+    #[tokio::test]
+    async fn lists_merge_requests_and_diffs_from_gitlab_api() {
+        let stub = start_http_stub(vec![
+            (
+                "GET /api/v4/projects/group%2Fapp/merge_requests?state=opened",
+                200,
+                vec![("X-Total", "7".to_string())],
+                r#"[{"iid":3,"title":"Add feature","web_url":"https://gl/mr/3","updated_at":"u","created_at":"c","description":"body","source_branch":"feat","target_branch":"main","draft":true,"author":{"username":"dev"}}]"#.to_string(),
+            ),
+            (
+                "GET /api/v4/projects/group%2Fapp/merge_requests/3/diffs",
+                404,
+                Vec::new(),
+                r#"{"message":"404 Not found"}"#.to_string(),
+            ),
+            (
+                "GET /api/v4/projects/group%2Fapp/merge_requests/3/changes",
+                200,
+                Vec::new(),
+                r#"{"changes":[{"old_path":"a.rs","new_path":"a.rs","new_file":true,"deleted_file":false,"renamed_file":false,"diff":"@@ -0,0 +1 @@\n+fn a() {}\n"}]}"#.to_string(),
+            ),
+        ]);
+        let remote =
+            parse_forge_remote(&format!("{}/group/app.git", stub.base_url), Some("gitlab"))
+                .expect("remote");
+        let client = ForgeClient::with_token(&remote, Some("secret".to_string())).expect("client");
+
+        let response = list_pull_requests(&client).await.expect("merge requests");
+        assert_eq!(response.total, 7);
+        let mr = &response.pull_requests[0];
+        assert_eq!(mr.number, 3);
+        assert!(mr.is_draft);
+        assert_eq!(mr.head_ref_name, "feat");
+        assert_eq!(mr.author.as_ref().map(|a| a.login.as_str()), Some("dev"));
+
+        let diffs = pull_request_diff(&client, 3).await.expect("diff");
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].status, "A");
+        assert!(diffs[0].diff.contains("+fn a() {}"));
+    }
+
+    #[tokio::test]
+    async fn reports_token_hint_on_unauthorized_gitlab_request() {
+        let stub = start_http_stub(vec![(
+            "GET /api/v4/projects/group%2Fapp/issues",
+            401,
+            Vec::new(),
+            r#"{"message":"401 Unauthorized"}"#.to_string(),
+        )]);
+        let remote =
+            parse_forge_remote(&format!("{}/group/app.git", stub.base_url), Some("gitlab"))
+                .expect("remote");
+        let client = ForgeClient::with_token(&remote, None).expect("client");
+        let error = list_issues(&client).await.expect_err("unauthorized");
+        assert!(error.contains("HTTP 401"));
+        assert!(error.contains("GITLAB_TOKEN"));
+    }
+
+    #[tokio::test]
+    async fn posts_inline_comment_as_positioned_discussion_on_gitlab() {
+        let stub = start_http_stub(vec![
+            (
+                "GET /api/v4/projects/group%2Fapp/merge_requests/3",
+                200,
+                Vec::new(),
+                r#"{"iid":3,"diff_refs":{"base_sha":"b","start_sha":"s","head_sha":"h"}}"#
+                    .to_string(),
+            ),
+            (
+                "GET /api/v4/projects/group%2Fapp/merge_requests/3/diffs",
+                200,
+                Vec::new(),
+                r#"[{"old_path":"src/old.rs","new_path":"src/lib.rs","new_file":false,"deleted_file":false,"renamed_file":true,"diff":""}]"#
+                    .to_string(),
+            ),
+            (
+                "POST /api/v4/projects/group%2Fapp/merge_requests/3/discussions",
+                201,
+                Vec::new(),
+                r#"{"id":"d1","notes":[{"id":55,"body":"Nit","created_at":"c","author":{"username":"rev"}}]}"#
+                    .to_string(),
+            ),
+        ]);
+        let remote =
+            parse_forge_remote(&format!("{}/group/app.git", stub.base_url), Some("gitlab"))
+                .expect("remote");
+        let client = ForgeClient::with_token(&remote, Some("secret".to_string())).expect("client");
+        let comment = GitHubPullRequestReviewComment {
+            path: "src/lib.rs".to_string(),
+            line: 12,
+            side: None,
+            start_line: None,
+            body: "Nit".to_string(),
+        };
+
+        let note = post_inline_comment(&client, 3, &comment)
+            .await
+            .expect("note");
+        assert_eq!(note.id, 55);
+        assert!(note.url.ends_with("/group/app/-/merge_requests/3#note_55"));
+
+        let requests = stub.requests.lock().expect("requests");
+        let (_, body) = requests
+            .iter()
+            .find(|(key, _)| key.starts_with("POST"))
+            .expect("discussion posted");
+        let payload: Value = serde_json::from_str(body).expect("json body");
+        assert_eq!(payload["position"]["head_sha"], "h");
+        assert_eq!(payload["position"]["old_path"], "src/old.rs");
+        assert_eq!(payload["position"]["new_path"], "src/lib.rs");
+        assert_eq!(payload["position"]["new_line"], 12);
+        assert!(payload["position"].get("old_line").is_none());
+    }
+}
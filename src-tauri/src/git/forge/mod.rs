@@ -0,0 +1,971 @@
+//! Code forge abstraction for issues and pull/merge requests.
+//!
+//! Every forge goes through the same `ForgeClient` entry points. GitHub is
+//! served by the `gh` CLI in `github.rs`, which reuses the user's `gh` login;
+//! GitLab and Gitea (including self-hosted instances) are called over HTTP
+//! with a token from the environment or, for GitLab, the `glab` login.
+
+use std::path::Path;
+use std::process::Stdio;
+
+use git2::Repository;
+use reqwest::Method;
+use serde_json::Value;
+use tauri::State;
+use tokio::time::{timeout, Duration};
+
+use super::reviews::{is_left_side, ReviewEvent};
+use super::{
+    build_existing_pr_workflow_result, build_failed_pr_workflow_result,
+    build_success_pr_workflow_result, open_repository_at_root, parse_pr_diff,
+    update_workflow_stage,
+};
+use crate::git_utils::resolve_git_root;
+use crate::state::AppState;
+use crate::types::{
+    GitForgeInfo, GitHubIssuesResponse, GitHubPullRequestComment, GitHubPullRequestDiff,
+    GitHubPullRequestReviewComment, GitHubPullRequestReviewResult, GitHubPullRequestsResponse,
+    GitPrExistingPullRequest, GitPrWorkflowResult, GitPrWorkflowStage,
+};
+
+mod gitea;
+mod github;
+mod gitlab;
+
+const FORGE_HTTP_TIMEOUT_SECS: u64 = 30;
+const GLAB_TOKEN_TIMEOUT_SECS: u64 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ForgeKind {
+    GitHub,
+    GitLab,
+    Gitea,
+}
+
+impl ForgeKind {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            ForgeKind::GitHub => "github",
+            ForgeKind::GitLab => "gitlab",
+            ForgeKind::Gitea => "gitea",
+        }
+    }
+
+    fn from_setting(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "github" => Some(ForgeKind::GitHub),
+            "gitlab" => Some(ForgeKind::GitLab),
+            "gitea" | "forgejo" => Some(ForgeKind::Gitea),
+            _ => None,
+        }
+    }
+
+    fn detect(host: &str) -> Option<Self> {
+        let host = host.to_lowercase();
+        let name = host.split(':').next().unwrap_or("");
+        if name == "github.com" || name.ends_with(".github.com") {
+            return Some(ForgeKind::GitHub);
+        }
+        if name.contains("gitlab") {
+            return Some(ForgeKind::GitLab);
+        }
+        if name.contains("gitea") || name.contains("forgejo") || name == "codeberg.org" {
+            return Some(ForgeKind::Gitea);
+        }
+        None
+    }
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            ForgeKind::GitHub => "GitHub",
+            ForgeKind::GitLab => "GitLab",
+            ForgeKind::Gitea => "Gitea",
+        }
+    }
+
+    fn token_env(&self) -> &'static str {
+        match self {
+            ForgeKind::GitHub => "GH_TOKEN",
+            ForgeKind::GitLab => "GITLAB_TOKEN",
+            ForgeKind::Gitea => "GITEA_TOKEN",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ForgeRemote {
+    pub(crate) kind: ForgeKind,
+    pub(crate) scheme: String,
+    /// Web host, including the port for http(s) remotes.
+    pub(crate) host: String,
+    /// Repository path, e.g. `owner/name` or `group/subgroup/name` on GitLab.
+    pub(crate) repo: String,
+}
+
+impl ForgeRemote {
+    pub(crate) fn web_base(&self) -> String {
+        format!("{}://{}", self.scheme, self.host)
+    }
+
+    fn api_base(&self) -> String {
+        match self.kind {
+            ForgeKind::GitHub => format!("{}://api.{}", self.scheme, self.host),
+            ForgeKind::GitLab => format!("{}/api/v4", self.web_base()),
+            ForgeKind::Gitea => format!("{}/api/v1", self.web_base()),
+        }
+    }
+
+    /// Repository argument for `gh --repo`, which needs the host for GitHub Enterprise.
+    pub(crate) fn gh_repo(&self) -> String {
+        if self.host == "github.com" {
+            self.repo.clone()
+        } else {
+            format!("{}/{}", self.host, self.repo)
+        }
+    }
+
+    /// Ref that exposes the head commit of a pull/merge request on the remote.
+    pub(crate) fn pull_request_head_ref(&self, number: u64) -> String {
+        match self.kind {
+            ForgeKind::GitLab => format!("refs/merge-requests/{number}/head"),
+            ForgeKind::GitHub | ForgeKind::Gitea => format!("refs/pull/{number}/head"),
+        }
+    }
+
+    pub(crate) fn with_repo(&self, repo: &str) -> Self {
+        Self {
+            repo: repo.trim().trim_matches('/').to_string(),
+            ..self.clone()
+        }
+    }
+}
+
+fn split_remote_url(remote_url: &str) -> Option<(String, String, String)> {
+    let trimmed = remote_url.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    let (scheme, host, path) = if let Some((scheme, rest)) = trimmed.split_once("://") {
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority.rsplit('@').next().unwrap_or(authority);
+        let scheme = scheme.to_lowercase();
+        match scheme.as_str() {
+            "http" | "https" => (scheme, host.to_string(), path.to_string()),
+            // SSH/git ports never match the web port, so drop them.
+            _ => (
+                "https".to_string(),
+                host.split(':').next().unwrap_or(host).to_string(),
+                path.to_string(),
+            ),
+        }
+    } else {
+        // scp-like syntax: `git@host:owner/repo.git`
+        let (authority, path) = trimmed.split_once(':')?;
+        if authority.contains('/') {
+            return None;
+        }
+        let host = authority.rsplit('@').next().unwrap_or(authority);
+        ("https".to_string(), host.to_string(), path.to_string())
+    };
+    let path = path
+        .trim_start_matches('/')
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .trim_end_matches('/')
+        .to_string();
+    if host.is_empty() || !path.contains('/') {
+        return None;
+    }
+    Some((scheme, host, path))
+}
+
+/// Resolves the forge behind a remote URL. `kind_override` comes from the
+/// workspace settings and is needed for self-hosted instances whose host name
+/// gives no hint.
+pub(crate) fn parse_forge_remote(
+    remote_url: &str,
+    kind_override: Option<&str>,
+) -> Option<ForgeRemote> {
+    let (scheme, host, repo) = split_remote_url(remote_url)?;
+    let kind = kind_override
+        .and_then(ForgeKind::from_setting)
+        .or_else(|| ForgeKind::detect(&host))?;
+    Some(ForgeRemote {
+        kind,
+        scheme,
+        host,
+        repo,
+    })
+}
+
+pub(crate) fn forge_remote_from_repo(
+    repo: &Repository,
+    remote_name: &str,
+    kind_override: Option<&str>,
+) -> Option<ForgeRemote> {
+    let remote = repo.find_remote(remote_name).ok()?;
+    parse_forge_remote(remote.url()?, kind_override)
+}
+
+/// Remote used for issues and pull requests: `origin`, else the first remote.
+pub(crate) fn default_remote_name(repo: &Repository) -> Result<String, String> {
+    let remotes = repo.remotes().map_err(|e| e.to_string())?;
+    let name = if remotes.iter().any(|remote| remote == Some("origin")) {
+        "origin".to_string()
+    } else {
+        remotes.iter().flatten().next().unwrap_or("").to_string()
+    };
+    if name.is_empty() {
+        return Err("No git remote configured.".to_string());
+    }
+    Ok(name)
+}
+
+pub(crate) fn forge_remote_from_path(
+    path: &Path,
+    kind_override: Option<&str>,
+) -> Result<ForgeRemote, String> {
+    let repo = open_repository_at_root(path)?;
+    let name = default_remote_name(&repo)?;
+    let remote = repo.find_remote(&name).map_err(|e| e.to_string())?;
+    let remote_url = remote.url().ok_or("Remote has no URL configured.")?;
+    parse_forge_remote(remote_url, kind_override).ok_or_else(|| {
+        "Remote is not a recognized GitHub, GitLab or Gitea repository. Set the forge type in workspace settings for self-hosted instances.".to_string()
+    })
+}
+
+fn encode_component(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+async fn glab_token(host: &str) -> Option<String> {
+    let mut command = crate::utils::async_command("glab");
+    command
+        .args(["config", "get", "token", "--host", host])
+        .env("PATH", crate::utils::git_env_path())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    let output = timeout(
+        Duration::from_secs(GLAB_TOKEN_TIMEOUT_SECS),
+        command.output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if token.is_empty() {
+        None
+    } else {
+        Some(token)
+    }
+}
+
+async fn resolve_token(remote: &ForgeRemote) -> Option<String> {
+    let from_env = std::env::var(remote.kind.token_env())
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    if from_env.is_some() {
+        return from_env;
+    }
+    if remote.kind == ForgeKind::GitLab {
+        return glab_token(remote.host.split(':').next().unwrap_or(&remote.host)).await;
+    }
+    None
+}
+
+struct ForgeResponse {
+    status: u16,
+    total: Option<usize>,
+    body: String,
+}
+
+pub(crate) struct ForgeClient {
+    remote: ForgeRemote,
+    token: Option<String>,
+    http: reqwest::Client,
+}
+
+impl ForgeClient {
+    pub(crate) async fn connect(remote: &ForgeRemote) -> Result<Self, String> {
+        let token = resolve_token(remote).await;
+        Self::with_token(remote, token)
+    }
+
+    fn with_token(remote: &ForgeRemote, token: Option<String>) -> Result<Self, String> {
+        let http = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(10))
+            .timeout(Duration::from_secs(FORGE_HTTP_TIMEOUT_SECS))
+            .build()
+            .map_err(|error| format!("Failed to configure forge client: {error}"))?;
+        Ok(Self {
+            remote: remote.clone(),
+            token,
+            http,
+        })
+    }
+
+    pub(crate) fn has_token(&self) -> bool {
+        self.token.is_some()
+    }
+
+    fn missing_token_hint(&self) -> String {
+        match self.remote.kind {
+            ForgeKind::GitLab => format!(
+                "Set {} or run `glab auth login --hostname {}`.",
+                self.remote.kind.token_env(),
+                self.remote.host
+            ),
+            _ => format!(
+                "Set {} to an access token for {}.",
+                self.remote.kind.token_env(),
+                self.remote.host
+            ),
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&Value>,
+    ) -> Result<ForgeResponse, String> {
+        let url = format!("{}{}", self.remote.api_base(), path);
+        let mut request = self
+            .http
+            .request(method, &url)
+            .header("Accept", "application/json");
+        if let Some(token) = self.token.as_deref() {
+            request = match self.remote.kind {
+                ForgeKind::GitLab => request.header("PRIVATE-TOKEN", token),
+                _ => request.header("Authorization", format!("token {token}")),
+            };
+        }
+        if let Some(body) = body {
+            request = request
+                .header("Content-Type", "application/json")
+                .body(body.to_string());
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|error| format!("{} request failed: {error}", self.remote.kind.label()))?;
+        let status = response.status().as_u16();
+        let total = ["x-total", "x-total-count"].iter().find_map(|name| {
+            response
+                .headers()
+                .get(*name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<usize>().ok())
+        });
+        let body = response.text().await.map_err(|error| error.to_string())?;
+        Ok(ForgeResponse {
+            status,
+            total,
+            body,
+        })
+    }
+
+    async fn request_text(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&Value>,
+    ) -> Result<(String, Option<usize>), String> {
+        let response = self.send(method, path, body).await?;
+        if (200..300).contains(&response.status) {
+            return Ok((response.body, response.total));
+        }
+        let detail = serde_json::from_str::<Value>(&response.body)
+            .ok()
+            .and_then(|value| {
+                value
+                    .get("message")
+                    .or_else(|| value.get("error"))
+                    .map(|message| match message {
+                        Value::String(text) => text.clone(),
+                        other => other.to_string(),
+                    })
+            })
+            .unwrap_or_else(|| response.body.trim().to_string());
+        let mut message = format!(
+            "{} API error (HTTP {}): {}",
+            self.remote.kind.label(),
+            response.status,
+            detail
+        );
+        if response.status == 401 || response.status == 403 {
+            message.push(' ');
+            message.push_str(&self.missing_token_hint());
+        }
+        Err(message)
+    }
+
+    async fn get_json(&self, path: &str) -> Result<(Value, Option<usize>), String> {
+        let (body, total) = self.request_text(Method::GET, path, None).await?;
+        let value = serde_json::from_str(&body).map_err(|e| e.to_string())?;
+        Ok((value, total))
+    }
+
+    async fn post_json(&self, path: &str, body: &Value) -> Result<Value, String> {
+        let (body, _) = self.request_text(Method::POST, path, Some(body)).await?;
+        serde_json::from_str(&body).map_err(|e| e.to_string())
+    }
+}
+
+fn json_str(value: &Value, key: &str) -> String {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .unwrap_or("")
+        .to_string()
+}
+
+/// Builds a `git diff`-style header so REST file diffs render like `gh pr diff`.
+fn build_file_patch(old_path: &str, new_path: &str, status: &str, hunks: &str) -> String {
+    let mut patch = format!("diff --git a/{old_path} b/{new_path}\n");
+    match status {
+        "A" => patch.push_str("new file mode 100644\n"),
+        "D" => patch.push_str("deleted file mode 100644\n"),
+        "R" => patch.push_str(&format!("rename from {old_path}\nrename to {new_path}\n")),
+        _ => {}
+    }
+    let old_label = if status == "A" {
+        "/dev/null".to_string()
+    } else {
+        format!("a/{old_path}")
+    };
+    let new_label = if status == "D" {
+        "/dev/null".to_string()
+    } else {
+        format!("b/{new_path}")
+    };
+    patch.push_str(&format!("--- {old_label}\n+++ {new_label}\n"));
+    patch.push_str(hunks.trim_end_matches('\n'));
+    patch
+}
+
+pub(crate) async fn list_issues(remote: &ForgeRemote) -> Result<GitHubIssuesResponse, String> {
+    let client = ForgeClient::connect(remote).await?;
+    match remote.kind {
+        ForgeKind::GitLab => gitlab::list_issues(&client).await,
+        ForgeKind::Gitea => gitea::list_issues(&client).await,
+        ForgeKind::GitHub => github::list_issues(&client).await,
+    }
+}
+
+pub(crate) async fn list_pull_requests(
+    remote: &ForgeRemote,
+) -> Result<GitHubPullRequestsResponse, String> {
+    let client = ForgeClient::connect(remote).await?;
+    match remote.kind {
+        ForgeKind::GitLab => gitlab::list_pull_requests(&client).await,
+        ForgeKind::Gitea => gitea::list_pull_requests(&client).await,
+        ForgeKind::GitHub => github::list_pull_requests(&client).await,
+    }
+}
+
+pub(crate) async fn pull_request_diff(
+    remote: &ForgeRemote,
+    number: u64,
+) -> Result<Vec<GitHubPullRequestDiff>, String> {
+    let client = ForgeClient::connect(remote).await?;
+    match remote.kind {
+        ForgeKind::GitLab => gitlab::pull_request_diff(&client, number).await,
+        ForgeKind::Gitea => gitea::pull_request_diff(&client, number).await,
+        ForgeKind::GitHub => github::pull_request_diff(&client, number).await,
+    }
+}
+
+pub(crate) async fn pull_request_comments(
+    remote: &ForgeRemote,
+    number: u64,
+) -> Result<Vec<GitHubPullRequestComment>, String> {
+    let client = ForgeClient::connect(remote).await?;
+    match remote.kind {
+        ForgeKind::GitLab => gitlab::pull_request_comments(&client, number).await,
+        ForgeKind::Gitea => gitea::pull_request_comments(&client, number).await,
+        ForgeKind::GitHub => github::pull_request_comments(&client, number).await,
+    }
+}
+
+/// The branch a pull request was opened from.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PullRequestHead {
+    /// Clone URL of the head repository, `None` when it is gone (a deleted
+    /// fork); the base repository's pull ref still has the commits.
+    pub(crate) clone_url: Option<String>,
+    pub(crate) branch: String,
+    pub(crate) sha: String,
+}
+
+/// Reads the `head` of a GitHub or Gitea pull request, which share the shape.
+fn pull_request_head_from(pull: &Value) -> PullRequestHead {
+    let head = pull.get("head").cloned().unwrap_or(Value::Null);
+    PullRequestHead {
+        clone_url: head
+            .get("repo")
+            .and_then(|repo| repo.get("clone_url"))
+            .and_then(Value::as_str)
+            .filter(|url| !url.is_empty())
+            .map(str::to_string),
+        branch: json_str(&head, "ref"),
+        sha: json_str(&head, "sha"),
+    }
+}
+
+pub(crate) async fn pull_request_head(
+    remote: &ForgeRemote,
+    number: u64,
+) -> Result<PullRequestHead, String> {
+    let client = ForgeClient::connect(remote).await?;
+    match remote.kind {
+        ForgeKind::GitLab => gitlab::pull_request_head(&client, number).await,
+        ForgeKind::Gitea => gitea::pull_request_head(&client, number).await,
+        ForgeKind::GitHub => github::pull_request_head(&client, number).await,
+    }
+}
+
+async fn find_open_pull_request(
+    client: &ForgeClient,
+    head_owner: &str,
+    head_branch: &str,
+) -> Result<Option<GitPrExistingPullRequest>, String> {
+    match client.remote.kind {
+        ForgeKind::GitLab => gitlab::find_open_pull_request(client, head_branch).await,
+        ForgeKind::Gitea => gitea::find_open_pull_request(client, head_owner, head_branch).await,
+        ForgeKind::GitHub => github::find_open_pull_request(client, head_owner, head_branch).await,
+    }
+}
+
+async fn create_pull_request(
+    client: &ForgeClient,
+    base_branch: &str,
+    head_owner: &str,
+    head_branch: &str,
+    title: &str,
+    body: &str,
+) -> Result<(String, u64), String> {
+    match client.remote.kind {
+        ForgeKind::GitLab => {
+            gitlab::create_pull_request(client, base_branch, head_owner, head_branch, title, body)
+                .await
+        }
+        ForgeKind::Gitea => {
+            gitea::create_pull_request(client, base_branch, head_owner, head_branch, title, body)
+                .await
+        }
+        ForgeKind::GitHub => {
+            github::create_pull_request(client, base_branch, head_owner, head_branch, title, body)
+                .await
+        }
+    }
+}
+
+async fn post_pull_request_comment(
+    client: &ForgeClient,
+    number: u64,
+    body: &str,
+) -> Result<GitHubPullRequestComment, String> {
+    match client.remote.kind {
+        ForgeKind::GitLab => gitlab::post_comment(client, number, body).await,
+        ForgeKind::Gitea => gitea::post_comment(client, number, body).await,
+        ForgeKind::GitHub => github::post_comment(client, number, body).await,
+    }
+}
+
+pub(crate) async fn comment_on_pull_request(
+    remote: &ForgeRemote,
+    number: u64,
+    body: &str,
+) -> Result<GitHubPullRequestComment, String> {
+    let client = ForgeClient::connect(remote).await?;
+    post_pull_request_comment(&client, number, body).await
+}
+
+pub(crate) async fn post_inline_comment(
+    remote: &ForgeRemote,
+    number: u64,
+    comment: &GitHubPullRequestReviewComment,
+) -> Result<GitHubPullRequestComment, String> {
+    let client = ForgeClient::connect(remote).await?;
+    match remote.kind {
+        ForgeKind::GitLab => gitlab::post_inline_comment(&client, number, comment).await,
+        ForgeKind::Gitea => gitea::post_inline_comment(&client, number, comment).await,
+        ForgeKind::GitHub => github::post_inline_comment(&client, number, comment).await,
+    }
+}
+
+pub(crate) async fn submit_review(
+    remote: &ForgeRemote,
+    number: u64,
+    event: ReviewEvent,
+    body: &str,
+    comments: &[GitHubPullRequestReviewComment],
+) -> Result<GitHubPullRequestReviewResult, String> {
+    let client = ForgeClient::connect(remote).await?;
+    match remote.kind {
+        ForgeKind::GitLab => gitlab::submit_review(&client, number, event, body, comments).await,
+        ForgeKind::Gitea => gitea::submit_review(&client, number, event, body, comments).await,
+        ForgeKind::GitHub => github::submit_review(&client, number, event, body, comments).await,
+    }
+}
+
+/// Precheck for the PR workflow on REST forges: creating a merge request
+/// always needs an access token.
+pub(crate) async fn precheck_pr_workflow(remote: &ForgeRemote) -> Result<ForgeClient, String> {
+    let client = ForgeClient::connect(remote).await?;
+    if !client.has_token() {
+        return Err(format!(
+            "No {} access token found. {}",
+            remote.kind.label(),
+            client.missing_token_hint()
+        ));
+    }
+    Ok(client)
+}
+
+/// Runs the `create` and `comment` stages of the PR workflow against a REST
+/// forge once the branch has been pushed.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn finish_pr_workflow(
+    client: &ForgeClient,
+    mut stages: Vec<GitPrWorkflowStage>,
+    base_branch: &str,
+    head_owner: &str,
+    head_branch: &str,
+    title: &str,
+    body: &str,
+    comment_body: Option<&str>,
+) -> GitPrWorkflowResult {
+    let label = client.remote.kind.label();
+    update_workflow_stage(
+        &mut stages,
+        "create",
+        "running",
+        format!("Detecting existing {label} request and creating a new one when needed."),
+        None,
+        None,
+        None,
+    );
+    match find_open_pull_request(client, head_owner, head_branch).await {
+        Ok(Some(existing)) => {
+            update_workflow_stage(
+                &mut stages,
+                "create",
+                "success",
+                format!("Existing PR found: #{} {}", existing.number, existing.title),
+                None,
+                None,
+                None,
+            );
+            update_workflow_stage(
+                &mut stages,
+                "comment",
+                "skipped",
+                "Skipped because workflow reused existing PR.".to_string(),
+                None,
+                None,
+                None,
+            );
+            return build_existing_pr_workflow_result(stages, existing);
+        }
+        Ok(None) => {}
+        Err(error) => {
+            update_workflow_stage(
+                &mut stages,
+                "create",
+                "failed",
+                error.clone(),
+                None,
+                None,
+                None,
+            );
+            return build_failed_pr_workflow_result(stages, "create", error, None);
+        }
+    }
+
+    let (pr_url, pr_number) = match create_pull_request(
+        client,
+        base_branch,
+        head_owner,
+        head_branch,
+        title,
+        body,
+    )
+    .await
+    {
+        Ok(created) => created,
+        Err(error) => {
+            update_workflow_stage(
+                &mut stages,
+                "create",
+                "failed",
+                error.clone(),
+                None,
+                None,
+                None,
+            );
+            return build_failed_pr_workflow_result(stages, "create", error, None);
+        }
+    };
+    update_workflow_stage(
+        &mut stages,
+        "create",
+        "success",
+        format!("PR created: {pr_url}"),
+        None,
+        None,
+        None,
+    );
+
+    let Some(comment_text) = comment_body
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    else {
+        update_workflow_stage(
+            &mut stages,
+            "comment",
+            "skipped",
+            "Comment step disabled.".to_string(),
+            None,
+            None,
+            None,
+        );
+        return build_success_pr_workflow_result(
+            stages,
+            pr_url,
+            Some(pr_number),
+            "PR workflow completed.".to_string(),
+        );
+    };
+    match post_pull_request_comment(client, pr_number, comment_text).await {
+        Ok(_) => {
+            update_workflow_stage(
+                &mut stages,
+                "comment",
+                "success",
+                format!("Comment posted on PR #{pr_number}."),
+                None,
+                None,
+                None,
+            );
+            build_success_pr_workflow_result(
+                stages,
+                pr_url,
+                Some(pr_number),
+                "PR workflow completed.".to_string(),
+            )
+        }
+        Err(error) => {
+            update_workflow_stage(&mut stages, "comment", "failed", error, None, None, None);
+            build_success_pr_workflow_result(
+                stages,
+                pr_url,
+                Some(pr_number),
+                "PR created, but comment step failed.".to_string(),
+            )
+        }
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn get_git_forge_info(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<GitForgeInfo, String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+    drop(workspaces);
+
+    let repo_root = resolve_git_root(&entry)?;
+    let remote = forge_remote_from_path(&repo_root, entry.settings.forge_kind.as_deref())?;
+    Ok(GitForgeInfo {
+        kind: remote.kind.as_str().to_string(),
+        web_url: format!("{}/{}", remote.web_base(), remote.repo),
+        host: remote.host,
+        repo: remote.repo,
+    })
+}
+
+#[cfg(test)]
+pub(super) mod test_support {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    /// Minimal HTTP/1.1 stub. `routes` maps `"METHOD /path?query"` prefixes to
+    /// `(status, extra headers, body)`; received requests are recorded.
+    pub(crate) struct HttpStub {
+        pub(crate) base_url: String,
+        pub(crate) requests: Arc<Mutex<Vec<(String, String)>>>,
+    }
+
+    pub(crate) type StubRoute = (&'static str, u16, Vec<(&'static str, String)>, String);
+
+    pub(crate) fn start_http_stub(routes: Vec<StubRoute>) -> HttpStub {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind stub");
+        let address = listener.local_addr().expect("stub address");
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&requests);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+                let mut buffer = Vec::new();
+                let mut chunk = [0u8; 4096];
+                let (head, body) = loop {
+                    let read = stream.read(&mut chunk).unwrap_or(0);
+                    if read == 0 {
+                        break (String::from_utf8_lossy(&buffer).to_string(), String::new());
+                    }
+                    buffer.extend_from_slice(&chunk[..read]);
+                    let text = String::from_utf8_lossy(&buffer).to_string();
+                    if let Some(index) = text.find("\r\n\r\n") {
+                        let head = text[..index].to_string();
+                        let content_length = head
+                            .lines()
+                            .find_map(|line| {
+                                let (name, value) = line.split_once(':')?;
+                                if name.eq_ignore_ascii_case("content-length") {
+                                    value.trim().parse::<usize>().ok()
+                                } else {
+                                    None
+                                }
+                            })
+                            .unwrap_or(0);
+                        let mut body = buffer[index + 4..].to_vec();
+                        while body.len() < content_length {
+                            let read = stream.read(&mut chunk).unwrap_or(0);
+                            if read == 0 {
+                                break;
+                            }
+                            body.extend_from_slice(&chunk[..read]);
+                        }
+                        break (head, String::from_utf8_lossy(&body).to_string());
+                    }
+                };
+                let request_line = head.lines().next().unwrap_or("").to_string();
+                let mut parts = request_line.split_whitespace();
+                let method = parts.next().unwrap_or("");
+                let target = parts.next().unwrap_or("");
+                let key = format!("{method} {target}");
+                recorded
+                    .lock()
+                    .expect("record request")
+                    .push((key.clone(), body));
+                let route = routes
+                    .iter()
+                    .filter(|(prefix, ..)| key.starts_with(prefix))
+                    .max_by_key(|(prefix, ..)| prefix.len());
+                let (status, headers, body) = match route {
+                    Some((_, status, headers, body)) => (*status, headers.clone(), body.clone()),
+                    None => (404, Vec::new(), "{\"message\":\"not found\"}".to_string()),
+                };
+                let mut response = format!(
+                    "HTTP/1.1 {status} Stub\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+                    body.len()
+                );
+                for (name, value) in headers {
+                    response.push_str(&format!("{name}: {value}\r\n"));
+                }
+                response.push_str("\r\n");
+                response.push_str(&body);
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        HttpStub {
+            base_url: format!("http://{address}"),
+            requests,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_forge_remote_detects_hosts_and_url_styles() {
+        let github = parse_forge_remote("git@github.com:octo/moss.git", None).expect("github");
+        assert_eq!(github.kind, ForgeKind::GitHub);
+        assert_eq!(github.repo, "octo/moss");
+        assert_eq!(github.gh_repo(), "octo/moss");
+
+        let gitlab =
+            parse_forge_remote("ssh://git@gitlab.example.com:2222/group/sub/app.git", None)
+                .expect("gitlab");
+        assert_eq!(gitlab.kind, ForgeKind::GitLab);
+        assert_eq!(gitlab.host, "gitlab.example.com");
+        assert_eq!(gitlab.repo, "group/sub/app");
+        assert_eq!(gitlab.api_base(), "https://gitlab.example.com/api/v4");
+
+        let gitea = parse_forge_remote("https://user@codeberg.org/team/tool", None).expect("gitea");
+        assert_eq!(gitea.kind, ForgeKind::Gitea);
+        assert_eq!(gitea.api_base(), "https://codeberg.org/api/v1");
+    }
+
+    #[test]
+    fn parse_forge_remote_uses_override_for_unknown_hosts() {
+        assert!(parse_forge_remote("https://git.corp.local/team/app.git", None).is_none());
+        let remote = parse_forge_remote("http://git.corp.local:8080/team/app.git", Some("gitlab"))
+            .expect("override");
+        assert_eq!(remote.kind, ForgeKind::GitLab);
+        assert_eq!(remote.web_base(), "http://git.corp.local:8080");
+    }
+
+    #[test]
+    fn pull_request_head_reads_the_fork_branch() {
+        let head = pull_request_head_from(&serde_json::json!({
+            "head": {
+                "ref": "fix/typo",
+                "sha": "abc123",
+                "repo": { "clone_url": "https://github.com/fork/moss.git" },
+            },
+        }));
+        assert_eq!(
+            head.clone_url.as_deref(),
+            Some("https://github.com/fork/moss.git")
+        );
+        assert_eq!(
+            (head.branch.as_str(), head.sha.as_str()),
+            ("fix/typo", "abc123")
+        );
+
+        // A deleted fork leaves `repo` null.
+        let orphan = pull_request_head_from(&serde_json::json!({
+            "head": { "ref": "fix/typo", "sha": "abc123", "repo": null },
+        }));
+        assert_eq!(orphan.clone_url, None);
+    }
+
+    #[test]
+    fn encode_component_escapes_gitlab_project_paths() {
+        assert_eq!(encode_component("group/sub/app"), "group%2Fsub%2Fapp");
+        assert_eq!(encode_component("feat/a b"), "feat%2Fa%20b");
+    }
+
+    #[test]
+    fn build_file_patch_renders_git_headers() {
+        let patch = build_file_patch("a.txt", "a.txt", "A", "@@ -0,0 +1 @@\n+hello\n");
+        assert!(patch.starts_with("diff --git a/a.txt b/a.txt\nnew file mode"));
+        assert!(patch.contains("--- /dev/null\n+++ b/a.txt\n@@ -0,0 +1 @@\n+hello"));
+    }
+}
@@ -0,0 +1,433 @@
+use std::path::Path;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use git2::{Oid, Repository, Sort};
+use tauri::State;
+
+use super::{
+    collect_commit_refs_map, commit_to_history_commit, open_repository_at_root, resolve_ref_to_oid,
+};
+use crate::git_utils::resolve_git_root;
+use crate::state::AppState;
+use crate::types::{GitCommitGraphEdge, GitCommitGraphResponse, GitCommitGraphRow};
+
+/// Layouts for the most recent tip sets; older entries are evicted first.
+const GRAPH_CACHE_ENTRIES: usize = 8;
+const MAX_GRAPH_ROWS: usize = 20_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Lane {
+    expecting: Oid,
+    color: usize,
+    /// Laid-out children waiting on `expecting`; more than one marks a fork.
+    children: usize,
+}
+
+/// Lane assignment for a topologically ordered commit stream. Rows are laid
+/// out one at a time so a cached layout can be extended for later pages.
+#[derive(Debug, Default, Clone)]
+struct GraphLayout {
+    lanes: Vec<Option<Lane>>,
+    next_color: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RowLayout {
+    column: usize,
+    color: usize,
+    edges: Vec<GitCommitGraphEdge>,
+    is_fork: bool,
+    is_tip: bool,
+    width: usize,
+}
+
+fn edge(kind: &str, from_column: usize, to_column: usize, color: usize) -> GitCommitGraphEdge {
+    GitCommitGraphEdge {
+        kind: kind.to_string(),
+        from_column,
+        to_column,
+        color,
+    }
+}
+
+impl GraphLayout {
+    fn allocate_color(&mut self) -> usize {
+        let color = self.next_color;
+        self.next_color += 1;
+        color
+    }
+
+    fn free_slot(&mut self) -> usize {
+        match self.lanes.iter().position(Option::is_none) {
+            Some(index) => index,
+            None => {
+                self.lanes.push(None);
+                self.lanes.len() - 1
+            }
+        }
+    }
+
+    fn place(&mut self, oid: Oid, parents: &[Oid]) -> RowLayout {
+        let incoming = self
+            .lanes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, lane)| lane.filter(|lane| lane.expecting == oid).map(|_| index))
+            .collect::<Vec<_>>();
+        let is_tip = incoming.is_empty();
+        let column = match incoming.first() {
+            Some(index) => *index,
+            None => self.free_slot(),
+        };
+        let children: usize = incoming
+            .iter()
+            .filter_map(|index| self.lanes[*index].map(|lane| lane.children))
+            .sum();
+        let color = match self.lanes[column] {
+            Some(lane) if !is_tip => lane.color,
+            _ => self.allocate_color(),
+        };
+
+        let mut edges = Vec::new();
+        for (index, lane) in self.lanes.iter().enumerate() {
+            if let Some(lane) = lane {
+                if incoming.contains(&index) {
+                    edges.push(edge("in", index, column, lane.color));
+                } else {
+                    edges.push(edge("pass", index, index, lane.color));
+                }
+            }
+        }
+        for index in &incoming {
+            self.lanes[*index] = None;
+        }
+
+        for (position, parent) in parents.iter().enumerate() {
+            let existing = self
+                .lanes
+                .iter()
+                .position(|lane| lane.is_some_and(|lane| lane.expecting == *parent));
+            if let Some(index) = existing {
+                // Another lane already waits for this parent: join it.
+                if let Some(lane) = self.lanes[index].as_mut() {
+                    lane.children += 1;
+                    edges.push(edge("out", column, index, lane.color));
+                }
+                continue;
+            }
+            let (index, lane_color) = if position == 0 {
+                (column, color)
+            } else {
+                let index = self.free_slot();
+                (index, self.allocate_color())
+            };
+            self.lanes[index] = Some(Lane {
+                expecting: *parent,
+                color: lane_color,
+                children: 1,
+            });
+            edges.push(edge("out", column, index, lane_color));
+        }
+
+        while self.lanes.last().is_some_and(Option::is_none) {
+            self.lanes.pop();
+        }
+        let width = edges
+            .iter()
+            .map(|edge| edge.from_column.max(edge.to_column) + 1)
+            .max()
+            .unwrap_or(0)
+            .max(column + 1);
+        RowLayout {
+            column,
+            color,
+            edges,
+            is_fork: children > 1,
+            is_tip,
+            width,
+        }
+    }
+}
+
+struct CachedGraph {
+    tips: Vec<Oid>,
+    layout: GraphLayout,
+    rows: Vec<GitCommitGraphRow>,
+    exhausted: bool,
+}
+
+/// Workspace id, cache key and the layout built so far.
+type GraphCacheEntry = (String, String, Arc<Mutex<CachedGraph>>);
+
+static GRAPH_CACHE: LazyLock<Mutex<Vec<GraphCacheEntry>>> =
+    LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Resolves the requested branches to tip commits. `None` means HEAD and
+/// `all`/`*` means every local and remote branch.
+fn resolve_graph_tips(
+    repo: &Repository,
+    branches: &[String],
+) -> Result<Vec<(String, Oid)>, String> {
+    let mut tips = Vec::new();
+    let wants_all = branches
+        .iter()
+        .any(|branch| matches!(branch.to_lowercase().as_str(), "all" | "*"));
+    if wants_all {
+        let references = repo.references().map_err(|e| e.to_string())?;
+        for reference in references.flatten() {
+            let Some(name) = reference.name() else {
+                continue;
+            };
+            let is_branch = name.starts_with("refs/heads/") || name.starts_with("refs/remotes/");
+            if !is_branch || name.ends_with("/HEAD") {
+                continue;
+            }
+            if let Some(oid) = reference.target() {
+                tips.push((name.to_string(), oid));
+            }
+        }
+    } else {
+        for branch in branches {
+            tips.push((branch.clone(), resolve_ref_to_oid(repo, branch)?));
+        }
+    }
+    if tips.is_empty() {
+        let head = repo
+            .head()
+            .ok()
+            .and_then(|head| head.target())
+            .ok_or("Repository has no commits yet.")?;
+        tips.push(("HEAD".to_string(), head));
+    }
+    tips.sort();
+    tips.dedup();
+    Ok(tips)
+}
+
+fn graph_cache_key(repo_root: &Path, tips: &[(String, Oid)]) -> String {
+    let tips = tips
+        .iter()
+        .map(|(name, oid)| format!("{name}={oid}"))
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("{}|{tips}", repo_root.display())
+}
+
+fn cached_graph(workspace_id: &str, key: &str, tips: Vec<Oid>) -> Arc<Mutex<CachedGraph>> {
+    let mut cache = GRAPH_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(index) = cache.iter().position(|(cached_workspace, cached_key, _)| {
+        cached_workspace == workspace_id && cached_key == key
+    }) {
+        let entry = cache.remove(index);
+        let graph = entry.2.clone();
+        cache.push(entry);
+        return graph;
+    }
+    let entry = Arc::new(Mutex::new(CachedGraph {
+        tips,
+        layout: GraphLayout::default(),
+        rows: Vec::new(),
+        exhausted: false,
+    }));
+    cache.push((workspace_id.to_string(), key.to_string(), entry.clone()));
+    if cache.len() > GRAPH_CACHE_ENTRIES {
+        cache.remove(0);
+    }
+    entry
+}
+
+/// Drops the cached graph layouts of a removed workspace.
+pub(crate) fn forget_commit_graphs(workspace_id: &str) {
+    let mut cache = GRAPH_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.retain(|(cached_workspace, _, _)| cached_workspace != workspace_id);
+}
+
+/// Lays out rows until `target` rows exist or history runs out. Layout
+/// resumes from the cached lane state, so earlier rows never shift.
+fn extend_graph(repo: &Repository, graph: &mut CachedGraph, target: usize) -> Result<(), String> {
+    if graph.exhausted || graph.rows.len() >= target {
+        return Ok(());
+    }
+    let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
+    revwalk
+        .set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+        .map_err(|e| e.to_string())?;
+    for tip in &graph.tips {
+        revwalk.push(*tip).map_err(|e| e.to_string())?;
+    }
+    let refs_map = collect_commit_refs_map(repo);
+    let mut walked = revwalk.skip(graph.rows.len());
+    while graph.rows.len() < target {
+        let Some(oid_result) = walked.next() else {
+            graph.exhausted = true;
+            break;
+        };
+        let oid = oid_result.map_err(|e| e.to_string())?;
+        let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+        let parents = commit.parent_ids().collect::<Vec<_>>();
+        let row = graph.layout.place(oid, &parents);
+        graph.rows.push(GitCommitGraphRow {
+            commit: commit_to_history_commit(&commit, &refs_map),
+            column: row.column,
+            color: row.color,
+            edges: row.edges,
+            is_merge: parents.len() > 1,
+            is_fork: row.is_fork,
+            is_tip: row.is_tip,
+            width: row.width,
+        });
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn get_git_commit_graph(
+    workspace_id: String,
+    branches: Option<Vec<String>>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<GitCommitGraphResponse, String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+    drop(workspaces);
+
+    let repo_root = resolve_git_root(&entry)?;
+    tokio::task::spawn_blocking(move || -> Result<GitCommitGraphResponse, String> {
+        let repo = open_repository_at_root(&repo_root)?;
+        let branches = branches
+            .unwrap_or_default()
+            .into_iter()
+            .map(|branch| branch.trim().to_string())
+            .filter(|branch| !branch.is_empty())
+            .collect::<Vec<_>>();
+        let tips = resolve_graph_tips(&repo, &branches)?;
+        let key = graph_cache_key(&repo_root, &tips);
+        let offset = offset.unwrap_or(0);
+        let limit = limit.unwrap_or(100).clamp(1, 500);
+        let target = offset
+            .saturating_add(limit)
+            .saturating_add(1)
+            .min(MAX_GRAPH_ROWS);
+
+        let entry = cached_graph(
+            &workspace_id,
+            &key,
+            tips.into_iter().map(|(_, oid)| oid).collect(),
+        );
+        let mut graph = entry.lock().unwrap_or_else(|e| e.into_inner());
+        extend_graph(&repo, &mut graph, target)?;
+        let rows = graph
+            .rows
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .cloned()
+            .collect::<Vec<_>>();
+        let has_more = offset.saturating_add(rows.len()) < graph.rows.len();
+        Ok(GitCommitGraphResponse {
+            cache_key: key,
+            offset,
+            limit,
+            has_more,
+            truncated: !graph.exhausted && graph.rows.len() >= MAX_GRAPH_ROWS,
+            max_columns: rows.iter().map(|row| row.width).max().unwrap_or(0),
+            rows,
+        })
+    })
+    .await
+    .map_err(|error| format!("Failed to walk the commit graph: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oid(value: u8) -> Oid {
+        Oid::from_str(&format!("{value:02x}").repeat(20)).unwrap()
+    }
+
+    fn kinds(row: &RowLayout) -> Vec<(String, usize, usize)> {
+        row.edges
+            .iter()
+            .map(|edge| (edge.kind.clone(), edge.from_column, edge.to_column))
+            .collect()
+    }
+
+    #[test]
+    fn lays_out_merge_and_fork_lanes() {
+        // 4 merges 3 into 2; 2 and 3 both fork from 1.
+        let mut layout = GraphLayout::default();
+        let merge = layout.place(oid(4), &[oid(2), oid(3)]);
+        assert_eq!((merge.column, merge.is_tip), (0, true));
+        assert_eq!(
+            kinds(&merge),
+            vec![("out".into(), 0, 0), ("out".into(), 0, 1)]
+        );
+
+        let left = layout.place(oid(2), &[oid(1)]);
+        assert_eq!(left.column, 0);
+        assert_eq!(
+            kinds(&left),
+            vec![
+                ("in".into(), 0, 0),
+                ("pass".into(), 1, 1),
+                ("out".into(), 0, 0)
+            ]
+        );
+
+        let right = layout.place(oid(3), &[oid(1)]);
+        assert_eq!(right.column, 1);
+        assert_eq!(
+            kinds(&right),
+            vec![
+                ("pass".into(), 0, 0),
+                ("in".into(), 1, 1),
+                ("out".into(), 1, 0)
+            ]
+        );
+        assert_eq!(right.color, merge.edges[1].color);
+
+        let base = layout.place(oid(1), &[]);
+        assert_eq!((base.column, base.is_fork, base.width), (0, true, 1));
+        assert!(layout.lanes.is_empty());
+    }
+
+    #[test]
+    fn joined_lanes_mark_fork_points() {
+        // Tips 3 and 2 both descend from 1; the second lane joins the first.
+        let mut layout = GraphLayout::default();
+        layout.place(oid(3), &[oid(1)]);
+        let second = layout.place(oid(2), &[oid(9)]);
+        assert_eq!(second.column, 1);
+        let join = layout.place(oid(9), &[oid(1)]);
+        assert_eq!(
+            kinds(&join),
+            vec![
+                ("pass".into(), 0, 0),
+                ("in".into(), 1, 1),
+                ("out".into(), 1, 0)
+            ]
+        );
+        let fork = layout.place(oid(1), &[]);
+        assert!(fork.is_fork);
+        assert_eq!(kinds(&fork), vec![("in".into(), 0, 0)]);
+    }
+
+    #[test]
+    fn removed_workspaces_drop_their_cached_graphs() {
+        let kept = cached_graph("graph-kept", "repo|HEAD", vec![oid(1)]);
+        let dropped = cached_graph("graph-dropped", "repo|HEAD", vec![oid(1)]);
+        assert!(!Arc::ptr_eq(&kept, &dropped));
+
+        forget_commit_graphs("graph-dropped");
+        let cache = GRAPH_CACHE.lock().unwrap();
+        assert!(cache
+            .iter()
+            .all(|(workspace_id, _, _)| workspace_id != "graph-dropped"));
+        assert!(cache.iter().any(|(_, _, graph)| Arc::ptr_eq(graph, &kept)));
+    }
+}
@@ -0,0 +1,513 @@
+use std::path::PathBuf;
+
+use git2::{DiffFormat, DiffOptions, Oid, Repository};
+use regex::{Regex, RegexBuilder};
+use tauri::State;
+
+use super::{
+    collect_commit_refs_map, commit_to_history_commit, open_repository_at_root, resolve_ref_to_oid,
+    trim_optional,
+};
+use crate::git_utils::resolve_git_root;
+use crate::shared::git_core::run_git_command;
+use crate::state::AppState;
+use crate::types::{GitHistorySearchHighlight, GitHistorySearchResponse, GitHistorySearchResult};
+use crate::utils::normalize_git_path;
+
+/// Matches collected before pagination; `total` becomes a lower bound past this.
+const HISTORY_SEARCH_SCAN_LIMIT: usize = 2_000;
+const MAX_DIFF_HIGHLIGHTS_PER_COMMIT: usize = 20;
+const MAX_HIGHLIGHT_TEXT_CHARS: usize = 400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PickaxeMode {
+    /// `git log -S`: commits that change the number of occurrences of a string.
+    Occurrences,
+    /// `git log -G`: commits whose added or removed lines match a regex.
+    Regex,
+}
+
+impl PickaxeMode {
+    fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.map(|value| value.trim().to_lowercase()).as_deref() {
+            None | Some("") | Some("string") | Some("s") => Ok(PickaxeMode::Occurrences),
+            Some("regex") | Some("g") => Ok(PickaxeMode::Regex),
+            Some(other) => Err(format!("Unsupported pickaxe mode `{other}`.")),
+        }
+    }
+}
+
+struct HistorySearchFilters {
+    message: Option<Regex>,
+    message_pattern: Option<String>,
+    author: Option<String>,
+    date_from: Option<i64>,
+    date_to: Option<i64>,
+    path: Option<String>,
+    pickaxe: Option<(String, PickaxeMode)>,
+}
+
+impl HistorySearchFilters {
+    /// `--regexp-ignore-case` applies to `-S`/`-G` as well as `--grep` and
+    /// `--author`, so with a pickaxe the message and author filters run in
+    /// `matches_text_filters` instead of git.
+    fn text_filters_in_git(&self) -> bool {
+        self.pickaxe.is_none()
+    }
+
+    fn pickaxe_matcher(&self) -> Option<Regex> {
+        let (pattern, mode) = self.pickaxe.as_ref()?;
+        let source = match mode {
+            PickaxeMode::Occurrences => regex::escape(pattern),
+            PickaxeMode::Regex => pattern.clone(),
+        };
+        Regex::new(&source).ok()
+    }
+}
+
+/// `revision` of `None` searches every local and remote branch.
+fn build_log_args(revision: Option<&str>, filters: &HistorySearchFilters) -> Vec<String> {
+    let mut args = vec![
+        "log".to_string(),
+        "--format=%H".to_string(),
+        format!("--max-count={HISTORY_SEARCH_SCAN_LIMIT}"),
+    ];
+    match revision {
+        Some(revision) => args.push(revision.to_string()),
+        None => {
+            args.push("--branches".to_string());
+            args.push("--remotes".to_string());
+        }
+    }
+    if filters.text_filters_in_git()
+        && (filters.message_pattern.is_some() || filters.author.is_some())
+    {
+        args.push("--extended-regexp".to_string());
+        args.push("--regexp-ignore-case".to_string());
+        if let Some(pattern) = filters.message_pattern.as_ref() {
+            args.push(format!("--grep={pattern}"));
+        }
+        // Escaped so the filter keeps the literal substring semantics of
+        // get_git_commit_history.
+        if let Some(author) = filters.author.as_ref() {
+            args.push(format!("--author={}", regex::escape(author)));
+        }
+    }
+    if let Some(from) = filters.date_from {
+        args.push(format!("--since=@{from}"));
+    }
+    if let Some(to) = filters.date_to {
+        args.push(format!("--until=@{to}"));
+    }
+    match filters.pickaxe.as_ref() {
+        Some((pattern, PickaxeMode::Occurrences)) => args.push(format!("-S{pattern}")),
+        Some((pattern, PickaxeMode::Regex)) => args.push(format!("-G{pattern}")),
+        None => {}
+    }
+    args.push("--".to_string());
+    if let Some(path) = filters.path.as_ref() {
+        args.push(path.clone());
+    }
+    args
+}
+
+/// Rejects regex syntax that the `regex` crate accepts but POSIX extended
+/// regular expressions, which git runs, do not define or read differently.
+/// Compiling with the `regex` crate then catches plain syntax errors.
+fn validate_extended_regex(pattern: &str) -> Result<(), String> {
+    let mut chars = pattern.chars().peekable();
+    let mut in_bracket = false;
+    let mut previous = None;
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => {
+                if let Some(escaped) = chars.next().filter(char::is_ascii_alphanumeric) {
+                    return Err(format!(
+                        "`\\{escaped}` is not supported; use a bracket expression like [[:alnum:]]"
+                    ));
+                }
+            }
+            '[' if !in_bracket => {
+                in_bracket = true;
+                // `]` right after `[` or `[^` is a literal member.
+                if chars.peek() == Some(&'^') {
+                    chars.next();
+                }
+                if chars.peek() == Some(&']') {
+                    chars.next();
+                }
+            }
+            '[' if in_bracket && matches!(chars.peek(), Some(':' | '.' | '=')) => {
+                let delimiter = chars.next().unwrap_or(':');
+                let mut closed = false;
+                while let Some(inner) = chars.next() {
+                    if inner == delimiter && chars.peek() == Some(&']') {
+                        chars.next();
+                        closed = true;
+                        break;
+                    }
+                }
+                if !closed {
+                    return Err("Unterminated character class".to_string());
+                }
+            }
+            ']' if in_bracket => in_bracket = false,
+            '(' if !in_bracket && chars.peek() == Some(&'?') => {
+                return Err("`(?` groups and flags are not supported".to_string());
+            }
+            '?' | '+' | '*' if !in_bracket && matches!(previous, Some('?' | '+' | '*' | '}')) => {
+                return Err("Lazy and possessive quantifiers are not supported".to_string());
+            }
+            _ => {}
+        }
+        previous = Some(ch);
+    }
+    Regex::new(pattern).map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// The message and author checks git skips when a pickaxe is set, with the
+/// same case-insensitive semantics as `--grep` and `--author`.
+fn matches_text_filters(commit: &git2::Commit<'_>, filters: &HistorySearchFilters) -> bool {
+    if let Some(matcher) = filters.message.as_ref() {
+        if !matcher.is_match(commit.message().unwrap_or("")) {
+            return false;
+        }
+    }
+    if let Some(author) = filters.author.as_ref() {
+        let signature = commit.author();
+        let ident = format!(
+            "{} <{}>",
+            signature.name().unwrap_or(""),
+            signature.email().unwrap_or("")
+        );
+        if !ident.to_lowercase().contains(&author.to_lowercase()) {
+            return false;
+        }
+    }
+    true
+}
+
+fn match_ranges(matcher: &Regex, text: &str) -> Vec<(usize, usize)> {
+    matcher
+        .find_iter(text)
+        .filter(|found| !found.as_str().is_empty())
+        .map(|found| {
+            let start = text[..found.start()].chars().count();
+            (start, start + found.as_str().chars().count())
+        })
+        .collect()
+}
+
+fn highlight(
+    field: &str,
+    path: Option<String>,
+    text: &str,
+    matcher: &Regex,
+) -> Option<GitHistorySearchHighlight> {
+    let text: String = text.chars().take(MAX_HIGHLIGHT_TEXT_CHARS).collect();
+    let ranges = match_ranges(matcher, &text);
+    if ranges.is_empty() {
+        return None;
+    }
+    Some(GitHistorySearchHighlight {
+        field: field.to_string(),
+        path,
+        text,
+        ranges,
+    })
+}
+
+fn collect_diff_highlights(
+    repo: &Repository,
+    commit: &git2::Commit<'_>,
+    path: Option<&str>,
+    matcher: &Regex,
+) -> Vec<GitHistorySearchHighlight> {
+    let Ok(tree) = commit.tree() else {
+        return Vec::new();
+    };
+    let parent_tree = commit.parent(0).ok().and_then(|parent| parent.tree().ok());
+    let mut options = DiffOptions::new();
+    if let Some(path) = path {
+        options.pathspec(path);
+    }
+    let Ok(diff) = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut options))
+    else {
+        return Vec::new();
+    };
+    let mut highlights = Vec::new();
+    // Returning false from the callback stops the walk; the resulting error is expected.
+    let _ = diff.print(DiffFormat::Patch, |delta, _hunk, line| {
+        let field = match line.origin() {
+            '+' => "added",
+            '-' => "removed",
+            _ => return true,
+        };
+        let content = String::from_utf8_lossy(line.content());
+        let file_path = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .map(|path| normalize_git_path(path.to_string_lossy().as_ref()));
+        if let Some(found) = highlight(field, file_path, content.trim_end(), matcher) {
+            highlights.push(found);
+        }
+        highlights.len() < MAX_DIFF_HIGHLIGHTS_PER_COMMIT
+    });
+    highlights
+}
+
+fn collect_highlights(
+    repo: &Repository,
+    commit: &git2::Commit<'_>,
+    filters: &HistorySearchFilters,
+    pickaxe_matcher: Option<&Regex>,
+) -> Vec<GitHistorySearchHighlight> {
+    let mut highlights = Vec::new();
+    if let Some(matcher) = filters.message.as_ref() {
+        let message = commit.message().unwrap_or("");
+        highlights.extend(
+            message
+                .lines()
+                .filter_map(|line| highlight("message", None, line, matcher)),
+        );
+    }
+    if let Some(author) = filters.author.as_ref() {
+        let matcher = RegexBuilder::new(&regex::escape(author))
+            .case_insensitive(true)
+            .build();
+        if let Ok(matcher) = matcher {
+            let signature = commit.author();
+            let text = format!(
+                "{} <{}>",
+                signature.name().unwrap_or(""),
+                signature.email().unwrap_or("")
+            );
+            highlights.extend(highlight("author", None, &text, &matcher));
+        }
+    }
+    if let Some(matcher) = pickaxe_matcher {
+        highlights.extend(collect_diff_highlights(
+            repo,
+            commit,
+            filters.path.as_deref(),
+            matcher,
+        ));
+    }
+    highlights
+}
+
+#[tauri::command]
+pub(crate) async fn search_git_history(
+    workspace_id: String,
+    branch: Option<String>,
+    message_pattern: Option<String>,
+    author: Option<String>,
+    date_from: Option<i64>,
+    date_to: Option<i64>,
+    path: Option<String>,
+    pickaxe: Option<String>,
+    pickaxe_mode: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<GitHistorySearchResponse, String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+    drop(workspaces);
+
+    let message_pattern = trim_optional(message_pattern);
+    let message = message_pattern
+        .as_deref()
+        .map(|pattern| {
+            validate_extended_regex(pattern)
+                .and_then(|()| {
+                    RegexBuilder::new(pattern)
+                        .case_insensitive(true)
+                        .multi_line(true)
+                        .build()
+                        .map_err(|e| e.to_string())
+                })
+                .map_err(|e| format!("Invalid message pattern: {e}"))
+        })
+        .transpose()?;
+    let pickaxe_mode = PickaxeMode::parse(pickaxe_mode.as_deref())?;
+    // Pickaxe strings are matched verbatim, so only drop fully blank input.
+    let pickaxe = pickaxe.filter(|value| !value.trim().is_empty());
+    if let (Some(pattern), PickaxeMode::Regex) = (pickaxe.as_ref(), pickaxe_mode) {
+        validate_extended_regex(pattern).map_err(|e| format!("Invalid pickaxe pattern: {e}"))?;
+    }
+    let filters = HistorySearchFilters {
+        message,
+        message_pattern,
+        author: trim_optional(author),
+        date_from,
+        date_to,
+        path: trim_optional(path).map(|value| normalize_git_path(&value)),
+        pickaxe: pickaxe.map(|pattern| (pattern, pickaxe_mode)),
+    };
+
+    let repo_root = resolve_git_root(&entry)?;
+    let revision = {
+        let repo = open_repository_at_root(&repo_root)?;
+        match trim_optional(branch) {
+            Some(value) if value == "all" || value == "*" => None,
+            Some(value) => Some(resolve_ref_to_oid(&repo, &value)?.to_string()),
+            None => Some("HEAD".to_string()),
+        }
+    };
+    let args = build_log_args(revision.as_deref(), &filters);
+    let arg_refs = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let output = run_git_command(&PathBuf::from(&repo_root), &arg_refs).await?;
+    let repo = open_repository_at_root(&repo_root)?;
+    let shas = output
+        .lines()
+        .filter_map(|line| Oid::from_str(line.trim()).ok())
+        .filter(|oid| {
+            filters.text_filters_in_git()
+                || repo
+                    .find_commit(*oid)
+                    .is_ok_and(|commit| matches_text_filters(&commit, &filters))
+        })
+        .collect::<Vec<_>>();
+
+    let offset = offset.unwrap_or(0);
+    let limit = limit.unwrap_or(50).clamp(1, 200);
+    let total = shas.len();
+    let refs_map = collect_commit_refs_map(&repo);
+    let pickaxe_matcher = filters.pickaxe_matcher();
+    let mut results = Vec::new();
+    for oid in shas.iter().skip(offset).take(limit) {
+        let commit = repo.find_commit(*oid).map_err(|e| e.to_string())?;
+        let highlights = collect_highlights(&repo, &commit, &filters, pickaxe_matcher.as_ref());
+        results.push(GitHistorySearchResult {
+            commit: commit_to_history_commit(&commit, &refs_map),
+            highlights,
+        });
+    }
+    Ok(GitHistorySearchResponse {
+        total,
+        offset,
+        limit,
+        has_more: offset.saturating_add(results.len()) < total,
+        truncated: total >= HISTORY_SEARCH_SCAN_LIMIT,
+        results,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::git;
+    use std::fs;
+
+    fn empty_filters() -> HistorySearchFilters {
+        HistorySearchFilters {
+            message: None,
+            message_pattern: None,
+            author: None,
+            date_from: None,
+            date_to: None,
+            path: None,
+            pickaxe: None,
+        }
+    }
+
+    #[test]
+    fn match_ranges_use_character_offsets() {
+        let matcher = Regex::new("needle").unwrap();
+        assert_eq!(match_ranges(&matcher, "héllo needle"), vec![(6, 12)]);
+    }
+
+    #[test]
+    fn log_args_map_pickaxe_modes() {
+        let mut filters = empty_filters();
+        filters.path = Some("src/lib.rs".to_string());
+        filters.pickaxe = Some(("parse_config".to_string(), PickaxeMode::Occurrences));
+        let args = build_log_args(Some("HEAD"), &filters);
+        assert!(args.contains(&"-Sparse_config".to_string()));
+        assert_eq!(&args[args.len() - 2..], ["--", "src/lib.rs"]);
+
+        filters.pickaxe = Some(("fn [a-z]+_config".to_string(), PickaxeMode::Regex));
+        let args = build_log_args(None, &filters);
+        assert!(args.contains(&"-Gfn [a-z]+_config".to_string()));
+        assert!(args.contains(&"--branches".to_string()));
+    }
+
+    #[test]
+    fn case_insensitive_text_filters_stay_out_of_pickaxe_searches() {
+        let mut filters = empty_filters();
+        filters.message_pattern = Some("fix".to_string());
+        filters.author = Some("ana".to_string());
+        let args = build_log_args(Some("HEAD"), &filters);
+        assert!(args.contains(&"--regexp-ignore-case".to_string()));
+        assert!(args.contains(&"--grep=fix".to_string()));
+
+        filters.pickaxe = Some(("Config".to_string(), PickaxeMode::Occurrences));
+        let args = build_log_args(Some("HEAD"), &filters);
+        assert!(!args.contains(&"--regexp-ignore-case".to_string()));
+        assert!(!args.iter().any(|arg| arg.starts_with("--grep")));
+        assert!(!args.iter().any(|arg| arg.starts_with("--author")));
+    }
+
+    #[test]
+    fn patterns_are_validated_as_extended_regexes() {
+        for pattern in ["fix(es)?", "[[:alpha:]_]+", "a{2,3}", "[]x]", "v1\\.2"] {
+            assert!(validate_extended_regex(pattern).is_ok(), "{pattern}");
+        }
+        for pattern in [
+            "\\w+",
+            "(?i)fix",
+            "a+?",
+            "(?:a)",
+            "\\d",
+            "a(b",
+            "[[:alpha:]",
+        ] {
+            assert!(validate_extended_regex(pattern).is_err(), "{pattern}");
+        }
+    }
+
+    #[tokio::test]
+    async fn pickaxe_finds_commit_that_introduced_symbol() {
+        let root =
+            std::env::temp_dir().join(format!("moss-history-search-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create temp repo");
+        git(&root, &["init", "-q", "-b", "main"]);
+        fs::write(root.join("lib.rs"), "fn main() {}\n").expect("write");
+        git(&root, &["add", "."]);
+        git(&root, &["commit", "-q", "-m", "initial"]);
+        fs::write(root.join("lib.rs"), "fn main() {}\nfn parse_config() {}\n").expect("write");
+        git(&root, &["add", "."]);
+        git(&root, &["commit", "-q", "-m", "feat: add config parser"]);
+        let introduced = git(&root, &["rev-parse", "HEAD"]);
+        fs::write(root.join("notes.md"), "unrelated\n").expect("write");
+        git(&root, &["add", "."]);
+        git(&root, &["commit", "-q", "-m", "docs: notes"]);
+
+        let mut filters = empty_filters();
+        filters.pickaxe = Some(("parse_config".to_string(), PickaxeMode::Occurrences));
+        let args = build_log_args(Some("HEAD"), &filters);
+        let arg_refs = args.iter().map(String::as_str).collect::<Vec<_>>();
+        let output = run_git_command(&root, &arg_refs).await.expect("git log");
+        assert_eq!(
+            output.lines().collect::<Vec<_>>(),
+            vec![introduced.as_str()]
+        );
+
+        let repo = Repository::open(&root).expect("open repo");
+        let commit = repo
+            .find_commit(Oid::from_str(&introduced).unwrap())
+            .expect("commit");
+        let matcher = filters.pickaxe_matcher().expect("matcher");
+        let highlights = collect_highlights(&repo, &commit, &filters, Some(&matcher));
+        assert_eq!(highlights.len(), 1);
+        assert_eq!(highlights[0].field, "added");
+        assert_eq!(highlights[0].path.as_deref(), Some("lib.rs"));
+        assert_eq!(highlights[0].ranges, vec![(3, 15)]);
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use git2::Oid;
+
+use super::open_repository_at_root;
+use crate::shared::git_core::run_git_command;
+use crate::types::{GitCommitSignature, GitIdentityProfile, WorkspaceEntry};
+
+/// Worktrees carry default settings, so fall back to the parent workspace's profile.
+pub(crate) fn identity_for_entry(
+    workspaces: &HashMap<String, WorkspaceEntry>,
+    entry: &WorkspaceEntry,
+) -> Option<GitIdentityProfile> {
+    if let Some(profile) = entry.settings.git_identity.clone() {
+        return Some(profile);
+    }
+    let parent_id = entry.parent_id.as_ref()?;
+    workspaces
+        .get(parent_id)
+        .and_then(|parent| parent.settings.git_identity.clone())
+}
+
+fn config_value(key: &str, value: Option<&String>) -> Result<Option<String>, String> {
+    let Some(value) = value
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+    else {
+        return Ok(None);
+    };
+    if value.chars().any(char::is_control) {
+        return Err(format!(
+            "Identity setting `{key}` contains control characters."
+        ));
+    }
+    Ok(Some(value.to_string()))
+}
+
+fn signing_format_config(value: &str) -> Result<&'static str, String> {
+    match value.trim().to_lowercase().as_str() {
+        "gpg" | "openpgp" => Ok("openpgp"),
+        "ssh" => Ok("ssh"),
+        "x509" => Ok("x509"),
+        other => Err(format!("Unsupported signing format `{other}`.")),
+    }
+}
+
+/// Renders a profile as `-c key=value` pairs. Git forwards these to child
+/// processes, so rebase `exec` steps and hooks see the same identity.
+pub(super) fn identity_config_args(profile: &GitIdentityProfile) -> Result<Vec<String>, String> {
+    let mut pairs = Vec::new();
+    if let Some(name) = config_value("name", profile.name.as_ref())? {
+        pairs.push(("user.name", name));
+    }
+    if let Some(email) = config_value("email", profile.email.as_ref())? {
+        pairs.push(("user.email", email));
+    }
+    let signing_key = config_value("signingKey", profile.signing_key.as_ref())?;
+    if let Some(key) = signing_key.clone() {
+        pairs.push(("user.signingkey", key));
+    }
+    if let Some(format) = config_value("signingFormat", profile.signing_format.as_ref())? {
+        pairs.push(("gpg.format", signing_format_config(&format)?.to_string()));
+    }
+    match profile.sign_commits {
+        Some(sign) => pairs.push(("commit.gpgsign", sign.to_string())),
+        None if signing_key.is_some() => pairs.push(("commit.gpgsign", "true".to_string())),
+        None => {}
+    }
+    Ok(pairs
+        .into_iter()
+        .flat_map(|(key, value)| ["-c".to_string(), format!("{key}={value}")])
+        .collect())
+}
+
+pub(crate) fn git_args_with_identity(
+    profile: Option<&GitIdentityProfile>,
+    args: &[&str],
+) -> Result<Vec<String>, String> {
+    let mut combined = match profile {
+        Some(profile) => identity_config_args(profile)?,
+        None => Vec::new(),
+    };
+    combined.extend(args.iter().map(|arg| arg.to_string()));
+    Ok(combined)
+}
+
+fn signature_format(signature: &[u8]) -> Option<&'static str> {
+    let text = String::from_utf8_lossy(signature);
+    let text = text.trim_start();
+    if text.starts_with("-----BEGIN PGP SIGNATURE-----") {
+        Some("gpg")
+    } else if text.starts_with("-----BEGIN SSH SIGNATURE-----") {
+        Some("ssh")
+    } else if text.starts_with("-----BEGIN SIGNED MESSAGE-----") {
+        Some("x509")
+    } else {
+        None
+    }
+}
+
+/// Maps git's `%G?` placeholder. `N` for a commit that does carry a signature
+/// means git could not verify it (e.g. no allowed signers file for SSH).
+fn signature_status(code: &str) -> &'static str {
+    match code.trim() {
+        "G" => "good",
+        "B" => "bad",
+        "U" => "untrusted",
+        "X" => "expired",
+        "Y" => "expired-key",
+        "R" => "revoked",
+        _ => "unverifiable",
+    }
+}
+
+pub(super) async fn read_commit_signature(
+    repo_root: &Path,
+    commit_hash: &str,
+) -> Option<GitCommitSignature> {
+    let oid = Oid::from_str(commit_hash).ok()?;
+    let format = {
+        let repo = open_repository_at_root(repo_root).ok()?;
+        let (signature, _) = repo.extract_signature(&oid, None).ok()?;
+        signature_format(&signature)
+    };
+    let sha = oid.to_string();
+    let output = run_git_command(
+        &PathBuf::from(repo_root),
+        &["log", "-1", "--format=%G?%n%GS%n%GK", &sha],
+    )
+    .await
+    .unwrap_or_default();
+    let mut lines = output.lines();
+    let status = signature_status(lines.next().unwrap_or(""));
+    let non_empty = |value: Option<&str>| {
+        value
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(ToOwned::to_owned)
+    };
+    Some(GitCommitSignature {
+        status: status.to_string(),
+        format: format.map(str::to_string),
+        signer: non_empty(lines.next()),
+        key: non_empty(lines.next()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity_profile_renders_config_args() {
+        let profile = GitIdentityProfile {
+            name: Some("Ada Lovelace".to_string()),
+            email: Some(" ada@example.com ".to_string()),
+            signing_key: Some("~/.ssh/id_ed25519.pub".to_string()),
+            signing_format: Some("ssh".to_string()),
+            sign_commits: None,
+        };
+        let args = git_args_with_identity(Some(&profile), &["commit", "-m", "msg"]).unwrap();
+        assert_eq!(
+            args,
+            vec![
+                "-c",
+                "user.name=Ada Lovelace",
+                "-c",
+                "user.email=ada@example.com",
+                "-c",
+                "user.signingkey=~/.ssh/id_ed25519.pub",
+                "-c",
+                "gpg.format=ssh",
+                "-c",
+                "commit.gpgsign=true",
+                "commit",
+                "-m",
+                "msg",
+            ]
+        );
+    }
+
+    #[test]
+    fn identity_profile_rejects_invalid_values() {
+        let mut profile = GitIdentityProfile {
+            email: Some("a@b.c\n[core]".to_string()),
+            ..GitIdentityProfile::default()
+        };
+        assert!(identity_config_args(&profile).is_err());
+        profile.email = None;
+        profile.signing_format = Some("pgp2".to_string());
+        assert!(identity_config_args(&profile).is_err());
+        profile.signing_format = Some("GPG".to_string());
+        profile.sign_commits = Some(false);
+        assert_eq!(
+            identity_config_args(&profile).unwrap(),
+            vec!["-c", "gpg.format=openpgp", "-c", "commit.gpgsign=false"]
+        );
+    }
+
+    #[test]
+    fn maps_signature_formats_and_statuses() {
+        assert_eq!(
+            signature_format(b"-----BEGIN SSH SIGNATURE-----\nabc"),
+            Some("ssh")
+        );
+        assert_eq!(
+            signature_format(b"-----BEGIN PGP SIGNATURE-----\nabc"),
+            Some("gpg")
+        );
+        assert_eq!(signature_format(b"garbage"), None);
+        assert_eq!(signature_status("G"), "good");
+        assert_eq!(signature_status("E"), "unverifiable");
+        assert_eq!(signature_status("N"), "unverifiable");
+    }
+}
@@ -0,0 +1,157 @@
+use std::fs;
+use std::path::PathBuf;
+
+use git2::Repository;
+
+const LFS_SPEC_PREFIX: &str = "version https://git-lfs.github.com/spec/";
+/// Pointer files are tiny; the spec caps them well below this.
+pub(super) const LFS_POINTER_MAX_BYTES: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct LfsPointer {
+    pub(super) oid: String,
+    pub(super) size: u64,
+}
+
+pub(super) fn parse_lfs_pointer(data: &[u8]) -> Option<LfsPointer> {
+    if data.len() > LFS_POINTER_MAX_BYTES || !data.starts_with(LFS_SPEC_PREFIX.as_bytes()) {
+        return None;
+    }
+    let text = std::str::from_utf8(data).ok()?;
+    let mut oid = None;
+    let mut size = None;
+    for line in text.lines() {
+        if let Some(value) = line.strip_prefix("oid sha256:") {
+            let value = value.trim();
+            if value.len() == 64 && value.chars().all(|ch| ch.is_ascii_hexdigit()) {
+                oid = Some(value.to_lowercase());
+            }
+        } else if let Some(value) = line.strip_prefix("size ") {
+            size = value.trim().parse::<u64>().ok();
+        }
+    }
+    Some(LfsPointer {
+        oid: oid?,
+        size: size?,
+    })
+}
+
+fn lfs_object_path(repo: &Repository, oid: &str) -> PathBuf {
+    // Objects live in the common dir so linked worktrees share one store.
+    repo.commondir()
+        .join("lfs")
+        .join("objects")
+        .join(&oid[0..2])
+        .join(&oid[2..4])
+        .join(oid)
+}
+
+/// Returns the smudged content for an LFS pointer when the object has been
+/// fetched locally; `None` for regular content or missing objects.
+pub(super) fn read_lfs_object(repo: &Repository, data: &[u8], max_bytes: usize) -> Option<Vec<u8>> {
+    let pointer = parse_lfs_pointer(data)?;
+    if pointer.size > max_bytes as u64 {
+        return None;
+    }
+    fs::read(lfs_object_path(repo, &pointer.oid)).ok()
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+/// Summarises a change between two LFS pointers in place of the pointer diff.
+pub(super) fn describe_lfs_change(old: Option<&LfsPointer>, new: Option<&LfsPointer>) -> String {
+    match (old, new) {
+        (Some(old), Some(new)) if old.oid == new.oid => {
+            format!("LFS object unchanged ({})", format_size(new.size))
+        }
+        (Some(old), Some(new)) => format!(
+            "LFS object changed (size {} \u{2192} {})",
+            format_size(old.size),
+            format_size(new.size)
+        ),
+        (None, Some(new)) => format!("LFS object added ({})", format_size(new.size)),
+        (Some(old), None) => format!("LFS object removed ({})", format_size(old.size)),
+        (None, None) => String::new(),
+    }
+}
+
+/// Summarises a working-tree file the LFS smudge filter checked out: the
+/// disk holds the real content while the index still holds a pointer.
+pub(super) fn describe_smudged_lfs_change(old: &LfsPointer, new_size: u64) -> String {
+    format!(
+        "LFS object modified (size {} \u{2192} {})",
+        format_size(old.size),
+        format_size(new_size)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pointer_text(oid_char: char, size: u64) -> String {
+        format!(
+            "version https://git-lfs.github.com/spec/v1\noid sha256:{}\nsize {size}\n",
+            oid_char.to_string().repeat(64)
+        )
+    }
+
+    #[test]
+    fn parses_lfs_pointer_files() {
+        let pointer = parse_lfs_pointer(pointer_text('a', 2048).as_bytes()).expect("pointer");
+        assert_eq!(pointer.oid, "a".repeat(64));
+        assert_eq!(pointer.size, 2048);
+        assert!(parse_lfs_pointer(b"fn main() {}\n").is_none());
+        assert!(
+            parse_lfs_pointer(b"version https://git-lfs.github.com/spec/v1\nsize 1\n").is_none()
+        );
+    }
+
+    #[test]
+    fn describes_lfs_size_changes() {
+        let old = parse_lfs_pointer(pointer_text('a', 512).as_bytes()).unwrap();
+        let new = parse_lfs_pointer(pointer_text('b', 3 * 1024 * 1024).as_bytes()).unwrap();
+        assert_eq!(
+            describe_lfs_change(Some(&old), Some(&new)),
+            "LFS object changed (size 512 B \u{2192} 3.0 MB)"
+        );
+        assert_eq!(
+            describe_lfs_change(None, Some(&old)),
+            "LFS object added (512 B)"
+        );
+        assert_eq!(
+            describe_smudged_lfs_change(&old, 2048),
+            "LFS object modified (size 512 B \u{2192} 2.0 KB)"
+        );
+    }
+
+    #[test]
+    fn reads_local_lfs_objects_from_common_dir() {
+        let root = std::env::temp_dir().join(format!("moss-lfs-test-{}", uuid::Uuid::new_v4()));
+        let repo = Repository::init(&root).expect("init repo");
+        let oid = "c".repeat(64);
+        let object_path = lfs_object_path(&repo, &oid);
+        fs::create_dir_all(object_path.parent().unwrap()).expect("object dir");
+        fs::write(&object_path, b"\x89PNG").expect("write object");
+        let pointer = pointer_text('c', 4);
+        assert_eq!(
+            read_lfs_object(&repo, pointer.as_bytes(), 1024),
+            Some(b"\x89PNG".to_vec())
+        );
+        assert_eq!(read_lfs_object(&repo, pointer.as_bytes(), 2), None);
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -12,21 +12,55 @@ use tokio::time::{timeout, Duration};
 
 use crate::git_utils::{
     checkout_branch, commit_to_entry, diff_patch_to_string, diff_stats_for_path, image_mime_type,
-    list_git_roots as scan_git_roots, parse_github_repo, resolve_git_root,
+    list_git_roots as scan_git_roots, resolve_git_root,
 };
 use crate::state::AppState;
 use crate::types::{
     BranchInfo, GitBranchCompareCommitSets, GitBranchListItem, GitCommitDetails, GitCommitDiff,
     GitCommitFileChange, GitFileDiff, GitFileStatus, GitHistoryCommit, GitHistoryResponse,
-    GitHubIssue, GitHubIssuesResponse, GitHubPullRequest, GitHubPullRequestComment,
-    GitHubPullRequestDiff, GitHubPullRequestsResponse, GitLogResponse, GitPrExistingPullRequest,
-    GitPrWorkflowDefaults, GitPrWorkflowResult, GitPrWorkflowStage, GitPushPreviewResponse,
+    GitHubIssuesResponse, GitHubPullRequestComment, GitHubPullRequestDiff,
+    GitHubPullRequestsResponse, GitLogResponse, GitPrExistingPullRequest, GitPrWorkflowDefaults,
+    GitPrWorkflowResult, GitPrWorkflowStage, GitPushPreviewResponse,
 };
 use crate::utils::{git_env_path, normalize_git_path, resolve_git_binary};
+use forge::{forge_remote_from_path, forge_remote_from_repo, ForgeKind};
 use validation::validate_local_branch_name;
 
+mod bisect;
+mod branch_hygiene;
+mod forge;
+mod graph;
+mod history_search;
+mod identity;
+mod lfs;
+mod pr_review;
+mod rebase;
+mod reviews;
+mod submodules;
+mod tags;
 mod validation;
 
+pub(crate) use bisect::run_git_bisect;
+pub(crate) use branch_hygiene::{classify_git_branches, delete_git_branches};
+pub(crate) use forge::get_git_forge_info;
+pub(crate) use graph::{forget_commit_graphs, get_git_commit_graph};
+pub(crate) use history_search::search_git_history;
+pub(crate) use identity::{git_args_with_identity, identity_for_entry};
+pub(crate) use pr_review::review_pull_request;
+pub(crate) use rebase::{
+    abort_git_rebase, continue_git_rebase, execute_git_rebase_plan, get_git_rebase_plan,
+};
+pub(crate) use reviews::{
+    post_github_pull_request_comment, post_github_pull_request_inline_comment,
+    submit_github_pull_request_review,
+};
+pub(crate) use submodules::{
+    forget_submodule_statuses, get_git_submodules, sync_git_submodules, update_git_submodules,
+};
+pub(crate) use tags::{
+    create_git_tag, delete_git_tag, generate_release_notes, list_git_tags, push_git_tag,
+};
+
 const INDEX_SKIP_WORKTREE_FLAG: u16 = 0x4000;
 const MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
 const MAX_COMMIT_DIFF_LINES: usize = 10_000;
@@ -240,22 +274,99 @@ fn encode_image_base64(data: &[u8]) -> Option<String> {
     Some(STANDARD.encode(data))
 }
 
-fn blob_to_base64(blob: git2::Blob) -> Option<String> {
+fn blob_to_base64(repo: &Repository, blob: git2::Blob) -> Option<String> {
     if blob.size() > MAX_IMAGE_BYTES {
         return None;
     }
+    if let Some(object) = lfs::read_lfs_object(repo, blob.content(), MAX_IMAGE_BYTES) {
+        return encode_image_base64(&object);
+    }
     encode_image_base64(blob.content())
 }
 
-fn read_image_base64(path: &Path) -> Option<String> {
+fn read_image_base64(repo: &Repository, path: &Path) -> Option<String> {
     let metadata = fs::metadata(path).ok()?;
     if metadata.len() > MAX_IMAGE_BYTES as u64 {
         return None;
     }
     let data = fs::read(path).ok()?;
+    // Checkouts without the LFS filter leave pointer files in the workdir.
+    if let Some(object) = lfs::read_lfs_object(repo, &data, MAX_IMAGE_BYTES) {
+        return encode_image_base64(&object);
+    }
     encode_image_base64(&data)
 }
 
+fn read_lfs_pointer_side(
+    repo: &Repository,
+    file: &git2::DiffFile<'_>,
+    workdir: Option<&Path>,
+) -> Option<lfs::LfsPointer> {
+    if file.size() > lfs::LFS_POINTER_MAX_BYTES as u64 {
+        return None;
+    }
+    if !file.id().is_zero() {
+        if let Ok(blob) = repo.find_blob(file.id()) {
+            return lfs::parse_lfs_pointer(blob.content());
+        }
+    }
+    let full_path = workdir?.join(file.path()?);
+    let metadata = fs::metadata(&full_path).ok()?;
+    if metadata.len() > lfs::LFS_POINTER_MAX_BYTES as u64 {
+        return None;
+    }
+    lfs::parse_lfs_pointer(&fs::read(full_path).ok()?)
+}
+
+/// Summarises submodule and LFS pointer deltas instead of diffing pointer
+/// lines. `workdir` is set when the new side may only exist on disk.
+fn describe_special_delta(
+    repo: &Repository,
+    delta: &git2::DiffDelta<'_>,
+    workdir: Option<&Path>,
+) -> Option<String> {
+    let old_file = delta.old_file();
+    let new_file = delta.new_file();
+    let path = new_file.path().or_else(|| old_file.path())?;
+    let is_gitlink = |file: &git2::DiffFile<'_>| file.mode() == git2::FileMode::Commit;
+    if is_gitlink(&old_file) || is_gitlink(&new_file) {
+        let side = |file: &git2::DiffFile<'_>| {
+            (is_gitlink(file) && !file.id().is_zero()).then(|| file.id())
+        };
+        return Some(submodules::describe_submodule_change(
+            repo,
+            &normalize_git_path(&path.to_string_lossy()),
+            side(&old_file),
+            side(&new_file),
+        ));
+    }
+    let old_pointer = match delta.status() {
+        git2::Delta::Added | git2::Delta::Untracked => None,
+        _ => read_lfs_pointer_side(repo, &old_file, None),
+    };
+    let new_pointer = match delta.status() {
+        git2::Delta::Deleted => None,
+        _ => read_lfs_pointer_side(repo, &new_file, workdir),
+    };
+    if old_pointer.is_none() && new_pointer.is_none() {
+        return None;
+    }
+    // A smudged checkout keeps the real content on disk, so a modified LFS
+    // file has a pointer only on the index side.
+    if let (Some(old), None, Some(workdir)) = (&old_pointer, &new_pointer, workdir) {
+        if delta.status() != git2::Delta::Deleted {
+            let on_disk = fs::metadata(workdir.join(path)).ok();
+            if let Some(metadata) = on_disk.filter(|metadata| metadata.is_file()) {
+                return Some(lfs::describe_smudged_lfs_change(old, metadata.len()));
+            }
+        }
+    }
+    Some(lfs::describe_lfs_change(
+        old_pointer.as_ref(),
+        new_pointer.as_ref(),
+    ))
+}
+
 async fn run_git_command(repo_root: &Path, args: &[&str]) -> Result<(), String> {
     let git_bin = resolve_git_binary().map_err(|e| format!("Failed to run git: {e}"))?;
     let mut command = crate::utils::async_command(git_bin);
@@ -555,7 +666,7 @@ async fn push_with_options(
     run_git_command(repo_root, &command).await
 }
 
-fn status_for_index(status: Status) -> Option<&'static str> {
+pub(crate) fn status_for_index(status: Status) -> Option<&'static str> {
     if status.contains(Status::INDEX_NEW) {
         Some("A")
     } else if status.contains(Status::INDEX_MODIFIED) {
@@ -571,7 +682,7 @@ fn status_for_index(status: Status) -> Option<&'static str> {
     }
 }
 
-fn status_for_workdir(status: Status) -> Option<&'static str> {
+pub(crate) fn status_for_workdir(status: Status) -> Option<&'static str> {
     if status.contains(Status::WT_NEW) {
         Some("A")
     } else if status.contains(Status::WT_MODIFIED) {
@@ -663,22 +774,6 @@ fn collect_workspace_diff(repo_root: &Path) -> Result<String, String> {
     Ok(build_combined_diff(&diff))
 }
 
-fn github_repo_from_path(path: &Path) -> Result<String, String> {
-    let repo = open_repository_at_root(path)?;
-    let remotes = repo.remotes().map_err(|e| e.to_string())?;
-    let name = if remotes.iter().any(|remote| remote == Some("origin")) {
-        "origin".to_string()
-    } else {
-        remotes.iter().flatten().next().unwrap_or("").to_string()
-    };
-    if name.is_empty() {
-        return Err("No git remote configured.".to_string());
-    }
-    let remote = repo.find_remote(&name).map_err(|e| e.to_string())?;
-    let remote_url = remote.url().ok_or("Remote has no URL configured.")?;
-    parse_github_repo(remote_url).ok_or("Remote is not a GitHub repository.".to_string())
-}
-
 fn parse_patch_diff_entries(diff: &str) -> Vec<GitCommitDiff> {
     let mut entries = Vec::new();
     let mut current_lines: Vec<&str> = Vec::new();
@@ -946,10 +1041,12 @@ fn parse_repo_owner(repo: &str) -> Option<String> {
         .map(ToOwned::to_owned)
 }
 
-fn resolve_remote_repo(repo: &Repository, remote_name: &str) -> Option<String> {
-    let remote = repo.find_remote(remote_name).ok()?;
-    let remote_url = remote.url()?;
-    parse_github_repo(remote_url)
+fn resolve_remote_repo(
+    repo: &Repository,
+    remote_name: &str,
+    forge_kind: Option<&str>,
+) -> Option<String> {
+    forge_remote_from_repo(repo, remote_name, forge_kind).map(|remote| remote.repo)
 }
 
 fn infer_remote_head_branch(repo: &Repository, remote_name: &str) -> Option<String> {
@@ -1036,7 +1133,8 @@ fn stage_error_category_and_hint(stage_key: &str, raw: &str) -> (String, String)
         if is_auth_related_error(raw) {
             return (
                 "gh-auth-missing".to_string(),
-                "Run `env -u GH_TOKEN -u GITHUB_TOKEN gh auth status -h github.com` and finish login.".to_string(),
+                "Run `env -u GH_TOKEN -u GITHUB_TOKEN gh auth status` and finish login."
+                    .to_string(),
             );
         }
         if raw.to_lowercase().contains("range gate") {
@@ -1336,6 +1434,8 @@ pub(crate) async fn get_git_status(
         }
     }
 
+    let submodules = submodules::cached_submodule_statuses(&workspace_id, &repo);
+
     Ok(json!({
         "branchName": branch_name,
         "files": files,
@@ -1343,6 +1443,7 @@ pub(crate) async fn get_git_status(
         "unstagedFiles": unstaged_files,
         "totalAdditions": total_additions,
         "totalDeletions": total_deletions,
+        "submodules": submodules,
     }))
 }
 
@@ -1464,9 +1565,13 @@ pub(crate) async fn commit_git(
         .get(&workspace_id)
         .ok_or("workspace not found")?
         .clone();
+    let identity = identity::identity_for_entry(&workspaces, &entry);
+    drop(workspaces);
 
     let repo_root = resolve_git_root(&entry)?;
-    run_git_command(&repo_root, &["commit", "-m", &message]).await
+    let args = identity::git_args_with_identity(identity.as_ref(), &["commit", "-m", &message])?;
+    let arg_refs = args.iter().map(String::as_str).collect::<Vec<_>>();
+    run_git_command(&repo_root, &arg_refs).await
 }
 
 #[tauri::command]
@@ -1723,9 +1828,14 @@ pub(crate) async fn cherry_pick_commit(
         .get(&workspace_id)
         .ok_or("workspace not found")?
         .clone();
+    let identity = identity::identity_for_entry(&workspaces, &entry);
+    drop(workspaces);
 
     let repo_root = resolve_git_root(&entry)?;
-    run_git_command(&repo_root, &["cherry-pick", commit_hash.trim()]).await
+    let args =
+        identity::git_args_with_identity(identity.as_ref(), &["cherry-pick", commit_hash.trim()])?;
+    let arg_refs = args.iter().map(String::as_str).collect::<Vec<_>>();
+    run_git_command(&repo_root, &arg_refs).await
 }
 
 #[tauri::command]
@@ -1739,9 +1849,16 @@ pub(crate) async fn revert_commit(
         .get(&workspace_id)
         .ok_or("workspace not found")?
         .clone();
+    let identity = identity::identity_for_entry(&workspaces, &entry);
+    drop(workspaces);
 
     let repo_root = resolve_git_root(&entry)?;
-    run_git_command(&repo_root, &["revert", "--no-edit", commit_hash.trim()]).await
+    let args = identity::git_args_with_identity(
+        identity.as_ref(),
+        &["revert", "--no-edit", commit_hash.trim()],
+    )?;
+    let arg_refs = args.iter().map(String::as_str).collect::<Vec<_>>();
+    run_git_command(&repo_root, &arg_refs).await
 }
 
 #[tauri::command]
@@ -1788,7 +1905,16 @@ pub(crate) async fn list_git_roots(
 
     let root = PathBuf::from(&entry.path);
     let depth = depth.unwrap_or(2).clamp(1, 6);
-    Ok(scan_git_roots(&root, depth, 200))
+    let mut roots = scan_git_roots(&root, depth, 200);
+    // Submodules nested deeper than the scan depth are still separate repositories.
+    if let Ok(repo) = open_repository_at_root(&root) {
+        for submodule in submodules::collect_submodule_statuses(&repo) {
+            if submodule.initialized && !roots.contains(&submodule.path) {
+                roots.push(submodule.path);
+            }
+        }
+    }
+    Ok(roots)
 }
 
 /// Helper function to get the combined diff for a workspace (used by commit message generation)
@@ -1863,7 +1989,7 @@ pub(crate) async fn get_git_diffs(
                         .as_ref()
                         .and_then(|tree| old_path.and_then(|path| tree.get_path(path).ok()))
                         .and_then(|entry| repo.find_blob(entry.id()).ok())
-                        .and_then(blob_to_base64)
+                        .and_then(|blob| blob_to_base64(&repo, blob))
                 } else {
                     None
                 };
@@ -1872,7 +1998,7 @@ pub(crate) async fn get_git_diffs(
                     match new_path {
                         Some(path) => {
                             let full_path = repo_root.join(path);
-                            read_image_base64(&full_path)
+                            read_image_base64(&repo, &full_path)
                         }
                         None => None,
                     }
@@ -1893,6 +2019,21 @@ pub(crate) async fn get_git_diffs(
                 continue;
             }
 
+            let summary = describe_special_delta(&repo, &delta, Some(repo_root.as_path()));
+            if let Some(summary) = summary {
+                results.push(GitFileDiff {
+                    path: normalized_path,
+                    diff: summary,
+                    is_binary: false,
+                    is_image: false,
+                    old_image_data: None,
+                    new_image_data: None,
+                    old_image_mime: None,
+                    new_image_mime: None,
+                });
+                continue;
+            }
+
             let patch = match git2::Patch::from_diff(&diff, index) {
                 Ok(patch) => patch,
                 Err(_) => continue,
@@ -2318,6 +2459,8 @@ pub(crate) async fn get_git_commit_details(
     drop(workspaces);
 
     let repo_root = resolve_git_root(&entry)?;
+    // Verification shells out to git, so resolve it before holding repository handles.
+    let signature = identity::read_commit_signature(&repo_root, commit_hash.trim()).await;
     let repo = open_repository_at_root(&repo_root)?;
     let oid = Oid::from_str(commit_hash.trim()).map_err(|e| e.to_string())?;
     let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
@@ -2380,6 +2523,12 @@ pub(crate) async fn get_git_commit_details(
                 is_binary = true;
             }
         }
+        if let Some(summary) = describe_special_delta(&repo, &delta, None) {
+            line_count = summary.lines().count();
+            diff_text = summary;
+            truncated = false;
+            is_binary = false;
+        }
 
         total_additions += additions;
         total_deletions += deletions;
@@ -2436,6 +2585,7 @@ pub(crate) async fn get_git_commit_details(
         files,
         total_additions,
         total_deletions,
+        signature,
     };
     Ok(details)
 }
@@ -2499,7 +2649,7 @@ pub(crate) async fn get_git_commit_diff(
                     .as_ref()
                     .and_then(|tree| old_path.and_then(|path| tree.get_path(path).ok()))
                     .and_then(|entry| repo.find_blob(entry.id()).ok())
-                    .and_then(blob_to_base64)
+                    .and_then(|blob| blob_to_base64(&repo, blob))
             } else {
                 None
             };
@@ -2508,7 +2658,7 @@ pub(crate) async fn get_git_commit_diff(
                 new_path
                     .and_then(|path| commit_tree.get_path(path).ok())
                     .and_then(|entry| repo.find_blob(entry.id()).ok())
-                    .and_then(blob_to_base64)
+                    .and_then(|blob| blob_to_base64(&repo, blob))
             } else {
                 None
             };
@@ -2527,6 +2677,21 @@ pub(crate) async fn get_git_commit_diff(
             continue;
         }
 
+        if let Some(summary) = describe_special_delta(&repo, &delta, None) {
+            results.push(GitCommitDiff {
+                path: normalized_path,
+                status: status_for_delta(delta.status()).to_string(),
+                diff: summary,
+                is_binary: false,
+                is_image: false,
+                old_image_data: None,
+                new_image_data: None,
+                old_image_mime: None,
+                new_image_mime: None,
+            });
+            continue;
+        }
+
         let patch = match git2::Patch::from_diff(&diff, index) {
             Ok(patch) => patch,
             Err(_) => continue,
@@ -2598,10 +2763,11 @@ pub(crate) async fn get_git_pr_workflow_defaults(
     let repo_root = resolve_git_root(&entry)?;
     let repo = open_repository_at_root(&repo_root)?;
     let head_branch = current_local_branch(&repo_root)?.unwrap_or_default();
-    let upstream_repo = resolve_remote_repo(&repo, "upstream")
-        .or_else(|| resolve_remote_repo(&repo, "origin"))
+    let forge_kind = entry.settings.forge_kind.as_deref();
+    let upstream_repo = resolve_remote_repo(&repo, "upstream", forge_kind)
+        .or_else(|| resolve_remote_repo(&repo, "origin", forge_kind))
         .unwrap_or_default();
-    let origin_repo = resolve_remote_repo(&repo, "origin");
+    let origin_repo = resolve_remote_repo(&repo, "origin", forge_kind);
     let tracked_upstream = upstream_remote_and_branch(&repo_root)?
         .filter(|(remote, _)| remote == "upstream" || remote == "origin")
         .map(|(_, branch)| branch);
@@ -2645,7 +2811,10 @@ pub(crate) async fn get_git_pr_workflow_defaults(
     let disabled_reason = if head_branch.trim().is_empty() {
         Some("Current branch is unavailable (detached HEAD or no local branch).".to_string())
     } else if upstream_repo.trim().is_empty() {
-        Some("No GitHub remote detected. Configure upstream/origin remote first.".to_string())
+        Some(
+            "No supported forge remote detected. Configure upstream/origin remote first, or set the forge type in workspace settings."
+                .to_string(),
+        )
     } else if head_owner.trim().is_empty() {
         Some("Cannot infer fork owner from origin remote URL.".to_string())
     } else {
@@ -2683,6 +2852,7 @@ pub(crate) async fn create_git_pr_workflow(
         .get(&workspace_id)
         .ok_or("workspace not found")?
         .clone();
+    let identity = identity::identity_for_entry(&workspaces, &entry);
     drop(workspaces);
 
     let repo_root = resolve_git_root(&entry)?;
@@ -2717,91 +2887,138 @@ pub(crate) async fn create_git_pr_workflow(
         ));
     }
 
-    update_workflow_stage(
-        &mut stages,
-        "precheck",
-        "running",
-        "Checking GitHub CLI readiness and PR range gate.".to_string(),
-        None,
-        None,
-        None,
-    );
-    let gh_version_args = vec!["--version".to_string()];
-    let gh_version_output =
-        match run_token_isolated_command(&repo_root, "gh", &gh_version_args, &[]).await {
-            Ok(output) => output,
-            Err(error) => {
-                update_workflow_stage(
-                    &mut stages,
-                    "precheck",
-                    "failed",
-                    error.clone(),
-                    None,
-                    None,
-                    None,
-                );
-                return Ok(build_failed_pr_workflow_result(
-                    stages, "precheck", error, None,
-                ));
+    let repo = open_repository_at_root(&repo_root)?;
+    let forge_kind = entry.settings.forge_kind.as_deref();
+    let detected_remote = forge_remote_from_repo(&repo, "upstream", forge_kind)
+        .or_else(|| forge_remote_from_repo(&repo, "origin", forge_kind))
+        .map(|remote| remote.with_repo(&upstream_repo));
+    // gh needs the host for GitHub Enterprise, in `auth status` and `--repo`.
+    let (github_host, gh_repo) = match detected_remote.as_ref() {
+        Some(remote) if remote.kind == ForgeKind::GitHub => (remote.host.clone(), remote.gh_repo()),
+        _ => ("github.com".to_string(), upstream_repo.clone()),
+    };
+    let forge_remote = detected_remote.filter(|remote| remote.kind != ForgeKind::GitHub);
+    let forge_client = match forge_remote {
+        Some(remote) => {
+            update_workflow_stage(
+                &mut stages,
+                "precheck",
+                "running",
+                format!(
+                    "Checking {} access token and PR range gate.",
+                    remote.kind.label()
+                ),
+                None,
+                None,
+                None,
+            );
+            match forge::precheck_pr_workflow(&remote).await {
+                Ok(client) => Some(client),
+                Err(error) => {
+                    update_workflow_stage(
+                        &mut stages,
+                        "precheck",
+                        "failed",
+                        error.clone(),
+                        None,
+                        None,
+                        None,
+                    );
+                    return Ok(build_failed_pr_workflow_result(
+                        stages, "precheck", error, None,
+                    ));
+                }
             }
-        };
-    if !gh_version_output.success {
-        let raw = summarize_command_failure(&gh_version_output);
-        update_workflow_stage(
-            &mut stages,
-            "precheck",
-            "failed",
-            raw.clone(),
-            Some(gh_version_output.command),
-            Some(truncate_debug_text(&gh_version_output.stdout, 1200)),
-            Some(truncate_debug_text(&gh_version_output.stderr, 1200)),
-        );
-        return Ok(build_failed_pr_workflow_result(
-            stages, "precheck", raw, None,
-        ));
-    }
+        }
+        None => None,
+    };
 
-    let gh_auth_args = vec![
-        "auth".to_string(),
-        "status".to_string(),
-        "-h".to_string(),
-        "github.com".to_string(),
-    ];
-    let gh_auth_output =
-        match run_token_isolated_command(&repo_root, "gh", &gh_auth_args, &[]).await {
-            Ok(output) => output,
-            Err(error) => {
-                update_workflow_stage(
-                    &mut stages,
-                    "precheck",
-                    "failed",
-                    error.clone(),
-                    None,
-                    None,
-                    None,
-                );
-                return Ok(build_failed_pr_workflow_result(
-                    stages, "precheck", error, None,
-                ));
-            }
-        };
-    if !gh_auth_output.success {
-        let raw = summarize_command_failure(&gh_auth_output);
+    if forge_client.is_none() {
         update_workflow_stage(
             &mut stages,
             "precheck",
-            "failed",
-            raw.clone(),
-            Some(gh_auth_output.command),
-            Some(truncate_debug_text(&gh_auth_output.stdout, 1200)),
-            Some(truncate_debug_text(&gh_auth_output.stderr, 1200)),
+            "running",
+            "Checking GitHub CLI readiness and PR range gate.".to_string(),
+            None,
+            None,
+            None,
         );
-        return Ok(build_failed_pr_workflow_result(
-            stages, "precheck", raw, None,
-        ));
+        let gh_version_args = vec!["--version".to_string()];
+        let gh_version_output =
+            match run_token_isolated_command(&repo_root, "gh", &gh_version_args, &[]).await {
+                Ok(output) => output,
+                Err(error) => {
+                    update_workflow_stage(
+                        &mut stages,
+                        "precheck",
+                        "failed",
+                        error.clone(),
+                        None,
+                        None,
+                        None,
+                    );
+                    return Ok(build_failed_pr_workflow_result(
+                        stages, "precheck", error, None,
+                    ));
+                }
+            };
+        if !gh_version_output.success {
+            let raw = summarize_command_failure(&gh_version_output);
+            update_workflow_stage(
+                &mut stages,
+                "precheck",
+                "failed",
+                raw.clone(),
+                Some(gh_version_output.command),
+                Some(truncate_debug_text(&gh_version_output.stdout, 1200)),
+                Some(truncate_debug_text(&gh_version_output.stderr, 1200)),
+            );
+            return Ok(build_failed_pr_workflow_result(
+                stages, "precheck", raw, None,
+            ));
+        }
+
+        let gh_auth_args = vec![
+            "auth".to_string(),
+            "status".to_string(),
+            "-h".to_string(),
+            github_host,
+        ];
+        let gh_auth_output =
+            match run_token_isolated_command(&repo_root, "gh", &gh_auth_args, &[]).await {
+                Ok(output) => output,
+                Err(error) => {
+                    update_workflow_stage(
+                        &mut stages,
+                        "precheck",
+                        "failed",
+                        error.clone(),
+                        None,
+                        None,
+                        None,
+                    );
+                    return Ok(build_failed_pr_workflow_result(
+                        stages, "precheck", error, None,
+                    ));
+                }
+            };
+        if !gh_auth_output.success {
+            let raw = summarize_command_failure(&gh_auth_output);
+            update_workflow_stage(
+                &mut stages,
+                "precheck",
+                "failed",
+                raw.clone(),
+                Some(gh_auth_output.command),
+                Some(truncate_debug_text(&gh_auth_output.stdout, 1200)),
+                Some(truncate_debug_text(&gh_auth_output.stderr, 1200)),
+            );
+            return Ok(build_failed_pr_workflow_result(
+                stages, "precheck", raw, None,
+            ));
+        }
     }
 
-    let repo = open_repository_at_root(&repo_root)?;
     if repo.find_remote("upstream").is_err() {
         let raw = "Range gate requires remote `upstream`. Add it first, then retry PR workflow."
             .to_string();
@@ -2952,12 +3169,25 @@ pub(crate) async fn create_git_pr_workflow(
         None,
     );
     let push_target = format!("HEAD:{head_branch}");
-    let push_args = vec![
-        "push".to_string(),
-        "-u".to_string(),
-        "origin".to_string(),
-        push_target.clone(),
-    ];
+    // Hooks that commit during push (e.g. changelog bots) use the workspace identity.
+    let push_args = match identity::git_args_with_identity(
+        identity.as_ref(),
+        &["push", "-u", "origin", &push_target],
+    ) {
+        Ok(args) => args,
+        Err(error) => {
+            update_workflow_stage(
+                &mut stages,
+                "push",
+                "failed",
+                error.clone(),
+                None,
+                None,
+                None,
+            );
+            return Ok(build_failed_pr_workflow_result(stages, "push", error, None));
+        }
+    };
     let push_output = match run_token_isolated_command(&repo_root, "git", &push_args, &[]).await {
         Ok(output) => output,
         Err(error) => {
@@ -2984,14 +3214,17 @@ pub(crate) async fn create_git_pr_workflow(
     if !push_output.success {
         let first_error = summarize_command_failure(&push_output);
         if is_http2_transport_error(&first_error) {
-            let push_http1_args = vec![
-                "-c".to_string(),
-                "http.version=HTTP/1.1".to_string(),
-                "push".to_string(),
-                "-u".to_string(),
-                "origin".to_string(),
-                push_target.clone(),
-            ];
+            let push_http1_args = identity::git_args_with_identity(
+                identity.as_ref(),
+                &[
+                    "-c",
+                    "http.version=HTTP/1.1",
+                    "push",
+                    "-u",
+                    "origin",
+                    &push_target,
+                ],
+            )?;
             let retry_output = match run_token_isolated_command(
                 &repo_root,
                 "git",
@@ -3081,6 +3314,21 @@ pub(crate) async fn create_git_pr_workflow(
         );
     }
 
+    if let Some(client) = forge_client.as_ref() {
+        let comment = comment_enabled.then_some(comment_text.as_str());
+        return Ok(forge::finish_pr_workflow(
+            client,
+            stages,
+            &base_branch,
+            &head_owner,
+            &head_branch,
+            &title,
+            &body,
+            comment,
+        )
+        .await);
+    }
+
     update_workflow_stage(
         &mut stages,
         "create",
@@ -3095,7 +3343,7 @@ pub(crate) async fn create_git_pr_workflow(
         "pr".to_string(),
         "list".to_string(),
         "--repo".to_string(),
-        upstream_repo.clone(),
+        gh_repo.clone(),
         "--state".to_string(),
         "all".to_string(),
         "--head".to_string(),
@@ -3192,7 +3440,7 @@ pub(crate) async fn create_git_pr_workflow(
         "pr".to_string(),
         "create".to_string(),
         "--repo".to_string(),
-        upstream_repo.clone(),
+        gh_repo.clone(),
         "--base".to_string(),
         base_branch.clone(),
         "--head".to_string(),
@@ -3251,7 +3499,7 @@ pub(crate) async fn create_git_pr_workflow(
             "pr".to_string(),
             "list".to_string(),
             "--repo".to_string(),
-            upstream_repo.clone(),
+            gh_repo.clone(),
             "--state".to_string(),
             "open".to_string(),
             "--head".to_string(),
@@ -3363,7 +3611,7 @@ pub(crate) async fn create_git_pr_workflow(
         "comment".to_string(),
         comment_pr_number.to_string(),
         "--repo".to_string(),
-        upstream_repo.clone(),
+        gh_repo.clone(),
         "--body".to_string(),
         comment_text,
     ];
@@ -3427,62 +3675,8 @@ pub(crate) async fn get_github_issues(
         .clone();
 
     let repo_root = resolve_git_root(&entry)?;
-    let repo_name = github_repo_from_path(&repo_root)?;
-
-    let output = crate::utils::async_command("gh")
-        .args([
-            "issue",
-            "list",
-            "--repo",
-            &repo_name,
-            "--limit",
-            "50",
-            "--json",
-            "number,title,url,updatedAt",
-        ])
-        .current_dir(&repo_root)
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run gh: {e}"))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let detail = if stderr.trim().is_empty() {
-            stdout.trim()
-        } else {
-            stderr.trim()
-        };
-        if detail.is_empty() {
-            return Err("GitHub CLI command failed.".to_string());
-        }
-        return Err(detail.to_string());
-    }
-
-    let issues: Vec<GitHubIssue> =
-        serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())?;
-
-    let search_query = format!("repo:{repo_name} is:issue is:open");
-    let search_query = search_query.replace(' ', "+");
-    let total = match crate::utils::async_command("gh")
-        .args([
-            "api",
-            &format!("/search/issues?q={search_query}"),
-            "--jq",
-            ".total_count",
-        ])
-        .current_dir(&repo_root)
-        .output()
-        .await
-    {
-        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
-            .trim()
-            .parse::<usize>()
-            .unwrap_or(issues.len()),
-        _ => issues.len(),
-    };
-
-    Ok(GitHubIssuesResponse { total, issues })
+    let remote = forge_remote_from_path(&repo_root, entry.settings.forge_kind.as_deref())?;
+    forge::list_issues(&remote).await
 }
 
 #[tauri::command]
@@ -3497,67 +3691,8 @@ pub(crate) async fn get_github_pull_requests(
         .clone();
 
     let repo_root = resolve_git_root(&entry)?;
-    let repo_name = github_repo_from_path(&repo_root)?;
-
-    let output = crate::utils::async_command("gh")
-        .args([
-            "pr",
-            "list",
-            "--repo",
-            &repo_name,
-            "--state",
-            "open",
-            "--limit",
-            "50",
-            "--json",
-            "number,title,url,updatedAt,createdAt,body,headRefName,baseRefName,isDraft,author",
-        ])
-        .current_dir(&repo_root)
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run gh: {e}"))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let detail = if stderr.trim().is_empty() {
-            stdout.trim()
-        } else {
-            stderr.trim()
-        };
-        if detail.is_empty() {
-            return Err("GitHub CLI command failed.".to_string());
-        }
-        return Err(detail.to_string());
-    }
-
-    let pull_requests: Vec<GitHubPullRequest> =
-        serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())?;
-
-    let search_query = format!("repo:{repo_name} is:pr is:open");
-    let search_query = search_query.replace(' ', "+");
-    let total = match crate::utils::async_command("gh")
-        .args([
-            "api",
-            &format!("/search/issues?q={search_query}"),
-            "--jq",
-            ".total_count",
-        ])
-        .current_dir(&repo_root)
-        .output()
-        .await
-    {
-        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
-            .trim()
-            .parse::<usize>()
-            .unwrap_or(pull_requests.len()),
-        _ => pull_requests.len(),
-    };
-
-    Ok(GitHubPullRequestsResponse {
-        total,
-        pull_requests,
-    })
+    let remote = forge_remote_from_path(&repo_root, entry.settings.forge_kind.as_deref())?;
+    forge::list_pull_requests(&remote).await
 }
 
 #[tauri::command]
@@ -3573,39 +3708,8 @@ pub(crate) async fn get_github_pull_request_diff(
         .clone();
 
     let repo_root = resolve_git_root(&entry)?;
-    let repo_name = github_repo_from_path(&repo_root)?;
-
-    let output = crate::utils::async_command("gh")
-        .args([
-            "pr",
-            "diff",
-            &pr_number.to_string(),
-            "--repo",
-            &repo_name,
-            "--color",
-            "never",
-        ])
-        .current_dir(&repo_root)
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run gh: {e}"))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let detail = if stderr.trim().is_empty() {
-            stdout.trim()
-        } else {
-            stderr.trim()
-        };
-        if detail.is_empty() {
-            return Err("GitHub CLI command failed.".to_string());
-        }
-        return Err(detail.to_string());
-    }
-
-    let diff_text = String::from_utf8_lossy(&output.stdout);
-    Ok(parse_pr_diff(&diff_text))
+    let remote = forge_remote_from_path(&repo_root, entry.settings.forge_kind.as_deref())?;
+    forge::pull_request_diff(&remote, pr_number).await
 }
 
 #[tauri::command]
@@ -3621,56 +3725,21 @@ pub(crate) async fn get_github_pull_request_comments(
         .clone();
 
     let repo_root = resolve_git_root(&entry)?;
-    let repo_name = github_repo_from_path(&repo_root)?;
-
-    let comments_endpoint = format!("/repos/{repo_name}/issues/{pr_number}/comments?per_page=30");
-    let jq_filter = r#"[.[] | {id, body, createdAt: .created_at, url: .html_url, author: (if .user then {login: .user.login} else null end)}]"#;
-
-    let output = crate::utils::async_command("gh")
-        .args(["api", &comments_endpoint, "--jq", jq_filter])
-        .current_dir(&repo_root)
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run gh: {e}"))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let detail = if stderr.trim().is_empty() {
-            stdout.trim()
-        } else {
-            stderr.trim()
-        };
-        if detail.is_empty() {
-            return Err("GitHub CLI command failed.".to_string());
-        }
-        return Err(detail.to_string());
-    }
-
-    let comments: Vec<GitHubPullRequestComment> =
-        serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())?;
-
-    Ok(comments)
+    let remote = forge_remote_from_path(&repo_root, entry.settings.forge_kind.as_deref())?;
+    forge::pull_request_comments(&remote, pr_number).await
 }
 
-#[tauri::command]
-pub(crate) async fn list_git_branches(
-    workspace_id: String,
-    state: State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
-    let repo_root = resolve_git_root(&entry)?;
-    let repo = open_repository_at_root(&repo_root)?;
-    let current_branch = repo
-        .head()
-        .ok()
-        .filter(|head| head.is_branch())
-        .and_then(|head| head.shorthand().map(|name| name.to_string()));
+type BranchListParts = (
+    Vec<BranchInfo>,
+    Vec<GitBranchListItem>,
+    Vec<GitBranchListItem>,
+);
 
+/// Local and remote branch rows shared by `list_git_branches` and branch hygiene.
+fn collect_branch_list(
+    repo: &Repository,
+    current_branch: Option<&str>,
+) -> Result<BranchListParts, String> {
     let mut legacy_branches = Vec::new();
     let mut local_branches = Vec::new();
     let refs = repo
@@ -3713,7 +3782,7 @@ pub(crate) async fn list_git_branches(
         });
         local_branches.push(GitBranchListItem {
             name: name.clone(),
-            is_current: current_branch.as_deref() == Some(name.as_str()),
+            is_current: current_branch == Some(name.as_str()),
             is_remote: false,
             remote: None,
             last_commit,
@@ -3757,6 +3826,29 @@ pub(crate) async fn list_git_branches(
         });
     }
     remote_branches.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok((legacy_branches, local_branches, remote_branches))
+}
+
+#[tauri::command]
+pub(crate) async fn list_git_branches(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+    let repo_root = resolve_git_root(&entry)?;
+    let repo = open_repository_at_root(&repo_root)?;
+    let current_branch = repo
+        .head()
+        .ok()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.shorthand().map(|name| name.to_string()));
+
+    let (legacy_branches, local_branches, remote_branches) =
+        collect_branch_list(&repo, current_branch.as_deref())?;
 
     Ok(json!({
         "branches": legacy_branches,
@@ -4015,12 +4107,16 @@ pub(crate) async fn merge_git_branch(
         .get(&workspace_id)
         .ok_or("workspace not found")?
         .clone();
+    let identity = identity::identity_for_entry(&workspaces, &entry);
+    drop(workspaces);
     let repo_root = resolve_git_root(&entry)?;
     let branch_name = name.trim();
     if branch_name.is_empty() {
         return Err("Branch name cannot be empty.".to_string());
     }
-    run_git_command(&repo_root, &["merge", branch_name]).await
+    let args = identity::git_args_with_identity(identity.as_ref(), &["merge", branch_name])?;
+    let arg_refs = args.iter().map(String::as_str).collect::<Vec<_>>();
+    run_git_command(&repo_root, &arg_refs).await
 }
 
 #[tauri::command]
@@ -4034,12 +4130,16 @@ pub(crate) async fn rebase_git_branch(
         .get(&workspace_id)
         .ok_or("workspace not found")?
         .clone();
+    let identity = identity::identity_for_entry(&workspaces, &entry);
+    drop(workspaces);
     let repo_root = resolve_git_root(&entry)?;
     let onto_branch_name = onto_branch.trim();
     if onto_branch_name.is_empty() {
         return Err("Branch name cannot be empty.".to_string());
     }
-    run_git_command(&repo_root, &["rebase", onto_branch_name]).await
+    let args = identity::git_args_with_identity(identity.as_ref(), &["rebase", onto_branch_name])?;
+    let arg_refs = args.iter().map(String::as_str).collect::<Vec<_>>();
+    run_git_command(&repo_root, &arg_refs).await
 }
 
 #[tauri::command]
@@ -4436,16 +4536,10 @@ pub(crate) async fn get_git_worktree_file_diff_against_branch(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::create_temp_repo;
     use std::fs;
     use validation::validate_local_branch_name;
 
-    fn create_temp_repo() -> (PathBuf, Repository) {
-        let root = std::env::temp_dir().join(format!("moss-x-test-{}", uuid::Uuid::new_v4()));
-        fs::create_dir_all(&root).expect("create temp repo root");
-        let repo = Repository::init(&root).expect("init repo");
-        (root, repo)
-    }
-
     #[test]
     fn collect_workspace_diff_prefers_staged_changes() {
         let (root, repo) = create_temp_repo();
@@ -4498,6 +4592,30 @@ mod tests {
         assert_eq!(paths, vec!["a.txt".to_string(), "b.txt".to_string()]);
     }
 
+    #[test]
+    fn describe_special_delta_reports_smudged_lfs_files_as_modified() {
+        let (root, repo) = create_temp_repo();
+        let pointer = format!(
+            "version https://git-lfs.github.com/spec/v1\noid sha256:{}\nsize 4\n",
+            "a".repeat(64)
+        );
+        fs::write(root.join("asset.bin"), pointer).expect("write pointer");
+        let mut index = repo.index().expect("repo index");
+        index.add_path(Path::new("asset.bin")).expect("add path");
+        index.write().expect("write index");
+        // The smudge filter puts the real object on disk.
+        fs::write(root.join("asset.bin"), vec![7u8; 2048]).expect("write content");
+
+        let diff = repo
+            .diff_index_to_workdir(None, None)
+            .expect("workdir diff");
+        let delta = diff.deltas().next().expect("delta");
+        assert_eq!(
+            describe_special_delta(&repo, &delta, Some(root.as_path())).as_deref(),
+            Some("LFS object modified (size 4 B \u{2192} 2.0 KB)")
+        );
+    }
+
     #[test]
     fn open_repository_at_root_does_not_search_parent_directories() {
         let (root, _repo) = create_temp_repo();
@@ -0,0 +1,448 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use git2::{Oid, Repository};
+use serde_json::Value;
+use tauri::{AppHandle, Manager, State};
+
+use super::forge::{
+    default_remote_name, forge_remote_from_path, parse_forge_remote, pull_request_head, ForgeRemote,
+};
+use super::{
+    get_github_pull_request_comments, get_github_pull_request_diff, open_repository_at_root,
+    run_git_command, truncate_diff_lines,
+};
+use crate::engine::EngineType;
+use crate::git_utils::resolve_git_root;
+use crate::state::AppState;
+use crate::types::{
+    GitHubPullRequestComment, GitHubPullRequestDiff, GitHubPullRequestReviewFinding,
+    GitHubPullRequestReviewReport, WorkspaceEntry, WorkspaceKind,
+};
+
+const MAX_REVIEW_DIFF_LINES: usize = 6000;
+const MAX_REVIEW_COMMENTS: usize = 20;
+const SEVERITIES: [&str; 4] = ["critical", "major", "minor", "nit"];
+
+/// New-side line numbers covered by each file's hunks. GitHub only accepts
+/// inline comments on these lines.
+fn diff_commentable_lines(diffs: &[GitHubPullRequestDiff]) -> HashMap<String, HashSet<u32>> {
+    let mut map = HashMap::new();
+    for entry in diffs {
+        let mut lines = HashSet::new();
+        let mut next_line: Option<u32> = None;
+        for line in entry.diff.lines() {
+            if let Some(header) = line.strip_prefix("@@ ") {
+                next_line = header
+                    .split_whitespace()
+                    .find_map(|part| part.strip_prefix('+'))
+                    .and_then(|range| range.split(',').next())
+                    .and_then(|start| start.parse::<u32>().ok());
+                continue;
+            }
+            let Some(current) = next_line else {
+                continue;
+            };
+            if line.starts_with('+') || line.starts_with(' ') {
+                lines.insert(current);
+                next_line = Some(current + 1);
+            }
+        }
+        map.insert(entry.path.clone(), lines);
+    }
+    map
+}
+
+fn build_review_prompt(
+    pr_number: u64,
+    head_sha: &str,
+    diffs: &[GitHubPullRequestDiff],
+    comments: &[GitHubPullRequestComment],
+) -> String {
+    let combined = diffs
+        .iter()
+        .map(|entry| entry.diff.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let (diff_text, total_lines, truncated) = truncate_diff_lines(&combined, MAX_REVIEW_DIFF_LINES);
+
+    let mut prompt = format!(
+        "Review pull request #{pr_number}. Its head commit {head_sha} is checked out in the current working directory; read surrounding code as needed but do not modify any files.\n\n"
+    );
+    prompt.push_str(
+        "Focus on correctness bugs, security issues, missing error handling, and behavior changes that lack tests. Skip style nits unless they hide a real problem.\n\n",
+    );
+    if !comments.is_empty() {
+        prompt.push_str("Existing discussion (do not repeat points already raised):\n");
+        for comment in comments.iter().rev().take(MAX_REVIEW_COMMENTS).rev() {
+            let author = comment
+                .author
+                .as_ref()
+                .map(|author| author.login.as_str())
+                .unwrap_or("unknown");
+            prompt.push_str(&format!("- {author}: {}\n", comment.body.trim()));
+        }
+        prompt.push('\n');
+    }
+    prompt.push_str("Diff:\n```diff\n");
+    prompt.push_str(&diff_text);
+    prompt.push_str("\n```\n");
+    if truncated {
+        prompt.push_str(&format!(
+            "(Diff truncated to {MAX_REVIEW_DIFF_LINES} of {total_lines} lines; inspect the remaining files in the working directory.)\n"
+        ));
+    }
+    prompt.push_str(
+        "\nRespond with only a JSON object of the form {\"summary\": string, \"findings\": [{\"path\": string, \"line\": number | null, \"severity\": \"critical\" | \"major\" | \"minor\" | \"nit\", \"message\": string}]}. Use repository-relative paths and line numbers from the new version of each file. Return an empty findings array when nothing needs to change.",
+    );
+    prompt
+}
+
+fn extract_json_object(text: &str) -> Option<Value> {
+    let fenced = text
+        .split("```")
+        .skip(1)
+        .step_by(2)
+        .map(|block| block.trim_start_matches("json").trim())
+        .find_map(|block| serde_json::from_str::<Value>(block).ok())
+        .filter(Value::is_object);
+    if fenced.is_some() {
+        return fenced;
+    }
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    if end <= start {
+        return None;
+    }
+    serde_json::from_str::<Value>(&text[start..=end])
+        .ok()
+        .filter(Value::is_object)
+}
+
+fn parse_review_response(
+    text: &str,
+    commentable: &HashMap<String, HashSet<u32>>,
+) -> (String, Vec<GitHubPullRequestReviewFinding>) {
+    let Some(value) = extract_json_object(text) else {
+        return (text.trim().to_string(), Vec::new());
+    };
+    let summary = value
+        .get("summary")
+        .and_then(Value::as_str)
+        .unwrap_or("")
+        .trim()
+        .to_string();
+    let findings = value
+        .get("findings")
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let path = item
+                        .get("path")
+                        .or_else(|| item.get("file"))
+                        .and_then(Value::as_str)?
+                        .trim()
+                        .trim_start_matches("./")
+                        .trim_start_matches('/')
+                        .to_string();
+                    let message = item.get("message").and_then(Value::as_str)?.trim();
+                    if path.is_empty() || message.is_empty() {
+                        return None;
+                    }
+                    let line = item
+                        .get("line")
+                        .and_then(Value::as_u64)
+                        .and_then(|line| u32::try_from(line).ok())
+                        .filter(|line| *line > 0);
+                    let severity = item
+                        .get("severity")
+                        .and_then(Value::as_str)
+                        .map(|value| value.trim().to_lowercase())
+                        .filter(|value| SEVERITIES.contains(&value.as_str()))
+                        .unwrap_or_else(|| "minor".to_string());
+                    let in_diff = line
+                        .map(|line| {
+                            commentable
+                                .get(&path)
+                                .is_some_and(|lines| lines.contains(&line))
+                        })
+                        .unwrap_or(false);
+                    Some(GitHubPullRequestReviewFinding {
+                        path,
+                        line,
+                        severity,
+                        message: message.to_string(),
+                        in_diff,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    (summary, findings)
+}
+
+async fn remove_review_worktree(repo_root: &Path, worktree_path: &Path) {
+    let path = worktree_path.to_string_lossy().to_string();
+    if run_git_command(repo_root, &["worktree", "remove", "--force", &path])
+        .await
+        .is_err()
+    {
+        let _ = std::fs::remove_dir_all(worktree_path);
+        let _ = run_git_command(repo_root, &["worktree", "prune"]).await;
+    }
+}
+
+async fn release_review_workspace(state: &AppState, workspace_id: &str) {
+    state.workspaces.lock().await.remove(workspace_id);
+    crate::storage::set_workspace_transient(workspace_id, false);
+    crate::workspaces::cleanup_engine_sessions_for_workspace(state, workspace_id).await;
+    if let Some(session) = state.sessions.lock().await.remove(workspace_id) {
+        let mut child = session.child.lock().await;
+        let _ = child.kill().await;
+    }
+}
+
+/// The review checkout and its hidden workspace entry. Dropping it cleans
+/// both up, so every exit path does, including a cancelled command.
+struct ReviewCheckout {
+    app: AppHandle,
+    review_id: String,
+    repo_root: PathBuf,
+    worktree_path: PathBuf,
+}
+
+impl Drop for ReviewCheckout {
+    fn drop(&mut self) {
+        let app = self.app.clone();
+        let review_id = std::mem::take(&mut self.review_id);
+        let repo_root = std::mem::take(&mut self.repo_root);
+        let worktree_path = std::mem::take(&mut self.worktree_path);
+        tauri::async_runtime::spawn(async move {
+            release_review_workspace(&app.state::<AppState>(), &review_id).await;
+            remove_review_worktree(&repo_root, &worktree_path).await;
+        });
+    }
+}
+
+/// The local remote that points at `url`, so its configured credentials are
+/// used for a fork that is already set up.
+fn remote_for_url(repo: &Repository, url: &str, kind_override: Option<&str>) -> Option<String> {
+    let target = parse_forge_remote(url, kind_override)?;
+    let names = repo.remotes().ok()?;
+    names.iter().flatten().find_map(|name| {
+        let remote = parse_forge_remote(repo.find_remote(name).ok()?.url()?, kind_override)?;
+        (remote.host.eq_ignore_ascii_case(&target.host)
+            && remote.repo.eq_ignore_ascii_case(&target.repo))
+        .then(|| name.to_string())
+    })
+}
+
+/// Fetches the pull request head from the repository it was opened from,
+/// falling back to the base repository's pull ref (a deleted fork, or no
+/// access to it). Returns the head commit.
+async fn fetch_pull_request_head(
+    repo_root: &Path,
+    remote: &ForgeRemote,
+    kind_override: Option<&str>,
+    pr_number: u64,
+) -> Result<String, String> {
+    let head = pull_request_head(remote, pr_number).await?;
+    let repo = open_repository_at_root(repo_root)?;
+    let base_remote = default_remote_name(&repo)?;
+    let mut fetched = false;
+    if let Some(url) = head
+        .clone_url
+        .as_deref()
+        .filter(|_| !head.branch.is_empty())
+    {
+        let source = remote_for_url(&repo, url, kind_override).unwrap_or_else(|| url.to_string());
+        let branch_ref = format!("refs/heads/{}", head.branch);
+        fetched = run_git_command(repo_root, &["fetch", "--no-tags", &source, &branch_ref])
+            .await
+            .is_ok();
+    }
+    if !fetched {
+        let pull_ref = remote.pull_request_head_ref(pr_number);
+        run_git_command(repo_root, &["fetch", "--no-tags", &base_remote, &pull_ref]).await?;
+    }
+    let repo = open_repository_at_root(repo_root)?;
+    // Prefer the commit the forge reported; the branch may have moved on
+    // since the diff was read.
+    if let Ok(oid) = Oid::from_str(&head.sha) {
+        if repo.find_commit(oid).is_ok() {
+            return Ok(head.sha);
+        }
+    }
+    repo.revparse_single("FETCH_HEAD")
+        .and_then(|object| object.peel_to_commit())
+        .map(|commit| commit.id().to_string())
+        .map_err(|e| format!("Failed to resolve pull request head: {e}"))
+}
+
+#[tauri::command]
+pub(crate) async fn review_pull_request(
+    workspace_id: String,
+    pr_number: u64,
+    engine: Option<EngineType>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<GitHubPullRequestReviewReport, String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+    drop(workspaces);
+
+    let repo_root = resolve_git_root(&entry)?;
+    let remote = forge_remote_from_path(&repo_root, entry.settings.forge_kind.as_deref())?;
+    let diffs =
+        get_github_pull_request_diff(workspace_id.clone(), pr_number, state.clone()).await?;
+    if diffs.is_empty() {
+        return Err(format!(
+            "Pull request #{pr_number} has no changes to review."
+        ));
+    }
+    let comments = get_github_pull_request_comments(workspace_id.clone(), pr_number, state.clone())
+        .await
+        .unwrap_or_default();
+
+    let head_sha = fetch_pull_request_head(
+        &repo_root,
+        &remote,
+        entry.settings.forge_kind.as_deref(),
+        pr_number,
+    )
+    .await?;
+
+    let review_id = format!("pr-review-{}", uuid::Uuid::new_v4());
+    let worktree_path: PathBuf = std::env::temp_dir().join(&review_id);
+    let worktree_path_string = worktree_path.to_string_lossy().to_string();
+    run_git_command(
+        &repo_root,
+        &[
+            "worktree",
+            "add",
+            "--detach",
+            &worktree_path_string,
+            &head_sha,
+        ],
+    )
+    .await?;
+    let checkout = ReviewCheckout {
+        app: app.clone(),
+        review_id: review_id.clone(),
+        repo_root: repo_root.clone(),
+        worktree_path,
+    };
+
+    // The engines resolve their working directory from a workspace entry, so
+    // the checkout is registered, hidden, for the duration of the review only.
+    crate::storage::set_workspace_transient(&review_id, true);
+    state.workspaces.lock().await.insert(
+        review_id.clone(),
+        WorkspaceEntry {
+            id: review_id.clone(),
+            name: format!("PR #{pr_number} review"),
+            path: worktree_path_string,
+            codex_bin: entry.codex_bin.clone(),
+            kind: WorkspaceKind::Worktree,
+            parent_id: Some(entry.id.clone()),
+            worktree: None,
+            settings: entry.settings.clone(),
+        },
+    );
+
+    let prompt = build_review_prompt(pr_number, &head_sha, &diffs, &comments);
+    let response = crate::engine::engine_send_message_sync(
+        review_id.clone(),
+        prompt,
+        engine,
+        None,
+        None,
+        Some("read-only".to_string()),
+        None,
+        false,
+        None,
+        None,
+        None,
+        None,
+        app,
+        state.clone(),
+    )
+    .await;
+
+    drop(checkout);
+
+    let response = response?;
+    let engine = response
+        .get("engine")
+        .and_then(Value::as_str)
+        .unwrap_or("")
+        .to_string();
+    let raw_text = response
+        .get("text")
+        .and_then(Value::as_str)
+        .unwrap_or("")
+        .to_string();
+    let (summary, findings) = parse_review_response(&raw_text, &diff_commentable_lines(&diffs));
+
+    Ok(GitHubPullRequestReviewReport {
+        pr_number,
+        engine,
+        head_sha,
+        summary,
+        findings,
+        raw_text,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff_entry(path: &str, diff: &str) -> GitHubPullRequestDiff {
+        GitHubPullRequestDiff {
+            path: path.to_string(),
+            status: "M".to_string(),
+            diff: diff.to_string(),
+        }
+    }
+
+    #[test]
+    fn diff_commentable_lines_tracks_new_side_hunks() {
+        let diffs = vec![diff_entry(
+            "src/lib.rs",
+            "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -10,3 +10,4 @@ fn main() {\n context\n-removed\n+added one\n+added two\n context\n",
+        )];
+        let lines = diff_commentable_lines(&diffs);
+        let file = lines.get("src/lib.rs").expect("file lines");
+        let mut sorted = file.iter().copied().collect::<Vec<_>>();
+        sorted.sort_unstable();
+        assert_eq!(sorted, vec![10, 11, 12, 13]);
+    }
+
+    #[test]
+    fn parse_review_response_reads_fenced_json_and_flags_diff_lines() {
+        let mut commentable = HashMap::new();
+        commentable.insert("src/lib.rs".to_string(), HashSet::from([11u32]));
+        let text = "Here is the review:\n```json\n{\"summary\":\"One bug\",\"findings\":[{\"path\":\"./src/lib.rs\",\"line\":11,\"severity\":\"MAJOR\",\"message\":\"Off by one\"},{\"file\":\"src/other.rs\",\"line\":3,\"severity\":\"weird\",\"message\":\"Check this\"},{\"path\":\"src/lib.rs\",\"message\":\"\"}]}\n```";
+        let (summary, findings) = parse_review_response(text, &commentable);
+        assert_eq!(summary, "One bug");
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].path, "src/lib.rs");
+        assert_eq!(findings[0].severity, "major");
+        assert!(findings[0].in_diff);
+        assert_eq!(findings[1].severity, "minor");
+        assert!(!findings[1].in_diff);
+    }
+
+    #[test]
+    fn parse_review_response_falls_back_to_plain_text() {
+        let (summary, findings) = parse_review_response("Looks good to me.", &HashMap::new());
+        assert_eq!(summary, "Looks good to me.");
+        assert!(findings.is_empty());
+    }
+}
@@ -0,0 +1,593 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use git2::{Repository, Sort, Status, StatusOptions};
+use serde_json::json;
+use tauri::{AppHandle, Emitter, State};
+use tokio::io::AsyncReadExt;
+use tokio::time::{sleep, Duration, Instant};
+
+use super::identity::{git_args_with_identity, identity_for_entry};
+use super::{open_repository_at_root, resolve_ref_to_oid, run_git_command};
+use crate::git_utils::resolve_git_root;
+use crate::state::AppState;
+use crate::types::{GitIdentityProfile, GitRebasePlan, GitRebasePlanEntry, GitRebaseResult};
+use crate::utils::{git_env_path, resolve_git_binary};
+
+const REBASE_TIMEOUT_SECS: u64 = 600;
+const REBASE_POLL_INTERVAL_MS: u64 = 200;
+const REBASE_PLAN_LIMIT: usize = 500;
+/// Per-rebase scratch files (todo list, reworded messages) live in the git dir
+/// so `exec` steps still find them after a conflict is resolved.
+const REBASE_SCRATCH_DIR: &str = "moss-rebase";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RebaseAction {
+    Pick,
+    Reword,
+    Squash,
+    Fixup,
+    Drop,
+}
+
+impl RebaseAction {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "pick" | "p" => Ok(RebaseAction::Pick),
+            "reword" | "r" => Ok(RebaseAction::Reword),
+            "squash" | "s" => Ok(RebaseAction::Squash),
+            "fixup" | "f" => Ok(RebaseAction::Fixup),
+            "drop" | "d" => Ok(RebaseAction::Drop),
+            other => Err(format!("Unsupported rebase action `{other}`.")),
+        }
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn collect_rebase_entries(
+    repo: &Repository,
+    onto: git2::Oid,
+    head: git2::Oid,
+) -> Result<Vec<GitRebasePlanEntry>, String> {
+    let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
+    revwalk.push(head).map_err(|e| e.to_string())?;
+    revwalk.hide(onto).map_err(|e| e.to_string())?;
+    revwalk
+        .set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)
+        .map_err(|e| e.to_string())?;
+    let mut entries = Vec::new();
+    for oid in revwalk {
+        let oid = oid.map_err(|e| e.to_string())?;
+        let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+        // `git rebase -i` without --rebase-merges linearizes and skips merges.
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        let sha = oid.to_string();
+        entries.push(GitRebasePlanEntry {
+            action: "pick".to_string(),
+            short_sha: sha.chars().take(7).collect(),
+            sha,
+            summary: commit.summary().unwrap_or("").to_string(),
+            author: commit.author().name().unwrap_or("").to_string(),
+            timestamp: commit.time().seconds(),
+            message: None,
+        });
+        if entries.len() > REBASE_PLAN_LIMIT {
+            return Err(format!(
+                "More than {REBASE_PLAN_LIMIT} commits to rebase. Pick a closer base."
+            ));
+        }
+    }
+    Ok(entries)
+}
+
+/// Renders the todo list. Replacement messages are applied with an `exec`
+/// amend right after their step, which keeps the whole run non-interactive.
+/// The entries must name each commit of `onto..head` exactly once, in any
+/// order; git would otherwise silently drop or duplicate commits.
+fn build_rebase_todo(
+    repo: &Repository,
+    onto: git2::Oid,
+    head: git2::Oid,
+    entries: &[GitRebasePlanEntry],
+    scratch_dir: &Path,
+) -> Result<String, String> {
+    let mut pending = collect_rebase_entries(repo, onto, head)?
+        .into_iter()
+        .map(|entry| entry.sha)
+        .collect::<HashSet<_>>();
+    let mut todo = String::new();
+    let mut has_base = false;
+    for (index, entry) in entries.iter().enumerate() {
+        let action = RebaseAction::parse(&entry.action)?;
+        let oid = repo
+            .revparse_single(entry.sha.trim())
+            .and_then(|object| object.peel_to_commit())
+            .map(|commit| commit.id())
+            .map_err(|_| format!("Commit not found: {}", entry.sha.trim()))?;
+        if !pending.remove(&oid.to_string()) {
+            return Err(format!(
+                "Commit {} is not in the rebase range or is listed twice.",
+                entry.sha.trim()
+            ));
+        }
+        if action == RebaseAction::Drop {
+            todo.push_str(&format!("drop {oid}\n"));
+            continue;
+        }
+        if matches!(action, RebaseAction::Squash | RebaseAction::Fixup) && !has_base {
+            return Err("The first kept commit cannot be squashed or fixed up.".to_string());
+        }
+        has_base = true;
+        let keyword = match action {
+            // Reword without a new message keeps the original one.
+            RebaseAction::Pick | RebaseAction::Reword => "pick",
+            RebaseAction::Squash => "squash",
+            RebaseAction::Fixup => "fixup",
+            RebaseAction::Drop => unreachable!(),
+        };
+        todo.push_str(&format!("{keyword} {oid}\n"));
+        let message = entry
+            .message
+            .as_deref()
+            .map(str::trim)
+            .filter(|message| !message.is_empty());
+        let applies_message = matches!(action, RebaseAction::Reword | RebaseAction::Squash);
+        if let (true, Some(message)) = (applies_message, message) {
+            let message_path = scratch_dir.join(format!("message-{index}.txt"));
+            fs::write(&message_path, format!("{message}\n"))
+                .map_err(|e| format!("Failed to write commit message: {e}"))?;
+            todo.push_str(&format!(
+                "exec git commit --amend --allow-empty --no-verify --cleanup=strip -F {}\n",
+                shell_quote(&message_path.to_string_lossy())
+            ));
+        }
+    }
+    if !pending.is_empty() {
+        return Err(format!(
+            "The plan is missing {} commit(s) of the rebase range. Reload it and try again.",
+            pending.len()
+        ));
+    }
+    if !has_base {
+        return Err("The plan drops every commit. Use a reset instead.".to_string());
+    }
+    Ok(todo)
+}
+
+fn read_step_file(path: &Path) -> Option<usize> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+fn rebase_progress(git_dir: &Path) -> Option<(usize, usize)> {
+    let merge_dir = git_dir.join("rebase-merge");
+    Some((
+        read_step_file(&merge_dir.join("msgnum"))?,
+        read_step_file(&merge_dir.join("end"))?,
+    ))
+}
+
+fn conflicted_files(repo: &Repository) -> Vec<String> {
+    let mut options = StatusOptions::new();
+    options.include_untracked(false);
+    let Ok(statuses) = repo.statuses(Some(&mut options)) else {
+        return Vec::new();
+    };
+    statuses
+        .iter()
+        .filter(|entry| entry.status().contains(Status::CONFLICTED))
+        .filter_map(|entry| entry.path().map(ToOwned::to_owned))
+        .collect()
+}
+
+async fn run_rebase_process(
+    repo_root: &Path,
+    git_dir: &Path,
+    args: &[String],
+    sequence_editor: Option<String>,
+    workspace_id: &str,
+    app: &AppHandle,
+) -> Result<(bool, String), String> {
+    let git_bin = resolve_git_binary().map_err(|e| format!("Failed to run git: {e}"))?;
+    let mut command = crate::utils::async_command(git_bin);
+    command
+        .args(args)
+        .current_dir(repo_root)
+        .env("PATH", git_env_path())
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GCM_INTERACTIVE", "never")
+        // Squash steps open the message editor; accept git's combined message.
+        .env("GIT_EDITOR", "true")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(editor) = sequence_editor {
+        command.env("GIT_SEQUENCE_EDITOR", editor);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to run git rebase: {e}"))?;
+    let mut stdout = child.stdout.take();
+    let mut stderr = child.stderr.take();
+    let stdout_task = tokio::spawn(async move {
+        let mut buffer = String::new();
+        if let Some(stdout) = stdout.as_mut() {
+            let _ = stdout.read_to_string(&mut buffer).await;
+        }
+        buffer
+    });
+    let stderr_task = tokio::spawn(async move {
+        let mut buffer = String::new();
+        if let Some(stderr) = stderr.as_mut() {
+            let _ = stderr.read_to_string(&mut buffer).await;
+        }
+        buffer
+    });
+
+    let deadline = Instant::now() + Duration::from_secs(REBASE_TIMEOUT_SECS);
+    let mut last_progress = None;
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill().await;
+            return Err(format!(
+                "git rebase timed out after {REBASE_TIMEOUT_SECS}s. Run `git rebase --abort` to restore the branch."
+            ));
+        }
+        let progress = rebase_progress(git_dir);
+        if progress.is_some() && progress != last_progress {
+            if let Some((current, total)) = progress {
+                let _ = app.emit(
+                    "git-rebase-progress",
+                    json!({
+                        "workspaceId": workspace_id,
+                        "currentStep": current,
+                        "totalSteps": total,
+                    }),
+                );
+            }
+            last_progress = progress;
+        }
+        sleep(Duration::from_millis(REBASE_POLL_INTERVAL_MS)).await;
+    };
+    let stdout = stdout_task.await.unwrap_or_default();
+    let stderr = stderr_task.await.unwrap_or_default();
+    let output = [stdout.trim(), stderr.trim()]
+        .iter()
+        .filter(|part| !part.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok((status.success(), output))
+}
+
+fn summarize_rebase(repo_root: &Path, success: bool, output: String) -> GitRebaseResult {
+    let repo = match open_repository_at_root(repo_root) {
+        Ok(repo) => repo,
+        Err(error) => {
+            return GitRebaseResult {
+                status: "failed".to_string(),
+                message: error,
+                current_step: None,
+                total_steps: None,
+                conflicted_files: Vec::new(),
+                head_sha: None,
+            }
+        }
+    };
+    let git_dir = repo.path().to_path_buf();
+    let head_sha = repo
+        .head()
+        .ok()
+        .and_then(|head| head.target())
+        .map(|oid| oid.to_string());
+    let in_progress = git_dir.join("rebase-merge").exists();
+    if !in_progress {
+        let _ = fs::remove_dir_all(git_dir.join(REBASE_SCRATCH_DIR));
+    }
+    let progress = rebase_progress(&git_dir);
+    let (status, message) = if success && !in_progress {
+        ("completed", "Rebase completed.".to_string())
+    } else if in_progress {
+        let files = conflicted_files(&repo);
+        let message = if files.is_empty() {
+            format!("Rebase stopped. Resolve the issue, then continue or abort.\n{output}")
+        } else {
+            "Rebase stopped on conflicts. Resolve and stage the files, then continue.".to_string()
+        };
+        return GitRebaseResult {
+            status: "conflict".to_string(),
+            message: message.trim().to_string(),
+            current_step: progress.map(|(current, _)| current),
+            total_steps: progress.map(|(_, total)| total),
+            conflicted_files: files,
+            head_sha,
+        };
+    } else if output.is_empty() {
+        ("failed", "git rebase failed.".to_string())
+    } else {
+        ("failed", output)
+    };
+    GitRebaseResult {
+        status: status.to_string(),
+        message,
+        current_step: progress.map(|(current, _)| current),
+        total_steps: progress.map(|(_, total)| total),
+        conflicted_files: Vec::new(),
+        head_sha,
+    }
+}
+
+async fn resolve_rebase_root(
+    workspace_id: &str,
+    state: &State<'_, AppState>,
+) -> Result<(PathBuf, Option<GitIdentityProfile>), String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+    let identity = identity_for_entry(&workspaces, &entry);
+    drop(workspaces);
+    Ok((resolve_git_root(&entry)?, identity))
+}
+
+#[tauri::command]
+pub(crate) async fn get_git_rebase_plan(
+    workspace_id: String,
+    onto: String,
+    state: State<'_, AppState>,
+) -> Result<GitRebasePlan, String> {
+    let (repo_root, _) = resolve_rebase_root(&workspace_id, &state).await?;
+    let onto = onto.trim().to_string();
+    if onto.is_empty() {
+        return Err("Base ref cannot be empty.".to_string());
+    }
+    let repo = open_repository_at_root(&repo_root)?;
+    let onto_oid = resolve_ref_to_oid(&repo, &onto)?;
+    let head = repo.head().map_err(|e| e.to_string())?;
+    let head_oid = head.target().ok_or("HEAD does not point to a commit.")?;
+    let branch = head
+        .is_branch()
+        .then(|| head.shorthand().map(ToOwned::to_owned))
+        .flatten();
+    let entries = collect_rebase_entries(&repo, onto_oid, head_oid)?;
+    Ok(GitRebasePlan {
+        onto,
+        onto_sha: onto_oid.to_string(),
+        head_sha: head_oid.to_string(),
+        branch,
+        entries,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn execute_git_rebase_plan(
+    workspace_id: String,
+    onto: String,
+    entries: Vec<GitRebasePlanEntry>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<GitRebaseResult, String> {
+    let (repo_root, identity) = resolve_rebase_root(&workspace_id, &state).await?;
+    let onto = onto.trim().to_string();
+    if onto.is_empty() {
+        return Err("Base ref cannot be empty.".to_string());
+    }
+    if entries.is_empty() {
+        return Err("Rebase plan is empty.".to_string());
+    }
+    let repo = open_repository_at_root(&repo_root)?;
+    let git_dir = repo.path().to_path_buf();
+    if git_dir.join("rebase-merge").exists() || git_dir.join("rebase-apply").exists() {
+        return Err("A rebase is already in progress. Continue or abort it first.".to_string());
+    }
+    let onto_oid = resolve_ref_to_oid(&repo, &onto)?;
+    let head_oid = repo
+        .head()
+        .ok()
+        .and_then(|head| head.target())
+        .ok_or("HEAD does not point to a commit.")?;
+
+    let scratch_dir = git_dir.join(REBASE_SCRATCH_DIR);
+    let _ = fs::remove_dir_all(&scratch_dir);
+    fs::create_dir_all(&scratch_dir).map_err(|e| format!("Failed to prepare rebase plan: {e}"))?;
+    let todo = match build_rebase_todo(&repo, onto_oid, head_oid, &entries, &scratch_dir) {
+        Ok(todo) => todo,
+        Err(error) => {
+            let _ = fs::remove_dir_all(&scratch_dir);
+            return Err(error);
+        }
+    };
+    let todo_path = scratch_dir.join("git-rebase-todo");
+    fs::write(&todo_path, todo).map_err(|e| format!("Failed to write rebase plan: {e}"))?;
+    drop(repo);
+
+    // git invokes the sequence editor with the todo path as its argument, so
+    // copying the prepared plan over it replaces the interactive step.
+    let sequence_editor = format!("cp {}", shell_quote(&todo_path.to_string_lossy()));
+    let onto_sha = onto_oid.to_string();
+    let args = git_args_with_identity(
+        identity.as_ref(),
+        &["rebase", "-i", "--no-autosquash", &onto_sha],
+    )?;
+    let (success, output) = run_rebase_process(
+        &repo_root,
+        &git_dir,
+        &args,
+        Some(sequence_editor),
+        &workspace_id,
+        &app,
+    )
+    .await?;
+    Ok(summarize_rebase(&repo_root, success, output))
+}
+
+#[tauri::command]
+pub(crate) async fn continue_git_rebase(
+    workspace_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<GitRebaseResult, String> {
+    let (repo_root, identity) = resolve_rebase_root(&workspace_id, &state).await?;
+    let git_dir = open_repository_at_root(&repo_root)?.path().to_path_buf();
+    if !git_dir.join("rebase-merge").exists() {
+        return Err("No interactive rebase in progress.".to_string());
+    }
+    let args = git_args_with_identity(identity.as_ref(), &["rebase", "--continue"])?;
+    let (success, output) =
+        run_rebase_process(&repo_root, &git_dir, &args, None, &workspace_id, &app).await?;
+    Ok(summarize_rebase(&repo_root, success, output))
+}
+
+#[tauri::command]
+pub(crate) async fn abort_git_rebase(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let (repo_root, _) = resolve_rebase_root(&workspace_id, &state).await?;
+    let git_dir = open_repository_at_root(&repo_root)?.path().to_path_buf();
+    run_git_command(&repo_root, &["rebase", "--abort"]).await?;
+    let _ = fs::remove_dir_all(git_dir.join(REBASE_SCRATCH_DIR));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::git;
+    use std::process::Command;
+
+    fn create_repo_with_commits(count: usize) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("moss-rebase-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create temp repo");
+        git(&root, &["init", "-q", "-b", "main"]);
+        fs::write(root.join("base.txt"), "base\n").expect("write base");
+        git(&root, &["add", "."]);
+        git(&root, &["commit", "-q", "-m", "base"]);
+        for index in 0..count {
+            fs::write(root.join(format!("file-{index}.txt")), "content\n").expect("write file");
+            git(&root, &["add", "."]);
+            git(&root, &["commit", "-q", "-m", &format!("wip {index}")]);
+        }
+        root
+    }
+
+    #[test]
+    fn rebase_plan_lists_commits_oldest_first() {
+        let root = create_repo_with_commits(3);
+        let repo = Repository::open(&root).expect("open repo");
+        let onto = resolve_ref_to_oid(&repo, "HEAD~3").expect("onto");
+        let head = repo.head().unwrap().target().unwrap();
+        let entries = collect_rebase_entries(&repo, onto, head).expect("entries");
+        let summaries = entries
+            .iter()
+            .map(|entry| entry.summary.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(summaries, vec!["wip 0", "wip 1", "wip 2"]);
+        assert!(entries.iter().all(|entry| entry.action == "pick"));
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn rebase_todo_rejects_leading_squash_and_adds_message_exec() {
+        let root = create_repo_with_commits(2);
+        let repo = Repository::open(&root).expect("open repo");
+        let onto = resolve_ref_to_oid(&repo, "HEAD~2").expect("onto");
+        let head = repo.head().unwrap().target().unwrap();
+        let mut entries = collect_rebase_entries(&repo, onto, head).expect("entries");
+        let scratch = root.join(".git").join(REBASE_SCRATCH_DIR);
+        fs::create_dir_all(&scratch).expect("scratch");
+
+        entries[0].action = "squash".to_string();
+        assert!(build_rebase_todo(&repo, onto, head, &entries, &scratch).is_err());
+
+        entries[0].action = "pick".to_string();
+        entries[1].action = "squash".to_string();
+        entries[1].message = Some("feat: combined".to_string());
+        let todo = build_rebase_todo(&repo, onto, head, &entries, &scratch).expect("todo");
+        let lines = todo.lines().collect::<Vec<_>>();
+        assert!(lines[0].starts_with("pick "));
+        assert!(lines[1].starts_with("squash "));
+        assert!(lines[2].starts_with("exec git commit --amend"));
+        assert_eq!(
+            fs::read_to_string(scratch.join("message-1.txt")).expect("message"),
+            "feat: combined\n"
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn rebase_todo_requires_every_commit_of_the_range_once() {
+        let root = create_repo_with_commits(3);
+        let repo = Repository::open(&root).expect("open repo");
+        let onto = resolve_ref_to_oid(&repo, "HEAD~2").expect("onto");
+        let head = repo.head().unwrap().target().unwrap();
+        let entries = collect_rebase_entries(&repo, onto, head).expect("entries");
+        let scratch = root.join(".git").join(REBASE_SCRATCH_DIR);
+        fs::create_dir_all(&scratch).expect("scratch");
+
+        let mut reordered = entries.clone();
+        reordered.reverse();
+        assert!(build_rebase_todo(&repo, onto, head, &reordered, &scratch).is_ok());
+        let missing = entries[..1].to_vec();
+        assert!(build_rebase_todo(&repo, onto, head, &missing, &scratch).is_err());
+        let duplicated = vec![entries[0].clone(), entries[0].clone()];
+        assert!(build_rebase_todo(&repo, onto, head, &duplicated, &scratch).is_err());
+        let mut foreign = entries.clone();
+        foreign[1].sha = onto.to_string();
+        assert!(build_rebase_todo(&repo, onto, head, &foreign, &scratch).is_err());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn generated_sequence_editor_squashes_wip_commits() {
+        let root = create_repo_with_commits(3);
+        let repo = Repository::open(&root).expect("open repo");
+        let onto = resolve_ref_to_oid(&repo, "HEAD~3").expect("onto");
+        let head = repo.head().unwrap().target().unwrap();
+        let mut entries = collect_rebase_entries(&repo, onto, head).expect("entries");
+        entries[1].action = "fixup".to_string();
+        entries[2].action = "squash".to_string();
+        entries[2].message = Some("feat: add files".to_string());
+        let scratch = repo.path().join(REBASE_SCRATCH_DIR);
+        fs::create_dir_all(&scratch).expect("scratch");
+        let todo = build_rebase_todo(&repo, onto, head, &entries, &scratch).expect("todo");
+        let todo_path = scratch.join("git-rebase-todo");
+        fs::write(&todo_path, todo).expect("write todo");
+
+        let output = Command::new("git")
+            .args(["rebase", "-i", "--no-autosquash", &onto.to_string()])
+            .current_dir(&root)
+            .env(
+                "GIT_SEQUENCE_EDITOR",
+                format!("cp {}", shell_quote(&todo_path.to_string_lossy())),
+            )
+            .env("GIT_EDITOR", "true")
+            .env("GIT_AUTHOR_NAME", "Tester")
+            .env("GIT_AUTHOR_EMAIL", "tester@example.com")
+            .env("GIT_COMMITTER_NAME", "Tester")
+            .env("GIT_COMMITTER_EMAIL", "tester@example.com")
+            .output()
+            .expect("run rebase");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let result = summarize_rebase(&root, true, String::new());
+        assert_eq!(result.status, "completed");
+        assert!(!scratch.exists());
+        assert_eq!(git(&root, &["rev-list", "--count", "HEAD"]), "2");
+        assert_eq!(git(&root, &["log", "-1", "--format=%s"]), "feat: add files");
+        let _ = fs::remove_dir_all(root);
+    }
+}
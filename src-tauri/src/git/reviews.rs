@@ -0,0 +1,185 @@
+use tauri::State;
+
+use super::forge::{self, forge_remote_from_path, ForgeRemote};
+use crate::git_utils::resolve_git_root;
+use crate::state::AppState;
+use crate::types::{
+    GitHubPullRequestComment, GitHubPullRequestReviewComment, GitHubPullRequestReviewResult,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReviewEvent {
+    Approve,
+    RequestChanges,
+    Comment,
+}
+
+impl ReviewEvent {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().replace('-', "_").as_str() {
+            "approve" | "approved" => Ok(ReviewEvent::Approve),
+            "request_changes" | "changes_requested" => Ok(ReviewEvent::RequestChanges),
+            "comment" | "commented" => Ok(ReviewEvent::Comment),
+            other => Err(format!(
+                "Unsupported review event `{other}`. Use approve, request_changes or comment."
+            )),
+        }
+    }
+}
+
+/// Returns true when the comment targets the old side of the diff.
+pub(crate) fn is_left_side(comment: &GitHubPullRequestReviewComment) -> bool {
+    comment
+        .side
+        .as_deref()
+        .map(|side| side.trim().eq_ignore_ascii_case("left"))
+        .unwrap_or(false)
+}
+
+fn normalize_review_comments(
+    comments: Vec<GitHubPullRequestReviewComment>,
+) -> Result<Vec<GitHubPullRequestReviewComment>, String> {
+    comments
+        .into_iter()
+        .map(|mut comment| {
+            comment.path = comment.path.trim().trim_start_matches('/').to_string();
+            comment.body = comment.body.trim().to_string();
+            if comment.path.is_empty() || comment.body.is_empty() || comment.line == 0 {
+                return Err("Review comments need a path, a line number and a body.".to_string());
+            }
+            comment.start_line = comment
+                .start_line
+                .filter(|start| *start > 0 && *start < comment.line);
+            Ok(comment)
+        })
+        .collect()
+}
+
+fn validate_review(
+    event: ReviewEvent,
+    body: &str,
+    comments: &[GitHubPullRequestReviewComment],
+) -> Result<(), String> {
+    match event {
+        ReviewEvent::RequestChanges if body.is_empty() => {
+            Err("Requesting changes needs a review summary.".to_string())
+        }
+        ReviewEvent::Comment if body.is_empty() && comments.is_empty() => {
+            Err("A comment review needs a summary or at least one inline comment.".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+async fn resolve_review_target(
+    workspace_id: &str,
+    state: &State<'_, AppState>,
+) -> Result<ForgeRemote, String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+    drop(workspaces);
+
+    let repo_root = resolve_git_root(&entry)?;
+    forge_remote_from_path(&repo_root, entry.settings.forge_kind.as_deref())
+}
+
+#[tauri::command]
+pub(crate) async fn post_github_pull_request_comment(
+    workspace_id: String,
+    pr_number: u64,
+    body: String,
+    state: State<'_, AppState>,
+) -> Result<GitHubPullRequestComment, String> {
+    let body = body.trim().to_string();
+    if body.is_empty() {
+        return Err("Comment body is required.".to_string());
+    }
+    let remote = resolve_review_target(&workspace_id, &state).await?;
+    forge::comment_on_pull_request(&remote, pr_number, &body).await
+}
+
+#[tauri::command]
+pub(crate) async fn post_github_pull_request_inline_comment(
+    workspace_id: String,
+    pr_number: u64,
+    comment: GitHubPullRequestReviewComment,
+    state: State<'_, AppState>,
+) -> Result<GitHubPullRequestComment, String> {
+    let comment = normalize_review_comments(vec![comment])?
+        .pop()
+        .ok_or("Review comment is required.")?;
+    let remote = resolve_review_target(&workspace_id, &state).await?;
+    forge::post_inline_comment(&remote, pr_number, &comment).await
+}
+
+#[tauri::command]
+pub(crate) async fn submit_github_pull_request_review(
+    workspace_id: String,
+    pr_number: u64,
+    event: String,
+    body: Option<String>,
+    comments: Option<Vec<GitHubPullRequestReviewComment>>,
+    state: State<'_, AppState>,
+) -> Result<GitHubPullRequestReviewResult, String> {
+    let event = ReviewEvent::parse(&event)?;
+    let body = body.unwrap_or_default().trim().to_string();
+    let comments = normalize_review_comments(comments.unwrap_or_default())?;
+    validate_review(event, &body, &comments)?;
+    let remote = resolve_review_target(&workspace_id, &state).await?;
+    forge::submit_review(&remote, pr_number, event, &body, &comments).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(
+        line: u32,
+        side: Option<&str>,
+        start_line: Option<u32>,
+    ) -> GitHubPullRequestReviewComment {
+        GitHubPullRequestReviewComment {
+            path: "/src/lib.rs".to_string(),
+            line,
+            side: side.map(ToOwned::to_owned),
+            start_line,
+            body: " Needs a test ".to_string(),
+        }
+    }
+
+    #[test]
+    fn review_event_accepts_common_spellings() {
+        assert_eq!(ReviewEvent::parse("APPROVE").unwrap(), ReviewEvent::Approve);
+        assert_eq!(
+            ReviewEvent::parse("request-changes").unwrap(),
+            ReviewEvent::RequestChanges
+        );
+        assert_eq!(ReviewEvent::parse("comment").unwrap(), ReviewEvent::Comment);
+        assert!(ReviewEvent::parse("merge").is_err());
+    }
+
+    #[test]
+    fn review_comments_are_trimmed_and_ranges_checked() {
+        let comments = normalize_review_comments(vec![
+            comment(12, None, Some(10)),
+            comment(4, Some("left"), Some(9)),
+        ])
+        .expect("valid comments");
+        assert_eq!(comments[0].path, "src/lib.rs");
+        assert_eq!(comments[0].body, "Needs a test");
+        assert_eq!(comments[0].start_line, Some(10));
+        assert!(is_left_side(&comments[1]));
+        assert_eq!(comments[1].start_line, None);
+    }
+
+    #[test]
+    fn validate_review_requires_content() {
+        assert!(validate_review(ReviewEvent::RequestChanges, "", &[]).is_err());
+        assert!(validate_review(ReviewEvent::Comment, "", &[]).is_err());
+        assert!(validate_review(ReviewEvent::Approve, "", &[]).is_ok());
+        assert!(normalize_review_comments(vec![comment(0, None, None)]).is_err());
+    }
+}
@@ -0,0 +1,329 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use git2::{Oid, Repository, Sort, SubmoduleIgnore, SubmoduleStatus};
+use tauri::State;
+
+use super::{open_repository_at_root, run_git_command};
+use crate::git_utils::resolve_git_root;
+use crate::state::AppState;
+use crate::types::GitSubmoduleStatus;
+use crate::utils::normalize_git_path;
+
+const SUBMODULE_LOG_LIMIT: usize = 20;
+/// How long `get_git_status` polls reuse submodule statuses.
+const STATUS_CACHE_TTL: Duration = Duration::from_secs(30);
+
+type CachedStatuses = (Instant, Vec<GitSubmoduleStatus>);
+
+/// Submodule statuses by workspace. Collecting them opens every submodule
+/// repository, which is too slow for each status poll, so polls reuse the
+/// last result until it expires or a submodule command refreshes it.
+static STATUS_CACHE: LazyLock<Mutex<HashMap<String, CachedStatuses>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn short_sha(oid: Option<Oid>) -> String {
+    oid.map(|oid| oid.to_string().chars().take(7).collect())
+        .unwrap_or_else(|| "0000000".to_string())
+}
+
+fn ahead_behind(repo: &Repository, local: Oid, recorded: Oid) -> (String, usize, usize) {
+    if local == recorded {
+        return ("none".to_string(), 0, 0);
+    }
+    match repo.graph_ahead_behind(local, recorded) {
+        Ok((ahead, behind)) => {
+            let drift = match (ahead > 0, behind > 0) {
+                (true, true) => "diverged",
+                (true, false) => "ahead",
+                (false, true) => "behind",
+                (false, false) => "none",
+            };
+            (drift.to_string(), ahead, behind)
+        }
+        Err(_) => ("unknown".to_string(), 0, 0),
+    }
+}
+
+pub(super) fn collect_submodule_statuses(repo: &Repository) -> Vec<GitSubmoduleStatus> {
+    let Ok(submodules) = repo.submodules() else {
+        return Vec::new();
+    };
+    let mut results = Vec::new();
+    for submodule in submodules {
+        let name = submodule.name().unwrap_or("").to_string();
+        let path = normalize_git_path(&submodule.path().to_string_lossy());
+        let status = repo
+            .submodule_status(&name, SubmoduleIgnore::None)
+            .unwrap_or(SubmoduleStatus::empty());
+        let initialized =
+            !status.contains(SubmoduleStatus::WD_UNINITIALIZED) && submodule.workdir_id().is_some();
+        let dirty = status.intersects(
+            SubmoduleStatus::WD_INDEX_MODIFIED
+                | SubmoduleStatus::WD_WD_MODIFIED
+                | SubmoduleStatus::WD_UNTRACKED,
+        );
+        let recorded = submodule.index_id().or_else(|| submodule.head_id());
+        let (drift, ahead, behind) = match (submodule.workdir_id(), recorded) {
+            (Some(local), Some(recorded)) => match submodule.open() {
+                Ok(sub_repo) => ahead_behind(&sub_repo, local, recorded),
+                Err(_) => ("unknown".to_string(), 0, 0),
+            },
+            _ => ("unknown".to_string(), 0, 0),
+        };
+        results.push(GitSubmoduleStatus {
+            name,
+            path,
+            url: submodule.url().map(ToOwned::to_owned),
+            branch: submodule.branch().map(ToOwned::to_owned),
+            head_sha: submodule.head_id().map(|oid| oid.to_string()),
+            index_sha: submodule.index_id().map(|oid| oid.to_string()),
+            workdir_sha: submodule.workdir_id().map(|oid| oid.to_string()),
+            initialized,
+            dirty,
+            drift,
+            ahead,
+            behind,
+        });
+    }
+    results.sort_by(|left, right| left.path.cmp(&right.path));
+    results
+}
+
+fn range_summaries(repo: &Repository, include: Oid, exclude: Oid) -> Option<Vec<String>> {
+    let mut revwalk = repo.revwalk().ok()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME).ok()?;
+    revwalk.push(include).ok()?;
+    revwalk.hide(exclude).ok()?;
+    Some(
+        revwalk
+            .take(SUBMODULE_LOG_LIMIT)
+            .filter_map(Result::ok)
+            .filter_map(|oid| repo.find_commit(oid).ok())
+            .map(|commit| commit.summary().unwrap_or("").to_string())
+            .collect(),
+    )
+}
+
+/// Renders a gitlink change the way `git diff --submodule=log` does, so the
+/// diff shows the commit range instead of `Subproject commit` lines.
+pub(super) fn describe_submodule_change(
+    repo: &Repository,
+    path: &str,
+    old: Option<Oid>,
+    new: Option<Oid>,
+) -> String {
+    let header = format!("Submodule {path} {}", short_sha(old));
+    match (old, new) {
+        (None, _) => format!("{header}...{} (new submodule)", short_sha(new)),
+        (_, None) => format!("{header}...{} (submodule deleted)", short_sha(new)),
+        (Some(old), Some(new)) if old == new => {
+            format!("Submodule {path} contains modified content")
+        }
+        (Some(old), Some(new)) => {
+            let sub_repo = repo
+                .find_submodule(path)
+                .and_then(|submodule| submodule.open());
+            let ranges = sub_repo.ok().and_then(|sub_repo| {
+                let added = range_summaries(&sub_repo, new, old)?;
+                let removed = range_summaries(&sub_repo, old, new)?;
+                Some((added, removed))
+            });
+            let Some((added, removed)) = ranges else {
+                return format!("{header}...{} (commits not present)", short_sha(Some(new)));
+            };
+            let mut lines = vec![format!("{header}..{}:", short_sha(Some(new)))];
+            lines.extend(added.iter().map(|summary| format!("  > {summary}")));
+            lines.extend(removed.iter().map(|summary| format!("  < {summary}")));
+            lines.join("\n")
+        }
+    }
+}
+
+async fn resolve_submodule_root(
+    workspace_id: &str,
+    state: &State<'_, AppState>,
+) -> Result<std::path::PathBuf, String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+    drop(workspaces);
+    resolve_git_root(&entry)
+}
+
+fn remember_submodule_statuses(workspace_id: &str, statuses: &[GitSubmoduleStatus]) {
+    let mut cache = STATUS_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.insert(
+        workspace_id.to_string(),
+        (Instant::now(), statuses.to_vec()),
+    );
+}
+
+/// Cached submodule statuses for the status poll, collected again once
+/// they are older than `STATUS_CACHE_TTL`.
+pub(super) fn cached_submodule_statuses(
+    workspace_id: &str,
+    repo: &Repository,
+) -> Vec<GitSubmoduleStatus> {
+    {
+        let cache = STATUS_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((collected_at, statuses)) = cache.get(workspace_id) {
+            if collected_at.elapsed() < STATUS_CACHE_TTL {
+                return statuses.clone();
+            }
+        }
+    }
+    let statuses = collect_submodule_statuses(repo);
+    remember_submodule_statuses(workspace_id, &statuses);
+    statuses
+}
+
+/// Drops the cached submodule statuses of a removed workspace.
+pub(crate) fn forget_submodule_statuses(workspace_id: &str) {
+    let mut cache = STATUS_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.remove(workspace_id);
+}
+
+#[tauri::command]
+pub(crate) async fn get_git_submodules(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<GitSubmoduleStatus>, String> {
+    let repo_root = resolve_submodule_root(&workspace_id, &state).await?;
+    let repo = open_repository_at_root(&repo_root)?;
+    let statuses = collect_submodule_statuses(&repo);
+    remember_submodule_statuses(&workspace_id, &statuses);
+    Ok(statuses)
+}
+
+#[tauri::command]
+pub(crate) async fn update_git_submodules(
+    workspace_id: String,
+    paths: Option<Vec<String>>,
+    init: Option<bool>,
+    recursive: Option<bool>,
+    remote: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<GitSubmoduleStatus>, String> {
+    let repo_root = resolve_submodule_root(&workspace_id, &state).await?;
+    let paths = paths
+        .unwrap_or_default()
+        .iter()
+        .map(|path| normalize_git_path(path.trim()))
+        .filter(|path| !path.is_empty())
+        .collect::<Vec<_>>();
+    let mut args = vec!["submodule", "update"];
+    if init.unwrap_or(true) {
+        args.push("--init");
+    }
+    if recursive.unwrap_or(false) {
+        args.push("--recursive");
+    }
+    if remote.unwrap_or(false) {
+        args.push("--remote");
+    }
+    args.push("--");
+    args.extend(paths.iter().map(String::as_str));
+    run_git_command(&repo_root, &args).await?;
+    let repo = open_repository_at_root(&repo_root)?;
+    let statuses = collect_submodule_statuses(&repo);
+    remember_submodule_statuses(&workspace_id, &statuses);
+    Ok(statuses)
+}
+
+#[tauri::command]
+pub(crate) async fn sync_git_submodules(
+    workspace_id: String,
+    recursive: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<GitSubmoduleStatus>, String> {
+    let repo_root = resolve_submodule_root(&workspace_id, &state).await?;
+    let mut args = vec!["submodule", "sync"];
+    if recursive.unwrap_or(false) {
+        args.push("--recursive");
+    }
+    run_git_command(&repo_root, &args).await?;
+    let repo = open_repository_at_root(&repo_root)?;
+    let statuses = collect_submodule_statuses(&repo);
+    remember_submodule_statuses(&workspace_id, &statuses);
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::git;
+    use std::fs;
+    use std::path::Path;
+
+    fn commit_file(root: &Path, name: &str, message: &str) -> String {
+        fs::write(root.join(name), message).expect("write file");
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", message]);
+        git(root, &["rev-parse", "HEAD"])
+    }
+
+    #[test]
+    fn reports_submodule_drift_and_commit_range() {
+        let base = std::env::temp_dir().join(format!("moss-submodule-{}", uuid::Uuid::new_v4()));
+        let library = base.join("library");
+        let app = base.join("app");
+        fs::create_dir_all(&library).expect("library dir");
+        fs::create_dir_all(&app).expect("app dir");
+        git(&library, &["init", "-q", "-b", "main"]);
+        let first = commit_file(&library, "lib.txt", "first");
+        git(&app, &["init", "-q", "-b", "main"]);
+        git(
+            &app,
+            &[
+                "submodule",
+                "add",
+                "-q",
+                &library.to_string_lossy(),
+                "vendor/library",
+            ],
+        );
+        git(&app, &["commit", "-q", "-m", "add submodule"]);
+
+        let checkout = app.join("vendor/library");
+        let second = commit_file(&checkout, "lib.txt", "second");
+
+        let repo = Repository::open(&app).expect("open app");
+        let statuses = collect_submodule_statuses(&repo);
+        assert_eq!(statuses.len(), 1);
+        let status = &statuses[0];
+        assert_eq!(status.path, "vendor/library");
+        assert!(status.initialized);
+        assert_eq!(status.index_sha.as_deref(), Some(first.as_str()));
+        assert_eq!(status.workdir_sha.as_deref(), Some(second.as_str()));
+        assert_eq!((status.drift.as_str(), status.ahead), ("ahead", 1));
+
+        // Status polls reuse the cached result until it is refreshed.
+        let workspace_id = format!("ws-{}", uuid::Uuid::new_v4());
+        assert_eq!(cached_submodule_statuses(&workspace_id, &repo)[0].ahead, 1);
+        commit_file(&checkout, "lib.txt", "third");
+        assert_eq!(cached_submodule_statuses(&workspace_id, &repo)[0].ahead, 1);
+        forget_submodule_statuses(&workspace_id);
+        assert_eq!(cached_submodule_statuses(&workspace_id, &repo)[0].ahead, 2);
+        git(&checkout, &["reset", "-q", "--hard", "HEAD~1"]);
+
+        let description = describe_submodule_change(
+            &repo,
+            "vendor/library",
+            Some(Oid::from_str(&first).unwrap()),
+            Some(Oid::from_str(&second).unwrap()),
+        );
+        assert_eq!(
+            description,
+            format!(
+                "Submodule vendor/library {}..{}:\n  > second",
+                &first[..7],
+                &second[..7]
+            )
+        );
+        let _ = fs::remove_dir_all(base);
+    }
+}
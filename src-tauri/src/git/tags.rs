@@ -0,0 +1,417 @@
+use std::path::Path;
+
+use git2::Repository;
+use tauri::{AppHandle, State};
+
+use super::validation::validate_tag_name;
+use super::{
+    collect_commit_refs_map, collect_unique_commits, open_repository_at_root, run_git_command,
+    trim_optional, upstream_remote_and_branch,
+};
+use crate::git_utils::resolve_git_root;
+use crate::state::AppState;
+use crate::types::{GitHistoryCommit, GitReleaseNotesResponse, GitTagInfo};
+
+const RELEASE_NOTES_MAX_COMMITS: usize = 400;
+
+fn collect_tags(repo: &Repository) -> Result<Vec<GitTagInfo>, String> {
+    let names = repo.tag_names(None).map_err(|e| e.to_string())?;
+    let mut tags = Vec::new();
+    for name in names.iter().flatten() {
+        let reference = match repo.find_reference(&format!("refs/tags/{name}")) {
+            Ok(reference) => reference,
+            Err(_) => continue,
+        };
+        let Some(target_oid) = reference.target() else {
+            continue;
+        };
+        let commit = match reference.peel_to_commit() {
+            Ok(commit) => commit,
+            Err(_) => continue,
+        };
+        let info = match repo.find_tag(target_oid) {
+            Ok(tag) => {
+                let tagger = tag.tagger();
+                GitTagInfo {
+                    name: name.to_string(),
+                    target_sha: commit.id().to_string(),
+                    annotated: true,
+                    message: tag
+                        .message()
+                        .map(|value| value.trim().to_string())
+                        .filter(|value| !value.is_empty()),
+                    tagger: tagger
+                        .as_ref()
+                        .and_then(|signature| signature.name().map(ToOwned::to_owned)),
+                    tagger_email: tagger
+                        .as_ref()
+                        .and_then(|signature| signature.email().map(ToOwned::to_owned)),
+                    timestamp: tagger
+                        .as_ref()
+                        .map(|signature| signature.when().seconds())
+                        .unwrap_or_else(|| commit.time().seconds()),
+                }
+            }
+            Err(_) => GitTagInfo {
+                name: name.to_string(),
+                target_sha: commit.id().to_string(),
+                annotated: false,
+                message: None,
+                tagger: None,
+                tagger_email: None,
+                timestamp: commit.time().seconds(),
+            },
+        };
+        tags.push(info);
+    }
+    tags.sort_by(|left, right| {
+        right
+            .timestamp
+            .cmp(&left.timestamp)
+            .then_with(|| right.name.cmp(&left.name))
+    });
+    Ok(tags)
+}
+
+fn default_tag_remote(repo_root: &Path, remote: Option<String>) -> String {
+    trim_optional(remote)
+        .or_else(|| {
+            upstream_remote_and_branch(repo_root)
+                .ok()
+                .flatten()
+                .map(|(name, _)| name)
+        })
+        .unwrap_or_else(|| "origin".to_string())
+}
+
+fn build_release_notes_prompt(
+    from_ref: &str,
+    to_ref: &str,
+    commits: &[GitHistoryCommit],
+    truncated: bool,
+) -> String {
+    let mut lines = Vec::new();
+    for commit in commits {
+        // Merge commits only repeat what the merged commits already say.
+        if commit.parents.len() > 1 {
+            continue;
+        }
+        lines.push(format!(
+            "- {} {} ({})",
+            commit.short_sha, commit.summary, commit.author
+        ));
+    }
+    let truncation_note = if truncated {
+        format!(
+            " Only the newest {RELEASE_NOTES_MAX_COMMITS} commits of the range are listed; end \
+the changelog with a line saying older changes are not included."
+        )
+    } else {
+        String::new()
+    };
+    format!(
+        "Draft release notes for the changes between `{from_ref}` and `{to_ref}`. \
+Group the commits below into a Markdown changelog with sections such as Features, Fixes, \
+Performance, Refactoring, Documentation and Other. Omit empty sections, merge duplicate \
+entries, rewrite each entry as a short user-facing sentence and keep the short SHA in \
+parentheses.{truncation_note} Only output the changelog, nothing else.\n\n\
+Commits:\n{}",
+        lines.join("\n")
+    )
+}
+
+#[tauri::command]
+pub(crate) async fn list_git_tags(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<GitTagInfo>, String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+    drop(workspaces);
+
+    let repo_root = resolve_git_root(&entry)?;
+    let repo = open_repository_at_root(&repo_root)?;
+    collect_tags(&repo)
+}
+
+#[tauri::command]
+pub(crate) async fn create_git_tag(
+    workspace_id: String,
+    name: String,
+    target: Option<String>,
+    message: Option<String>,
+    sign: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+    drop(workspaces);
+
+    let repo_root = resolve_git_root(&entry)?;
+    let tag_name = validate_tag_name(&name)?;
+    let target = trim_optional(target).unwrap_or_else(|| "HEAD".to_string());
+    let message = trim_optional(message);
+    let sign = sign.unwrap_or(false);
+
+    let mut args = vec!["tag".to_string()];
+    if sign {
+        // Signed tags are always annotated; fall back to the tag name as message.
+        args.push("-s".to_string());
+        args.push("-m".to_string());
+        args.push(message.unwrap_or_else(|| tag_name.clone()));
+    } else if let Some(message) = message {
+        args.push("-a".to_string());
+        args.push("-m".to_string());
+        args.push(message);
+    }
+    args.push("--".to_string());
+    args.push(tag_name);
+    args.push(target);
+    let command: Vec<&str> = args.iter().map(String::as_str).collect();
+    run_git_command(&repo_root, &command).await
+}
+
+#[tauri::command]
+pub(crate) async fn delete_git_tag(
+    workspace_id: String,
+    name: String,
+    remote: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+    drop(workspaces);
+
+    let repo_root = resolve_git_root(&entry)?;
+    let tag_name = validate_tag_name(&name)?;
+    run_git_command(&repo_root, &["tag", "-d", tag_name.as_str()]).await?;
+    if let Some(remote_name) = trim_optional(remote) {
+        let refspec = format!("refs/tags/{tag_name}");
+        run_git_command(
+            &repo_root,
+            &["push", remote_name.as_str(), "--delete", refspec.as_str()],
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn push_git_tag(
+    workspace_id: String,
+    name: Option<String>,
+    remote: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+    drop(workspaces);
+
+    let repo_root = resolve_git_root(&entry)?;
+    let remote_name = default_tag_remote(&repo_root, remote);
+    match trim_optional(name) {
+        Some(name) => {
+            let tag_name = validate_tag_name(&name)?;
+            let refspec = format!("refs/tags/{tag_name}");
+            run_git_command(
+                &repo_root,
+                &["push", remote_name.as_str(), refspec.as_str()],
+            )
+            .await
+        }
+        None => run_git_command(&repo_root, &["push", remote_name.as_str(), "--tags"]).await,
+    }
+}
+
+/// Collects the commits between two refs and asks the active engine to
+/// group them into a changelog draft.
+#[tauri::command]
+pub(crate) async fn generate_release_notes(
+    workspace_id: String,
+    from_tag: String,
+    to_ref: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<GitReleaseNotesResponse, String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+    drop(workspaces);
+
+    let from_ref = from_tag.trim().to_string();
+    if from_ref.is_empty() {
+        return Err("Start tag cannot be empty.".to_string());
+    }
+    let to_ref = trim_optional(to_ref).unwrap_or_else(|| "HEAD".to_string());
+
+    // One extra commit tells whether the range goes past the cap.
+    let mut commits = {
+        let repo_root = resolve_git_root(&entry)?;
+        let repo = open_repository_at_root(&repo_root)?;
+        let refs_map = collect_commit_refs_map(&repo);
+        collect_unique_commits(
+            &repo,
+            &to_ref,
+            &from_ref,
+            &refs_map,
+            RELEASE_NOTES_MAX_COMMITS + 1,
+        )?
+    };
+    if commits.is_empty() {
+        return Err(format!("No commits found between {from_ref} and {to_ref}."));
+    }
+
+    let truncated = commits.len() > RELEASE_NOTES_MAX_COMMITS;
+    commits.truncate(RELEASE_NOTES_MAX_COMMITS);
+
+    let prompt = build_release_notes_prompt(&from_ref, &to_ref, &commits, truncated);
+    let response = crate::engine::engine_send_message_sync(
+        workspace_id,
+        prompt,
+        None,
+        None,
+        None,
+        Some("read-only".to_string()),
+        None,
+        false,
+        None,
+        None,
+        None,
+        None,
+        app,
+        state,
+    )
+    .await?;
+    let engine = response
+        .get("engine")
+        .and_then(|value| value.as_str())
+        .unwrap_or("")
+        .to_string();
+    let draft = response
+        .get("text")
+        .and_then(|value| value.as_str())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| "No release notes were generated".to_string())?;
+
+    Ok(GitReleaseNotesResponse {
+        from_ref,
+        to_ref,
+        engine,
+        draft,
+        commits,
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_temp_repo;
+    use std::fs;
+
+    fn commit_file(repo: &Repository, root: &Path, name: &str, message: &str) -> git2::Oid {
+        fs::write(root.join(name), message).expect("write file");
+        let mut index = repo.index().expect("repo index");
+        index.add_path(Path::new(name)).expect("add path");
+        index.write().expect("write index");
+        let tree_id = index.write_tree().expect("write tree");
+        let tree = repo.find_tree(tree_id).expect("find tree");
+        let sig = git2::Signature::now("Test", "test@example.com").expect("signature");
+        let parents = repo
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_commit().ok())
+            .into_iter()
+            .collect::<Vec<_>>();
+        let parent_refs = parents.iter().collect::<Vec<_>>();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parent_refs)
+            .expect("commit")
+    }
+
+    #[test]
+    fn collect_tags_reports_lightweight_and_annotated_tags() {
+        let (root, repo) = create_temp_repo();
+        let first = commit_file(&repo, &root, "a.txt", "first");
+        let second = commit_file(&repo, &root, "b.txt", "second");
+        let first_commit = repo.find_commit(first).expect("first commit");
+        let second_commit = repo.find_commit(second).expect("second commit");
+        repo.tag_lightweight("v0.1.0", first_commit.as_object(), false)
+            .expect("lightweight tag");
+        let sig = git2::Signature::now("Tagger", "tagger@example.com").expect("signature");
+        repo.tag(
+            "v0.2.0",
+            second_commit.as_object(),
+            &sig,
+            "Release 0.2.0",
+            false,
+        )
+        .expect("annotated tag");
+
+        let tags = collect_tags(&repo).expect("collect tags");
+        let lightweight = tags
+            .iter()
+            .find(|tag| tag.name == "v0.1.0")
+            .expect("lightweight tag listed");
+        assert!(!lightweight.annotated);
+        assert_eq!(lightweight.target_sha, first.to_string());
+        let annotated = tags
+            .iter()
+            .find(|tag| tag.name == "v0.2.0")
+            .expect("annotated tag listed");
+        assert!(annotated.annotated);
+        assert_eq!(annotated.target_sha, second.to_string());
+        assert_eq!(annotated.message.as_deref(), Some("Release 0.2.0"));
+        assert_eq!(annotated.tagger.as_deref(), Some("Tagger"));
+    }
+
+    #[test]
+    fn release_notes_prompt_skips_merge_commits() {
+        let commit = |sha: &str, summary: &str, parents: usize| GitHistoryCommit {
+            sha: sha.to_string(),
+            short_sha: sha.to_string(),
+            summary: summary.to_string(),
+            message: summary.to_string(),
+            author: "tester".to_string(),
+            author_email: "tester@example.com".to_string(),
+            timestamp: 0,
+            parents: (0..parents).map(|index| format!("p{index}")).collect(),
+            refs: Vec::new(),
+        };
+        let prompt = build_release_notes_prompt(
+            "v1.0.0",
+            "HEAD",
+            &[
+                commit("aaa1111", "feat: add tags", 1),
+                commit("bbb2222", "Merge branch 'feat/tags'", 2),
+            ],
+            false,
+        );
+        assert!(prompt.contains("`v1.0.0` and `HEAD`"));
+        assert!(prompt.contains("- aaa1111 feat: add tags (tester)"));
+        assert!(!prompt.contains("bbb2222"));
+        assert!(!prompt.contains("older changes"));
+
+        let capped = build_release_notes_prompt(
+            "v1.0.0",
+            "HEAD",
+            &[commit("aaa1111", "feat: add tags", 1)],
+            true,
+        );
+        assert!(capped.contains("older changes are not included"));
+    }
+}
@@ -9,3 +9,15 @@ pub(crate) fn validate_local_branch_name(name: &str) -> Result<String, String> {
     }
     Ok(trimmed.to_string())
 }
+
+pub(crate) fn validate_tag_name(name: &str) -> Result<String, String> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err("Tag name cannot be empty.".to_string());
+    }
+    let full_ref = format!("refs/tags/{trimmed}");
+    if !git2::Reference::is_valid_name(&full_ref) {
+        return Err(format!("Invalid tag name: {trimmed}"));
+    }
+    Ok(trimmed.to_string())
+}
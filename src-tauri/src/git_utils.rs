@@ -102,33 +102,6 @@ mod tests {
     }
 }
 
-pub(crate) fn parse_github_repo(remote_url: &str) -> Option<String> {
-    let trimmed = remote_url.trim();
-    if trimmed.is_empty() {
-        return None;
-    }
-    let mut path = if trimmed.starts_with("git@github.com:") {
-        trimmed.trim_start_matches("git@github.com:").to_string()
-    } else if trimmed.starts_with("ssh://git@github.com/") {
-        trimmed
-            .trim_start_matches("ssh://git@github.com/")
-            .to_string()
-    } else if let Some(index) = trimmed.find("github.com/") {
-        trimmed[index + "github.com/".len()..].to_string()
-    } else {
-        return None;
-    };
-    path = path
-        .trim_end_matches(".git")
-        .trim_end_matches('/')
-        .to_string();
-    if path.is_empty() {
-        None
-    } else {
-        Some(path)
-    }
-}
-
 pub(crate) fn resolve_git_root(entry: &WorkspaceEntry) -> Result<PathBuf, String> {
     let base = PathBuf::from(&entry.path);
     let root = entry
@@ -31,17 +31,21 @@ mod git;
 mod git_utils;
 mod input_history;
 mod local_usage;
+mod lsp;
 mod menu;
 mod project_memory;
 mod prompts;
 mod remote_backend;
 mod rules;
+mod session_idle;
 mod settings;
 mod shared;
 mod skills;
 mod state;
 mod storage;
 mod terminal;
+#[cfg(test)]
+mod test_support;
 mod types;
 mod utils;
 mod vendors;
@@ -141,6 +145,14 @@ pub fn run() {
             // Suppress unused variable warning on non-Windows
             let _ = &window;
 
+            let prune_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                workspaces::prune_worktrees_on_startup(prune_handle).await;
+            });
+            session_idle::spawn_idle_session_reaper(app.handle().clone());
+            lsp::listen_for_turn_diagnostics(app.handle());
+            files::history::listen_for_agent_writes(app.handle());
+
             Ok(())
         });
 
@@ -170,8 +182,24 @@ pub fn run() {
             // Files
             files::file_read,
             files::file_write,
+            files::history::file_history_list,
+            files::history::file_history_diff,
+            files::history::file_history_restore,
             code_intel::code_intel_definition,
             code_intel::code_intel_references,
+            code_intel::code_intel_workspace_symbols,
+            code_intel::code_intel_document_symbols,
+            code_intel::code_intel_rename,
+            code_intel::code_intel_apply_edit,
+            code_intel::code_intel_undo_edit,
+            lsp::lsp_list_servers,
+            lsp::lsp_stop_servers,
+            lsp::lsp_diagnostics,
+            lsp::lsp_hover,
+            lsp::lsp_definition,
+            lsp::lsp_references,
+            lsp::lsp_rename,
+            lsp::lsp_code_actions,
             // Menu
             menu::menu_set_accelerators,
             menu::menu_update_labels,
@@ -254,10 +282,28 @@ pub fn run() {
             workspaces::rename_worktree,
             workspaces::rename_worktree_upstream,
             workspaces::apply_worktree_changes,
+            workspaces::preview_worktree_changes,
+            workspaces::apply_worktree_change_selection,
+            workspaces::list_worktree_apply_records,
+            workspaces::list_worktree_lifecycle,
+            workspaces::archive_worktree,
+            workspaces::list_worktree_archives,
+            workspaces::restore_worktree_archive,
+            workspaces::prune_worktrees,
+            workspaces::start_fanout_run,
+            workspaces::get_fanout_run,
+            workspaces::list_fanout_runs,
+            workspaces::apply_fanout_winner,
             workspaces::update_workspace_settings,
             workspaces::update_workspace_codex_bin,
             workspaces::connect_workspace,
             workspaces::list_workspace_files,
+            workspaces::watch_workspace_files,
+            workspaces::unwatch_workspace_files,
+            workspaces::search_workspace_content,
+            workspaces::cancel_workspace_search,
+            workspaces::preview_workspace_replace,
+            workspaces::apply_workspace_replace,
             workspaces::list_external_spec_tree,
             workspaces::read_workspace_file,
             workspaces::read_external_spec_file,
@@ -272,10 +318,15 @@ pub fn run() {
             // Git
             git::get_git_status,
             git::list_git_roots,
+            git::get_git_submodules,
+            git::update_git_submodules,
+            git::sync_git_submodules,
             git::get_git_diffs,
             git::get_git_file_full_diff,
             git::get_git_log,
             git::get_git_commit_history,
+            git::get_git_commit_graph,
+            git::search_git_history,
             git::get_git_commit_details,
             git::get_git_push_preview,
             git::resolve_git_commit_ref,
@@ -303,20 +354,37 @@ pub fn run() {
             git::get_github_pull_requests,
             git::get_github_pull_request_diff,
             git::get_github_pull_request_comments,
+            git::post_github_pull_request_comment,
+            git::post_github_pull_request_inline_comment,
+            git::submit_github_pull_request_review,
+            git::review_pull_request,
             git::list_git_branches,
             git::checkout_git_branch,
             git::create_git_branch,
             git::create_git_branch_from_branch,
             git::create_git_branch_from_commit,
             git::delete_git_branch,
+            git::classify_git_branches,
+            git::delete_git_branches,
             git::rename_git_branch,
             git::merge_git_branch,
             git::rebase_git_branch,
+            git::get_git_rebase_plan,
+            git::execute_git_rebase_plan,
+            git::continue_git_rebase,
+            git::abort_git_rebase,
+            git::run_git_bisect,
             git::get_git_branch_compare_commits,
             git::get_git_branch_diff_between_branches,
             git::get_git_branch_file_diff_between_branches,
             git::get_git_worktree_diff_against_branch,
             git::get_git_worktree_file_diff_against_branch,
+            git::get_git_forge_info,
+            git::list_git_tags,
+            git::create_git_tag,
+            git::delete_git_tag,
+            git::push_git_tag,
+            git::generate_release_notes,
             // Prompts
             claude_commands::claude_commands_list,
             prompts::prompts_list,
@@ -0,0 +1,722 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader,
+};
+use tokio::process::Child;
+use tokio::sync::{mpsc, oneshot, Mutex};
+
+use super::config::LspServerSpec;
+
+const INITIALIZE_TIMEOUT: Duration = Duration::from_secs(60);
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+type PendingRequests = Arc<StdMutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>;
+type DiagnosticsHandler = Arc<dyn Fn(&str, &[Value]) + Send + Sync>;
+
+/// Latest `publishDiagnostics` payload for a document. `generation` bumps on
+/// every publish so callers can wait for a fresh one.
+#[derive(Debug, Clone, Default)]
+struct PublishedDiagnostics {
+    generation: u64,
+    items: Vec<Value>,
+}
+
+struct OpenDocument {
+    version: i32,
+    text: String,
+}
+
+/// One language server process speaking JSON-RPC over stdio.
+pub(crate) struct LspClient {
+    spec: LspServerSpec,
+    root: PathBuf,
+    /// Messages for the writer task, which owns the server's stdin.
+    outgoing: mpsc::UnboundedSender<Value>,
+    child: Mutex<Option<Child>>,
+    next_id: AtomicI64,
+    pending: PendingRequests,
+    documents: Mutex<HashMap<String, OpenDocument>>,
+    diagnostics: Arc<StdMutex<HashMap<String, PublishedDiagnostics>>>,
+    publish_count: Arc<AtomicU64>,
+    capabilities: StdMutex<Value>,
+    alive: Arc<AtomicBool>,
+}
+
+/// Frames a JSON-RPC message with the `Content-Length` header LSP expects.
+pub(super) fn encode_message(message: &Value) -> Vec<u8> {
+    let body = message.to_string();
+    let mut framed = format!("Content-Length: {}\r\n\r\n", body.len()).into_bytes();
+    framed.extend_from_slice(body.as_bytes());
+    framed
+}
+
+/// Reads one framed message. `Ok(None)` means the stream closed cleanly.
+pub(super) async fn read_message<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+) -> std::io::Result<Option<Value>> {
+    let mut content_length: Option<usize> = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().ok();
+            }
+        }
+    }
+    let length = content_length.unwrap_or_default();
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body).await?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+}
+
+async fn write_message<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    message: &Value,
+) -> Result<(), String> {
+    writer
+        .write_all(&encode_message(message))
+        .await
+        .map_err(|err| format!("Failed to write to language server: {err}"))?;
+    writer
+        .flush()
+        .await
+        .map_err(|err| format!("Failed to write to language server: {err}"))
+}
+
+/// Default answer for requests the server sends us. We do not implement any
+/// of them, but servers stall if they never get a reply.
+fn reply_to_server_request(method: &str, params: &Value) -> Value {
+    match method {
+        "workspace/configuration" => {
+            let count = params
+                .get("items")
+                .and_then(Value::as_array)
+                .map(Vec::len)
+                .unwrap_or(0);
+            Value::Array(vec![Value::Null; count])
+        }
+        "workspace/workspaceFolders" => Value::Null,
+        "workspace/applyEdit" => json!({ "applied": false }),
+        _ => Value::Null,
+    }
+}
+
+fn fail_pending(pending: &PendingRequests) {
+    if let Ok(mut pending) = pending.lock() {
+        for (_, sender) in pending.drain() {
+            let _ = sender.send(Err("Language server exited".to_string()));
+        }
+    }
+}
+
+pub(crate) fn path_to_uri(path: &Path) -> String {
+    url::Url::from_file_path(path)
+        .map(|uri| uri.to_string())
+        .unwrap_or_else(|_| format!("file://{}", path.to_string_lossy().replace('\\', "/")))
+}
+
+pub(crate) fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    url::Url::parse(uri).ok()?.to_file_path().ok()
+}
+
+/// Units a server counts `character` offsets in, per the negotiated
+/// `positionEncoding`. Servers that do not say use UTF-16.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PositionEncoding {
+    Utf8,
+    Utf16,
+    Utf32,
+}
+
+impl PositionEncoding {
+    fn width(self, ch: char) -> u32 {
+        match self {
+            Self::Utf8 => ch.len_utf8() as u32,
+            Self::Utf16 => ch.len_utf16() as u32,
+            Self::Utf32 => 1,
+        }
+    }
+
+    /// Offset in these units of the first `chars` characters of `line`.
+    pub(crate) fn units_for_chars(self, line: &str, chars: u32) -> u32 {
+        line.chars()
+            .take(chars as usize)
+            .map(|ch| self.width(ch))
+            .sum()
+    }
+
+    /// Characters of `line` before an offset in these units, clamped to the
+    /// end of the line.
+    pub(crate) fn chars_for_units(self, line: &str, units: u32) -> u32 {
+        let mut consumed = 0u32;
+        let mut chars = 0u32;
+        for ch in line.chars() {
+            if consumed >= units {
+                break;
+            }
+            consumed += self.width(ch);
+            chars += 1;
+        }
+        chars
+    }
+}
+
+impl LspClient {
+    /// Spawns the server and completes the `initialize` handshake.
+    pub(super) async fn start(
+        spec: LspServerSpec,
+        binary: &Path,
+        root: &Path,
+        on_diagnostics: DiagnosticsHandler,
+    ) -> Result<Arc<Self>, String> {
+        let mut command =
+            crate::backend::app_server::build_command_for_binary(&binary.to_string_lossy());
+        command
+            .args(&spec.args)
+            .current_dir(root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(path) = crate::backend::app_server::build_codex_path_env(None) {
+            command.env("PATH", path);
+        }
+        let mut child = command
+            .spawn()
+            .map_err(|err| format!("Failed to start {}: {err}", spec.command))?;
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| "Language server stdin unavailable".to_string())?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| "Language server stdout unavailable".to_string())?;
+        if let Some(stderr) = child.stderr.take() {
+            let server_id = spec.id.clone();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    log::debug!("[lsp:{server_id}] {line}");
+                }
+            });
+        }
+
+        let client = Self::connect(spec, root, Some(child), stdout, stdin, on_diagnostics);
+        client.initialize().await?;
+        Ok(client)
+    }
+
+    /// A client speaking to a server over `reader` and `writer`, before the
+    /// `initialize` handshake.
+    fn connect<R, W>(
+        spec: LspServerSpec,
+        root: &Path,
+        child: Option<Child>,
+        reader: R,
+        writer: W,
+        on_diagnostics: DiagnosticsHandler,
+    ) -> Arc<Self>
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let (outgoing, queue) = mpsc::unbounded_channel();
+        let client = Arc::new(Self {
+            spec,
+            root: root.to_path_buf(),
+            outgoing,
+            child: Mutex::new(child),
+            next_id: AtomicI64::new(1),
+            pending: Arc::new(StdMutex::new(HashMap::new())),
+            documents: Mutex::new(HashMap::new()),
+            diagnostics: Arc::new(StdMutex::new(HashMap::new())),
+            publish_count: Arc::new(AtomicU64::new(0)),
+            capabilities: StdMutex::new(Value::Null),
+            alive: Arc::new(AtomicBool::new(true)),
+        });
+        client.spawn_writer(writer, queue);
+        client.spawn_reader(reader, on_diagnostics);
+        client
+    }
+
+    async fn initialize(&self) -> Result<(), String> {
+        let root = self.root.as_path();
+        let root_uri = path_to_uri(root);
+        let root_name = root
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "workspace".to_string());
+        let result = self
+            .request_with_timeout(
+                "initialize",
+                json!({
+                    "processId": std::process::id(),
+                    "rootUri": root_uri,
+                    "rootPath": root.to_string_lossy(),
+                    "workspaceFolders": [{ "uri": root_uri, "name": root_name }],
+                    "initializationOptions": self.spec.initialization_options,
+                    "capabilities": client_capabilities(),
+                }),
+                INITIALIZE_TIMEOUT,
+            )
+            .await?;
+        if let Ok(mut capabilities) = self.capabilities.lock() {
+            *capabilities = result.get("capabilities").cloned().unwrap_or(Value::Null);
+        }
+        self.notify("initialized", json!({})).await
+    }
+
+    /// Writes queued messages in order. The reader answers server requests
+    /// through the same queue, so it never waits on a write that is itself
+    /// stuck behind a server busy writing to us.
+    fn spawn_writer<W>(&self, mut writer: W, mut queue: mpsc::UnboundedReceiver<Value>)
+    where
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let pending = Arc::clone(&self.pending);
+        let alive = Arc::clone(&self.alive);
+        let server_id = self.spec.id.clone();
+        tokio::spawn(async move {
+            while let Some(message) = queue.recv().await {
+                if let Err(err) = write_message(&mut writer, &message).await {
+                    log::warn!("[lsp:{server_id}] {err}");
+                    alive.store(false, Ordering::Release);
+                    fail_pending(&pending);
+                    break;
+                }
+            }
+        });
+    }
+
+    fn spawn_reader<R>(&self, reader: R, on_diagnostics: DiagnosticsHandler)
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        let outgoing = self.outgoing.clone();
+        let pending = Arc::clone(&self.pending);
+        let diagnostics = Arc::clone(&self.diagnostics);
+        let publish_count = Arc::clone(&self.publish_count);
+        let alive = Arc::clone(&self.alive);
+        let server_id = self.spec.id.clone();
+        tokio::spawn(async move {
+            let mut reader = BufReader::new(reader);
+            loop {
+                let message = match read_message(&mut reader).await {
+                    Ok(Some(message)) => message,
+                    Ok(None) => break,
+                    Err(err) => {
+                        log::warn!("[lsp:{server_id}] unreadable message: {err}");
+                        break;
+                    }
+                };
+                let method = message.get("method").and_then(Value::as_str);
+                let id = message.get("id").cloned();
+                match (method, id) {
+                    (Some(method), Some(id)) => {
+                        let params = message.get("params").cloned().unwrap_or(Value::Null);
+                        let reply = json!({
+                            "jsonrpc": "2.0",
+                            "id": id,
+                            "result": reply_to_server_request(method, &params),
+                        });
+                        let _ = outgoing.send(reply);
+                    }
+                    (Some("textDocument/publishDiagnostics"), None) => {
+                        let Some(params) = message.get("params") else {
+                            continue;
+                        };
+                        let Some(uri) = params.get("uri").and_then(Value::as_str) else {
+                            continue;
+                        };
+                        let items = params
+                            .get("diagnostics")
+                            .and_then(Value::as_array)
+                            .cloned()
+                            .unwrap_or_default();
+                        let generation = publish_count.fetch_add(1, Ordering::AcqRel) + 1;
+                        on_diagnostics(uri, &items);
+                        if let Ok(mut diagnostics) = diagnostics.lock() {
+                            diagnostics.insert(
+                                uri.to_string(),
+                                PublishedDiagnostics { generation, items },
+                            );
+                        }
+                    }
+                    (Some(_), None) => {}
+                    (None, Some(id)) => {
+                        let Some(id) = id.as_i64() else {
+                            continue;
+                        };
+                        let sender = pending
+                            .lock()
+                            .ok()
+                            .and_then(|mut pending| pending.remove(&id));
+                        if let Some(sender) = sender {
+                            let result = match message.get("error") {
+                                Some(error) => Err(error
+                                    .get("message")
+                                    .and_then(Value::as_str)
+                                    .unwrap_or("Language server request failed")
+                                    .to_string()),
+                                None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+                            };
+                            let _ = sender.send(result);
+                        }
+                    }
+                    (None, None) => {}
+                }
+            }
+            alive.store(false, Ordering::Release);
+            fail_pending(&pending);
+            log::info!("[lsp:{server_id}] server exited");
+        });
+    }
+
+    pub(crate) fn server_id(&self) -> &str {
+        &self.spec.id
+    }
+
+    pub(crate) fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub(crate) fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn capabilities(&self) -> Value {
+        self.capabilities
+            .lock()
+            .map(|capabilities| capabilities.clone())
+            .unwrap_or(Value::Null)
+    }
+
+    pub(crate) fn position_encoding(&self) -> PositionEncoding {
+        match self
+            .capabilities()
+            .get("positionEncoding")
+            .and_then(Value::as_str)
+        {
+            Some("utf-8") => PositionEncoding::Utf8,
+            Some("utf-32") => PositionEncoding::Utf32,
+            _ => PositionEncoding::Utf16,
+        }
+    }
+
+    fn send(&self, message: Value) -> Result<(), String> {
+        self.outgoing
+            .send(message)
+            .map_err(|_| format!("{} is not running", self.spec.command))
+    }
+
+    pub(crate) async fn notify(&self, method: &str, params: Value) -> Result<(), String> {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+    }
+
+    pub(crate) async fn request_with_timeout(
+        &self,
+        method: &str,
+        params: Value,
+        timeout: Duration,
+    ) -> Result<Value, String> {
+        if !self.is_alive() {
+            return Err(format!("{} is not running", self.spec.command));
+        }
+        let id = self.next_id.fetch_add(1, Ordering::AcqRel);
+        let (sender, receiver) = oneshot::channel();
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(id, sender);
+        }
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        if let Err(err) = self.send(message) {
+            if let Ok(mut pending) = self.pending.lock() {
+                pending.remove(&id);
+            }
+            return Err(err);
+        }
+        match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err("Language server exited".to_string()),
+            Err(_) => {
+                if let Ok(mut pending) = self.pending.lock() {
+                    pending.remove(&id);
+                }
+                let _ = self.notify("$/cancelRequest", json!({ "id": id })).await;
+                Err(format!(
+                    "{method} timed out after {}ms",
+                    timeout.as_millis()
+                ))
+            }
+        }
+    }
+
+    /// Opens the document, or sends its full text again when it changed
+    /// since the last sync. Returns true when the server saw new content.
+    pub(crate) async fn sync_document(
+        &self,
+        path: &Path,
+        language_id: &str,
+        text: String,
+    ) -> Result<bool, String> {
+        let uri = path_to_uri(path);
+        let mut documents = self.documents.lock().await;
+        match documents.get_mut(&uri) {
+            Some(document) if document.text == text => Ok(false),
+            Some(document) => {
+                document.version += 1;
+                document.text = text.clone();
+                let version = document.version;
+                drop(documents);
+                self.notify(
+                    "textDocument/didChange",
+                    json!({
+                        "textDocument": { "uri": uri, "version": version },
+                        "contentChanges": [{ "text": text }],
+                    }),
+                )
+                .await?;
+                Ok(true)
+            }
+            None => {
+                documents.insert(
+                    uri.clone(),
+                    OpenDocument {
+                        version: 1,
+                        text: text.clone(),
+                    },
+                );
+                drop(documents);
+                self.notify(
+                    "textDocument/didOpen",
+                    json!({
+                        "textDocument": {
+                            "uri": uri,
+                            "languageId": language_id,
+                            "version": 1,
+                            "text": text,
+                        },
+                    }),
+                )
+                .await?;
+                Ok(true)
+            }
+        }
+    }
+
+    pub(crate) fn publish_generation(&self) -> u64 {
+        self.publish_count.load(Ordering::Acquire)
+    }
+
+    /// Pushed diagnostics for `uri` if a publish newer than `after` arrived.
+    pub(crate) fn published_diagnostics(&self, uri: &str, after: u64) -> Option<Vec<Value>> {
+        let diagnostics = self.diagnostics.lock().ok()?;
+        diagnostics
+            .get(uri)
+            .filter(|published| published.generation > after)
+            .map(|published| published.items.clone())
+    }
+
+    pub(crate) fn latest_diagnostics(&self, uri: &str) -> Vec<Value> {
+        self.diagnostics
+            .lock()
+            .ok()
+            .and_then(|diagnostics| {
+                diagnostics
+                    .get(uri)
+                    .map(|published| published.items.clone())
+            })
+            .unwrap_or_default()
+    }
+
+    /// Polite shutdown, falling back to killing the process.
+    pub(crate) async fn shutdown(&self) {
+        if self.is_alive()
+            && self
+                .request_with_timeout("shutdown", Value::Null, SHUTDOWN_TIMEOUT)
+                .await
+                .is_ok()
+        {
+            let _ = self.notify("exit", Value::Null).await;
+        }
+        if let Some(child) = self.child.lock().await.as_mut() {
+            if tokio::time::timeout(SHUTDOWN_TIMEOUT, child.wait())
+                .await
+                .is_err()
+            {
+                let _ = child.kill().await;
+            }
+        }
+        self.alive.store(false, Ordering::Release);
+    }
+}
+
+fn client_capabilities() -> Value {
+    json!({
+        "general": { "positionEncodings": ["utf-32", "utf-16"] },
+        "workspace": {
+            "configuration": true,
+            "workspaceFolders": true,
+            "applyEdit": false,
+            "workspaceEdit": { "documentChanges": true },
+        },
+        "textDocument": {
+            "synchronization": { "didSave": false, "dynamicRegistration": false },
+            "hover": { "contentFormat": ["markdown", "plaintext"] },
+            "definition": { "linkSupport": true },
+            "references": {},
+            "rename": { "prepareSupport": false },
+            "publishDiagnostics": { "relatedInformation": true, "versionSupport": true },
+            "diagnostic": { "dynamicRegistration": false },
+            "codeAction": {
+                "codeActionLiteralSupport": {
+                    "codeActionKind": {
+                        "valueSet": [
+                            "quickfix",
+                            "refactor",
+                            "refactor.extract",
+                            "refactor.inline",
+                            "refactor.rewrite",
+                            "source",
+                            "source.organizeImports",
+                        ],
+                    },
+                },
+                "resolveSupport": { "properties": ["edit"] },
+            },
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn framed_messages_round_trip() {
+        let first = json!({ "jsonrpc": "2.0", "id": 1, "result": { "ok": true } });
+        let second = json!({ "jsonrpc": "2.0", "method": "window/logMessage", "params": { "message": "héllo" } });
+        let mut stream = encode_message(&first);
+        stream.extend(b"Content-Type: application/vscode-jsonrpc; charset=utf-8\r\n".iter());
+        stream.extend(encode_message(&second));
+
+        let mut reader = BufReader::new(stream.as_slice());
+        assert_eq!(read_message(&mut reader).await.unwrap(), Some(first));
+        assert_eq!(read_message(&mut reader).await.unwrap(), Some(second));
+        assert_eq!(read_message(&mut reader).await.unwrap(), None);
+    }
+
+    #[test]
+    fn server_requests_get_shaped_replies() {
+        let params = json!({ "items": [{ "section": "rust-analyzer" }, { "section": "files" }] });
+        assert_eq!(
+            reply_to_server_request("workspace/configuration", &params),
+            json!([null, null])
+        );
+        assert_eq!(
+            reply_to_server_request("window/workDoneProgress/create", &json!({})),
+            Value::Null
+        );
+    }
+
+    #[tokio::test]
+    async fn talks_to_a_stdio_server_and_answers_its_requests() {
+        // A small pipe, so neither side can buffer its way out of waiting.
+        let (client_io, server_io) = tokio::io::duplex(64);
+        let (client_read, client_write) = tokio::io::split(client_io);
+        let (server_read, mut server_write) = tokio::io::split(server_io);
+        let server = tokio::spawn(async move {
+            let mut reader = BufReader::new(server_read);
+            let initialize = read_message(&mut reader).await.unwrap().unwrap();
+            assert_eq!(initialize["method"], "initialize");
+            let capabilities = json!({ "capabilities": { "positionEncoding": "utf-32" } });
+            write_message(
+                &mut server_write,
+                &json!({ "jsonrpc": "2.0", "id": initialize["id"], "result": capabilities }),
+            )
+            .await
+            .unwrap();
+            let initialized = read_message(&mut reader).await.unwrap().unwrap();
+            assert_eq!(initialized["method"], "initialized");
+
+            // Ask the client for configuration before answering its request.
+            let hover = read_message(&mut reader).await.unwrap().unwrap();
+            write_message(
+                &mut server_write,
+                &json!({
+                    "jsonrpc": "2.0",
+                    "id": "config-1",
+                    "method": "workspace/configuration",
+                    "params": { "items": [{ "section": "fake" }] },
+                }),
+            )
+            .await
+            .unwrap();
+            let reply = read_message(&mut reader).await.unwrap().unwrap();
+            write_message(
+                &mut server_write,
+                &json!({ "jsonrpc": "2.0", "id": hover["id"], "result": { "contents": "docs" } }),
+            )
+            .await
+            .unwrap();
+            reply
+        });
+
+        let spec = LspServerSpec {
+            id: "fake".to_string(),
+            command: "fake-language-server".to_string(),
+            args: Vec::new(),
+            extensions: vec!["rs".to_string()],
+            initialization_options: None,
+        };
+        let client = LspClient::connect(
+            spec,
+            &std::env::temp_dir(),
+            None,
+            client_read,
+            client_write,
+            Arc::new(|_, _| {}),
+        );
+        client.initialize().await.unwrap();
+        assert_eq!(client.position_encoding(), PositionEncoding::Utf32);
+
+        let hover = client
+            .request_with_timeout("textDocument/hover", json!({}), Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(hover, json!({ "contents": "docs" }));
+        assert_eq!(
+            server.await.unwrap(),
+            json!({ "jsonrpc": "2.0", "id": "config-1", "result": [null] })
+        );
+    }
+
+    #[test]
+    fn file_uris_round_trip_with_escaping() {
+        let path = std::env::temp_dir().join("moss lsp/src/main.rs");
+        let uri = path_to_uri(&path);
+        assert!(uri.starts_with("file://"));
+        assert!(uri.contains("moss%20lsp"));
+        assert_eq!(uri_to_path(&uri), Some(path));
+    }
+}
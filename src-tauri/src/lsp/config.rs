@@ -0,0 +1,178 @@
+use std::ffi::OsStr;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::types::LspServerConfig;
+
+/// A language server MossX knows how to launch.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LspServerSpec {
+    pub(crate) id: String,
+    pub(crate) command: String,
+    pub(crate) args: Vec<String>,
+    /// Lowercase file extensions, without the dot.
+    pub(crate) extensions: Vec<String>,
+    pub(crate) initialization_options: Option<Value>,
+}
+
+impl LspServerSpec {
+    fn builtin(id: &str, command: &str, args: &[&str], extensions: &[&str]) -> Self {
+        Self {
+            id: id.to_string(),
+            command: command.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            extensions: extensions.iter().map(|ext| ext.to_string()).collect(),
+            initialization_options: None,
+        }
+    }
+
+    pub(crate) fn handles(&self, path: &Path) -> bool {
+        file_extension(path).is_some_and(|ext| self.extensions.contains(&ext))
+    }
+}
+
+fn builtin_servers() -> Vec<LspServerSpec> {
+    vec![
+        LspServerSpec::builtin("rust-analyzer", "rust-analyzer", &[], &["rs"]),
+        LspServerSpec::builtin(
+            "typescript",
+            "typescript-language-server",
+            &["--stdio"],
+            &["ts", "tsx", "js", "jsx", "mjs", "cjs", "mts", "cts"],
+        ),
+        LspServerSpec::builtin(
+            "pyright",
+            "pyright-langserver",
+            &["--stdio"],
+            &["py", "pyi"],
+        ),
+        LspServerSpec::builtin("gopls", "gopls", &[], &["go"]),
+        LspServerSpec::builtin("jdtls", "jdtls", &[], &["java"]),
+    ]
+}
+
+/// Built-in table with the user's `lspServers` settings applied: entries
+/// matching a built-in id override its fields, `enabled: false` removes it,
+/// and unknown ids add a server when they name a command and extensions.
+pub(crate) fn resolve_servers(overrides: &[LspServerConfig]) -> Vec<LspServerSpec> {
+    let mut servers = builtin_servers();
+    for entry in overrides {
+        let existing = servers.iter().position(|server| server.id == entry.id);
+        if !entry.enabled {
+            if let Some(index) = existing {
+                servers.remove(index);
+            }
+            continue;
+        }
+        let mut server = match existing {
+            Some(index) => servers.remove(index),
+            None => {
+                let command = entry.command.clone().unwrap_or_default();
+                if command.trim().is_empty() || entry.extensions.as_ref().is_none_or(Vec::is_empty)
+                {
+                    continue;
+                }
+                LspServerSpec::builtin(&entry.id, &command, &[], &[])
+            }
+        };
+        if let Some(command) = entry
+            .command
+            .as_ref()
+            .filter(|value| !value.trim().is_empty())
+        {
+            server.command = command.clone();
+        }
+        if let Some(args) = entry.args.as_ref() {
+            server.args = args.clone();
+        }
+        if let Some(extensions) = entry.extensions.as_ref() {
+            server.extensions = extensions
+                .iter()
+                .map(|ext| ext.trim_start_matches('.').to_ascii_lowercase())
+                .collect();
+        }
+        if entry.initialization_options.is_some() {
+            server.initialization_options = entry.initialization_options.clone();
+        }
+        servers.push(server);
+    }
+    servers
+}
+
+fn file_extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(OsStr::to_str)
+        .map(|ext| ext.to_ascii_lowercase())
+}
+
+/// LSP `languageId` for `textDocument/didOpen`.
+pub(crate) fn language_id_for(path: &Path) -> String {
+    let ext = file_extension(path).unwrap_or_default();
+    match ext.as_str() {
+        "rs" => "rust",
+        "ts" | "mts" | "cts" => "typescript",
+        "tsx" => "typescriptreact",
+        "js" | "mjs" | "cjs" => "javascript",
+        "jsx" => "javascriptreact",
+        "py" | "pyi" => "python",
+        "go" => "go",
+        "java" => "java",
+        "kt" | "kts" => "kotlin",
+        "cs" => "csharp",
+        "c" | "h" => "c",
+        "cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx" => "cpp",
+        "rb" => "ruby",
+        other => other,
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(id: &str) -> LspServerConfig {
+        LspServerConfig {
+            id: id.to_string(),
+            enabled: true,
+            command: None,
+            args: None,
+            extensions: None,
+            initialization_options: None,
+        }
+    }
+
+    #[test]
+    fn settings_override_disable_and_extend_the_builtin_table() {
+        let mut gopls = config("gopls");
+        gopls.command = Some("/opt/go/bin/gopls".to_string());
+        let mut jdtls = config("jdtls");
+        jdtls.enabled = false;
+        let mut clangd = config("clangd");
+        clangd.command = Some("clangd".to_string());
+        clangd.extensions = Some(vec![".C".to_string(), "h".to_string()]);
+        let incomplete = config("ruby-lsp");
+
+        let servers = resolve_servers(&[gopls, jdtls, clangd, incomplete]);
+        let ids: Vec<&str> = servers.iter().map(|server| server.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec!["rust-analyzer", "typescript", "pyright", "gopls", "clangd"]
+        );
+        assert_eq!(servers[3].command, "/opt/go/bin/gopls");
+        assert_eq!(servers[3].extensions, vec!["go".to_string()]);
+        assert_eq!(
+            servers[4].extensions,
+            vec!["c".to_string(), "h".to_string()]
+        );
+        assert!(servers[4].handles(Path::new("src/main.C")));
+    }
+
+    #[test]
+    fn language_ids_follow_the_lsp_spec_names() {
+        assert_eq!(language_id_for(Path::new("App.tsx")), "typescriptreact");
+        assert_eq!(language_id_for(Path::new("lib.rs")), "rust");
+        assert_eq!(language_id_for(Path::new("main.hpp")), "cpp");
+    }
+}
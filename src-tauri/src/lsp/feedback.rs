@@ -0,0 +1,748 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Listener, Manager};
+use tokio::sync::oneshot;
+
+use crate::engine::EngineType;
+use crate::remote_backend;
+use crate::state::AppState;
+use crate::types::{LspTurnDiagnosticsEvent, LspTurnDiagnosticsFile};
+
+const TURN_DIAGNOSTICS_EVENT: &str = "lsp-turn-diagnostics";
+const MAX_CHECKED_FILES: usize = 20;
+const MAX_PROMPT_ERRORS: usize = 30;
+const EDIT_ARGUMENT_KEYS: [&str; 4] = ["file_path", "filePath", "path", "notebook_path"];
+const BASELINE_WAIT: Duration = Duration::from_secs(15);
+
+/// What the feedback loop remembers about the current turn of a thread.
+#[derive(Debug, Default)]
+struct TurnTracker {
+    touched: BTreeSet<String>,
+    /// Files whose errors were recorded before their first edit this turn.
+    baselined: HashSet<String>,
+    /// Baselines still being taken; the check waits for them.
+    pending_baselines: Vec<oneshot::Receiver<()>>,
+    /// Error fingerprints of each file before the turn edited it, or after
+    /// the last check.
+    known_errors: HashMap<String, HashSet<String>>,
+    follow_ups: u32,
+    awaiting_follow_up: bool,
+}
+
+/// Trackers keyed by workspace and thread id.
+type Turns = HashMap<(String, String), TurnTracker>;
+
+static TURNS: LazyLock<Mutex<Turns>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Paths an `item/started` or `item/completed` message edits: Codex
+/// `fileChange` items and edit/write tool calls from the other engines.
+pub(crate) fn edited_paths(message: &Value) -> Vec<String> {
+    let Some(item) = message.get("params").and_then(|params| params.get("item")) else {
+        return Vec::new();
+    };
+    if item.get("status").and_then(Value::as_str) == Some("failed") {
+        return Vec::new();
+    }
+    match item.get("type").and_then(Value::as_str) {
+        Some("fileChange") => item
+            .get("changes")
+            .and_then(Value::as_array)
+            .map(|changes| {
+                changes
+                    .iter()
+                    .filter(|change| {
+                        let kind = change.get("kind");
+                        let kind = kind
+                            .and_then(Value::as_str)
+                            .or_else(|| kind?.get("type")?.as_str());
+                        kind != Some("delete")
+                    })
+                    .filter_map(|change| change.get("path")?.as_str())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        Some("mcpToolCall") => {
+            let tool = item
+                .get("tool")
+                .and_then(Value::as_str)
+                .unwrap_or("")
+                .to_ascii_lowercase();
+            if !["edit", "write", "patch"]
+                .iter()
+                .any(|verb| tool.contains(verb))
+            {
+                return Vec::new();
+            }
+            let Some(arguments) = item.get("arguments") else {
+                return Vec::new();
+            };
+            EDIT_ARGUMENT_KEYS
+                .iter()
+                .filter_map(|key| arguments.get(*key)?.as_str())
+                .filter(|path| !path.trim().is_empty())
+                .map(str::to_string)
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Tracker key of a message: its thread, or the workspace's only tracked
+/// thread for messages that carry no thread id.
+fn turn_key(turns: &Turns, workspace_id: &str, message: &Value) -> Option<(String, String)> {
+    let thread_id = message
+        .get("params")
+        .and_then(|params| params.get("threadId"))
+        .and_then(Value::as_str);
+    if let Some(thread_id) = thread_id {
+        return Some((workspace_id.to_string(), thread_id.to_string()));
+    }
+    let mut keys = turns
+        .keys()
+        .filter(|(workspace, _)| workspace == workspace_id);
+    match (keys.next(), keys.next()) {
+        (Some(key), None) => Some(key.clone()),
+        _ => None,
+    }
+}
+
+/// What one `app-server-event` asks of the feedback loop.
+#[derive(Debug, PartialEq)]
+enum TurnEvent {
+    /// Files about to be edited for the first time this turn, whose errors
+    /// should be recorded first.
+    Baseline(String, Vec<String>),
+    /// The turn completed after editing these files.
+    Completed(String, BTreeSet<String>),
+}
+
+/// Folds one `app-server-event` into the trackers.
+fn record_turn_event(turns: &mut Turns, workspace_id: &str, message: &Value) -> Option<TurnEvent> {
+    let method = message.get("method").and_then(Value::as_str)?;
+    let key = turn_key(turns, workspace_id, message)?;
+    match method {
+        "turn/started" => {
+            let tracker = turns.entry(key).or_default();
+            if !tracker.awaiting_follow_up {
+                tracker.follow_ups = 0;
+            }
+            tracker.awaiting_follow_up = false;
+            tracker.touched.clear();
+            tracker.baselined.clear();
+            None
+        }
+        "item/started" => {
+            let paths = edited_paths(message);
+            if paths.is_empty() {
+                return None;
+            }
+            let thread_id = key.1.clone();
+            let tracker = turns.entry(key).or_default();
+            let fresh: Vec<String> = paths
+                .into_iter()
+                .filter(|path| tracker.baselined.insert(path.clone()))
+                .collect();
+            (!fresh.is_empty()).then_some(TurnEvent::Baseline(thread_id, fresh))
+        }
+        "item/completed" => {
+            let paths = edited_paths(message);
+            if !paths.is_empty() {
+                let tracker = turns.entry(key).or_default();
+                tracker.touched.extend(paths);
+            }
+            None
+        }
+        "turn/completed" => {
+            let tracker = turns.get_mut(&key)?;
+            let touched = std::mem::take(&mut tracker.touched);
+            (!touched.is_empty()).then_some(TurnEvent::Completed(key.1, touched))
+        }
+        "turn/error" => {
+            if let Some(tracker) = turns.get_mut(&key) {
+                tracker.touched.clear();
+                tracker.awaiting_follow_up = false;
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+fn is_error(diagnostic: &Value) -> bool {
+    match diagnostic.get("severity") {
+        None | Some(Value::Null) => true,
+        Some(Value::String(severity)) => severity.eq_ignore_ascii_case("error"),
+        Some(severity) => severity.as_u64() == Some(1),
+    }
+}
+
+/// Identifies an error across edits. Ranges are left out because unrelated
+/// edits above an error move it without making it new.
+fn error_fingerprint(diagnostic: &Value) -> String {
+    let field = |key: &str| match diagnostic.get(key) {
+        Some(Value::String(value)) => value.clone(),
+        Some(Value::Null) | None => String::new(),
+        Some(value) => value.to_string(),
+    };
+    format!("{}|{}|{}", field("source"), field("code"), field("message"))
+}
+
+/// Errors in `diagnostics` that were not known before, replacing the
+/// remembered set with the current one. A file with no baseline, because
+/// its server could not be reached before the edit, has every error new.
+fn take_new_errors(known: &mut HashSet<String>, diagnostics: &[Value]) -> Vec<Value> {
+    let errors: Vec<&Value> = diagnostics.iter().filter(|item| is_error(item)).collect();
+    let current: HashSet<String> = errors.iter().map(|item| error_fingerprint(item)).collect();
+    let fresh = errors
+        .into_iter()
+        .filter(|item| !known.contains(&error_fingerprint(item)))
+        .cloned()
+        .collect();
+    *known = current;
+    fresh
+}
+
+/// Diagnostics from `opencode debug lsp diagnostics`, which prints either a
+/// list or an object keyed by file.
+fn opencode_diagnostic_items(result: &Value) -> Vec<Value> {
+    match result {
+        Value::Array(items) => items.clone(),
+        Value::Object(map) => match map.get("diagnostics") {
+            Some(Value::Array(items)) => items.clone(),
+            _ => map
+                .values()
+                .filter_map(Value::as_array)
+                .flatten()
+                .cloned()
+                .collect(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+fn follow_up_prompt(files: &[LspTurnDiagnosticsFile]) -> String {
+    let mut lines = vec![
+        "The language server reports new errors in files you just changed:".to_string(),
+        String::new(),
+    ];
+    let errors = files
+        .iter()
+        .flat_map(|file| file.errors.iter().map(move |error| (file, error)));
+    let total = files.iter().map(|file| file.errors.len()).sum::<usize>();
+    for (file, error) in errors.take(MAX_PROMPT_ERRORS) {
+        let start = error
+            .get("range")
+            .and_then(|range| range.get("start"))
+            .cloned()
+            .unwrap_or(Value::Null);
+        let line = start.get("line").and_then(Value::as_u64).unwrap_or(0) + 1;
+        let column = start.get("character").and_then(Value::as_u64).unwrap_or(0) + 1;
+        let message = error
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .lines()
+            .next()
+            .unwrap_or("");
+        let code = match error.get("code") {
+            Some(Value::String(code)) => format!(" [{code}]"),
+            Some(Value::Number(code)) => format!(" [{code}]"),
+            _ => String::new(),
+        };
+        lines.push(format!(
+            "- {}:{line}:{column}{code} {message} ({})",
+            file.path, file.server_id
+        ));
+    }
+    if total > MAX_PROMPT_ERRORS {
+        lines.push(format!("- …and {} more", total - MAX_PROMPT_ERRORS));
+    }
+    lines.push(String::new());
+    lines.push("Please fix these errors.".to_string());
+    lines.join("\n")
+}
+
+/// Engine and session behind a thread id, as the frontend names them.
+fn follow_up_target(thread_id: &str) -> Option<(EngineType, Option<String>)> {
+    if let Some(session_id) = thread_id.strip_prefix("claude:") {
+        return Some((EngineType::Claude, Some(session_id.to_string())));
+    }
+    if let Some(session_id) = thread_id.strip_prefix("opencode:") {
+        return Some((EngineType::OpenCode, Some(session_id.to_string())));
+    }
+    // Other prefixed engines, and Claude/OpenCode turns that never got a
+    // session id ("claude-pending-…", "claude-turn-…").
+    if thread_id.contains(':') || thread_id.contains("-pending-") || thread_id.contains("-turn-") {
+        return None;
+    }
+    Some((EngineType::Codex, None))
+}
+
+async fn send_follow_up(
+    app: &AppHandle,
+    workspace_id: &str,
+    thread_id: &str,
+    text: String,
+) -> Result<(), String> {
+    let (engine, session_id) =
+        follow_up_target(thread_id).ok_or_else(|| format!("Cannot continue thread {thread_id}"))?;
+    if engine == EngineType::Codex {
+        crate::codex::send_user_message(
+            workspace_id.to_string(),
+            thread_id.to_string(),
+            text,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            app.state(),
+            app.clone(),
+        )
+        .await?;
+        return Ok(());
+    }
+    crate::engine::engine_send_message(
+        workspace_id.to_string(),
+        text,
+        Some(engine),
+        None,
+        None,
+        None,
+        None,
+        true,
+        Some(thread_id.to_string()),
+        session_id,
+        None,
+        None,
+        None,
+        app.clone(),
+        app.state(),
+    )
+    .await?;
+    Ok(())
+}
+
+fn relative_path(path: &str, roots: &[PathBuf]) -> String {
+    let candidate = Path::new(path);
+    roots
+        .iter()
+        .find_map(|root| candidate.strip_prefix(root).ok())
+        .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|| path.to_string())
+}
+
+async fn diagnostics_for(
+    state: &AppState,
+    app: &AppHandle,
+    workspace_id: &str,
+    thread_id: &str,
+    path: &str,
+) -> Option<(String, Vec<Value>)> {
+    match super::file_diagnostics(state, app, workspace_id, path).await {
+        Ok(result) => Some(result),
+        Err(err) if thread_id.starts_with("opencode:") => {
+            log::debug!("[lsp] falling back to OpenCode diagnostics for {path}: {err}");
+            let response = crate::engine::opencode_lsp_diagnostics(
+                workspace_id.to_string(),
+                path.to_string(),
+                app.state(),
+            )
+            .await
+            .ok()?;
+            let items = opencode_diagnostic_items(response.get("result").unwrap_or(&Value::Null));
+            Some(("opencode".to_string(), items))
+        }
+        Err(err) => {
+            log::debug!("[lsp] no diagnostics for {path}: {err}");
+            None
+        }
+    }
+}
+
+async fn workspace_roots(state: &AppState, workspace_id: &str) -> Option<Vec<PathBuf>> {
+    let workspaces = state.workspaces.lock().await;
+    let path = PathBuf::from(&workspaces.get(workspace_id)?.path);
+    let canonical = path.canonicalize().ok();
+    Some(std::iter::once(path).chain(canonical).collect())
+}
+
+/// Records the errors of files before the turn first edits them, so errors
+/// they already had are not reported as new. Files that do not exist yet
+/// start with none.
+async fn take_baseline(
+    app: AppHandle,
+    workspace_id: String,
+    thread_id: String,
+    paths: Vec<String>,
+    done: oneshot::Sender<()>,
+) {
+    let state = app.state::<AppState>();
+    if !state.app_settings.lock().await.lsp_feedback_enabled
+        || remote_backend::is_remote_mode(&state).await
+    {
+        return;
+    }
+    let Some(roots) = workspace_roots(&state, &workspace_id).await else {
+        return;
+    };
+    for path in paths.iter().take(MAX_CHECKED_FILES) {
+        let relative = relative_path(path, &roots);
+        let exists = roots[0].join(&relative).is_file();
+        let diagnostics = if exists {
+            match diagnostics_for(&state, &app, &workspace_id, &thread_id, &relative).await {
+                Some((_, diagnostics)) => diagnostics,
+                None => continue,
+            }
+        } else {
+            Vec::new()
+        };
+        let Ok(mut turns) = TURNS.lock() else {
+            return;
+        };
+        let tracker = turns
+            .entry((workspace_id.clone(), thread_id.clone()))
+            .or_default();
+        let known = diagnostics
+            .iter()
+            .filter(|item| is_error(item))
+            .map(error_fingerprint)
+            .collect();
+        tracker.known_errors.insert(relative, known);
+    }
+    let _ = done.send(());
+}
+
+async fn check_turn(
+    app: AppHandle,
+    workspace_id: String,
+    thread_id: String,
+    touched: BTreeSet<String>,
+) {
+    let key = (workspace_id.clone(), thread_id.clone());
+    let pending = TURNS
+        .lock()
+        .ok()
+        .and_then(|mut turns| {
+            turns
+                .get_mut(&key)
+                .map(|tracker| std::mem::take(&mut tracker.pending_baselines))
+        })
+        .unwrap_or_default();
+    for baseline in pending {
+        let _ = tokio::time::timeout(BASELINE_WAIT, baseline).await;
+    }
+    let state = app.state::<AppState>();
+    let (auto_fix, max_iterations) = {
+        let settings = state.app_settings.lock().await;
+        if !settings.lsp_feedback_enabled {
+            return;
+        }
+        (
+            settings.lsp_feedback_auto_fix,
+            settings.lsp_feedback_max_iterations,
+        )
+    };
+    if remote_backend::is_remote_mode(&state).await {
+        return;
+    }
+    let Some(roots) = workspace_roots(&state, &workspace_id).await else {
+        return;
+    };
+
+    let mut files = Vec::new();
+    for path in touched.iter().take(MAX_CHECKED_FILES) {
+        let relative = relative_path(path, &roots);
+        let Some((server_id, diagnostics)) =
+            diagnostics_for(&state, &app, &workspace_id, &thread_id, &relative).await
+        else {
+            continue;
+        };
+        let errors = {
+            let Ok(mut turns) = TURNS.lock() else {
+                return;
+            };
+            let tracker = turns.entry(key.clone()).or_default();
+            let known = tracker.known_errors.entry(relative.clone()).or_default();
+            take_new_errors(known, &diagnostics)
+        };
+        if !errors.is_empty() {
+            files.push(LspTurnDiagnosticsFile {
+                path: relative,
+                server_id,
+                errors,
+            });
+        }
+    }
+    if files.is_empty() {
+        return;
+    }
+
+    let send = {
+        let Ok(mut turns) = TURNS.lock() else {
+            return;
+        };
+        let tracker = turns.entry(key.clone()).or_default();
+        let send = auto_fix && tracker.follow_ups < max_iterations;
+        if send {
+            tracker.follow_ups += 1;
+            tracker.awaiting_follow_up = true;
+        }
+        send
+    };
+    let mut follow_up_sent = false;
+    if send {
+        match send_follow_up(&app, &workspace_id, &thread_id, follow_up_prompt(&files)).await {
+            Ok(()) => follow_up_sent = true,
+            Err(err) => {
+                log::warn!("[lsp] diagnostics follow-up for {workspace_id} failed: {err}");
+                if let Ok(mut turns) = TURNS.lock() {
+                    if let Some(tracker) = turns.get_mut(&key) {
+                        tracker.awaiting_follow_up = false;
+                    }
+                }
+            }
+        }
+    }
+    let follow_ups = TURNS
+        .lock()
+        .ok()
+        .and_then(|turns| turns.get(&key).map(|tracker| tracker.follow_ups))
+        .unwrap_or_default();
+    let _ = app.emit(
+        TURN_DIAGNOSTICS_EVENT,
+        LspTurnDiagnosticsEvent {
+            workspace_id,
+            thread_id,
+            files,
+            follow_ups,
+            follow_up_sent,
+        },
+    );
+}
+
+/// Drops what the feedback loop tracked for a removed workspace.
+pub(crate) fn forget_workspace(workspace_id: &str) {
+    if let Ok(mut turns) = TURNS.lock() {
+        turns.retain(|(workspace, _), _| workspace != workspace_id);
+    }
+}
+
+/// Checks the files each agent turn edited once it completes, reports new
+/// language server errors as `lsp-turn-diagnostics` and, when
+/// `lspFeedbackAutoFix` is on, asks the same session to fix them, up to
+/// `lspFeedbackMaxIterations` follow-ups in a row.
+pub(crate) fn listen_for_turn_diagnostics(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen("app-server-event", move |event| {
+        let Ok(payload) = serde_json::from_str::<Value>(event.payload()) else {
+            return;
+        };
+        let Some(workspace_id) = payload.get("workspace_id").and_then(Value::as_str) else {
+            return;
+        };
+        let Some(message) = payload.get("message") else {
+            return;
+        };
+        let Ok(mut turns) = TURNS.lock() else {
+            return;
+        };
+        match record_turn_event(&mut turns, workspace_id, message) {
+            Some(TurnEvent::Baseline(thread_id, paths)) => {
+                let (done, finished) = oneshot::channel();
+                let key = (workspace_id.to_string(), thread_id.clone());
+                turns
+                    .entry(key)
+                    .or_default()
+                    .pending_baselines
+                    .push(finished);
+                drop(turns);
+                tauri::async_runtime::spawn(take_baseline(
+                    handle.clone(),
+                    workspace_id.to_string(),
+                    thread_id,
+                    paths,
+                    done,
+                ));
+            }
+            Some(TurnEvent::Completed(thread_id, touched)) => {
+                drop(turns);
+                tauri::async_runtime::spawn(check_turn(
+                    handle.clone(),
+                    workspace_id.to_string(),
+                    thread_id,
+                    touched,
+                ));
+            }
+            None => {}
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn error(message: &str, line: u64) -> Value {
+        json!({
+            "severity": 1,
+            "source": "rustc",
+            "message": message,
+            "range": {
+                "start": { "line": line, "character": 4 },
+                "end": { "line": line, "character": 9 },
+            },
+        })
+    }
+
+    #[test]
+    fn tracks_edited_files_until_the_turn_completes() {
+        let mut turns = HashMap::new();
+        let started = json!({ "method": "turn/started", "params": { "threadId": "t-1" } });
+        let codex_edit = json!({
+            "method": "item/completed",
+            "params": { "item": {
+                "type": "fileChange",
+                "changes": [
+                    { "path": "src/lib.rs", "kind": { "type": "update" } },
+                    { "path": "src/old.rs", "kind": { "type": "delete" } },
+                ],
+            } },
+        });
+        let claude_write = json!({
+            "method": "item/completed",
+            "params": { "item": {
+                "type": "mcpToolCall",
+                "tool": "Write",
+                "arguments": { "file_path": "/repo/src/main.rs" },
+                "status": "completed",
+            } },
+        });
+        let claude_read = json!({
+            "method": "item/completed",
+            "params": { "item": {
+                "type": "mcpToolCall",
+                "tool": "Read",
+                "arguments": { "file_path": "/repo/README.md" },
+            } },
+        });
+        let completed = json!({ "method": "turn/completed", "params": {} });
+
+        assert!(record_turn_event(&mut turns, "ws", &started).is_none());
+        for message in [&codex_edit, &claude_write, &claude_read] {
+            assert!(record_turn_event(&mut turns, "ws", message).is_none());
+        }
+        let Some(TurnEvent::Completed(thread_id, touched)) =
+            record_turn_event(&mut turns, "ws", &completed)
+        else {
+            panic!("turn should complete");
+        };
+        assert_eq!(thread_id, "t-1");
+        assert_eq!(
+            touched.into_iter().collect::<Vec<_>>(),
+            vec!["/repo/src/main.rs".to_string(), "src/lib.rs".to_string()]
+        );
+        assert!(record_turn_event(&mut turns, "ws", &completed).is_none());
+    }
+
+    #[test]
+    fn baselines_first_edits_and_keeps_threads_apart() {
+        let mut turns = HashMap::new();
+        let started =
+            |thread: &str| json!({ "method": "turn/started", "params": { "threadId": thread } });
+        let edit = |thread: &str, path: &str| {
+            json!({
+                "method": "item/started",
+                "params": { "threadId": thread, "item": {
+                    "type": "fileChange",
+                    "changes": [{ "path": path, "kind": "update" }],
+                } },
+            })
+        };
+        record_turn_event(&mut turns, "ws", &started("t-1"));
+        record_turn_event(&mut turns, "ws", &started("t-2"));
+
+        assert_eq!(
+            record_turn_event(&mut turns, "ws", &edit("t-1", "src/lib.rs")),
+            Some(TurnEvent::Baseline(
+                "t-1".to_string(),
+                vec!["src/lib.rs".to_string()]
+            ))
+        );
+        assert!(record_turn_event(&mut turns, "ws", &edit("t-1", "src/lib.rs")).is_none());
+        assert_eq!(
+            record_turn_event(&mut turns, "ws", &edit("t-2", "src/lib.rs")),
+            Some(TurnEvent::Baseline(
+                "t-2".to_string(),
+                vec!["src/lib.rs".to_string()]
+            ))
+        );
+
+        turns
+            .get_mut(&("ws".to_string(), "t-1".to_string()))
+            .unwrap()
+            .follow_ups = 2;
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[&("ws".to_string(), "t-2".to_string())].follow_ups, 0);
+    }
+
+    #[test]
+    fn only_unseen_errors_count_as_new() {
+        let mut known = HashSet::new();
+        let warning = json!({ "severity": 2, "message": "unused variable" });
+        let first = take_new_errors(&mut known, &[error("mismatched types", 3), warning]);
+        assert_eq!(first.len(), 1);
+
+        let moved = take_new_errors(
+            &mut known,
+            &[error("mismatched types", 7), error("cannot find value", 9)],
+        );
+        assert_eq!(moved, vec![error("cannot find value", 9)]);
+
+        assert!(take_new_errors(&mut known, &[]).is_empty());
+        assert_eq!(
+            take_new_errors(&mut known, &[error("mismatched types", 7)]).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn follow_ups_continue_the_thread_they_came_from() {
+        assert_eq!(
+            follow_up_target("claude:abc"),
+            Some((EngineType::Claude, Some("abc".to_string())))
+        );
+        assert_eq!(
+            follow_up_target("opencode:ses_1"),
+            Some((EngineType::OpenCode, Some("ses_1".to_string())))
+        );
+        assert_eq!(
+            follow_up_target("019a-thread"),
+            Some((EngineType::Codex, None))
+        );
+        assert_eq!(follow_up_target("claude-pending-123"), None);
+
+        let prompt = follow_up_prompt(&[LspTurnDiagnosticsFile {
+            path: "src/lib.rs".to_string(),
+            server_id: "rust-analyzer".to_string(),
+            errors: vec![error("mismatched types\nexpected u32", 3)],
+        }]);
+        assert!(prompt.contains("- src/lib.rs:4:5 mismatched types (rust-analyzer)"));
+    }
+
+    #[test]
+    fn reads_opencode_diagnostics_in_either_shape() {
+        let list = json!([error("a", 0)]);
+        let keyed = json!({ "file:///repo/src/lib.rs": [error("a", 0), error("b", 1)] });
+        assert_eq!(opencode_diagnostic_items(&list).len(), 1);
+        assert_eq!(opencode_diagnostic_items(&keyed).len(), 2);
+    }
+}
@@ -0,0 +1,835 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::OnceCell;
+
+use crate::remote_backend;
+use crate::state::AppState;
+use crate::types::LspDiagnosticsEvent;
+
+mod client;
+mod config;
+mod feedback;
+
+use client::path_to_uri;
+pub(crate) use client::{uri_to_path, LspClient, PositionEncoding};
+use config::{language_id_for, resolve_servers, LspServerSpec};
+pub(crate) use feedback::{edited_paths, listen_for_turn_diagnostics};
+
+const LSP_DIAGNOSTICS_EVENT: &str = "lsp-diagnostics";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+/// Code intel answers from its own index when the server is slower than this.
+const CODE_INTEL_TIMEOUT: Duration = Duration::from_secs(3);
+const DIAGNOSTICS_WAIT: Duration = Duration::from_secs(3);
+const DIAGNOSTICS_POLL: Duration = Duration::from_millis(100);
+
+/// A language server slot for one workspace. Concurrent callers share the
+/// same startup; a failed start leaves the slot empty for the next attempt.
+pub(crate) type LspSlot = Arc<OnceCell<Arc<LspClient>>>;
+
+/// A location from a definition or references response, with
+/// `LocationLink`s flattened to their target selection.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LspLocation {
+    pub(crate) uri: String,
+    pub(crate) path: Option<PathBuf>,
+    pub(crate) start: (u32, u32),
+    pub(crate) end: (u32, u32),
+}
+
+fn range_bounds(range: &Value) -> Option<((u32, u32), (u32, u32))> {
+    let point = |key: &str| -> Option<(u32, u32)> {
+        let position = range.get(key)?;
+        Some((
+            position.get("line")?.as_u64()? as u32,
+            position.get("character")?.as_u64()? as u32,
+        ))
+    };
+    Some((point("start")?, point("end")?))
+}
+
+pub(crate) fn locations_from_response(response: &Value) -> Vec<LspLocation> {
+    let items: Vec<&Value> = match response {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(_) => vec![response],
+        _ => Vec::new(),
+    };
+    items
+        .into_iter()
+        .filter_map(|item| {
+            let (uri, range) = match item.get("targetUri") {
+                Some(uri) => (
+                    uri.as_str()?,
+                    item.get("targetSelectionRange")
+                        .or_else(|| item.get("targetRange"))?,
+                ),
+                None => (item.get("uri")?.as_str()?, item.get("range")?),
+            };
+            let (start, end) = range_bounds(range)?;
+            Some(LspLocation {
+                uri: uri.to_string(),
+                path: uri_to_path(uri),
+                start,
+                end,
+            })
+        })
+        .collect()
+}
+
+/// Lines of the files a response points into, read once per response.
+#[derive(Default)]
+struct LineCache {
+    files: HashMap<String, Option<Vec<String>>>,
+}
+
+impl LineCache {
+    fn line(&mut self, uri: &str, line: u32) -> Option<&str> {
+        self.files
+            .entry(uri.to_string())
+            .or_insert_with(|| {
+                let text = std::fs::read_to_string(uri_to_path(uri)?).ok()?;
+                Some(text.split('\n').map(str::to_string).collect())
+            })
+            .as_ref()?
+            .get(line as usize)
+            .map(String::as_str)
+    }
+}
+
+/// Rewrites every `{ line, character }` position in `value` between
+/// characters, which the commands use, and the server's encoding units.
+/// Positions belong to `uri` unless an enclosing object names another
+/// document, as locations and workspace edits do.
+fn convert_columns(
+    value: &mut Value,
+    uri: &str,
+    encoding: PositionEncoding,
+    to_units: bool,
+    lines: &mut LineCache,
+) {
+    match value {
+        Value::Object(map) => {
+            let line = map.get("line").and_then(Value::as_u64);
+            let character = map.get("character").and_then(Value::as_u64);
+            if let (Some(line), Some(character), 2) = (line, character, map.len()) {
+                if let Some(text) = lines.line(uri, line as u32) {
+                    let text = text.trim_end_matches('\r');
+                    let converted = if to_units {
+                        encoding.units_for_chars(text, character as u32)
+                    } else {
+                        encoding.chars_for_units(text, character as u32)
+                    };
+                    map.insert("character".to_string(), json!(converted));
+                }
+                return;
+            }
+            let uri = ["targetUri", "uri"]
+                .iter()
+                .find_map(|key| map.get(*key).and_then(Value::as_str))
+                .or_else(|| map.get("textDocument")?.get("uri")?.as_str())
+                .unwrap_or(uri)
+                .to_string();
+            for (key, child) in map.iter_mut() {
+                match (key.as_str(), child) {
+                    // `WorkspaceEdit.changes` is keyed by document.
+                    ("changes", Value::Object(changes)) => {
+                        for (document, edits) in changes.iter_mut() {
+                            convert_columns(edits, document, encoding, to_units, lines);
+                        }
+                    }
+                    (_, child) => convert_columns(child, &uri, encoding, to_units, lines),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                convert_columns(item, uri, encoding, to_units, lines);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Sends a `textDocument/*` request whose positions count characters, and
+/// returns the response with its positions counting characters too.
+async fn request_in_chars(
+    client: &LspClient,
+    absolute: &Path,
+    method: &str,
+    extra: Value,
+    timeout: Duration,
+) -> Result<Value, String> {
+    let uri = path_to_uri(absolute);
+    let encoding = client.position_encoding();
+    let mut lines = LineCache::default();
+    let mut params = json!({ "textDocument": { "uri": uri } });
+    if let (Some(params), Value::Object(extra)) = (params.as_object_mut(), extra) {
+        params.extend(extra);
+    }
+    if encoding != PositionEncoding::Utf32 {
+        convert_columns(&mut params, &uri, encoding, true, &mut lines);
+    }
+    let mut result = client.request_with_timeout(method, params, timeout).await?;
+    if encoding != PositionEncoding::Utf32 {
+        convert_columns(&mut result, &uri, encoding, false, &mut lines);
+    }
+    Ok(result)
+}
+
+async fn resolve_file(
+    state: &AppState,
+    workspace_id: &str,
+    file_path: &str,
+) -> Result<(PathBuf, PathBuf), String> {
+    let workspace_path = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(workspace_id)
+            .map(|entry| PathBuf::from(&entry.path))
+            .ok_or_else(|| "Workspace not found".to_string())?
+    };
+    let root = workspace_path
+        .canonicalize()
+        .map_err(|err| format!("Failed to resolve workspace root: {err}"))?;
+    let absolute = root
+        .join(file_path)
+        .canonicalize()
+        .map_err(|err| format!("Failed to open file: {err}"))?;
+    if !absolute.starts_with(&root) {
+        return Err("Invalid file path".to_string());
+    }
+    Ok((root, absolute))
+}
+
+async fn server_for(state: &AppState, path: &Path) -> Option<LspServerSpec> {
+    let overrides = state.app_settings.lock().await.lsp_servers.clone();
+    resolve_servers(&overrides)
+        .into_iter()
+        .find(|server| server.handles(path))
+}
+
+fn find_server_binary(spec: &LspServerSpec) -> Option<PathBuf> {
+    let explicit = Path::new(&spec.command).components().count() > 1;
+    crate::backend::app_server::find_cli_binary(
+        &spec.command,
+        explicit.then_some(spec.command.as_str()),
+    )
+}
+
+/// The running server for `path`, without starting one.
+async fn running_client(
+    state: &AppState,
+    workspace_id: &str,
+    path: &Path,
+) -> Option<Arc<LspClient>> {
+    let spec = server_for(state, path).await?;
+    let clients = state.lsp_clients.lock().await;
+    let client = clients
+        .get(&(workspace_id.to_string(), spec.id))?
+        .get()?
+        .clone();
+    client.is_alive().then_some(client)
+}
+
+/// The server for `path`, started on first use.
+async fn client_for(
+    state: &AppState,
+    app: &AppHandle,
+    workspace_id: &str,
+    root: &Path,
+    path: &Path,
+) -> Result<Arc<LspClient>, String> {
+    let spec = server_for(state, path)
+        .await
+        .ok_or_else(|| "No language server is configured for this file type".to_string())?;
+    let key = (workspace_id.to_string(), spec.id.clone());
+    let slot = {
+        let mut clients = state.lsp_clients.lock().await;
+        let stale = clients.get(&key).is_some_and(|slot| {
+            slot.get()
+                .is_some_and(|client| !client.is_alive() || client.root() != root)
+        });
+        if stale {
+            clients.remove(&key);
+        }
+        Arc::clone(clients.entry(key).or_default())
+    };
+
+    let client = slot
+        .get_or_try_init(|| async {
+            let binary = find_server_binary(&spec)
+                .ok_or_else(|| format!("{} was not found on PATH", spec.command))?;
+            let emitter = app.clone();
+            let event_workspace = workspace_id.to_string();
+            let event_root = root.to_path_buf();
+            let event_server = spec.id.clone();
+            let on_diagnostics = Arc::new(move |uri: &str, diagnostics: &[Value]| {
+                let path = uri_to_path(uri).and_then(|path| {
+                    path.strip_prefix(&event_root)
+                        .ok()
+                        .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+                });
+                let _ = emitter.emit(
+                    LSP_DIAGNOSTICS_EVENT,
+                    LspDiagnosticsEvent {
+                        workspace_id: event_workspace.clone(),
+                        server_id: event_server.clone(),
+                        uri: uri.to_string(),
+                        path,
+                        diagnostics: diagnostics.to_vec(),
+                    },
+                );
+            });
+            log::info!("[lsp] starting {} for workspace {workspace_id}", spec.id);
+            LspClient::start(spec.clone(), &binary, root, on_diagnostics).await
+        })
+        .await?;
+    Ok(Arc::clone(client))
+}
+
+async fn sync_file(client: &LspClient, path: &Path) -> Result<bool, String> {
+    let text = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|err| format!("Failed to read file: {err}"))?;
+    client
+        .sync_document(path, &language_id_for(path), text)
+        .await
+}
+
+/// Starts (if needed) the server for a file, syncs the file and sends a
+/// `textDocument/*` request for it. Columns count characters both ways.
+async fn document_request(
+    state: &AppState,
+    app: &AppHandle,
+    workspace_id: &str,
+    file_path: &str,
+    method: &str,
+    extra: Value,
+) -> Result<(Arc<LspClient>, Value), String> {
+    let (root, absolute) = resolve_file(state, workspace_id, file_path).await?;
+    let client = client_for(state, app, workspace_id, &root, &absolute).await?;
+    sync_file(&client, &absolute).await?;
+    let result = request_in_chars(&client, &absolute, method, extra, REQUEST_TIMEOUT).await?;
+    Ok((client, result))
+}
+
+/// Current diagnostics for a file, pulled when the server supports
+/// `textDocument/diagnostic` and otherwise taken from the next publish.
+pub(crate) async fn file_diagnostics(
+    state: &AppState,
+    app: &AppHandle,
+    workspace_id: &str,
+    file_path: &str,
+) -> Result<(String, Vec<Value>), String> {
+    let (root, absolute) = resolve_file(state, workspace_id, file_path).await?;
+    let client = client_for(state, app, workspace_id, &root, &absolute).await?;
+    let uri = path_to_uri(&absolute);
+    let generation = client.publish_generation();
+    let changed = sync_file(&client, &absolute).await?;
+    let server_id = client.server_id().to_string();
+
+    if client
+        .capabilities()
+        .get("diagnosticProvider")
+        .is_some_and(|provider| !provider.is_null())
+    {
+        let report = client
+            .request_with_timeout(
+                "textDocument/diagnostic",
+                json!({ "textDocument": { "uri": uri } }),
+                REQUEST_TIMEOUT,
+            )
+            .await?;
+        let items = report
+            .get("items")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        return Ok((server_id, items));
+    }
+
+    let waited_since = tokio::time::Instant::now();
+    let mut latest = client.latest_diagnostics(&uri);
+    while changed && waited_since.elapsed() < DIAGNOSTICS_WAIT {
+        if let Some(fresh) = client.published_diagnostics(&uri, generation) {
+            latest = fresh;
+            break;
+        }
+        tokio::time::sleep(DIAGNOSTICS_POLL).await;
+    }
+    Ok((server_id, latest))
+}
+
+/// Definition or references from an already running server, for
+/// `code_intel`. Returns `None` when no server is running (one is started in
+/// the background if available), the request fails, or nothing was found.
+pub(crate) async fn code_intel_query(
+    state: &AppState,
+    app: &AppHandle,
+    workspace_id: &str,
+    workspace_root: &Path,
+    file_path: &str,
+    method: &str,
+    extra: Value,
+) -> Option<Vec<LspLocation>> {
+    let absolute = workspace_root.join(file_path);
+    let Some(client) = running_client(state, workspace_id, &absolute).await else {
+        let app = app.clone();
+        let workspace_id = workspace_id.to_string();
+        let root = workspace_root.to_path_buf();
+        tauri::async_runtime::spawn(async move {
+            let state = app.state::<AppState>();
+            if let Err(err) = client_for(&state, &app, &workspace_id, &root, &absolute).await {
+                log::debug!("[lsp] not available for code intel: {err}");
+            }
+        });
+        return None;
+    };
+    sync_file(&client, &absolute).await.ok()?;
+    let response = request_in_chars(&client, &absolute, method, extra, CODE_INTEL_TIMEOUT)
+        .await
+        .map_err(|err| log::debug!("[lsp] {method} failed: {err}"))
+        .ok()?;
+    let locations = locations_from_response(&response);
+    (!locations.is_empty()).then_some(locations)
+}
+
+/// `textDocument/rename` at a code intel position, whose column counts
+/// characters. `Ok(None)` means no language server is available for the
+/// file; otherwise the `WorkspaceEdit` is returned with the encoding its
+/// ranges use.
+pub(crate) async fn rename_edit(
+    state: &AppState,
+    app: &AppHandle,
+    workspace_id: &str,
+    file_path: &str,
+    line: u32,
+    character: u32,
+    new_name: &str,
+) -> Result<Option<(Value, PositionEncoding)>, String> {
+    let (root, absolute) = resolve_file(state, workspace_id, file_path).await?;
+    let client = match client_for(state, app, workspace_id, &root, &absolute).await {
+        Ok(client) => client,
+        Err(err) => {
+            log::debug!("[lsp] rename without a language server: {err}");
+            return Ok(None);
+        }
+    };
+    let text = tokio::fs::read_to_string(&absolute)
+        .await
+        .map_err(|err| format!("Failed to read file: {err}"))?;
+    let encoding = client.position_encoding();
+    let character = text
+        .split('\n')
+        .nth(line as usize)
+        .map(|line_text| encoding.units_for_chars(line_text, character))
+        .unwrap_or(character);
+    client
+        .sync_document(&absolute, &language_id_for(&absolute), text)
+        .await?;
+    let mut params = position_params(line, character);
+    params["textDocument"] = json!({ "uri": path_to_uri(&absolute) });
+    params["newName"] = json!(new_name);
+    let edit = client
+        .request_with_timeout("textDocument/rename", params, REQUEST_TIMEOUT)
+        .await?;
+    Ok(Some((edit, encoding)))
+}
+
+/// Shuts down every language server started for a workspace.
+pub(crate) async fn stop_workspace_servers(state: &AppState, workspace_id: &str) {
+    feedback::forget_workspace(workspace_id);
+    let slots: Vec<LspSlot> = {
+        let mut clients = state.lsp_clients.lock().await;
+        let keys: Vec<(String, String)> = clients
+            .keys()
+            .filter(|(id, _)| id == workspace_id)
+            .cloned()
+            .collect();
+        keys.iter().filter_map(|key| clients.remove(key)).collect()
+    };
+    for slot in slots {
+        if let Some(client) = slot.get() {
+            client.shutdown().await;
+        }
+    }
+}
+
+fn position_params(line: u32, character: u32) -> Value {
+    json!({ "position": { "line": line, "character": character } })
+}
+
+async fn ensure_local(state: &AppState) -> Result<(), String> {
+    if remote_backend::is_remote_mode(state).await {
+        return Err("Language servers are not supported in remote mode yet.".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn lsp_list_servers(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<Value, String> {
+    ensure_local(&state).await?;
+    let overrides = state.app_settings.lock().await.lsp_servers.clone();
+    let clients = state.lsp_clients.lock().await;
+    let servers: Vec<Value> = resolve_servers(&overrides)
+        .into_iter()
+        .map(|server| {
+            let running = clients
+                .get(&(workspace_id.clone(), server.id.clone()))
+                .and_then(|slot| slot.get())
+                .is_some_and(|client| client.is_alive());
+            let binary = find_server_binary(&server);
+            json!({
+                "id": server.id,
+                "command": server.command,
+                "args": server.args,
+                "extensions": server.extensions,
+                "binaryPath": binary.as_ref().map(|path| path.to_string_lossy().to_string()),
+                "available": binary.is_some(),
+                "running": running,
+            })
+        })
+        .collect();
+    Ok(json!({ "workspaceId": workspace_id, "result": servers }))
+}
+
+#[tauri::command]
+pub(crate) async fn lsp_stop_servers(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    stop_workspace_servers(&state, &workspace_id).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn lsp_diagnostics(
+    workspace_id: String,
+    file_path: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Value, String> {
+    ensure_local(&state).await?;
+    let (server_id, diagnostics) =
+        file_diagnostics(&state, &app, &workspace_id, &file_path).await?;
+    Ok(json!({
+        "filePath": file_path,
+        "serverId": server_id,
+        "result": diagnostics,
+    }))
+}
+
+#[tauri::command]
+pub(crate) async fn lsp_hover(
+    workspace_id: String,
+    file_path: String,
+    line: u32,
+    character: u32,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Value, String> {
+    ensure_local(&state).await?;
+    let (client, result) = document_request(
+        &state,
+        &app,
+        &workspace_id,
+        &file_path,
+        "textDocument/hover",
+        position_params(line, character),
+    )
+    .await?;
+    Ok(json!({
+        "filePath": file_path,
+        "line": line,
+        "character": character,
+        "serverId": client.server_id(),
+        "result": result,
+    }))
+}
+
+#[tauri::command]
+pub(crate) async fn lsp_definition(
+    workspace_id: String,
+    file_path: String,
+    line: u32,
+    character: u32,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Value, String> {
+    ensure_local(&state).await?;
+    let (client, result) = document_request(
+        &state,
+        &app,
+        &workspace_id,
+        &file_path,
+        "textDocument/definition",
+        position_params(line, character),
+    )
+    .await?;
+    Ok(json!({
+        "filePath": file_path,
+        "line": line,
+        "character": character,
+        "serverId": client.server_id(),
+        "result": result,
+    }))
+}
+
+#[tauri::command]
+pub(crate) async fn lsp_references(
+    workspace_id: String,
+    file_path: String,
+    line: u32,
+    character: u32,
+    include_declaration: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Value, String> {
+    ensure_local(&state).await?;
+    let include_declaration = include_declaration.unwrap_or(false);
+    let mut params = position_params(line, character);
+    params["context"] = json!({ "includeDeclaration": include_declaration });
+    let (client, result) = document_request(
+        &state,
+        &app,
+        &workspace_id,
+        &file_path,
+        "textDocument/references",
+        params,
+    )
+    .await?;
+    Ok(json!({
+        "filePath": file_path,
+        "line": line,
+        "character": character,
+        "includeDeclaration": include_declaration,
+        "serverId": client.server_id(),
+        "result": result,
+    }))
+}
+
+/// Returns the server's `WorkspaceEdit` without applying it.
+#[tauri::command]
+pub(crate) async fn lsp_rename(
+    workspace_id: String,
+    file_path: String,
+    line: u32,
+    character: u32,
+    new_name: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Value, String> {
+    ensure_local(&state).await?;
+    if new_name.trim().is_empty() {
+        return Err("New name cannot be empty".to_string());
+    }
+    let mut params = position_params(line, character);
+    params["newName"] = json!(new_name);
+    let (client, result) = document_request(
+        &state,
+        &app,
+        &workspace_id,
+        &file_path,
+        "textDocument/rename",
+        params,
+    )
+    .await?;
+    Ok(json!({
+        "filePath": file_path,
+        "line": line,
+        "character": character,
+        "newName": new_name,
+        "serverId": client.server_id(),
+        "result": result,
+    }))
+}
+
+fn overlaps_lines(diagnostic: &Value, start_line: u32, end_line: u32) -> bool {
+    range_bounds(diagnostic.get("range").unwrap_or(&Value::Null))
+        .is_some_and(|(start, end)| start.0 <= end_line && end.0 >= start_line)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn lsp_code_actions(
+    workspace_id: String,
+    file_path: String,
+    start_line: u32,
+    start_character: u32,
+    end_line: u32,
+    end_character: u32,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Value, String> {
+    ensure_local(&state).await?;
+    let (root, absolute) = resolve_file(&state, &workspace_id, &file_path).await?;
+    let client = client_for(&state, &app, &workspace_id, &root, &absolute).await?;
+    sync_file(&client, &absolute).await?;
+    let uri = path_to_uri(&absolute);
+    let mut diagnostics = Value::Array(
+        client
+            .latest_diagnostics(&uri)
+            .into_iter()
+            .filter(|diagnostic| overlaps_lines(diagnostic, start_line, end_line))
+            .collect(),
+    );
+    // Published diagnostics keep the server's units; the request below
+    // converts everything it sends from characters.
+    let encoding = client.position_encoding();
+    if encoding != PositionEncoding::Utf32 {
+        convert_columns(
+            &mut diagnostics,
+            &uri,
+            encoding,
+            false,
+            &mut LineCache::default(),
+        );
+    }
+    let result = request_in_chars(
+        &client,
+        &absolute,
+        "textDocument/codeAction",
+        json!({
+            "range": {
+                "start": { "line": start_line, "character": start_character },
+                "end": { "line": end_line, "character": end_character },
+            },
+            "context": { "diagnostics": diagnostics },
+        }),
+        REQUEST_TIMEOUT,
+    )
+    .await?;
+    Ok(json!({
+        "filePath": file_path,
+        "serverId": client.server_id(),
+        "result": result,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locations_accept_single_list_and_link_responses() {
+        let root = std::env::temp_dir().join("moss-lsp");
+        let uri = path_to_uri(&root.join("src/lib.rs"));
+        let range = json!({
+            "start": { "line": 3, "character": 4 },
+            "end": { "line": 3, "character": 9 },
+        });
+
+        let single = locations_from_response(&json!({ "uri": uri, "range": range }));
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].path, Some(root.join("src/lib.rs")));
+        assert_eq!((single[0].start, single[0].end), ((3, 4), (3, 9)));
+
+        let links = locations_from_response(&json!([{
+            "targetUri": uri,
+            "targetRange": { "start": { "line": 1, "character": 0 }, "end": { "line": 8, "character": 1 } },
+            "targetSelectionRange": range,
+        }]));
+        assert_eq!(links[0].start, (3, 4));
+
+        assert!(locations_from_response(&Value::Null).is_empty());
+    }
+
+    #[test]
+    fn code_action_context_keeps_overlapping_diagnostics() {
+        let diagnostic = json!({
+            "range": { "start": { "line": 10, "character": 0 }, "end": { "line": 12, "character": 3 } },
+            "message": "unused variable",
+        });
+        assert!(overlaps_lines(&diagnostic, 12, 14));
+        assert!(!overlaps_lines(&diagnostic, 13, 14));
+    }
+
+    #[test]
+    fn converts_columns_per_document_between_chars_and_units() {
+        let root = std::env::temp_dir().join(format!("moss-lsp-columns-{}", std::process::id()));
+        std::fs::create_dir_all(&root).expect("create root");
+        let here = root.join("here.rs");
+        let there = root.join("there.rs");
+        std::fs::write(&here, "let 😀 = 1;\n").expect("write here");
+        std::fs::write(&there, "x\n\u{e9}\u{e9}y\n").expect("write there");
+        let (here_uri, there_uri) = (path_to_uri(&here), path_to_uri(&there));
+        let mut lines = LineCache::default();
+
+        let mut params = json!({
+            "textDocument": { "uri": here_uri },
+            "position": { "line": 0, "character": 6 },
+        });
+        convert_columns(
+            &mut params,
+            &here_uri,
+            PositionEncoding::Utf16,
+            true,
+            &mut lines,
+        );
+        assert_eq!(params["position"]["character"], 7);
+
+        let mut response = json!([
+            { "uri": there_uri, "range": {
+                "start": { "line": 1, "character": 4 },
+                "end": { "line": 1, "character": 5 },
+            } },
+        ]);
+        convert_columns(
+            &mut response,
+            &here_uri,
+            PositionEncoding::Utf8,
+            false,
+            &mut lines,
+        );
+        assert_eq!(response[0]["range"]["start"]["character"], 2);
+        assert_eq!(response[0]["range"]["end"]["character"], 3);
+
+        let mut edit = json!({ "changes": { there_uri.clone(): [{
+            "range": { "start": { "line": 1, "character": 2 }, "end": { "line": 1, "character": 4 } },
+            "newText": "z",
+        }] } });
+        convert_columns(
+            &mut edit,
+            &here_uri,
+            PositionEncoding::Utf8,
+            false,
+            &mut lines,
+        );
+        assert_eq!(
+            edit["changes"][&there_uri][0]["range"]["end"]["character"],
+            2
+        );
+
+        let mut actions = json!([{
+            "title": "Rename",
+            "edit": { "documentChanges": [{
+                "textDocument": { "uri": there_uri, "version": 1 },
+                "edits": [{
+                    "range": { "start": { "line": 1, "character": 4 }, "end": { "line": 1, "character": 5 } },
+                    "newText": "w",
+                }],
+            }] },
+        }]);
+        convert_columns(
+            &mut actions,
+            &here_uri,
+            PositionEncoding::Utf8,
+            false,
+            &mut lines,
+        );
+        let range = &actions[0]["edit"]["documentChanges"][0]["edits"][0]["range"];
+        assert_eq!(
+            (&range["start"]["character"], &range["end"]["character"]),
+            (&json!(2), &json!(3))
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
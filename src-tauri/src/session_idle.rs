@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::backend::events::AppServerEvent;
+use crate::state::AppState;
+
+const REAPER_INTERVAL: Duration = Duration::from_secs(60);
+const LAST_ACTIVITY_FILE: &str = "session-activity.json";
+
+#[derive(Debug, Clone, Copy)]
+struct SessionActivity {
+    last_activity: Instant,
+    turn_active: bool,
+}
+
+static SESSION_ACTIVITY: LazyLock<Mutex<HashMap<String, SessionActivity>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Wall-clock time (ms) of the last session event per workspace. Unlike
+/// `SESSION_ACTIVITY` it survives reaping and is saved across restarts, so
+/// worktree pruning knows when an agent last worked somewhere.
+static LAST_ACTIVITY_MS: LazyLock<Mutex<HashMap<String, i64>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+static LAST_ACTIVITY_DIRTY: AtomicBool = AtomicBool::new(false);
+
+/// Marks a workspace session as recently used. Turn lifecycle methods keep a
+/// session pinned while a turn is running so it is never reaped mid-turn.
+pub(crate) fn record_session_activity(workspace_id: &str, method: Option<&str>) {
+    if let Ok(mut last) = LAST_ACTIVITY_MS.lock() {
+        last.insert(
+            workspace_id.to_string(),
+            chrono::Utc::now().timestamp_millis(),
+        );
+        LAST_ACTIVITY_DIRTY.store(true, Ordering::Relaxed);
+    }
+    let Ok(mut activity) = SESSION_ACTIVITY.lock() else {
+        return;
+    };
+    let entry = activity
+        .entry(workspace_id.to_string())
+        .or_insert(SessionActivity {
+            last_activity: Instant::now(),
+            turn_active: false,
+        });
+    entry.last_activity = Instant::now();
+    match method {
+        Some("turn/started") => entry.turn_active = true,
+        Some("turn/completed") | Some("turn/error") => entry.turn_active = false,
+        _ => {}
+    }
+}
+
+fn last_activity_path(state: &AppState) -> PathBuf {
+    state
+        .storage_path
+        .parent()
+        .map(|dir| dir.join(LAST_ACTIVITY_FILE))
+        .unwrap_or_else(|| PathBuf::from(LAST_ACTIVITY_FILE))
+}
+
+fn read_last_activity(path: &Path) -> HashMap<String, i64> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+/// Last session activity per workspace, from this run and the saved record
+/// of earlier ones.
+pub(crate) fn last_session_activity(state: &AppState) -> HashMap<String, i64> {
+    let mut merged = read_last_activity(&last_activity_path(state));
+    if let Ok(last) = LAST_ACTIVITY_MS.lock() {
+        for (workspace_id, at) in last.iter() {
+            let entry = merged.entry(workspace_id.clone()).or_insert(*at);
+            *entry = (*entry).max(*at);
+        }
+    }
+    merged
+}
+
+fn save_last_activity(state: &AppState) {
+    if !LAST_ACTIVITY_DIRTY.swap(false, Ordering::Relaxed) {
+        return;
+    }
+    let path = last_activity_path(state);
+    let merged = last_session_activity(state);
+    let written = serde_json::to_string(&merged)
+        .map_err(|err| err.to_string())
+        .and_then(|data| std::fs::write(&path, data).map_err(|err| err.to_string()));
+    if let Err(err) = written {
+        log::warn!("[session_idle] Failed to save session activity: {err}");
+    }
+}
+
+fn forget_session_activity(workspace_id: &str) {
+    if let Ok(mut activity) = SESSION_ACTIVITY.lock() {
+        activity.remove(workspace_id);
+    }
+}
+
+fn idle_workspaces(
+    session_ids: &[String],
+    activity: &HashMap<String, SessionActivity>,
+    now: Instant,
+    idle_after: Duration,
+) -> Vec<String> {
+    session_ids
+        .iter()
+        .filter(|id| match activity.get(id.as_str()) {
+            Some(entry) => {
+                !entry.turn_active && now.duration_since(entry.last_activity) >= idle_after
+            }
+            // Sessions we never saw traffic for are left alone; they get
+            // tracked as soon as they emit their first event.
+            None => false,
+        })
+        .cloned()
+        .collect()
+}
+
+async fn reap_idle_sessions(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let idle_minutes = state.app_settings.lock().await.engine_session_idle_minutes;
+    if idle_minutes == 0 {
+        return;
+    }
+    let idle_after = Duration::from_secs(u64::from(idle_minutes) * 60);
+    let session_ids: Vec<String> = state.sessions.lock().await.keys().cloned().collect();
+    let idle = {
+        let Ok(activity) = SESSION_ACTIVITY.lock() else {
+            return;
+        };
+        idle_workspaces(&session_ids, &activity, Instant::now(), idle_after)
+    };
+    for workspace_id in idle {
+        let session = state.sessions.lock().await.remove(&workspace_id);
+        let Some(session) = session else {
+            continue;
+        };
+        log::info!(
+            "[session_idle] Stopping Codex session for workspace {} after {} idle minutes",
+            workspace_id,
+            idle_minutes
+        );
+        let _ = session.child.lock().await.kill().await;
+        state
+            .engine_manager
+            .remove_codex_adapter(&workspace_id)
+            .await;
+        forget_session_activity(&workspace_id);
+        // Emitted straight to the app rather than through the event sink so
+        // the stopped session is not tracked as active again.
+        let _ = app.emit(
+            "app-server-event",
+            AppServerEvent {
+                workspace_id: workspace_id.clone(),
+                message: json!({
+                    "method": "codex/disconnected",
+                    "params": { "workspaceId": workspace_id, "reason": "idle" }
+                }),
+            },
+        );
+    }
+}
+
+/// Periodically stops Codex app-server sessions that have been idle longer
+/// than `engineSessionIdleMinutes` and emits `codex/disconnected` for each.
+/// Each pass also saves the last activity times for worktree pruning.
+/// Sessions are respawned lazily on next use. Only the desktop app runs the
+/// reaper; the remote daemon keeps its sessions until they are disconnected.
+pub(crate) fn spawn_idle_session_reaper(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(REAPER_INTERVAL).await;
+            save_last_activity(&app.state::<AppState>());
+            reap_idle_sessions(&app).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(age: Duration, turn_active: bool, now: Instant) -> SessionActivity {
+        SessionActivity {
+            last_activity: now - age,
+            turn_active,
+        }
+    }
+
+    #[test]
+    fn idle_workspaces_skips_active_turns_and_recent_sessions() {
+        let now = Instant::now();
+        let idle_after = Duration::from_secs(600);
+        let mut map = HashMap::new();
+        map.insert(
+            "old".to_string(),
+            activity(Duration::from_secs(900), false, now),
+        );
+        map.insert(
+            "busy".to_string(),
+            activity(Duration::from_secs(900), true, now),
+        );
+        map.insert(
+            "fresh".to_string(),
+            activity(Duration::from_secs(30), false, now),
+        );
+        let ids = vec![
+            "old".to_string(),
+            "busy".to_string(),
+            "fresh".to_string(),
+            "untracked".to_string(),
+        ];
+
+        assert_eq!(idle_workspaces(&ids, &map, now, idle_after), vec!["old"]);
+    }
+
+    #[test]
+    fn record_session_activity_tracks_turn_lifecycle() {
+        let id = format!("ws-{}", uuid::Uuid::new_v4());
+        record_session_activity(&id, Some("turn/started"));
+        assert!(SESSION_ACTIVITY.lock().unwrap()[&id].turn_active);
+        record_session_activity(&id, Some("item/agentMessage/delta"));
+        assert!(SESSION_ACTIVITY.lock().unwrap()[&id].turn_active);
+        record_session_activity(&id, Some("turn/completed"));
+        assert!(!SESSION_ACTIVITY.lock().unwrap()[&id].turn_active);
+        forget_session_activity(&id);
+        assert!(!SESSION_ACTIVITY.lock().unwrap().contains_key(&id));
+        // Reaping forgets the session, not when it was last used.
+        assert!(LAST_ACTIVITY_MS.lock().unwrap().contains_key(&id));
+    }
+}
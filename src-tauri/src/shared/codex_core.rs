@@ -93,7 +93,7 @@ fn resolve_execution_policy(
     (sandbox_policy, approval_policy, None)
 }
 
-fn extract_thread_id_from_response(value: &Value) -> Option<String> {
+pub(crate) fn extract_thread_id_from_response(value: &Value) -> Option<String> {
     value
         .get("result")
         .and_then(|result| result.get("threadId"))
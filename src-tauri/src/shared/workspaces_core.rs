@@ -6,9 +6,10 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::backend::app_server::WorkspaceSession;
+use crate::backend::sandbox::validate_workspace_sandbox;
 use crate::codex::args::resolve_workspace_codex_args;
 use crate::codex::home::resolve_workspace_codex_home;
-use crate::storage::write_workspaces;
+use crate::storage::{is_workspace_transient, set_workspace_transient, write_workspaces};
 use crate::types::{
     AppSettings, WorkspaceEntry, WorkspaceInfo, WorkspaceKind, WorkspaceSettings, WorktreeInfo,
     WorktreeSetupStatus,
@@ -45,21 +46,12 @@ pub(crate) async fn list_workspaces_core(
     let sessions = sessions.lock().await;
     let mut result = Vec::new();
     for entry in workspaces.values() {
-        // Determine connected status based on engine type:
-        // - Claude: Always "connected" (no persistent session needed)
-        // - Codex: Check if session exists in sessions HashMap
-        let is_claude_engine = entry
-            .settings
-            .engine_type
-            .as_deref()
-            .map(|e| e.eq_ignore_ascii_case("claude"))
-            .unwrap_or(true); // Default to Claude if not specified
-
-        let connected = if is_claude_engine {
-            true // Claude is always "connected"
-        } else {
-            sessions.contains_key(&entry.id)
-        };
+        if is_workspace_transient(&entry.id) {
+            continue;
+        }
+        // CLI engines need no persistent session; Codex is connected once its
+        // app-server session is running.
+        let connected = !workspace_uses_codex_session(entry) || sessions.contains_key(&entry.id);
 
         result.push(WorkspaceInfo {
             id: entry.id.clone(),
@@ -159,19 +151,25 @@ pub(crate) async fn worktree_setup_mark_ran_core(
     Ok(())
 }
 
-pub(crate) async fn add_workspace_core<F, Fut>(
+/// Whether the workspace's engine runs through a Codex app-server session.
+/// Other engines spawn their CLI per turn, so they are usable without one.
+pub(crate) fn workspace_uses_codex_session(entry: &WorkspaceEntry) -> bool {
+    entry
+        .settings
+        .engine_type
+        .as_deref()
+        .is_some_and(|engine| engine.eq_ignore_ascii_case("codex"))
+}
+
+/// Registers a workspace without starting any engine process; the session
+/// for its engine is started on first use.
+pub(crate) async fn add_workspace_core(
     path: String,
     codex_bin: Option<String>,
+    engine_type: Option<String>,
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
-    sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
-    app_settings: &Mutex<AppSettings>,
     storage_path: &PathBuf,
-    spawn_session: F,
-) -> Result<WorkspaceInfo, String>
-where
-    F: Fn(WorkspaceEntry, Option<String>, Option<String>, Option<PathBuf>) -> Fut,
-    Fut: Future<Output = Result<Arc<WorkspaceSession>, String>>,
-{
+) -> Result<WorkspaceInfo, String> {
     if !PathBuf::from(&path).is_dir() {
         return Err("Workspace path must be a folder.".to_string());
     }
@@ -189,42 +187,28 @@ where
         kind: WorkspaceKind::Main,
         parent_id: None,
         worktree: None,
-        settings: WorkspaceSettings::default(),
-    };
-
-    let (default_bin, codex_args) = {
-        let settings = app_settings.lock().await;
-        (
-            settings.codex_bin.clone(),
-            resolve_workspace_codex_args(&entry, None, Some(&settings)),
-        )
+        settings: WorkspaceSettings {
+            engine_type,
+            ..WorkspaceSettings::default()
+        },
     };
-    let codex_home = resolve_workspace_codex_home(&entry, None);
-    let session = spawn_session(entry.clone(), default_bin, codex_args, codex_home).await?;
 
-    if let Err(error) = {
+    {
         let mut workspaces = workspaces.lock().await;
         workspaces.insert(entry.id.clone(), entry.clone());
         let list: Vec<_> = workspaces.values().cloned().collect();
-        write_workspaces(storage_path, &list)
-    } {
-        {
-            let mut workspaces = workspaces.lock().await;
+        if let Err(error) = write_workspaces(storage_path, &list) {
             workspaces.remove(&entry.id);
+            return Err(error);
         }
-        let mut child = session.child.lock().await;
-        let _ = child.kill().await;
-        return Err(error);
     }
 
-    sessions.lock().await.insert(entry.id.clone(), session);
-
     Ok(WorkspaceInfo {
+        connected: !workspace_uses_codex_session(&entry),
         id: entry.id,
         name: entry.name,
         path: entry.path,
         codex_bin: entry.codex_bin,
-        connected: true,
         kind: entry.kind,
         parent_id: entry.parent_id,
         worktree: entry.worktree,
@@ -280,8 +264,6 @@ fn resolve_base_ref_to_commit(repo_path: &PathBuf, base_ref: &str) -> Result<Str
 }
 
 pub(crate) async fn add_worktree_core<
-    FSpawn,
-    FutSpawn,
     FSanitize,
     FUniquePath,
     FBranchExists,
@@ -295,21 +277,17 @@ pub(crate) async fn add_worktree_core<
     branch: String,
     base_ref: Option<String>,
     publish_to_origin: bool,
+    transient: bool,
     data_dir: &PathBuf,
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
-    sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
-    app_settings: &Mutex<AppSettings>,
     storage_path: &PathBuf,
     sanitize_worktree_name: FSanitize,
     unique_worktree_path: FUniquePath,
     git_branch_exists: FBranchExists,
     git_find_remote_tracking_branch: Option<FFindRemoteTracking>,
     run_git_command: FRunGit,
-    spawn_session: FSpawn,
 ) -> Result<WorkspaceInfo, String>
 where
-    FSpawn: Fn(WorkspaceEntry, Option<String>, Option<String>, Option<PathBuf>) -> FutSpawn,
-    FutSpawn: Future<Output = Result<Arc<WorkspaceSession>, String>>,
     FSanitize: Fn(&str) -> String,
     FUniquePath: Fn(&PathBuf, &str) -> Result<PathBuf, String>,
     FBranchExists: Fn(&PathBuf, &str) -> FutBranchExists,
@@ -437,20 +415,17 @@ where
             worktree_setup_script: normalize_setup_script(
                 parent_entry.settings.worktree_setup_script.clone(),
             ),
+            engine_type: parent_entry.settings.engine_type.clone(),
+            sandbox: parent_entry.settings.sandbox.clone(),
             ..WorkspaceSettings::default()
         },
     };
 
-    let (default_bin, codex_args) = {
-        let settings = app_settings.lock().await;
-        (
-            settings.codex_bin.clone(),
-            resolve_workspace_codex_args(&entry, Some(&parent_entry), Some(&settings)),
-        )
-    };
-    let codex_home = resolve_workspace_codex_home(&entry, Some(&parent_entry));
-    let session = spawn_session(entry.clone(), default_bin, codex_args, codex_home).await?;
-
+    // Transient worktrees are marked before they are stored, so they never
+    // reach disk or the workspace list.
+    if transient {
+        set_workspace_transient(&entry.id, true);
+    }
     {
         let mut workspaces = workspaces.lock().await;
         workspaces.insert(entry.id.clone(), entry.clone());
@@ -458,14 +433,12 @@ where
         write_workspaces(storage_path, &list)?;
     }
 
-    sessions.lock().await.insert(entry.id.clone(), session);
-
     Ok(WorkspaceInfo {
+        connected: !workspace_uses_codex_session(&entry),
         id: entry.id,
         name: entry.name,
         path: entry.path,
         codex_bin: entry.codex_bin,
-        connected: true,
         kind: entry.kind,
         parent_id: entry.parent_id,
         worktree: entry.worktree,
@@ -828,7 +801,8 @@ where
         }
     }
 
-    let connected = sessions.lock().await.contains_key(&entry_snapshot.id);
+    let connected = !workspace_uses_codex_session(&entry_snapshot)
+        || sessions.lock().await.contains_key(&entry_snapshot.id);
     Ok(WorkspaceInfo {
         id: entry_snapshot.id,
         name: entry_snapshot.name,
@@ -978,6 +952,9 @@ where
     FutSpawn: Future<Output = Result<Arc<WorkspaceSession>, String>>,
 {
     settings.worktree_setup_script = normalize_setup_script(settings.worktree_setup_script);
+    if let Some(sandbox) = settings.sandbox.as_ref() {
+        validate_workspace_sandbox(sandbox)?;
+    }
 
     let (
         previous_entry,
@@ -1022,8 +999,9 @@ where
     let codex_args_changed = previous_codex_args != entry_snapshot.settings.codex_args;
     let worktree_setup_script_changed =
         previous_worktree_setup_script != entry_snapshot.settings.worktree_setup_script;
+    let sandbox_changed = previous_entry.settings.sandbox != entry_snapshot.settings.sandbox;
     let connected = sessions.lock().await.contains_key(&id);
-    if connected && (codex_home_changed || codex_args_changed) {
+    if connected && (codex_home_changed || codex_args_changed || sandbox_changed) {
         let rollback_entry = previous_entry.clone();
         let (default_bin, codex_args) = {
             let settings = app_settings.lock().await;
@@ -1128,11 +1106,11 @@ where
     };
     write_workspaces(storage_path, &list)?;
     Ok(WorkspaceInfo {
+        connected: connected || !workspace_uses_codex_session(&entry_snapshot),
         id: entry_snapshot.id,
         name: entry_snapshot.name,
         path: entry_snapshot.path,
         codex_bin: entry_snapshot.codex_bin,
-        connected,
         kind: entry_snapshot.kind,
         parent_id: entry_snapshot.parent_id,
         worktree: entry_snapshot.worktree,
@@ -1161,7 +1139,8 @@ pub(crate) async fn update_workspace_codex_bin_core(
     };
     write_workspaces(storage_path, &list)?;
 
-    let connected = sessions.lock().await.contains_key(&id);
+    let connected =
+        !workspace_uses_codex_session(&entry_snapshot) || sessions.lock().await.contains_key(&id);
     Ok(WorkspaceInfo {
         id: entry_snapshot.id,
         name: entry_snapshot.name,
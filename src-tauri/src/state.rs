@@ -1,5 +1,6 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use tauri::{AppHandle, Manager};
 use tokio::sync::oneshot;
@@ -8,7 +9,7 @@ use tokio::sync::Mutex;
 use crate::dictation::DictationState;
 use crate::engine::EngineManager;
 use crate::storage::{read_settings, read_workspaces};
-use crate::types::{AppSettings, WorkspaceEntry};
+use crate::types::{AppSettings, FanoutRun, WorkspaceEntry};
 
 pub(crate) struct AppState {
     pub(crate) workspaces: Mutex<HashMap<String, WorkspaceEntry>>,
@@ -22,6 +23,13 @@ pub(crate) struct AppState {
     pub(crate) codex_login_cancels: Mutex<HashMap<String, oneshot::Sender<()>>>,
     /// Multi-engine manager
     pub(crate) engine_manager: EngineManager,
+    pub(crate) fanout_runs: Mutex<HashMap<String, FanoutRun>>,
+    pub(crate) file_watchers: Mutex<HashMap<String, crate::workspaces::WorkspaceFileWatcher>>,
+    pub(crate) content_searches: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    pub(crate) code_intel_indexes: Mutex<HashMap<String, Arc<crate::code_intel::SymbolIndex>>>,
+    pub(crate) lsp_clients: Mutex<HashMap<(String, String), crate::lsp::LspSlot>>,
+    /// Undo snapshots for recent code intel edits, oldest first.
+    pub(crate) code_intel_edit_snapshots: Mutex<VecDeque<crate::code_intel::CodeIntelEditSnapshot>>,
 }
 
 impl AppState {
@@ -45,6 +53,12 @@ impl AppState {
             dictation: Mutex::new(DictationState::default()),
             codex_login_cancels: Mutex::new(HashMap::new()),
             engine_manager: EngineManager::new(),
+            fanout_runs: Mutex::new(HashMap::new()),
+            file_watchers: Mutex::new(HashMap::new()),
+            content_searches: Mutex::new(HashMap::new()),
+            code_intel_indexes: Mutex::new(HashMap::new()),
+            lsp_clients: Mutex::new(HashMap::new()),
+            code_intel_edit_snapshots: Mutex::new(VecDeque::new()),
         }
     }
 }
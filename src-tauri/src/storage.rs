@@ -1,5 +1,6 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
 
 use crate::types::{AppSettings, WorkspaceEntry};
 
@@ -15,11 +16,36 @@ pub(crate) fn read_workspaces(path: &PathBuf) -> Result<HashMap<String, Workspac
         .collect())
 }
 
+/// Workspaces registered for one internal task, like a pull request review
+/// checkout. They are never written to disk or listed.
+static TRANSIENT_WORKSPACES: LazyLock<Mutex<HashSet<String>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+pub(crate) fn set_workspace_transient(workspace_id: &str, transient: bool) {
+    if let Ok(mut ids) = TRANSIENT_WORKSPACES.lock() {
+        if transient {
+            ids.insert(workspace_id.to_string());
+        } else {
+            ids.remove(workspace_id);
+        }
+    }
+}
+
+pub(crate) fn is_workspace_transient(workspace_id: &str) -> bool {
+    TRANSIENT_WORKSPACES
+        .lock()
+        .is_ok_and(|ids| ids.contains(workspace_id))
+}
+
 pub(crate) fn write_workspaces(path: &PathBuf, entries: &[WorkspaceEntry]) -> Result<(), String> {
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
-    let data = serde_json::to_string_pretty(entries).map_err(|e| e.to_string())?;
+    let entries = entries
+        .iter()
+        .filter(|entry| !is_workspace_transient(&entry.id))
+        .collect::<Vec<_>>();
+    let data = serde_json::to_string_pretty(&entries).map_err(|e| e.to_string())?;
     std::fs::write(path, data).map_err(|e| e.to_string())
 }
 
@@ -41,7 +67,7 @@ pub(crate) fn write_settings(path: &PathBuf, settings: &AppSettings) -> Result<(
 
 #[cfg(test)]
 mod tests {
-    use super::{read_workspaces, write_workspaces};
+    use super::{read_workspaces, set_workspace_transient, write_workspaces};
     use crate::types::{WorkspaceEntry, WorkspaceKind, WorkspaceSettings};
     use uuid::Uuid;
 
@@ -81,4 +107,29 @@ mod tests {
             Some("--profile personal")
         );
     }
+
+    #[test]
+    fn transient_workspaces_are_not_persisted() {
+        let temp_dir = std::env::temp_dir().join(format!("moss-x-test-{}", Uuid::new_v4()));
+        let path = temp_dir.join("workspaces.json");
+        let entry = |id: &str| WorkspaceEntry {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: "/tmp".to_string(),
+            codex_bin: None,
+            kind: WorkspaceKind::Main,
+            parent_id: None,
+            worktree: None,
+            settings: WorkspaceSettings::default(),
+        };
+        let review_id = format!("pr-review-{}", Uuid::new_v4());
+        set_workspace_transient(&review_id, true);
+
+        write_workspaces(&path, &[entry("w1"), entry(&review_id)]).expect("write workspaces");
+        set_workspace_transient(&review_id, false);
+        let read = read_workspaces(&path).expect("read workspaces");
+        assert!(read.contains_key("w1"));
+        assert!(!read.contains_key(&review_id));
+        let _ = std::fs::remove_dir_all(temp_dir);
+    }
 }
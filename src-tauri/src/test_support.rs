@@ -0,0 +1,38 @@
+//! Helpers shared by tests that need a real git repository on disk.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use git2::Repository;
+
+/// Creates an empty repository in a fresh temp directory.
+pub(crate) fn create_temp_repo() -> (PathBuf, Repository) {
+    let root = std::env::temp_dir().join(format!("moss-x-test-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&root).expect("create temp repo root");
+    let repo = Repository::init(&root).expect("init repo");
+    (root, repo)
+}
+
+/// Runs the git CLI in `root` with a fixed identity and returns trimmed
+/// stdout, failing the test when git exits non-zero. Local file remotes are
+/// allowed so submodule tests can clone sibling repositories.
+pub(crate) fn git(root: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(["-c", "protocol.file.allow=always"])
+        .args(args)
+        .current_dir(root)
+        .env("GIT_AUTHOR_NAME", "Tester")
+        .env("GIT_AUTHOR_EMAIL", "tester@example.com")
+        .env("GIT_COMMITTER_NAME", "Tester")
+        .env("GIT_COMMITTER_EMAIL", "tester@example.com")
+        .output()
+        .expect("run git");
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
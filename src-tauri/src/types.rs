@@ -97,6 +97,97 @@ pub(crate) struct GitHistoryResponse {
     pub(crate) commits: Vec<GitHistoryCommit>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub(crate) struct GitCommitGraphEdge {
+    /// "pass" continues a lane, "in" ends at the commit, "out" leaves it.
+    pub(crate) kind: String,
+    #[serde(rename = "fromColumn")]
+    pub(crate) from_column: usize,
+    #[serde(rename = "toColumn")]
+    pub(crate) to_column: usize,
+    pub(crate) color: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitCommitGraphRow {
+    pub(crate) commit: GitHistoryCommit,
+    pub(crate) column: usize,
+    pub(crate) color: usize,
+    pub(crate) edges: Vec<GitCommitGraphEdge>,
+    #[serde(rename = "isMerge")]
+    pub(crate) is_merge: bool,
+    #[serde(rename = "isFork")]
+    pub(crate) is_fork: bool,
+    #[serde(rename = "isTip")]
+    pub(crate) is_tip: bool,
+    pub(crate) width: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitCommitGraphResponse {
+    #[serde(rename = "cacheKey")]
+    pub(crate) cache_key: String,
+    pub(crate) offset: usize,
+    pub(crate) limit: usize,
+    #[serde(rename = "hasMore")]
+    pub(crate) has_more: bool,
+    pub(crate) truncated: bool,
+    #[serde(rename = "maxColumns")]
+    pub(crate) max_columns: usize,
+    pub(crate) rows: Vec<GitCommitGraphRow>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitHistorySearchHighlight {
+    /// "message", "author", "added" or "removed".
+    pub(crate) field: String,
+    pub(crate) path: Option<String>,
+    pub(crate) text: String,
+    /// `[start, end)` character offsets into `text`.
+    pub(crate) ranges: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitHistorySearchResult {
+    pub(crate) commit: GitHistoryCommit,
+    pub(crate) highlights: Vec<GitHistorySearchHighlight>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitHistorySearchResponse {
+    pub(crate) total: usize,
+    pub(crate) offset: usize,
+    pub(crate) limit: usize,
+    #[serde(rename = "hasMore")]
+    pub(crate) has_more: bool,
+    /// True when the scan stopped at the match cap, so `total` is a lower bound.
+    pub(crate) truncated: bool,
+    pub(crate) results: Vec<GitHistorySearchResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitSubmoduleStatus {
+    pub(crate) name: String,
+    pub(crate) path: String,
+    pub(crate) url: Option<String>,
+    pub(crate) branch: Option<String>,
+    /// Commit recorded in the superproject's HEAD tree.
+    #[serde(rename = "headSha")]
+    pub(crate) head_sha: Option<String>,
+    /// Commit recorded in the superproject's index.
+    #[serde(rename = "indexSha")]
+    pub(crate) index_sha: Option<String>,
+    /// Commit currently checked out inside the submodule.
+    #[serde(rename = "workdirSha")]
+    pub(crate) workdir_sha: Option<String>,
+    pub(crate) initialized: bool,
+    pub(crate) dirty: bool,
+    /// "none", "ahead", "behind", "diverged" or "unknown".
+    pub(crate) drift: String,
+    pub(crate) ahead: usize,
+    pub(crate) behind: usize,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct GitPushPreviewResponse {
     #[serde(rename = "sourceBranch")]
@@ -223,6 +314,18 @@ pub(crate) struct GitCommitDetails {
     pub(crate) total_additions: i64,
     #[serde(rename = "totalDeletions")]
     pub(crate) total_deletions: i64,
+    #[serde(default)]
+    pub(crate) signature: Option<GitCommitSignature>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitCommitSignature {
+    /// "good", "bad", "untrusted", "expired", "expired-key", "revoked" or "unverifiable".
+    pub(crate) status: String,
+    /// "gpg", "ssh" or "x509".
+    pub(crate) format: Option<String>,
+    pub(crate) signer: Option<String>,
+    pub(crate) key: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -243,6 +346,147 @@ pub(crate) struct GitBranchListItem {
     pub(crate) upstream: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitBranchHygieneEntry {
+    pub(crate) branch: GitBranchListItem,
+    /// Any of "merged", "gone-upstream", "stale" and "worktree".
+    pub(crate) categories: Vec<String>,
+    #[serde(rename = "worktreePath")]
+    pub(crate) worktree_path: Option<String>,
+    /// Merged into the base and not checked out anywhere, so `git branch -d` will succeed.
+    #[serde(rename = "safeToDelete")]
+    pub(crate) safe_to_delete: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitBranchHygieneReport {
+    #[serde(rename = "baseBranch")]
+    pub(crate) base_branch: String,
+    #[serde(rename = "baseSha")]
+    pub(crate) base_sha: String,
+    #[serde(rename = "staleDays")]
+    pub(crate) stale_days: u32,
+    pub(crate) branches: Vec<GitBranchHygieneEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitBranchDeleteResult {
+    pub(crate) name: String,
+    #[serde(rename = "isRemote")]
+    pub(crate) is_remote: bool,
+    pub(crate) deleted: bool,
+    pub(crate) error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitTagInfo {
+    pub(crate) name: String,
+    #[serde(rename = "targetSha")]
+    pub(crate) target_sha: String,
+    pub(crate) annotated: bool,
+    #[serde(default)]
+    pub(crate) message: Option<String>,
+    #[serde(default)]
+    pub(crate) tagger: Option<String>,
+    #[serde(default, rename = "taggerEmail")]
+    pub(crate) tagger_email: Option<String>,
+    pub(crate) timestamp: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitReleaseNotesResponse {
+    #[serde(rename = "fromRef")]
+    pub(crate) from_ref: String,
+    #[serde(rename = "toRef")]
+    pub(crate) to_ref: String,
+    pub(crate) engine: String,
+    pub(crate) draft: String,
+    pub(crate) commits: Vec<GitHistoryCommit>,
+    /// The range had more than the commits listed; older ones were left out.
+    #[serde(default)]
+    pub(crate) truncated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitRebasePlanEntry {
+    /// "pick", "reword", "squash", "fixup" or "drop".
+    pub(crate) action: String,
+    pub(crate) sha: String,
+    #[serde(default, rename = "shortSha")]
+    pub(crate) short_sha: String,
+    #[serde(default)]
+    pub(crate) summary: String,
+    #[serde(default)]
+    pub(crate) author: String,
+    #[serde(default)]
+    pub(crate) timestamp: i64,
+    /// Replacement commit message for reword/squash steps.
+    #[serde(default)]
+    pub(crate) message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitRebasePlan {
+    pub(crate) onto: String,
+    #[serde(rename = "ontoSha")]
+    pub(crate) onto_sha: String,
+    #[serde(rename = "headSha")]
+    pub(crate) head_sha: String,
+    pub(crate) branch: Option<String>,
+    pub(crate) entries: Vec<GitRebasePlanEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitRebaseResult {
+    /// "completed", "conflict" or "failed".
+    pub(crate) status: String,
+    pub(crate) message: String,
+    #[serde(rename = "currentStep")]
+    pub(crate) current_step: Option<usize>,
+    #[serde(rename = "totalSteps")]
+    pub(crate) total_steps: Option<usize>,
+    #[serde(rename = "conflictedFiles")]
+    pub(crate) conflicted_files: Vec<String>,
+    #[serde(rename = "headSha")]
+    pub(crate) head_sha: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitBisectStep {
+    pub(crate) sha: String,
+    pub(crate) summary: String,
+    /// "good", "bad" or "skip".
+    pub(crate) verdict: String,
+    #[serde(rename = "exitCode")]
+    pub(crate) exit_code: i32,
+    #[serde(rename = "remainingRevisions")]
+    pub(crate) remaining_revisions: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitBisectResult {
+    /// "found" or "inconclusive" (only skipped commits were left to test).
+    pub(crate) status: String,
+    #[serde(rename = "goodRef")]
+    pub(crate) good_ref: String,
+    #[serde(rename = "badRef")]
+    pub(crate) bad_ref: String,
+    pub(crate) steps: Vec<GitBisectStep>,
+    #[serde(rename = "firstBadCommit")]
+    pub(crate) first_bad_commit: Option<GitCommitDetails>,
+    /// Possible culprits when skipped commits prevent a single answer.
+    pub(crate) candidates: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitForgeInfo {
+    pub(crate) kind: String,
+    pub(crate) host: String,
+    pub(crate) repo: String,
+    #[serde(rename = "webUrl")]
+    pub(crate) web_url: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct GitHubIssue {
     pub(crate) number: u64,
@@ -310,6 +554,54 @@ pub(crate) struct GitHubPullRequestComment {
     pub(crate) author: Option<GitHubPullRequestAuthor>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitHubPullRequestReviewComment {
+    pub(crate) path: String,
+    /// Line number in the file version selected by `side`, as shown in the PR diff.
+    pub(crate) line: u32,
+    /// "right" (new file, default) or "left" (old file).
+    #[serde(default)]
+    pub(crate) side: Option<String>,
+    /// First line of a multi-line comment range. Only honored by GitHub.
+    #[serde(default, rename = "startLine")]
+    pub(crate) start_line: Option<u32>,
+    pub(crate) body: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitHubPullRequestReviewResult {
+    pub(crate) id: Option<u64>,
+    pub(crate) url: Option<String>,
+    pub(crate) state: String,
+    #[serde(rename = "commentCount")]
+    pub(crate) comment_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitHubPullRequestReviewFinding {
+    pub(crate) path: String,
+    pub(crate) line: Option<u32>,
+    /// "critical", "major", "minor" or "nit".
+    pub(crate) severity: String,
+    pub(crate) message: String,
+    /// Whether `line` falls inside a changed hunk, i.e. it can be posted inline.
+    #[serde(rename = "inDiff")]
+    pub(crate) in_diff: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitHubPullRequestReviewReport {
+    #[serde(rename = "prNumber")]
+    pub(crate) pr_number: u64,
+    pub(crate) engine: String,
+    #[serde(rename = "headSha")]
+    pub(crate) head_sha: String,
+    pub(crate) summary: String,
+    pub(crate) findings: Vec<GitHubPullRequestReviewFinding>,
+    #[serde(rename = "rawText")]
+    pub(crate) raw_text: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct LocalUsageDay {
@@ -459,6 +751,77 @@ pub(crate) struct WorkspaceSettings {
     /// Engine type for this workspace: "claude" or "codex". If not set, use app default.
     #[serde(default, rename = "engineType")]
     pub(crate) engine_type: Option<String>,
+    /// Code forge behind the remote: "github", "gitlab" or "gitea". Detected from the remote host when unset.
+    #[serde(default, rename = "forgeKind")]
+    pub(crate) forge_kind: Option<String>,
+    /// Author identity and signing setup for commits made from the app. Worktrees inherit the parent's.
+    #[serde(default, rename = "gitIdentity")]
+    pub(crate) git_identity: Option<GitIdentityProfile>,
+    /// Runs the engine CLIs in a container or bubblewrap jail. Worktrees copy the parent's on creation.
+    #[serde(default)]
+    pub(crate) sandbox: Option<WorkspaceSandbox>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct WorkspaceSandbox {
+    #[serde(default)]
+    pub(crate) enabled: bool,
+    /// "bubblewrap", "podman" or "docker".
+    #[serde(default = "default_sandbox_runner")]
+    pub(crate) runner: String,
+    /// Overrides the runner executable found on PATH.
+    #[serde(default, rename = "runnerPath")]
+    pub(crate) runner_path: Option<String>,
+    /// Container image providing the engine CLIs. Required for podman and docker.
+    #[serde(default)]
+    pub(crate) image: Option<String>,
+    #[serde(default = "default_sandbox_allow_network", rename = "allowNetwork")]
+    pub(crate) allow_network: bool,
+    #[serde(default, rename = "memoryMb")]
+    pub(crate) memory_mb: Option<u64>,
+    #[serde(default)]
+    pub(crate) cpus: Option<f64>,
+    #[serde(default, rename = "pidsLimit")]
+    pub(crate) pids_limit: Option<u32>,
+}
+
+impl Default for WorkspaceSandbox {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            runner: default_sandbox_runner(),
+            runner_path: None,
+            image: None,
+            allow_network: true,
+            memory_mb: None,
+            cpus: None,
+            pids_limit: None,
+        }
+    }
+}
+
+fn default_sandbox_runner() -> String {
+    "bubblewrap".to_string()
+}
+
+fn default_sandbox_allow_network() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub(crate) struct GitIdentityProfile {
+    #[serde(default)]
+    pub(crate) name: Option<String>,
+    #[serde(default)]
+    pub(crate) email: Option<String>,
+    #[serde(default, rename = "signingKey")]
+    pub(crate) signing_key: Option<String>,
+    /// "gpg", "ssh" or "x509". Falls back to git's configured format when unset.
+    #[serde(default, rename = "signingFormat")]
+    pub(crate) signing_format: Option<String>,
+    /// Defaults to signing whenever a signing key is set.
+    #[serde(default, rename = "signCommits")]
+    pub(crate) sign_commits: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -477,6 +840,368 @@ pub(crate) struct WorktreeSetupStatus {
     pub(crate) script: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub(crate) struct WorktreeChangeHunk {
+    pub(crate) index: usize,
+    pub(crate) header: String,
+    pub(crate) additions: usize,
+    pub(crate) deletions: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct WorktreeFileChange {
+    pub(crate) path: String,
+    #[serde(rename = "oldPath")]
+    pub(crate) old_path: Option<String>,
+    /// "added", "deleted", "renamed" or "modified".
+    pub(crate) status: String,
+    pub(crate) binary: bool,
+    pub(crate) additions: usize,
+    pub(crate) deletions: usize,
+    pub(crate) hunks: Vec<WorktreeChangeHunk>,
+    pub(crate) patch: String,
+    /// Predicted outcome against the parent: "clean", "three-way",
+    /// "already-applied" or "conflict".
+    pub(crate) prediction: String,
+    #[serde(rename = "predictionDetail")]
+    pub(crate) prediction_detail: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct WorktreeChangePreview {
+    #[serde(rename = "workspaceId")]
+    pub(crate) workspace_id: String,
+    #[serde(rename = "parentId")]
+    pub(crate) parent_id: String,
+    #[serde(rename = "parentDirty")]
+    pub(crate) parent_dirty: bool,
+    pub(crate) files: Vec<WorktreeFileChange>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct WorktreeApplySelection {
+    pub(crate) path: String,
+    /// Hunk indexes to apply; `None` applies the whole file.
+    #[serde(default)]
+    pub(crate) hunks: Option<Vec<usize>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct WorktreeApplyRecord {
+    pub(crate) id: String,
+    #[serde(rename = "workspaceId")]
+    pub(crate) workspace_id: String,
+    #[serde(rename = "parentId")]
+    pub(crate) parent_id: String,
+    pub(crate) branch: Option<String>,
+    #[serde(rename = "appliedAt")]
+    pub(crate) applied_at: i64,
+    pub(crate) files: Vec<WorktreeApplySelection>,
+    #[serde(rename = "commitSha")]
+    pub(crate) commit_sha: Option<String>,
+    #[serde(rename = "commitMessage")]
+    pub(crate) commit_message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct WorktreeLifecycleInfo {
+    #[serde(rename = "workspaceId")]
+    pub(crate) workspace_id: String,
+    #[serde(rename = "parentId")]
+    pub(crate) parent_id: Option<String>,
+    pub(crate) name: String,
+    pub(crate) branch: Option<String>,
+    pub(crate) path: String,
+    pub(crate) exists: bool,
+    #[serde(rename = "sizeBytes")]
+    pub(crate) size_bytes: u64,
+    /// Latest agent session event or commit, in milliseconds.
+    #[serde(rename = "lastActivityAt")]
+    pub(crate) last_activity_at: Option<i64>,
+    /// Whether the branch is contained in the parent's current HEAD.
+    pub(crate) merged: Option<bool>,
+    pub(crate) dirty: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct WorktreeArchive {
+    pub(crate) id: String,
+    #[serde(rename = "workspaceId")]
+    pub(crate) workspace_id: String,
+    #[serde(rename = "parentId")]
+    pub(crate) parent_id: String,
+    pub(crate) name: String,
+    pub(crate) branch: String,
+    #[serde(rename = "baseRef")]
+    pub(crate) base_ref: Option<String>,
+    #[serde(rename = "headSha")]
+    pub(crate) head_sha: String,
+    #[serde(rename = "archivedAt")]
+    pub(crate) archived_at: i64,
+    #[serde(rename = "patchBytes")]
+    pub(crate) patch_bytes: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct WorktreePrunePolicy {
+    #[serde(default, rename = "runOnStartup")]
+    pub(crate) run_on_startup: bool,
+    #[serde(
+        default = "default_prune_merged_after_days",
+        rename = "mergedAfterDays"
+    )]
+    pub(crate) merged_after_days: u32,
+    /// Archive merged worktrees that still have uncommitted changes instead of skipping them.
+    #[serde(default, rename = "archiveDirty")]
+    pub(crate) archive_dirty: bool,
+}
+
+fn default_prune_merged_after_days() -> u32 {
+    14
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct WorktreePruneResult {
+    #[serde(rename = "workspaceId")]
+    pub(crate) workspace_id: String,
+    pub(crate) name: String,
+    /// "removed", "archived", "would-remove", "would-archive" or "skipped".
+    pub(crate) action: String,
+    pub(crate) reason: Option<String>,
+    pub(crate) error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct FanoutCandidateSpec {
+    /// "claude", "codex" or "opencode"; defaults to the active engine.
+    #[serde(default)]
+    pub(crate) engine: Option<String>,
+    #[serde(default)]
+    pub(crate) model: Option<String>,
+    #[serde(default)]
+    pub(crate) effort: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub(crate) struct FanoutDiffStats {
+    pub(crate) files: usize,
+    pub(crate) additions: usize,
+    pub(crate) deletions: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct FanoutTestResult {
+    #[serde(rename = "exitCode")]
+    pub(crate) exit_code: i32,
+    pub(crate) success: bool,
+    /// Tail of the combined stdout/stderr.
+    pub(crate) output: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct FanoutCandidate {
+    #[serde(rename = "workspaceId")]
+    pub(crate) workspace_id: String,
+    pub(crate) branch: String,
+    pub(crate) engine: Option<String>,
+    pub(crate) model: Option<String>,
+    pub(crate) effort: Option<String>,
+    #[serde(rename = "threadId")]
+    pub(crate) thread_id: Option<String>,
+    /// "running", "completed" or "failed".
+    pub(crate) status: String,
+    pub(crate) error: Option<String>,
+    #[serde(rename = "tokenUsage")]
+    pub(crate) token_usage: Option<u64>,
+    #[serde(rename = "diffStats")]
+    pub(crate) diff_stats: Option<FanoutDiffStats>,
+    #[serde(rename = "testResult")]
+    pub(crate) test_result: Option<FanoutTestResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct FanoutRun {
+    pub(crate) id: String,
+    #[serde(rename = "parentId")]
+    pub(crate) parent_id: String,
+    pub(crate) prompt: String,
+    pub(crate) title: Option<String>,
+    #[serde(rename = "baseSha")]
+    pub(crate) base_sha: String,
+    #[serde(rename = "testCommand")]
+    pub(crate) test_command: Option<Vec<String>>,
+    #[serde(rename = "createdAt")]
+    pub(crate) created_at: i64,
+    /// "running", "evaluating" or "completed".
+    pub(crate) status: String,
+    pub(crate) candidates: Vec<FanoutCandidate>,
+    #[serde(rename = "winnerWorkspaceId")]
+    pub(crate) winner_workspace_id: Option<String>,
+    #[serde(skip)]
+    pub(crate) listener_id: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub(crate) struct WorkspaceGitPathStatus {
+    pub(crate) path: String,
+    /// Git status letter for the path, or `None` once it is clean again.
+    pub(crate) status: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub(crate) struct WorkspaceFilesChangedEvent {
+    #[serde(rename = "workspaceId")]
+    pub(crate) workspace_id: String,
+    pub(crate) added: Vec<String>,
+    pub(crate) removed: Vec<String>,
+    pub(crate) modified: Vec<String>,
+    #[serde(rename = "gitStatus")]
+    pub(crate) git_status: Vec<WorkspaceGitPathStatus>,
+    /// HEAD, refs, or the index moved; the full git status should be reloaded.
+    #[serde(rename = "gitChanged")]
+    pub(crate) git_changed: bool,
+    /// The watcher dropped events; the file tree should be listed again.
+    pub(crate) rescan: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct LspDiagnosticsEvent {
+    #[serde(rename = "workspaceId")]
+    pub(crate) workspace_id: String,
+    #[serde(rename = "serverId")]
+    pub(crate) server_id: String,
+    pub(crate) uri: String,
+    /// Workspace-relative path, when the document is inside the workspace.
+    pub(crate) path: Option<String>,
+    pub(crate) diagnostics: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct LspTurnDiagnosticsFile {
+    pub(crate) path: String,
+    #[serde(rename = "serverId")]
+    pub(crate) server_id: String,
+    /// Errors that were not reported for this file after the previous turn.
+    pub(crate) errors: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct LspTurnDiagnosticsEvent {
+    #[serde(rename = "workspaceId")]
+    pub(crate) workspace_id: String,
+    #[serde(rename = "threadId")]
+    pub(crate) thread_id: String,
+    pub(crate) files: Vec<LspTurnDiagnosticsFile>,
+    /// Follow-up messages sent for this thread so far, including this one.
+    #[serde(rename = "followUps")]
+    pub(crate) follow_ups: u32,
+    #[serde(rename = "followUpSent")]
+    pub(crate) follow_up_sent: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct WorkspaceContentSearchQuery {
+    pub(crate) pattern: String,
+    #[serde(default, rename = "isRegex")]
+    pub(crate) is_regex: bool,
+    #[serde(default, rename = "caseSensitive")]
+    pub(crate) case_sensitive: bool,
+    #[serde(default, rename = "wholeWord")]
+    pub(crate) whole_word: bool,
+    /// Glob patterns a file must match; empty means every file.
+    #[serde(default)]
+    pub(crate) include: Vec<String>,
+    #[serde(default)]
+    pub(crate) exclude: Vec<String>,
+    #[serde(default, rename = "contextLines")]
+    pub(crate) context_lines: usize,
+    #[serde(default = "default_search_max_results", rename = "maxResults")]
+    pub(crate) max_results: usize,
+}
+
+fn default_search_max_results() -> usize {
+    2000
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub(crate) struct WorkspaceSearchMatchRange {
+    /// Character offsets into `lineText`.
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct WorkspaceSearchLineMatch {
+    #[serde(rename = "lineNumber")]
+    pub(crate) line_number: u64,
+    #[serde(rename = "lineText")]
+    pub(crate) line_text: String,
+    pub(crate) ranges: Vec<WorkspaceSearchMatchRange>,
+    #[serde(rename = "contextBefore")]
+    pub(crate) context_before: Vec<String>,
+    #[serde(rename = "contextAfter")]
+    pub(crate) context_after: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct WorkspaceSearchFileResult {
+    pub(crate) path: String,
+    pub(crate) matches: Vec<WorkspaceSearchLineMatch>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct WorkspaceSearchMatchEvent {
+    #[serde(rename = "searchId")]
+    pub(crate) search_id: String,
+    #[serde(rename = "workspaceId")]
+    pub(crate) workspace_id: String,
+    pub(crate) file: WorkspaceSearchFileResult,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub(crate) struct WorkspaceSearchSummary {
+    #[serde(rename = "searchId")]
+    pub(crate) search_id: String,
+    #[serde(rename = "totalMatches")]
+    pub(crate) total_matches: usize,
+    #[serde(rename = "filesMatched")]
+    pub(crate) files_matched: usize,
+    pub(crate) truncated: bool,
+    pub(crate) cancelled: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct WorkspaceReplaceFilePreview {
+    pub(crate) path: String,
+    pub(crate) replacements: usize,
+    /// SHA-256 of the file as previewed; pass back to apply to detect edits.
+    #[serde(rename = "originalHash")]
+    pub(crate) original_hash: String,
+    pub(crate) diff: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct WorkspaceReplacePreview {
+    pub(crate) files: Vec<WorkspaceReplaceFilePreview>,
+    #[serde(rename = "totalReplacements")]
+    pub(crate) total_replacements: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct WorkspaceReplaceSkip {
+    pub(crate) path: String,
+    pub(crate) reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub(crate) struct WorkspaceReplaceResult {
+    #[serde(rename = "filesChanged")]
+    pub(crate) files_changed: Vec<String>,
+    #[serde(rename = "totalReplacements")]
+    pub(crate) total_replacements: usize,
+    pub(crate) skipped: Vec<WorkspaceReplaceSkip>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct OpenAppTarget {
     pub(crate) id: String,
@@ -735,6 +1460,61 @@ pub(crate) struct AppSettings {
     /// Default engine type: "claude", "codex", or "opencode". If not set, auto-detect.
     #[serde(default, rename = "defaultEngine")]
     pub(crate) default_engine: Option<String>,
+    #[serde(default, rename = "worktreePrunePolicy")]
+    pub(crate) worktree_prune_policy: Option<WorktreePrunePolicy>,
+    /// Minutes without activity before a Codex app-server session is shut down. 0 keeps sessions alive.
+    /// Desktop only: sessions the remote daemon spawns are not reaped.
+    #[serde(
+        default = "default_engine_session_idle_minutes",
+        rename = "engineSessionIdleMinutes"
+    )]
+    pub(crate) engine_session_idle_minutes: u32,
+    /// Overrides for the built-in language server table, matched by id.
+    #[serde(default, rename = "lspServers")]
+    pub(crate) lsp_servers: Vec<LspServerConfig>,
+    /// Check files an agent turn edited for new language server errors.
+    #[serde(default, rename = "lspFeedbackEnabled")]
+    pub(crate) lsp_feedback_enabled: bool,
+    /// Send new errors back to the same session as a follow-up message.
+    #[serde(default, rename = "lspFeedbackAutoFix")]
+    pub(crate) lsp_feedback_auto_fix: bool,
+    /// Follow-ups sent in a row before the loop waits for the user.
+    #[serde(
+        default = "default_lsp_feedback_max_iterations",
+        rename = "lspFeedbackMaxIterations"
+    )]
+    pub(crate) lsp_feedback_max_iterations: u32,
+    /// Snapshot files before agent tool writes and editor saves.
+    #[serde(
+        default = "default_file_history_enabled",
+        rename = "fileHistoryEnabled"
+    )]
+    pub(crate) file_history_enabled: bool,
+    /// Snapshots kept per file; older ones are pruned.
+    #[serde(
+        default = "default_file_history_max_snapshots",
+        rename = "fileHistoryMaxSnapshots"
+    )]
+    pub(crate) file_history_max_snapshots: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct LspServerConfig {
+    pub(crate) id: String,
+    #[serde(default = "default_lsp_server_enabled")]
+    pub(crate) enabled: bool,
+    #[serde(default)]
+    pub(crate) command: Option<String>,
+    #[serde(default)]
+    pub(crate) args: Option<Vec<String>>,
+    #[serde(default)]
+    pub(crate) extensions: Option<Vec<String>>,
+    #[serde(default, rename = "initializationOptions")]
+    pub(crate) initialization_options: Option<serde_json::Value>,
+}
+
+fn default_lsp_server_enabled() -> bool {
+    true
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -750,6 +1530,22 @@ impl Default for BackendMode {
     }
 }
 
+fn default_engine_session_idle_minutes() -> u32 {
+    30
+}
+
+fn default_lsp_feedback_max_iterations() -> u32 {
+    2
+}
+
+fn default_file_history_enabled() -> bool {
+    true
+}
+
+fn default_file_history_max_snapshots() -> u32 {
+    50
+}
+
 fn default_access_mode() -> String {
     "full-access".to_string()
 }
@@ -1094,6 +1890,14 @@ impl Default for AppSettings {
             workspace_groups: default_workspace_groups(),
             open_app_targets: default_open_app_targets(),
             selected_open_app_id: default_selected_open_app_id(),
+            worktree_prune_policy: None,
+            engine_session_idle_minutes: default_engine_session_idle_minutes(),
+            lsp_servers: Vec::new(),
+            lsp_feedback_enabled: false,
+            lsp_feedback_auto_fix: false,
+            lsp_feedback_max_iterations: default_lsp_feedback_max_iterations(),
+            file_history_enabled: default_file_history_enabled(),
+            file_history_max_snapshots: default_file_history_max_snapshots(),
         }
     }
 }
@@ -11,9 +11,8 @@ use uuid::Uuid;
 
 use super::files::{
     copy_workspace_item_inner, list_external_spec_tree_inner, list_workspace_files_inner,
-    read_external_spec_file_inner, read_workspace_file_inner, trash_workspace_item_inner,
-    write_external_spec_file_inner, write_workspace_file_inner, ExternalSpecFileResponse,
-    WorkspaceFileResponse, WorkspaceFilesResponse,
+    read_external_spec_file_inner, trash_workspace_item_inner, write_external_spec_file_inner,
+    write_workspace_file_inner, ExternalSpecFileResponse, WorkspaceFilesResponse,
 };
 use super::git::{
     git_branch_exists, git_find_remote_for_branch, git_get_origin_url, git_remote_branch_exists,
@@ -27,12 +26,17 @@ use super::worktree::{
     build_clone_destination_path, null_device_path, sanitize_worktree_name, unique_worktree_path,
     unique_worktree_path_for_rename,
 };
+use super::worktree_apply::resolve_worktree_and_parent;
 
 use crate::backend::app_server::WorkspaceSession;
 use crate::codex::args::resolve_workspace_codex_args;
 use crate::codex::home::resolve_workspace_codex_home;
 use crate::codex::spawn_workspace_session;
 use crate::engine::{resolve_engine_type, EngineType};
+use crate::files::content::{
+    read_workspace_file_inner, WorkspaceFileReadOptions, WorkspaceFileResponse,
+    WorkspaceFileWriteOptions,
+};
 use crate::git_utils::resolve_git_root;
 use crate::remote_backend;
 use crate::shared::workspaces_core;
@@ -273,7 +277,7 @@ async fn collect_workspace_cleanup_ids(
     ids
 }
 
-async fn cleanup_engine_sessions_for_workspace(state: &AppState, workspace_id: &str) {
+pub(crate) async fn cleanup_engine_sessions_for_workspace(state: &AppState, workspace_id: &str) {
     crate::engine::commands::clear_mcp_toggle_state(workspace_id);
     state
         .engine_manager
@@ -289,10 +293,23 @@ async fn cleanup_engine_sessions_for_workspace(state: &AppState, workspace_id: &
         .await;
 }
 
+/// Stops the sessions, watchers and language servers of a removed workspace
+/// and drops everything cached for it.
+async fn forget_workspace_caches(state: &AppState, workspace_id: &str) {
+    cleanup_engine_sessions_for_workspace(state, workspace_id).await;
+    state.file_watchers.lock().await.remove(workspace_id);
+    crate::code_intel::drop_symbol_index(state, workspace_id).await;
+    crate::git::forget_submodule_statuses(workspace_id);
+    crate::git::forget_commit_graphs(workspace_id);
+    crate::lsp::stop_workspace_servers(state, workspace_id).await;
+    crate::files::history::drop_file_history(state, workspace_id);
+}
+
 #[tauri::command]
 pub(crate) async fn read_workspace_file(
     workspace_id: String,
     path: String,
+    options: Option<WorkspaceFileReadOptions>,
     state: State<'_, AppState>,
     app: AppHandle,
 ) -> Result<WorkspaceFileResponse, String> {
@@ -301,17 +318,18 @@ pub(crate) async fn read_workspace_file(
             &*state,
             app,
             "read_workspace_file",
-            json!({ "workspaceId": workspace_id, "path": path }),
+            json!({ "workspaceId": workspace_id, "path": path, "options": options }),
         )
         .await?;
         return serde_json::from_value(response).map_err(|err| err.to_string());
     }
 
+    let options = options.unwrap_or_default();
     workspaces_core::read_workspace_file_core(
         &state.workspaces,
         &workspace_id,
         &path,
-        |root, rel_path| read_workspace_file_inner(root, rel_path),
+        |root, rel_path| read_workspace_file_inner(root, rel_path, &options),
     )
     .await
 }
@@ -321,6 +339,7 @@ pub(crate) async fn write_workspace_file(
     workspace_id: String,
     path: String,
     content: String,
+    options: Option<WorkspaceFileWriteOptions>,
     state: State<'_, AppState>,
     app: AppHandle,
 ) -> Result<(), String> {
@@ -329,18 +348,25 @@ pub(crate) async fn write_workspace_file(
             &*state,
             app,
             "write_workspace_file",
-            json!({ "workspaceId": workspace_id, "path": path, "content": content }),
+            json!({
+                "workspaceId": workspace_id,
+                "path": path,
+                "content": content,
+                "options": options,
+            }),
         )
         .await?;
         return Ok(());
     }
 
+    let options = options.unwrap_or_default();
+    crate::files::history::snapshot_before_write(&state, &workspace_id, &path).await;
     workspaces_core::write_workspace_file_core(
         &state.workspaces,
         &workspace_id,
         &path,
         &content,
-        |root, rel_path, data| write_workspace_file_inner(root, rel_path, data),
+        |root, rel_path, data| write_workspace_file_inner(root, rel_path, data, &options),
     )
     .await
 }
@@ -617,17 +643,13 @@ pub(crate) async fn add_workspace(
             add_workspace_for_cli_engine(EngineType::Claude, path, codex_bin, &state).await
         }
         EngineType::Codex => {
-            // For Codex: Use existing app-server based session
+            // The Codex app-server session is started on first use.
             workspaces_core::add_workspace_core(
                 path,
                 codex_bin,
+                Some("codex".to_string()),
                 &state.workspaces,
-                &state.sessions,
-                &state.app_settings,
                 &state.storage_path,
-                |entry, default_bin, codex_args, codex_home| {
-                    spawn_with_app(&app, entry, default_bin, codex_args, codex_home)
-                },
             )
             .await
         }
@@ -679,46 +701,14 @@ async fn add_workspace_for_cli_engine(
         return Err(format!("CLI_NOT_FOUND:{}", engine_name));
     }
 
-    let name = PathBuf::from(&path)
-        .file_name()
-        .and_then(|s| s.to_str())
-        .unwrap_or("Workspace")
-        .to_string();
-
-    let settings = WorkspaceSettings {
-        engine_type: Some(engine_name.to_string()),
-        ..WorkspaceSettings::default()
-    };
-
-    let entry = WorkspaceEntry {
-        id: Uuid::new_v4().to_string(),
-        name: name.clone(),
-        path: path.clone(),
+    workspaces_core::add_workspace_core(
+        path,
         codex_bin,
-        kind: WorkspaceKind::Main,
-        parent_id: None,
-        worktree: None,
-        settings,
-    };
-
-    {
-        let mut workspaces = state.workspaces.lock().await;
-        workspaces.insert(entry.id.clone(), entry.clone());
-        let list: Vec<_> = workspaces.values().cloned().collect();
-        write_workspaces(&state.storage_path, &list)?;
-    }
-
-    Ok(WorkspaceInfo {
-        id: entry.id,
-        name: entry.name,
-        path: entry.path,
-        codex_bin: entry.codex_bin,
-        connected: true,
-        kind: entry.kind,
-        parent_id: entry.parent_id,
-        worktree: entry.worktree,
-        settings: entry.settings,
-    })
+        Some(engine_name.to_string()),
+        &state.workspaces,
+        &state.storage_path,
+    )
+    .await
 }
 
 #[tauri::command]
@@ -883,6 +873,29 @@ pub(crate) async fn add_worktree(
         return serde_json::from_value(response).map_err(|err| err.to_string());
     }
 
+    add_local_worktree(
+        parent_id,
+        branch,
+        base_ref,
+        publish_to_origin,
+        false,
+        &state,
+        &app,
+    )
+    .await
+}
+
+/// Creates a worktree of a local workspace. Transient worktrees are kept out
+/// of storage and the workspace list, for checkouts the app cleans up itself.
+pub(crate) async fn add_local_worktree(
+    parent_id: String,
+    branch: String,
+    base_ref: Option<String>,
+    publish_to_origin: bool,
+    transient: bool,
+    state: &AppState,
+    app: &AppHandle,
+) -> Result<WorkspaceInfo, String> {
     let data_dir = app
         .path()
         .app_data_dir()
@@ -893,10 +906,9 @@ pub(crate) async fn add_worktree(
         branch,
         base_ref,
         publish_to_origin,
+        transient,
         &data_dir,
         &state.workspaces,
-        &state.sessions,
-        &state.app_settings,
         &state.storage_path,
         |value| sanitize_worktree_name(value),
         |root, name| Ok(unique_worktree_path(root, name)),
@@ -911,9 +923,6 @@ pub(crate) async fn add_worktree(
                 run_git_command_owned(repo, args_owned)
             })
         },
-        |entry, default_bin, codex_args, codex_home| {
-            spawn_with_app(&app, entry, default_bin, codex_args, codex_home)
-        },
     )
     .await
 }
@@ -1000,7 +1009,7 @@ pub(crate) async fn remove_workspace(
     .await?;
 
     for workspace_id in cleanup_ids {
-        cleanup_engine_sessions_for_workspace(&state, &workspace_id).await;
+        forget_workspace_caches(&state, &workspace_id).await;
     }
 
     Ok(())
@@ -1035,7 +1044,7 @@ pub(crate) async fn remove_worktree(
     )
     .await?;
 
-    cleanup_engine_sessions_for_workspace(&state, &id).await;
+    forget_workspace_caches(&state, &id).await;
 
     Ok(())
 }
@@ -1155,22 +1164,7 @@ pub(crate) async fn apply_worktree_changes(
     workspace_id: String,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    let (entry, parent) = {
-        let workspaces = state.workspaces.lock().await;
-        let entry = workspaces
-            .get(&workspace_id)
-            .cloned()
-            .ok_or("workspace not found")?;
-        if !entry.kind.is_worktree() {
-            return Err("Not a worktree workspace.".to_string());
-        }
-        let parent_id = entry.parent_id.clone().ok_or("worktree parent not found")?;
-        let parent = workspaces
-            .get(&parent_id)
-            .cloned()
-            .ok_or("worktree parent not found")?;
-        (entry, parent)
-    };
+    let (entry, parent) = resolve_worktree_and_parent(&state, &workspace_id).await?;
 
     let worktree_root = resolve_git_root(&entry)?;
     let parent_root = resolve_git_root(&parent)?;
@@ -1361,16 +1355,8 @@ pub(crate) async fn connect_workspace(
             .ok_or_else(|| "workspace not found".to_string())?
     };
 
-    // Check engine type - default to Claude if not specified
-    let is_claude_engine = entry
-        .settings
-        .engine_type
-        .as_deref()
-        .map(|e| e.eq_ignore_ascii_case("claude"))
-        .unwrap_or(true);
-
-    if is_claude_engine {
-        // For Claude: No persistent session needed, already "connected"
+    if !workspaces_core::workspace_uses_codex_session(&entry) {
+        // CLI engines spawn per turn; there is no session to start.
         Ok(())
     } else {
         // For Codex: Use existing session spawn logic
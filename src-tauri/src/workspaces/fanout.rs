@@ -0,0 +1,806 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Listener, Manager, State};
+use uuid::Uuid;
+
+use super::commands::{add_worktree, remove_worktree, run_workspace_command};
+use super::git::run_git_command;
+use super::worktree_apply::{
+    apply_output_detail, collect_worktree_patch_since, is_repo_dirty, resolve_worktree_and_parent,
+    run_git_apply,
+};
+
+use crate::engine::EngineType;
+use crate::git::{git_args_with_identity, identity_for_entry};
+use crate::git_utils::resolve_git_root;
+use crate::remote_backend;
+use crate::shared::codex_core::extract_thread_id_from_response;
+use crate::state::AppState;
+use crate::types::{
+    FanoutCandidate, FanoutCandidateSpec, FanoutDiffStats, FanoutRun, FanoutTestResult,
+};
+
+const MAX_FANOUT_CANDIDATES: usize = 8;
+const TEST_TIMEOUT_MS: u64 = 10 * 60 * 1000;
+const TEST_OUTPUT_TAIL_CHARS: usize = 4000;
+const FANOUT_EVENT: &str = "fanout-run-updated";
+
+fn fallback_worktree_name(run_id: &str) -> String {
+    format!("fanout/{}", &run_id[..8.min(run_id.len())])
+}
+
+fn candidate_branch(base_name: &str, index: usize) -> String {
+    format!("{}-{}", base_name.trim_end_matches('-'), index + 1)
+}
+
+fn parse_engine(value: Option<&str>) -> Result<Option<EngineType>, String> {
+    let Some(value) = value.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(None);
+    };
+    serde_json::from_value(Value::String(value.to_lowercase()))
+        .map(Some)
+        .map_err(|_| format!("Unknown engine: {value}"))
+}
+
+/// Totals for `git diff --numstat`; binary files count as changed files only.
+fn parse_numstat(output: &str) -> FanoutDiffStats {
+    let mut stats = FanoutDiffStats::default();
+    for line in output.lines() {
+        let mut parts = line.splitn(3, '\t');
+        let (Some(added), Some(removed), Some(_path)) = (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        stats.files += 1;
+        stats.additions += added.parse::<usize>().unwrap_or(0);
+        stats.deletions += removed.parse::<usize>().unwrap_or(0);
+    }
+    stats
+}
+
+fn tail_chars(text: &str, max: usize) -> String {
+    let count = text.chars().count();
+    if count <= max {
+        return text.to_string();
+    }
+    text.chars().skip(count - max).collect()
+}
+
+/// Folds one `app-server-event` message into the candidate running in
+/// `workspace_id`. Candidate worktrees are created for the run, so every turn
+/// event in one belongs to it. Returns whether the run changed.
+fn apply_candidate_event(run: &mut FanoutRun, workspace_id: &str, message: &Value) -> bool {
+    if run.status != "running" {
+        return false;
+    }
+    let Some(candidate) = run
+        .candidates
+        .iter_mut()
+        .find(|candidate| candidate.workspace_id == workspace_id)
+    else {
+        return false;
+    };
+    if candidate.status != "running" {
+        return false;
+    }
+    let params = message.get("params");
+    match message.get("method").and_then(Value::as_str) {
+        Some("turn/completed") => {
+            candidate.status = "completed".to_string();
+            true
+        }
+        Some("turn/error") => {
+            candidate.status = "failed".to_string();
+            candidate.error = Some(
+                params
+                    .and_then(|params| params.get("error"))
+                    .and_then(|error| {
+                        error
+                            .as_str()
+                            .or_else(|| error.get("message").and_then(Value::as_str))
+                    })
+                    .unwrap_or("Turn failed")
+                    .to_string(),
+            );
+            true
+        }
+        Some("thread/tokenUsage/updated") => {
+            let total = params
+                .and_then(|params| params.get("tokenUsage"))
+                .and_then(|usage| usage.get("total"))
+                .and_then(|total| total.get("totalTokens"))
+                .and_then(Value::as_u64);
+            if total.is_none() || total == candidate.token_usage {
+                return false;
+            }
+            candidate.token_usage = total;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Moves a running run to "evaluating" once no candidate is still running.
+/// Returns true exactly once per run, for the caller that should evaluate it.
+fn begin_evaluation(run: &mut FanoutRun) -> bool {
+    if run.status != "running"
+        || run
+            .candidates
+            .iter()
+            .any(|candidate| candidate.status == "running")
+    {
+        return false;
+    }
+    run.status = "evaluating".to_string();
+    true
+}
+
+fn emit_run(app: &AppHandle, run: &FanoutRun) {
+    let _ = app.emit(FANOUT_EVENT, run);
+}
+
+async fn handle_candidate_event(
+    app: AppHandle,
+    run_id: String,
+    workspace_id: String,
+    message: Value,
+) {
+    let state = app.state::<AppState>();
+    let evaluate = {
+        let mut runs = state.fanout_runs.lock().await;
+        let Some(run) = runs.get_mut(&run_id) else {
+            return;
+        };
+        if !apply_candidate_event(run, &workspace_id, &message) {
+            return;
+        }
+        let evaluate = begin_evaluation(run);
+        emit_run(&app, run);
+        evaluate
+    };
+    if evaluate {
+        evaluate_fanout_run(&app, &run_id).await;
+    }
+}
+
+fn listen_for_candidate_events(app: &AppHandle, run_id: &str) -> u32 {
+    let handle = app.clone();
+    let run_id = run_id.to_string();
+    app.listen("app-server-event", move |event| {
+        let Ok(payload) = serde_json::from_str::<Value>(event.payload()) else {
+            return;
+        };
+        let method = payload
+            .get("message")
+            .and_then(|message| message.get("method"))
+            .and_then(Value::as_str)
+            .unwrap_or("");
+        if !matches!(
+            method,
+            "turn/completed" | "turn/error" | "thread/tokenUsage/updated"
+        ) {
+            return;
+        }
+        let Some(workspace_id) = payload.get("workspace_id").and_then(Value::as_str) else {
+            return;
+        };
+        tauri::async_runtime::spawn(handle_candidate_event(
+            handle.clone(),
+            run_id.clone(),
+            workspace_id.to_string(),
+            payload["message"].clone(),
+        ));
+    })
+}
+
+async fn candidate_diff_stats(
+    state: &State<'_, AppState>,
+    workspace_id: &str,
+    base_sha: &str,
+) -> Result<FanoutDiffStats, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    let root = resolve_git_root(&entry)?;
+    let numstat = run_git_command(&root, &["diff", "--numstat", base_sha]).await?;
+    let mut stats = parse_numstat(&numstat);
+    let untracked = run_git_command(&root, &["ls-files", "--others", "--exclude-standard"]).await?;
+    for path in untracked.lines().filter(|line| !line.is_empty()) {
+        stats.files += 1;
+        if let Ok(contents) = fs::read_to_string(root.join(path)) {
+            stats.additions += contents.lines().count();
+        }
+    }
+    Ok(stats)
+}
+
+async fn run_candidate_test(
+    app: &AppHandle,
+    workspace_id: &str,
+    command: &[String],
+) -> FanoutTestResult {
+    match run_workspace_command(
+        workspace_id.to_string(),
+        command.to_vec(),
+        Some(TEST_TIMEOUT_MS),
+        app.state(),
+        app.clone(),
+    )
+    .await
+    {
+        Ok(result) => {
+            let output = if result.stderr.trim().is_empty() {
+                result.stdout
+            } else {
+                format!("{}\n{}", result.stdout, result.stderr)
+            };
+            FanoutTestResult {
+                exit_code: result.exit_code,
+                success: result.success,
+                output: tail_chars(output.trim(), TEST_OUTPUT_TAIL_CHARS),
+            }
+        }
+        Err(error) => FanoutTestResult {
+            exit_code: -1,
+            success: false,
+            output: error,
+        },
+    }
+}
+
+/// Collects diff stats and test results for every candidate. Tests run one
+/// candidate at a time so they don't compete for the same machine.
+async fn evaluate_fanout_run(app: &AppHandle, run_id: &str) {
+    let state = app.state::<AppState>();
+    let (candidates, base_sha, test_command) = {
+        let mut runs = state.fanout_runs.lock().await;
+        let Some(run) = runs.get_mut(run_id) else {
+            return;
+        };
+        if let Some(listener_id) = run.listener_id.take() {
+            app.unlisten(listener_id);
+        }
+        (
+            run.candidates.clone(),
+            run.base_sha.clone(),
+            run.test_command.clone(),
+        )
+    };
+
+    for candidate in candidates
+        .iter()
+        .filter(|candidate| !candidate.workspace_id.is_empty())
+    {
+        let diff_stats = candidate_diff_stats(&state, &candidate.workspace_id, &base_sha)
+            .await
+            .ok();
+        let test_result = match test_command.as_deref() {
+            Some(command) if !command.is_empty() && candidate.status == "completed" => {
+                Some(run_candidate_test(app, &candidate.workspace_id, command).await)
+            }
+            _ => None,
+        };
+        let mut runs = state.fanout_runs.lock().await;
+        let Some(run) = runs.get_mut(run_id) else {
+            return;
+        };
+        if let Some(entry) = run
+            .candidates
+            .iter_mut()
+            .find(|entry| entry.workspace_id == candidate.workspace_id)
+        {
+            entry.diff_stats = diff_stats;
+            entry.test_result = test_result;
+        }
+        emit_run(app, run);
+    }
+
+    let mut runs = state.fanout_runs.lock().await;
+    if let Some(run) = runs.get_mut(run_id) {
+        run.status = "completed".to_string();
+        emit_run(app, run);
+    }
+}
+
+async fn start_candidate_turn(
+    app: &AppHandle,
+    workspace_id: &str,
+    prompt: &str,
+    spec: &FanoutCandidateSpec,
+    access_mode: Option<String>,
+) -> Result<Option<String>, String> {
+    let state = app.state::<AppState>();
+    let engine = match parse_engine(spec.engine.as_deref())? {
+        Some(engine) => engine,
+        None => state.engine_manager.get_active_engine().await,
+    };
+    if engine == EngineType::Codex {
+        let response =
+            crate::codex::start_thread(workspace_id.to_string(), app.state(), app.clone()).await?;
+        let thread_id = extract_thread_id_from_response(&response)
+            .ok_or("Failed to start a thread for the candidate.")?;
+        crate::codex::send_user_message(
+            workspace_id.to_string(),
+            thread_id.clone(),
+            prompt.to_string(),
+            spec.model.clone(),
+            spec.effort.clone(),
+            access_mode,
+            None,
+            None,
+            None,
+            None,
+            app.state(),
+            app.clone(),
+        )
+        .await?;
+        return Ok(Some(thread_id));
+    }
+    let response = crate::engine::engine_send_message(
+        workspace_id.to_string(),
+        prompt.to_string(),
+        Some(engine),
+        spec.model.clone(),
+        spec.effort.clone(),
+        access_mode,
+        None,
+        false,
+        None,
+        None,
+        None,
+        None,
+        None,
+        app.clone(),
+        app.state(),
+    )
+    .await?;
+    Ok(extract_thread_id_from_response(&response))
+}
+
+async fn mark_candidate_started(
+    app: &AppHandle,
+    run_id: &str,
+    workspace_id: &str,
+    thread_id: String,
+) {
+    let state = app.state::<AppState>();
+    let mut runs = state.fanout_runs.lock().await;
+    let Some(run) = runs.get_mut(run_id) else {
+        return;
+    };
+    if let Some(candidate) = run
+        .candidates
+        .iter_mut()
+        .find(|candidate| candidate.workspace_id == workspace_id)
+    {
+        candidate.thread_id = Some(thread_id);
+        emit_run(app, run);
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn start_fanout_run(
+    parent_id: String,
+    prompt: String,
+    candidates: Vec<FanoutCandidateSpec>,
+    base_ref: Option<String>,
+    test_command: Option<Vec<String>>,
+    access_mode: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<FanoutRun, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return Err("Fan-out runs are not supported in remote mode yet.".to_string());
+    }
+    let prompt = prompt.trim().to_string();
+    if prompt.is_empty() {
+        return Err("Prompt is required.".to_string());
+    }
+    if candidates.is_empty() {
+        return Err("At least one candidate is required.".to_string());
+    }
+    if candidates.len() > MAX_FANOUT_CANDIDATES {
+        return Err(format!(
+            "A fan-out run supports at most {MAX_FANOUT_CANDIDATES} candidates."
+        ));
+    }
+    for spec in &candidates {
+        parse_engine(spec.engine.as_deref())?;
+    }
+
+    let parent = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&parent_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    if parent.kind.is_worktree() {
+        return Err("Fan-out runs must start from a main workspace.".to_string());
+    }
+    let parent_root = resolve_git_root(&parent)?;
+    let base_ref = base_ref
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "HEAD".to_string());
+    let base_sha = run_git_command(
+        &parent_root,
+        &["rev-parse", "--verify", &format!("{base_ref}^{{commit}}")],
+    )
+    .await?
+    .trim()
+    .to_string();
+
+    let run_id = Uuid::new_v4().to_string();
+    let metadata = crate::codex::generate_run_metadata(
+        parent_id.clone(),
+        prompt.clone(),
+        app.state(),
+        app.clone(),
+    )
+    .await
+    .ok();
+    let title = metadata
+        .as_ref()
+        .and_then(|value| value.get("title"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let worktree_name = metadata
+        .as_ref()
+        .and_then(|value| value.get("worktreeName"))
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| fallback_worktree_name(&run_id));
+
+    let test_command = test_command.filter(|command| !command.is_empty());
+    let listener_id = listen_for_candidate_events(&app, &run_id);
+    let run = FanoutRun {
+        id: run_id.clone(),
+        parent_id: parent_id.clone(),
+        prompt: prompt.clone(),
+        title,
+        base_sha: base_sha.clone(),
+        test_command,
+        created_at: chrono::Utc::now().timestamp_millis(),
+        status: "running".to_string(),
+        candidates: Vec::new(),
+        winner_workspace_id: None,
+        listener_id: Some(listener_id),
+    };
+    state.fanout_runs.lock().await.insert(run_id.clone(), run);
+
+    for (index, spec) in candidates.into_iter().enumerate() {
+        let branch = candidate_branch(&worktree_name, index);
+        let worktree = add_worktree(
+            parent_id.clone(),
+            branch.clone(),
+            Some(base_sha.clone()),
+            Some(false),
+            app.state(),
+            app.clone(),
+        )
+        .await;
+        let (workspace_id, status, error) = match &worktree {
+            Ok(info) => (info.id.clone(), "running", None),
+            Err(error) => (String::new(), "failed", Some(error.clone())),
+        };
+        {
+            let mut runs = state.fanout_runs.lock().await;
+            if let Some(run) = runs.get_mut(&run_id) {
+                run.candidates.push(FanoutCandidate {
+                    workspace_id: workspace_id.clone(),
+                    branch,
+                    engine: spec.engine.clone(),
+                    model: spec.model.clone(),
+                    effort: spec.effort.clone(),
+                    thread_id: None,
+                    status: status.to_string(),
+                    error,
+                    token_usage: None,
+                    diff_stats: None,
+                    test_result: None,
+                });
+            }
+        }
+        if worktree.is_err() {
+            continue;
+        }
+
+        // Turns are started concurrently; some engines only return once the
+        // turn has finished.
+        let handle = app.clone();
+        let run_id = run_id.clone();
+        let prompt = prompt.clone();
+        let access_mode = access_mode.clone();
+        tauri::async_runtime::spawn(async move {
+            match start_candidate_turn(&handle, &workspace_id, &prompt, &spec, access_mode).await {
+                Ok(Some(thread_id)) => {
+                    mark_candidate_started(&handle, &run_id, &workspace_id, thread_id).await;
+                }
+                Ok(None) => {}
+                Err(error) => {
+                    let message = json!({ "method": "turn/error", "params": { "error": error } });
+                    handle_candidate_event(handle.clone(), run_id, workspace_id, message).await;
+                }
+            }
+        });
+    }
+
+    let (run, evaluate) = {
+        let mut runs = state.fanout_runs.lock().await;
+        let run = runs.get_mut(&run_id).ok_or("fan-out run not found")?;
+        let evaluate = begin_evaluation(run);
+        emit_run(&app, run);
+        (run.clone(), evaluate)
+    };
+    if evaluate {
+        evaluate_fanout_run(&app, &run_id).await;
+    }
+    Ok(run)
+}
+
+#[tauri::command]
+pub(crate) async fn get_fanout_run(
+    run_id: String,
+    state: State<'_, AppState>,
+) -> Result<FanoutRun, String> {
+    state
+        .fanout_runs
+        .lock()
+        .await
+        .get(&run_id)
+        .cloned()
+        .ok_or_else(|| "fan-out run not found".to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn list_fanout_runs(
+    parent_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<FanoutRun>, String> {
+    let mut runs: Vec<FanoutRun> = state
+        .fanout_runs
+        .lock()
+        .await
+        .values()
+        .filter(|run| {
+            parent_id
+                .as_deref()
+                .is_none_or(|parent_id| run.parent_id == parent_id)
+        })
+        .cloned()
+        .collect();
+    runs.sort_by_key(|run| std::cmp::Reverse(run.created_at));
+    Ok(runs)
+}
+
+/// Applies the winning candidate's changes since the run's base commit to the
+/// parent workspace, optionally committing and removing the other candidates.
+#[tauri::command]
+pub(crate) async fn apply_fanout_winner(
+    run_id: String,
+    workspace_id: String,
+    commit: Option<bool>,
+    commit_message: Option<String>,
+    remove_others: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<FanoutRun, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return Err("Fan-out runs are not supported in remote mode yet.".to_string());
+    }
+    let run = state
+        .fanout_runs
+        .lock()
+        .await
+        .get(&run_id)
+        .cloned()
+        .ok_or("fan-out run not found")?;
+    if run.status != "completed" {
+        return Err("Fan-out run is still in progress.".to_string());
+    }
+    let candidate = run
+        .candidates
+        .iter()
+        .find(|candidate| candidate.workspace_id == workspace_id && !workspace_id.is_empty())
+        .cloned()
+        .ok_or("Workspace is not a candidate of this fan-out run.")?;
+
+    let (entry, parent) = resolve_worktree_and_parent(&state, &workspace_id).await?;
+    let worktree_root = resolve_git_root(&entry)?;
+    let parent_root: PathBuf = resolve_git_root(&parent)?;
+    if is_repo_dirty(&parent_root).await? {
+        return Err(
+            "Commit or stash changes in the parent workspace before applying a candidate."
+                .to_string(),
+        );
+    }
+    let patch = collect_worktree_patch_since(&worktree_root, &run.base_sha).await?;
+    if patch.iter().all(|byte| byte.is_ascii_whitespace()) {
+        return Err("Candidate has no changes to apply.".to_string());
+    }
+    let output = run_git_apply(
+        &parent_root,
+        &["--3way", "--whitespace=nowarn"],
+        &patch,
+        None,
+    )
+    .await?;
+    if !output.status.success() {
+        let detail = apply_output_detail(&output);
+        return Err(if detail.is_empty() {
+            "Git apply failed.".to_string()
+        } else {
+            detail
+        });
+    }
+
+    if commit.unwrap_or(false) {
+        let message = commit_message
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| {
+                let summary = run
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| run.prompt.lines().next().unwrap_or("").to_string());
+                format!(
+                    "{summary}\n\nApplied fan-out candidate {}",
+                    candidate.branch
+                )
+            });
+        let identity = {
+            let workspaces = state.workspaces.lock().await;
+            identity_for_entry(&workspaces, &parent)
+        };
+        let args = git_args_with_identity(identity.as_ref(), &["commit", "-m", &message])?;
+        let arg_refs = args.iter().map(String::as_str).collect::<Vec<_>>();
+        run_git_command(&parent_root, &arg_refs).await?;
+    }
+
+    if remove_others.unwrap_or(false) {
+        for other in run
+            .candidates
+            .iter()
+            .filter(|other| !other.workspace_id.is_empty() && other.workspace_id != workspace_id)
+        {
+            if let Err(error) =
+                remove_worktree(other.workspace_id.clone(), app.state(), app.clone()).await
+            {
+                log::warn!(
+                    "Failed to remove fan-out candidate {}: {error}",
+                    other.branch
+                );
+            }
+        }
+    }
+
+    let mut runs = state.fanout_runs.lock().await;
+    let run = runs.get_mut(&run_id).ok_or("fan-out run not found")?;
+    run.winner_workspace_id = Some(workspace_id);
+    emit_run(&app, run);
+    Ok(run.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(workspace_id: &str) -> FanoutCandidate {
+        FanoutCandidate {
+            workspace_id: workspace_id.to_string(),
+            branch: format!("fanout/x-{workspace_id}"),
+            engine: None,
+            model: None,
+            effort: None,
+            thread_id: None,
+            status: "running".to_string(),
+            error: None,
+            token_usage: None,
+            diff_stats: None,
+            test_result: None,
+        }
+    }
+
+    fn run(candidates: Vec<FanoutCandidate>) -> FanoutRun {
+        FanoutRun {
+            id: "run".to_string(),
+            parent_id: "parent".to_string(),
+            prompt: "do it".to_string(),
+            title: None,
+            base_sha: "abc".to_string(),
+            test_command: None,
+            created_at: 0,
+            status: "running".to_string(),
+            candidates,
+            winner_workspace_id: None,
+            listener_id: None,
+        }
+    }
+
+    #[test]
+    fn parses_numstat_including_binary_files() {
+        let stats = parse_numstat("3\t1\tsrc/a.rs\n-\t-\tlogo.png\n0\t7\tREADME.md\n");
+        assert_eq!(
+            stats,
+            FanoutDiffStats {
+                files: 3,
+                additions: 3,
+                deletions: 8,
+            }
+        );
+        assert_eq!(parse_numstat(""), FanoutDiffStats::default());
+    }
+
+    #[test]
+    fn names_candidate_branches_from_the_run_name() {
+        assert_eq!(candidate_branch("feat/login", 0), "feat/login-1");
+        assert_eq!(candidate_branch("feat/login-", 2), "feat/login-3");
+        assert_eq!(
+            fallback_worktree_name("0123456789abcdef"),
+            "fanout/01234567"
+        );
+    }
+
+    #[test]
+    fn parses_engine_names() {
+        assert_eq!(parse_engine(None), Ok(None));
+        assert_eq!(parse_engine(Some(" ")), Ok(None));
+        assert_eq!(parse_engine(Some("Claude")), Ok(Some(EngineType::Claude)));
+        assert_eq!(
+            parse_engine(Some("opencode")),
+            Ok(Some(EngineType::OpenCode))
+        );
+        assert!(parse_engine(Some("vim")).is_err());
+    }
+
+    #[test]
+    fn candidate_events_drive_the_run_to_evaluation() {
+        let mut run = run(vec![candidate("a"), candidate("b")]);
+        let usage = json!({
+            "method": "thread/tokenUsage/updated",
+            "params": { "tokenUsage": { "total": { "totalTokens": 1200 } } }
+        });
+        assert!(apply_candidate_event(&mut run, "a", &usage));
+        assert!(!apply_candidate_event(&mut run, "a", &usage));
+        assert_eq!(run.candidates[0].token_usage, Some(1200));
+        assert!(!apply_candidate_event(
+            &mut run,
+            "other",
+            &json!({ "method": "turn/completed" })
+        ));
+
+        assert!(apply_candidate_event(
+            &mut run,
+            "a",
+            &json!({ "method": "turn/completed", "params": {} })
+        ));
+        assert!(!begin_evaluation(&mut run));
+        assert!(apply_candidate_event(
+            &mut run,
+            "b",
+            &json!({ "method": "turn/error", "params": { "error": { "message": "boom" } } })
+        ));
+        assert_eq!(run.candidates[1].status, "failed");
+        assert_eq!(run.candidates[1].error.as_deref(), Some("boom"));
+
+        assert!(begin_evaluation(&mut run));
+        assert_eq!(run.status, "evaluating");
+        assert!(!begin_evaluation(&mut run));
+        assert!(!apply_candidate_event(&mut run, "a", &usage));
+    }
+
+    #[test]
+    fn keeps_the_tail_of_long_output() {
+        assert_eq!(tail_chars("short", 10), "short");
+        assert_eq!(tail_chars("0123456789", 4), "6789");
+    }
+}
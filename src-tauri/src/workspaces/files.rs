@@ -6,16 +6,17 @@ use git2::Repository;
 use ignore::WalkBuilder;
 use serde::{Deserialize, Serialize};
 
+use crate::files::content::{encode_file_content, WorkspaceFileWriteOptions};
 use crate::utils::normalize_git_path;
 
-fn should_always_skip(name: &str) -> bool {
+pub(super) fn should_always_skip(name: &str) -> bool {
     name == ".git"
 }
 
 /// Dependency / build-output directories whose deep contents create
 /// excessive noise. We still list the directory itself in the response
 /// (so the frontend can show it grayed out) but we do NOT recurse into it.
-fn is_heavy_directory(name: &str) -> bool {
+pub(super) fn is_heavy_directory(name: &str) -> bool {
     matches!(
         name,
         "node_modules"
@@ -151,12 +152,6 @@ pub(crate) fn list_workspace_files_inner(
 
 const MAX_WORKSPACE_FILE_BYTES: u64 = 400_000;
 
-#[derive(Serialize, Deserialize, Clone)]
-pub(crate) struct WorkspaceFileResponse {
-    content: String,
-    truncated: bool,
-}
-
 #[derive(Serialize, Deserialize, Clone)]
 pub(crate) struct ExternalSpecFileResponse {
     pub(crate) exists: bool,
@@ -360,45 +355,11 @@ pub(crate) fn write_external_spec_file_inner(
     Ok(())
 }
 
-pub(crate) fn read_workspace_file_inner(
-    root: &PathBuf,
-    relative_path: &str,
-) -> Result<WorkspaceFileResponse, String> {
-    let canonical_root = root
-        .canonicalize()
-        .map_err(|err| format!("Failed to resolve workspace root: {err}"))?;
-    let candidate = canonical_root.join(relative_path);
-    let canonical_path = candidate
-        .canonicalize()
-        .map_err(|err| format!("Failed to open file: {err}"))?;
-    if !canonical_path.starts_with(&canonical_root) {
-        return Err("Invalid file path".to_string());
-    }
-    let metadata = std::fs::metadata(&canonical_path)
-        .map_err(|err| format!("Failed to read file metadata: {err}"))?;
-    if !metadata.is_file() {
-        return Err("Path is not a file".to_string());
-    }
-
-    let file = File::open(&canonical_path).map_err(|err| format!("Failed to open file: {err}"))?;
-    let mut buffer = Vec::new();
-    file.take(MAX_WORKSPACE_FILE_BYTES + 1)
-        .read_to_end(&mut buffer)
-        .map_err(|err| format!("Failed to read file: {err}"))?;
-
-    let truncated = buffer.len() > MAX_WORKSPACE_FILE_BYTES as usize;
-    if truncated {
-        buffer.truncate(MAX_WORKSPACE_FILE_BYTES as usize);
-    }
-
-    let content = String::from_utf8(buffer).map_err(|_| "File is not valid UTF-8".to_string())?;
-    Ok(WorkspaceFileResponse { content, truncated })
-}
-
 pub(crate) fn write_workspace_file_inner(
     root: &PathBuf,
     relative_path: &str,
     content: &str,
+    options: &WorkspaceFileWriteOptions,
 ) -> Result<(), String> {
     let canonical_root = root
         .canonicalize()
@@ -425,11 +386,12 @@ pub(crate) fn write_workspace_file_inner(
         return Err("Cannot write to .git directory".to_string());
     }
 
-    if content.len() > MAX_WORKSPACE_FILE_BYTES as usize {
+    let bytes = encode_file_content(content, options)?;
+    if bytes.len() > MAX_WORKSPACE_FILE_BYTES as usize {
         return Err("File content exceeds maximum allowed size".to_string());
     }
 
-    std::fs::write(&candidate, content).map_err(|err| format!("Failed to write file: {err}"))?;
+    std::fs::write(&candidate, bytes).map_err(|err| format!("Failed to write file: {err}"))?;
     Ok(())
 }
 
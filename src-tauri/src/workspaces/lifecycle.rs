@@ -0,0 +1,512 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use git2::{Oid, Repository};
+use tauri::{AppHandle, Manager, State};
+use uuid::Uuid;
+
+use super::commands::{add_worktree, remove_worktree};
+use super::git::{run_git_command, run_git_command_bytes};
+use super::worktree_apply::{
+    apply_output_detail, collect_worktree_patch, resolve_worktree_and_parent, run_git_apply,
+};
+
+use crate::git_utils::resolve_git_root;
+use crate::remote_backend;
+use crate::state::AppState;
+use crate::types::{
+    WorkspaceEntry, WorkspaceInfo, WorktreeArchive, WorktreeLifecycleInfo, WorktreePrunePolicy,
+    WorktreePruneResult,
+};
+
+const ARCHIVES_DIR: &str = "worktree-archives";
+const ARCHIVE_METADATA_FILE: &str = "archive.json";
+const ARCHIVE_PATCH_FILE: &str = "changes.patch";
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries.flatten().map(|entry| dir_size(&entry.path())).sum()
+}
+
+/// Paths from `git status --porcelain -z`; rename/copy sources are skipped.
+fn parse_porcelain_paths(output: &[u8]) -> Vec<String> {
+    let mut paths = Vec::new();
+    let mut entries = output.split(|byte| *byte == 0);
+    while let Some(entry) = entries.next() {
+        if entry.len() < 4 {
+            continue;
+        }
+        paths.push(String::from_utf8_lossy(&entry[3..]).to_string());
+        if matches!(entry[0], b'R' | b'C') {
+            entries.next();
+        }
+    }
+    paths
+}
+
+/// Whether the branch's own commits are in the parent's HEAD. A branch with
+/// nothing beyond its base is fresh, not merged; without a recorded base a
+/// tip equal to HEAD is ambiguous and counts as unmerged too.
+fn branch_merged_into_head(
+    parent_root: &Path,
+    branch: &str,
+    base_commit: Option<&str>,
+) -> Option<bool> {
+    let repo = Repository::open(parent_root).ok()?;
+    let branch_oid = repo.refname_to_id(&format!("refs/heads/{branch}")).ok()?;
+    let head_oid = repo.head().ok()?.target()?;
+    let base_oid = base_commit.and_then(|sha| Oid::from_str(sha).ok());
+    match base_oid {
+        Some(base) if base == branch_oid => return Some(false),
+        None if branch_oid == head_oid => return Some(false),
+        _ => {}
+    }
+    Some(branch_oid == head_oid || repo.graph_descendant_of(head_oid, branch_oid).ok()?)
+}
+
+async fn worktree_lifecycle_info(
+    entry: &WorkspaceEntry,
+    parent: Option<&WorkspaceEntry>,
+    session_activity: Option<i64>,
+) -> WorktreeLifecycleInfo {
+    let root = PathBuf::from(&entry.path);
+    let branch = entry.worktree.as_ref().map(|info| info.branch.clone());
+    let exists = root.exists();
+    let mut info = WorktreeLifecycleInfo {
+        workspace_id: entry.id.clone(),
+        parent_id: entry.parent_id.clone(),
+        name: entry.name.clone(),
+        branch: branch.clone(),
+        path: entry.path.clone(),
+        exists,
+        size_bytes: 0,
+        last_activity_at: None,
+        merged: None,
+        dirty: false,
+    };
+    if !exists {
+        return info;
+    }
+
+    let size_root = root.clone();
+    info.size_bytes = tokio::task::spawn_blocking(move || dir_size(&size_root))
+        .await
+        .unwrap_or(0);
+
+    let status = run_git_command_bytes(&root, &["status", "--porcelain", "-z"])
+        .await
+        .unwrap_or_default();
+    info.dirty = !parse_porcelain_paths(&status).is_empty();
+
+    // Agent sessions and commits count as activity; file times do not, since
+    // checkouts, builds and indexers touch them too.
+    let head_time = run_git_command(&root, &["log", "-1", "--format=%ct"])
+        .await
+        .ok()
+        .and_then(|secs| secs.trim().parse::<i64>().ok())
+        .map(|secs| secs * 1000);
+    info.last_activity_at = session_activity.into_iter().chain(head_time).max();
+
+    if let (Some(parent), Some(branch)) = (parent, branch.as_deref()) {
+        if let Ok(parent_root) = resolve_git_root(parent) {
+            let base_commit = entry
+                .worktree
+                .as_ref()
+                .and_then(|info| info.base_commit.as_deref());
+            info.merged = branch_merged_into_head(&parent_root, branch, base_commit);
+        }
+    }
+    info
+}
+
+async fn collect_lifecycle_infos(state: &State<'_, AppState>) -> Vec<WorktreeLifecycleInfo> {
+    let pairs = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .values()
+            .filter(|entry| entry.kind.is_worktree())
+            .map(|entry| {
+                let parent = entry
+                    .parent_id
+                    .as_ref()
+                    .and_then(|parent_id| workspaces.get(parent_id))
+                    .cloned();
+                (entry.clone(), parent)
+            })
+            .collect::<Vec<_>>()
+    };
+    let activity: HashMap<String, i64> = crate::session_idle::last_session_activity(state);
+    let mut infos = Vec::new();
+    for (entry, parent) in &pairs {
+        let session_activity = activity.get(&entry.id).copied();
+        infos.push(worktree_lifecycle_info(entry, parent.as_ref(), session_activity).await);
+    }
+    infos.sort_by_key(|info| std::cmp::Reverse(info.size_bytes));
+    infos
+}
+
+fn archives_root(state: &AppState) -> PathBuf {
+    state
+        .storage_path
+        .parent()
+        .map(|dir| dir.join(ARCHIVES_DIR))
+        .unwrap_or_else(|| PathBuf::from(ARCHIVES_DIR))
+}
+
+fn read_archive(dir: &Path) -> Result<WorktreeArchive, String> {
+    let data = fs::read_to_string(dir.join(ARCHIVE_METADATA_FILE)).map_err(|e| e.to_string())?;
+    serde_json::from_str(&data).map_err(|e| e.to_string())
+}
+
+/// Decides what a prune pass does with one worktree, or `None` to keep it.
+fn prune_action(
+    info: &WorktreeLifecycleInfo,
+    policy: &WorktreePrunePolicy,
+    now_ms: i64,
+) -> Option<&'static str> {
+    if info.merged != Some(true) {
+        return None;
+    }
+    let cutoff = now_ms - i64::from(policy.merged_after_days) * MILLIS_PER_DAY;
+    if info
+        .last_activity_at
+        .is_some_and(|activity| activity > cutoff)
+    {
+        return None;
+    }
+    match (info.dirty, policy.archive_dirty) {
+        (false, _) => Some("removed"),
+        (true, true) => Some("archived"),
+        (true, false) => Some("skipped"),
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn list_worktree_lifecycle(
+    state: State<'_, AppState>,
+) -> Result<Vec<WorktreeLifecycleInfo>, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return Err("list_worktree_lifecycle is not supported in remote mode yet.".to_string());
+    }
+    Ok(collect_lifecycle_infos(&state).await)
+}
+
+#[tauri::command]
+pub(crate) async fn archive_worktree(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<WorktreeArchive, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return Err("archive_worktree is not supported in remote mode yet.".to_string());
+    }
+    let (entry, parent) = resolve_worktree_and_parent(&state, &workspace_id).await?;
+    let worktree = entry.worktree.clone().ok_or("worktree branch not found")?;
+    let root = PathBuf::from(&entry.path);
+    let patch = collect_worktree_patch(&root).await?;
+    let head_sha = run_git_command(&root, &["rev-parse", "HEAD"]).await?;
+
+    let archive = WorktreeArchive {
+        id: Uuid::new_v4().to_string(),
+        workspace_id: entry.id.clone(),
+        parent_id: parent.id.clone(),
+        name: entry.name.clone(),
+        branch: worktree.branch,
+        base_ref: worktree.base_ref,
+        head_sha,
+        archived_at: chrono::Utc::now().timestamp_millis(),
+        patch_bytes: patch.len(),
+    };
+    let dir = archives_root(&state).join(&archive.id);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create archive: {e}"))?;
+    fs::write(dir.join(ARCHIVE_PATCH_FILE), &patch)
+        .map_err(|e| format!("Failed to write archive patch: {e}"))?;
+    let metadata = serde_json::to_string_pretty(&archive).map_err(|e| e.to_string())?;
+    fs::write(dir.join(ARCHIVE_METADATA_FILE), metadata)
+        .map_err(|e| format!("Failed to write archive metadata: {e}"))?;
+
+    // The branch is kept, so committed work survives the worktree removal.
+    if let Err(error) = remove_worktree(entry.id.clone(), state, app).await {
+        let _ = fs::remove_dir_all(&dir);
+        return Err(error);
+    }
+    Ok(archive)
+}
+
+#[tauri::command]
+pub(crate) async fn list_worktree_archives(
+    state: State<'_, AppState>,
+) -> Result<Vec<WorktreeArchive>, String> {
+    let root = archives_root(&state);
+    let Ok(entries) = fs::read_dir(&root) else {
+        return Ok(Vec::new());
+    };
+    let mut archives = entries
+        .flatten()
+        .filter_map(|entry| read_archive(&entry.path()).ok())
+        .collect::<Vec<_>>();
+    archives.sort_by_key(|archive| std::cmp::Reverse(archive.archived_at));
+    Ok(archives)
+}
+
+#[tauri::command]
+pub(crate) async fn restore_worktree_archive(
+    archive_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<WorkspaceInfo, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return Err("restore_worktree_archive is not supported in remote mode yet.".to_string());
+    }
+    if archive_id.contains(['/', '\\']) || archive_id.contains("..") {
+        return Err("Invalid archive id.".to_string());
+    }
+    let dir = archives_root(&state).join(&archive_id);
+    let archive = read_archive(&dir)?;
+    let patch = fs::read(dir.join(ARCHIVE_PATCH_FILE)).unwrap_or_default();
+
+    let info = add_worktree(
+        archive.parent_id.clone(),
+        archive.branch.clone(),
+        Some(archive.head_sha.clone()),
+        Some(false),
+        state,
+        app,
+    )
+    .await?;
+    if !patch.is_empty() {
+        let output = run_git_apply(
+            &PathBuf::from(&info.path),
+            &["--whitespace=nowarn"],
+            &patch,
+            None,
+        )
+        .await?;
+        if !output.status.success() {
+            return Err(format!(
+                "Worktree restored, but its saved changes did not apply: {}. The archive was kept.",
+                apply_output_detail(&output)
+            ));
+        }
+    }
+    let _ = fs::remove_dir_all(&dir);
+    Ok(info)
+}
+
+async fn prune_worktrees_with_policy(
+    policy: &WorktreePrunePolicy,
+    dry_run: bool,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Vec<WorktreePruneResult> {
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let mut results = Vec::new();
+    for info in collect_lifecycle_infos(&state).await {
+        let Some(action) = prune_action(&info, policy, now_ms) else {
+            continue;
+        };
+        let mut result = WorktreePruneResult {
+            workspace_id: info.workspace_id.clone(),
+            name: info.name.clone(),
+            action: action.to_string(),
+            reason: Some(format!(
+                "Merged and inactive for {} days",
+                policy.merged_after_days
+            )),
+            error: None,
+        };
+        if action == "skipped" {
+            result.reason = Some("Merged but has uncommitted changes".to_string());
+        } else if dry_run {
+            let planned = if action == "archived" {
+                "would-archive"
+            } else {
+                "would-remove"
+            };
+            result.action = planned.to_string();
+        } else {
+            let outcome = if action == "archived" {
+                archive_worktree(info.workspace_id.clone(), state.clone(), app.clone())
+                    .await
+                    .map(|_| ())
+            } else {
+                remove_worktree(info.workspace_id.clone(), state.clone(), app.clone()).await
+            };
+            if let Err(error) = outcome {
+                result.action = "skipped".to_string();
+                result.error = Some(error);
+            }
+        }
+        results.push(result);
+    }
+    results
+}
+
+#[tauri::command]
+pub(crate) async fn prune_worktrees(
+    policy: Option<WorktreePrunePolicy>,
+    dry_run: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<WorktreePruneResult>, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return Err("prune_worktrees is not supported in remote mode yet.".to_string());
+    }
+    let policy = match policy {
+        Some(policy) => policy,
+        None => state
+            .app_settings
+            .lock()
+            .await
+            .worktree_prune_policy
+            .clone()
+            .ok_or("No worktree prune policy configured.")?,
+    };
+    Ok(prune_worktrees_with_policy(&policy, dry_run.unwrap_or(false), state, app).await)
+}
+
+/// Runs the configured prune policy once, when it opts into startup pruning.
+pub(crate) async fn prune_worktrees_on_startup(app: AppHandle) {
+    let state = app.state::<AppState>();
+    if remote_backend::is_remote_mode(&*state).await {
+        return;
+    }
+    let policy = state
+        .app_settings
+        .lock()
+        .await
+        .worktree_prune_policy
+        .clone();
+    let Some(policy) = policy.filter(|policy| policy.run_on_startup) else {
+        return;
+    };
+    for result in prune_worktrees_with_policy(&policy, false, state, app.clone()).await {
+        if let Some(error) = result.error {
+            log::warn!("Failed to prune worktree {}: {error}", result.name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(
+        merged: Option<bool>,
+        dirty: bool,
+        last_activity_at: Option<i64>,
+    ) -> WorktreeLifecycleInfo {
+        WorktreeLifecycleInfo {
+            workspace_id: "wt".to_string(),
+            parent_id: Some("main".to_string()),
+            name: "feature".to_string(),
+            branch: Some("feature".to_string()),
+            path: "/tmp/feature".to_string(),
+            exists: true,
+            size_bytes: 0,
+            last_activity_at,
+            merged,
+            dirty,
+        }
+    }
+
+    #[test]
+    fn prune_policy_targets_old_merged_worktrees() {
+        let policy = WorktreePrunePolicy {
+            run_on_startup: true,
+            merged_after_days: 14,
+            archive_dirty: true,
+        };
+        let now = 100 * MILLIS_PER_DAY;
+        let old = Some(now - 20 * MILLIS_PER_DAY);
+        let recent = Some(now - 2 * MILLIS_PER_DAY);
+        assert_eq!(
+            prune_action(&info(Some(true), false, old), &policy, now),
+            Some("removed")
+        );
+        assert_eq!(
+            prune_action(&info(Some(true), true, old), &policy, now),
+            Some("archived")
+        );
+        assert_eq!(
+            prune_action(&info(Some(true), false, recent), &policy, now),
+            None
+        );
+        assert_eq!(
+            prune_action(&info(Some(false), false, old), &policy, now),
+            None
+        );
+        assert_eq!(prune_action(&info(None, false, old), &policy, now), None);
+        let keep_dirty = WorktreePrunePolicy {
+            archive_dirty: false,
+            ..policy
+        };
+        assert_eq!(
+            prune_action(&info(Some(true), true, old), &keep_dirty, now),
+            Some("skipped")
+        );
+    }
+
+    #[test]
+    fn fresh_branches_are_not_merged() {
+        use crate::test_support::git;
+
+        let root = std::env::temp_dir().join(format!("moss-lifecycle-merged-{}", Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create repo");
+        git(&root, &["init", "-q", "-b", "main"]);
+        fs::write(root.join("a.txt"), "a\n").expect("write");
+        git(&root, &["add", "."]);
+        git(&root, &["commit", "-q", "-m", "base"]);
+        let base = git(&root, &["rev-parse", "HEAD"]);
+        git(&root, &["branch", "feature"]);
+
+        assert_eq!(
+            branch_merged_into_head(&root, "feature", Some(&base)),
+            Some(false)
+        );
+        assert_eq!(branch_merged_into_head(&root, "feature", None), Some(false));
+
+        git(&root, &["checkout", "-q", "feature"]);
+        fs::write(root.join("b.txt"), "b\n").expect("write");
+        git(&root, &["add", "."]);
+        git(&root, &["commit", "-q", "-m", "feature work"]);
+        git(&root, &["checkout", "-q", "main"]);
+        assert_eq!(
+            branch_merged_into_head(&root, "feature", Some(&base)),
+            Some(false)
+        );
+
+        git(&root, &["merge", "-q", "--ff-only", "feature"]);
+        assert_eq!(
+            branch_merged_into_head(&root, "feature", Some(&base)),
+            Some(true)
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn parses_porcelain_paths_and_measures_dirs() {
+        let output = b" M src/lib.rs\0R  new.rs\0old.rs\0?? notes.txt\0";
+        assert_eq!(
+            parse_porcelain_paths(output),
+            vec!["src/lib.rs", "new.rs", "notes.txt"]
+        );
+
+        let root = std::env::temp_dir().join(format!("moss-lifecycle-{}", Uuid::new_v4()));
+        fs::create_dir_all(root.join("nested")).expect("create dirs");
+        fs::write(root.join("a.bin"), [0u8; 10]).expect("write");
+        fs::write(root.join("nested").join("b.bin"), [0u8; 32]).expect("write");
+        assert_eq!(dir_size(&root), 42);
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -1,11 +1,29 @@
 mod commands;
+mod fanout;
 mod files;
 mod git;
+mod lifecycle;
 mod macos;
+mod search;
 mod settings;
+mod watcher;
 mod worktree;
+mod worktree_apply;
 
 pub(crate) use commands::*;
+pub(crate) use fanout::{apply_fanout_winner, get_fanout_run, list_fanout_runs, start_fanout_run};
+pub(crate) use lifecycle::{
+    archive_worktree, list_worktree_archives, list_worktree_lifecycle, prune_worktrees,
+    prune_worktrees_on_startup, restore_worktree_archive,
+};
+pub(crate) use search::{
+    apply_workspace_replace, cancel_workspace_search, content_hash, preview_workspace_replace,
+    search_workspace_content, unified_diff,
+};
+pub(crate) use watcher::{unwatch_workspace_files, watch_workspace_files, WorkspaceFileWatcher};
+pub(crate) use worktree_apply::{
+    apply_worktree_change_selection, list_worktree_apply_records, preview_worktree_changes,
+};
 
 #[cfg(test)]
 mod tests;
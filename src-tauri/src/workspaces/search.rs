@@ -0,0 +1,659 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use grep_matcher::{Captures, Matcher};
+use grep_regex::{RegexMatcher, RegexMatcherBuilder};
+use grep_searcher::{
+    BinaryDetection, Searcher, SearcherBuilder, Sink, SinkContext, SinkContextKind, SinkMatch,
+};
+use ignore::overrides::{Override, OverrideBuilder};
+use ignore::WalkBuilder;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, State};
+use uuid::Uuid;
+
+use super::files::{is_heavy_directory, should_always_skip};
+use crate::remote_backend;
+use crate::state::AppState;
+use crate::types::{
+    WorkspaceContentSearchQuery, WorkspaceReplaceFilePreview, WorkspaceReplacePreview,
+    WorkspaceReplaceResult, WorkspaceReplaceSkip, WorkspaceSearchFileResult,
+    WorkspaceSearchLineMatch, WorkspaceSearchMatchEvent, WorkspaceSearchMatchRange,
+    WorkspaceSearchSummary,
+};
+use crate::utils::normalize_git_path;
+
+const SEARCH_MATCH_EVENT: &str = "workspace-search-match";
+const MAX_SEARCH_FILE_BYTES: u64 = 4 * 1024 * 1024;
+const MAX_CONTEXT_LINES: usize = 10;
+const MAX_LINE_CHARS: usize = 500;
+
+fn build_matcher(query: &WorkspaceContentSearchQuery) -> Result<RegexMatcher, String> {
+    if query.pattern.is_empty() {
+        return Err("Search pattern is required.".to_string());
+    }
+    RegexMatcherBuilder::new()
+        .case_insensitive(!query.case_sensitive)
+        .word(query.whole_word)
+        .fixed_strings(!query.is_regex)
+        .line_terminator(Some(b'\n'))
+        .build(&query.pattern)
+        .map_err(|err| format!("Invalid search pattern: {err}"))
+}
+
+fn build_overrides(root: &Path, query: &WorkspaceContentSearchQuery) -> Result<Override, String> {
+    let mut builder = OverrideBuilder::new(root);
+    for glob in query.include.iter().map(|glob| glob.trim()) {
+        if !glob.is_empty() {
+            builder
+                .add(glob)
+                .map_err(|err| format!("Invalid include glob `{glob}`: {err}"))?;
+        }
+    }
+    for glob in query.exclude.iter().map(|glob| glob.trim()) {
+        if !glob.is_empty() {
+            builder
+                .add(&format!("!{glob}"))
+                .map_err(|err| format!("Invalid exclude glob `{glob}`: {err}"))?;
+        }
+    }
+    builder.build().map_err(|err| err.to_string())
+}
+
+/// Files eligible for content search: gitignore-aware, skipping `.git`,
+/// heavy dependency/build directories, and anything filtered by the globs.
+fn searchable_files(
+    root: &Path,
+    query: &WorkspaceContentSearchQuery,
+) -> Result<Vec<PathBuf>, String> {
+    let overrides = build_overrides(root, query)?;
+    let walker = WalkBuilder::new(root)
+        .hidden(false)
+        .follow_links(false)
+        .max_filesize(Some(MAX_SEARCH_FILE_BYTES))
+        .overrides(overrides)
+        .filter_entry(|entry| {
+            if entry.depth() == 0 {
+                return true;
+            }
+            if entry.file_type().is_some_and(|ft| ft.is_dir()) {
+                let name = entry.file_name().to_string_lossy();
+                return !should_always_skip(&name) && !is_heavy_directory(&name);
+            }
+            true
+        })
+        .build();
+    let mut files: Vec<PathBuf> = walker
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+        .map(|entry| entry.into_path())
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn relative_path(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    normalize_git_path(&relative.to_string_lossy())
+}
+
+fn line_text(bytes: &[u8]) -> String {
+    let trimmed = bytes
+        .strip_suffix(b"\n")
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .unwrap_or(bytes);
+    let text = String::from_utf8_lossy(trimmed);
+    if text.chars().count() > MAX_LINE_CHARS {
+        text.chars().take(MAX_LINE_CHARS).collect()
+    } else {
+        text.into_owned()
+    }
+}
+
+/// Match ranges within one line, as character offsets into `line_text`.
+fn match_ranges(matcher: &RegexMatcher, line: &[u8]) -> Vec<WorkspaceSearchMatchRange> {
+    let mut ranges = Vec::new();
+    let _ = matcher.find_iter(line, |found| {
+        let start = String::from_utf8_lossy(&line[..found.start()])
+            .chars()
+            .count();
+        let length = String::from_utf8_lossy(&line[found.start()..found.end()])
+            .chars()
+            .count();
+        ranges.push(WorkspaceSearchMatchRange {
+            start,
+            end: start + length,
+        });
+        true
+    });
+    ranges
+}
+
+struct FileMatchSink<'a> {
+    matcher: &'a RegexMatcher,
+    cancel: &'a AtomicBool,
+    remaining: usize,
+    matches: Vec<WorkspaceSearchLineMatch>,
+    match_count: usize,
+    pending_before: Vec<String>,
+    limit_reached: bool,
+}
+
+impl Sink for FileMatchSink<'_> {
+    type Error = io::Error;
+
+    fn matched(&mut self, _searcher: &Searcher, mat: &SinkMatch<'_>) -> Result<bool, io::Error> {
+        if self.cancel.load(Ordering::Relaxed) {
+            return Ok(false);
+        }
+        let ranges = match_ranges(self.matcher, mat.bytes());
+        let count = ranges.len().max(1);
+        self.matches.push(WorkspaceSearchLineMatch {
+            line_number: mat.line_number().unwrap_or(0),
+            line_text: line_text(mat.bytes()),
+            ranges,
+            context_before: std::mem::take(&mut self.pending_before),
+            context_after: Vec::new(),
+        });
+        self.match_count += count;
+        self.remaining = self.remaining.saturating_sub(count);
+        if self.remaining == 0 {
+            self.limit_reached = true;
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    fn context(
+        &mut self,
+        _searcher: &Searcher,
+        context: &SinkContext<'_>,
+    ) -> Result<bool, io::Error> {
+        let text = line_text(context.bytes());
+        match context.kind() {
+            SinkContextKind::Before => self.pending_before.push(text),
+            SinkContextKind::After => {
+                if let Some(last) = self.matches.last_mut() {
+                    last.context_after.push(text);
+                }
+            }
+            SinkContextKind::Other => {}
+        }
+        Ok(true)
+    }
+}
+
+/// Runs the search synchronously, handing each file with matches to
+/// `on_file` as soon as it has been scanned.
+fn search_content(
+    root: &Path,
+    query: &WorkspaceContentSearchQuery,
+    cancel: &AtomicBool,
+    mut on_file: impl FnMut(WorkspaceSearchFileResult),
+) -> Result<WorkspaceSearchSummary, String> {
+    let matcher = build_matcher(query)?;
+    let context_lines = query.context_lines.min(MAX_CONTEXT_LINES);
+    let mut searcher = SearcherBuilder::new()
+        .line_number(true)
+        .before_context(context_lines)
+        .after_context(context_lines)
+        .binary_detection(BinaryDetection::quit(b'\x00'))
+        .build();
+
+    let mut summary = WorkspaceSearchSummary::default();
+    let mut remaining = query.max_results.max(1);
+    for path in searchable_files(root, query)? {
+        if cancel.load(Ordering::Relaxed) {
+            summary.cancelled = true;
+            break;
+        }
+        let mut sink = FileMatchSink {
+            matcher: &matcher,
+            cancel,
+            remaining,
+            matches: Vec::new(),
+            match_count: 0,
+            pending_before: Vec::new(),
+            limit_reached: false,
+        };
+        if let Err(err) = searcher.search_path(&matcher, &path, &mut sink) {
+            log::debug!("[search] skipped {}: {err}", path.display());
+            continue;
+        }
+        remaining = sink.remaining;
+        if !sink.matches.is_empty() {
+            summary.total_matches += sink.match_count;
+            summary.files_matched += 1;
+            on_file(WorkspaceSearchFileResult {
+                path: relative_path(root, &path),
+                matches: sink.matches,
+            });
+        }
+        if sink.limit_reached {
+            summary.truncated = true;
+            break;
+        }
+    }
+    if cancel.load(Ordering::Relaxed) {
+        summary.cancelled = true;
+    }
+    Ok(summary)
+}
+
+pub(crate) fn content_hash(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Replaces every match in `content`. Regex queries expand `$1`/`${name}`
+/// capture references; literal queries insert the replacement verbatim.
+fn replace_matches(
+    matcher: &RegexMatcher,
+    query: &WorkspaceContentSearchQuery,
+    content: &[u8],
+    replacement: &str,
+) -> Result<(Vec<u8>, usize), String> {
+    let mut output = Vec::with_capacity(content.len());
+    let mut count = 0usize;
+    if query.is_regex {
+        let mut caps = matcher.new_captures().map_err(|err| err.to_string())?;
+        matcher
+            .replace_with_captures(content, &mut caps, &mut output, |caps, dst| {
+                count += 1;
+                caps.interpolate(
+                    |name| matcher.capture_index(name),
+                    content,
+                    replacement.as_bytes(),
+                    dst,
+                );
+                true
+            })
+            .map_err(|err| err.to_string())?;
+    } else {
+        matcher
+            .replace(content, &mut output, |_, dst| {
+                count += 1;
+                dst.extend_from_slice(replacement.as_bytes());
+                true
+            })
+            .map_err(|err| err.to_string())?;
+    }
+    Ok((output, count))
+}
+
+pub(crate) fn unified_diff(path: &str, before: &[u8], after: &[u8]) -> Result<String, String> {
+    let mut options = git2::DiffOptions::new();
+    options.context_lines(3);
+    let mut patch = git2::Patch::from_buffers(
+        before,
+        Some(Path::new(path)),
+        after,
+        Some(Path::new(path)),
+        Some(&mut options),
+    )
+    .map_err(|err| err.to_string())?;
+    let buffer = patch.to_buf().map_err(|err| err.to_string())?;
+    Ok(String::from_utf8_lossy(&buffer).into_owned())
+}
+
+struct PlannedReplace {
+    path: PathBuf,
+    relative: String,
+    original_hash: String,
+    replaced: Vec<u8>,
+    preview: WorkspaceReplaceFilePreview,
+}
+
+fn plan_replace(
+    root: &Path,
+    query: &WorkspaceContentSearchQuery,
+    replacement: &str,
+) -> Result<Vec<PlannedReplace>, String> {
+    let matcher = build_matcher(query)?;
+    let mut planned = Vec::new();
+    for path in searchable_files(root, query)? {
+        let Ok(content) = std::fs::read(&path) else {
+            continue;
+        };
+        if content.contains(&0) || std::str::from_utf8(&content).is_err() {
+            continue;
+        }
+        let (replaced, count) = replace_matches(&matcher, query, &content, replacement)?;
+        if count == 0 || replaced == content {
+            continue;
+        }
+        let relative = relative_path(root, &path);
+        let original_hash = content_hash(&content);
+        let preview = WorkspaceReplaceFilePreview {
+            path: relative.clone(),
+            replacements: count,
+            original_hash: original_hash.clone(),
+            diff: unified_diff(&relative, &content, &replaced)?,
+        };
+        planned.push(PlannedReplace {
+            path,
+            relative,
+            original_hash,
+            replaced,
+            preview,
+        });
+    }
+    Ok(planned)
+}
+
+fn apply_replace_plan(
+    planned: Vec<PlannedReplace>,
+    paths: Option<&[String]>,
+    expected_hashes: &HashMap<String, String>,
+) -> WorkspaceReplaceResult {
+    let mut result = WorkspaceReplaceResult::default();
+    for plan in planned {
+        if paths.is_some_and(|paths| !paths.contains(&plan.relative)) {
+            continue;
+        }
+        let reason = match expected_hashes.get(&plan.relative) {
+            None => Some("File was not part of the preview."),
+            Some(expected) if *expected != plan.original_hash => {
+                Some("File changed since the preview was generated.")
+            }
+            Some(_) => None,
+        };
+        if let Some(reason) = reason {
+            result.skipped.push(WorkspaceReplaceSkip {
+                path: plan.relative,
+                reason: reason.to_string(),
+            });
+            continue;
+        }
+        match std::fs::write(&plan.path, &plan.replaced) {
+            Ok(()) => {
+                result.total_replacements += plan.preview.replacements;
+                result.files_changed.push(plan.relative);
+            }
+            Err(err) => result.skipped.push(WorkspaceReplaceSkip {
+                path: plan.relative,
+                reason: format!("Failed to write file: {err}"),
+            }),
+        }
+    }
+    result
+}
+
+async fn workspace_root(state: &AppState, workspace_id: &str) -> Result<PathBuf, String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(workspace_id)
+        .ok_or_else(|| "workspace not found".to_string())?;
+    Ok(PathBuf::from(&entry.path))
+}
+
+#[tauri::command]
+pub(crate) async fn search_workspace_content(
+    workspace_id: String,
+    query: WorkspaceContentSearchQuery,
+    search_id: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<WorkspaceSearchSummary, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return Err("Content search is not supported in remote mode yet.".to_string());
+    }
+    let root = workspace_root(&state, &workspace_id).await?;
+    let search_id = search_id
+        .filter(|id| !id.trim().is_empty())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let cancel = Arc::new(AtomicBool::new(false));
+    state
+        .content_searches
+        .lock()
+        .await
+        .insert(search_id.clone(), cancel.clone());
+
+    let event_search_id = search_id.clone();
+    let event_workspace_id = workspace_id.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        search_content(&root, &query, &cancel, |file| {
+            let _ = app.emit(
+                SEARCH_MATCH_EVENT,
+                WorkspaceSearchMatchEvent {
+                    search_id: event_search_id.clone(),
+                    workspace_id: event_workspace_id.clone(),
+                    file,
+                },
+            );
+        })
+    })
+    .await
+    .map_err(|err| err.to_string());
+
+    state.content_searches.lock().await.remove(&search_id);
+    let mut summary = result??;
+    summary.search_id = search_id;
+    Ok(summary)
+}
+
+#[tauri::command]
+pub(crate) async fn cancel_workspace_search(
+    search_id: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    let searches = state.content_searches.lock().await;
+    match searches.get(&search_id) {
+        Some(cancel) => {
+            cancel.store(true, Ordering::Relaxed);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn preview_workspace_replace(
+    workspace_id: String,
+    query: WorkspaceContentSearchQuery,
+    replacement: String,
+    state: State<'_, AppState>,
+) -> Result<WorkspaceReplacePreview, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return Err("Replace in files is not supported in remote mode yet.".to_string());
+    }
+    let root = workspace_root(&state, &workspace_id).await?;
+    let planned = tokio::task::spawn_blocking(move || plan_replace(&root, &query, &replacement))
+        .await
+        .map_err(|err| err.to_string())??;
+    let files: Vec<WorkspaceReplaceFilePreview> =
+        planned.into_iter().map(|plan| plan.preview).collect();
+    Ok(WorkspaceReplacePreview {
+        total_replacements: files.iter().map(|file| file.replacements).sum(),
+        files,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn apply_workspace_replace(
+    workspace_id: String,
+    query: WorkspaceContentSearchQuery,
+    replacement: String,
+    paths: Option<Vec<String>>,
+    expected_hashes: HashMap<String, String>,
+    state: State<'_, AppState>,
+) -> Result<WorkspaceReplaceResult, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return Err("Replace in files is not supported in remote mode yet.".to_string());
+    }
+    let root = workspace_root(&state, &workspace_id).await?;
+    tokio::task::spawn_blocking(move || {
+        let planned = plan_replace(&root, &query, &replacement)?;
+        Ok(apply_replace_plan(
+            planned,
+            paths.as_deref(),
+            &expected_hashes,
+        ))
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_workspace() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("moss-search-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "fn alpha() {}\n\nfn beta() {\n    alpha();\n}\n// alphabet\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("src/notes.md"), "Alpha release notes\n").unwrap();
+        std::fs::write(root.join("node_modules/pkg/index.js"), "alpha();\n").unwrap();
+        std::fs::write(root.join("image.bin"), b"alpha\x00\x01\x02").unwrap();
+        root
+    }
+
+    fn query(pattern: &str) -> WorkspaceContentSearchQuery {
+        WorkspaceContentSearchQuery {
+            pattern: pattern.to_string(),
+            is_regex: false,
+            case_sensitive: true,
+            whole_word: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            context_lines: 0,
+            max_results: 100,
+        }
+    }
+
+    fn run(
+        root: &Path,
+        query: &WorkspaceContentSearchQuery,
+    ) -> (Vec<WorkspaceSearchFileResult>, WorkspaceSearchSummary) {
+        let mut files = Vec::new();
+        let summary = search_content(root, query, &AtomicBool::new(false), |file| {
+            files.push(file)
+        })
+        .unwrap();
+        (files, summary)
+    }
+
+    #[test]
+    fn search_content_skips_heavy_dirs_and_binary_files() {
+        let root = temp_workspace();
+        let (files, summary) = run(&root, &query("alpha"));
+        let paths: Vec<&str> = files.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/lib.rs"]);
+        assert_eq!(summary.total_matches, 3);
+        let first = &files[0].matches[0];
+        assert_eq!(first.line_number, 1);
+        assert_eq!(first.ranges[0].start, 3);
+        assert_eq!(first.ranges[0].end, 8);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn search_content_honours_word_case_globs_and_context() {
+        let root = temp_workspace();
+        let mut word = query("alpha");
+        word.whole_word = true;
+        word.case_sensitive = false;
+        word.context_lines = 1;
+        let (files, summary) = run(&root, &word);
+        assert_eq!(summary.total_matches, 3);
+        assert_eq!(files.len(), 2);
+        let lib = files.iter().find(|file| file.path == "src/lib.rs").unwrap();
+        assert_eq!(
+            lib.matches[1].context_before,
+            vec!["fn beta() {".to_string()]
+        );
+        assert_eq!(lib.matches[1].context_after, vec!["}".to_string()]);
+
+        word.include = vec!["*.md".to_string()];
+        let (files, _) = run(&root, &word);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "src/notes.md");
+
+        word.include = Vec::new();
+        word.exclude = vec!["src/*.md".to_string()];
+        let (files, _) = run(&root, &word);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "src/lib.rs");
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn search_content_stops_at_limit_and_on_cancel() {
+        let root = temp_workspace();
+        let mut limited = query("alpha");
+        limited.max_results = 2;
+        let (_, summary) = run(&root, &limited);
+        assert_eq!(summary.total_matches, 2);
+        assert!(summary.truncated);
+
+        let cancelled = AtomicBool::new(true);
+        let summary = search_content(&root, &query("alpha"), &cancelled, |_| {}).unwrap();
+        assert!(summary.cancelled);
+        assert_eq!(summary.files_matched, 0);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn replace_preview_expands_captures_and_apply_checks_hashes() {
+        let root = temp_workspace();
+        let mut regex = query(r"fn (\w+)\(\)");
+        regex.is_regex = true;
+        let planned = plan_replace(&root, &regex, "fn ${1}_renamed()").unwrap();
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].preview.replacements, 2);
+        assert!(planned[0].preview.diff.contains("+fn alpha_renamed() {}"));
+        assert!(planned[0].preview.diff.contains("-fn beta() {"));
+
+        let mut stale = HashMap::new();
+        stale.insert("src/lib.rs".to_string(), "deadbeef".to_string());
+        let result = apply_replace_plan(planned, None, &stale);
+        assert!(result.files_changed.is_empty());
+        assert_eq!(result.skipped.len(), 1);
+
+        let planned = plan_replace(&root, &regex, "fn ${1}_renamed()").unwrap();
+        let result = apply_replace_plan(planned, None, &HashMap::new());
+        assert!(result.files_changed.is_empty());
+        assert_eq!(
+            result.skipped[0].reason,
+            "File was not part of the preview."
+        );
+
+        let planned = plan_replace(&root, &regex, "fn ${1}_renamed()").unwrap();
+        let mut fresh = HashMap::new();
+        fresh.insert(
+            "src/lib.rs".to_string(),
+            planned[0].preview.original_hash.clone(),
+        );
+        let result = apply_replace_plan(planned, None, &fresh);
+        assert_eq!(result.files_changed, vec!["src/lib.rs".to_string()]);
+        assert_eq!(result.total_replacements, 2);
+        let content = std::fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        assert!(content.starts_with("fn alpha_renamed() {}\n\nfn beta_renamed() {"));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn literal_replace_does_not_expand_dollar_signs() {
+        let root = temp_workspace();
+        let literal = query("beta()");
+        let planned = plan_replace(&root, &literal, "$1gamma()").unwrap();
+        let hashes: HashMap<String, String> = planned
+            .iter()
+            .map(|plan| (plan.relative.clone(), plan.original_hash.clone()))
+            .collect();
+        let result = apply_replace_plan(planned, Some(&["src/lib.rs".to_string()]), &hashes);
+        assert_eq!(result.total_replacements, 1);
+        let content = std::fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        assert!(content.contains("fn $1gamma() {"));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -54,6 +54,9 @@ fn workspace_with_id_and_kind(
             launch_scripts: None,
             worktree_setup_script: None,
             engine_type: None,
+            forge_kind: None,
+            git_identity: None,
+            sandbox: None,
         },
     }
 }
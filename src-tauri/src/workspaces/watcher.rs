@@ -0,0 +1,679 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex as StdMutex, Weak};
+use std::time::Duration;
+
+use git2::Repository;
+use ignore::WalkBuilder;
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::mpsc;
+
+use super::files::{is_heavy_directory, should_always_skip};
+use crate::git::{status_for_index, status_for_workdir};
+use crate::remote_backend;
+use crate::state::AppState;
+use crate::types::{WorkspaceFilesChangedEvent, WorkspaceGitPathStatus};
+use crate::utils::normalize_git_path;
+
+const FILES_CHANGED_EVENT: &str = "workspace-files-changed";
+const DEBOUNCE_QUIET: Duration = Duration::from_millis(250);
+const DEBOUNCE_MAX: Duration = Duration::from_secs(2);
+/// Above this many changed paths the UI is told to reload git status in full
+/// instead of patching it path by path.
+const MAX_INCREMENTAL_GIT_PATHS: usize = 200;
+
+type SharedWatcher = Arc<StdMutex<RecommendedWatcher>>;
+
+/// Live watcher for one workspace. Dropping it stops the underlying notify
+/// watcher, which closes the event channel and ends the debounce task.
+pub(crate) struct WorkspaceFileWatcher {
+    root: PathBuf,
+    _watcher: SharedWatcher,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum PathChange {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum PathClass {
+    Workspace(String),
+    GitState,
+    Skipped,
+}
+
+#[derive(Debug, Default)]
+struct PendingBatch {
+    changes: HashMap<String, PathChange>,
+    git_changed: bool,
+    rescan: bool,
+}
+
+impl PendingBatch {
+    fn record(&mut self, path: String, change: PathChange) {
+        match merge_change(self.changes.get(&path).copied(), change) {
+            Some(merged) => {
+                self.changes.insert(path, merged);
+            }
+            None => {
+                self.changes.remove(&path);
+            }
+        }
+    }
+
+    fn push(&mut self, root: &Path, git_dirs: &[PathBuf], result: notify::Result<Event>) {
+        let event = match result {
+            Ok(event) => event,
+            Err(err) => {
+                log::debug!("[watcher] notify error for {}: {err}", root.display());
+                self.rescan = true;
+                return;
+            }
+        };
+        if event.need_rescan() {
+            self.rescan = true;
+        }
+        for (path, change) in event_changes(&event) {
+            match classify_path(root, git_dirs, &path) {
+                PathClass::Workspace(relative) => self.record(relative, change),
+                PathClass::GitState => self.git_changed = true,
+                PathClass::Skipped => {}
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.changes.is_empty() && !self.git_changed && !self.rescan
+    }
+}
+
+/// Folds a new change for a path into what the batch already saw for it.
+/// A file created and deleted inside one batch disappears entirely.
+fn merge_change(previous: Option<PathChange>, next: PathChange) -> Option<PathChange> {
+    match (previous, next) {
+        (None, next) => Some(next),
+        (Some(PathChange::Added), PathChange::Removed) => None,
+        (Some(PathChange::Added), _) => Some(PathChange::Added),
+        (Some(PathChange::Removed), PathChange::Removed) => Some(PathChange::Removed),
+        (Some(PathChange::Removed), _) => Some(PathChange::Modified),
+        (Some(PathChange::Modified), PathChange::Removed) => Some(PathChange::Removed),
+        (Some(PathChange::Modified), _) => Some(PathChange::Modified),
+    }
+}
+
+fn event_changes(event: &Event) -> Vec<(PathBuf, PathChange)> {
+    let all = |change: PathChange| {
+        event
+            .paths
+            .iter()
+            .map(|path| (path.clone(), change))
+            .collect::<Vec<_>>()
+    };
+    match &event.kind {
+        EventKind::Access(_) => Vec::new(),
+        EventKind::Create(_) => all(PathChange::Added),
+        EventKind::Remove(_) => all(PathChange::Removed),
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => all(PathChange::Removed),
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => all(PathChange::Added),
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => vec![
+            (event.paths[0].clone(), PathChange::Removed),
+            (event.paths[1].clone(), PathChange::Added),
+        ],
+        EventKind::Modify(ModifyKind::Name(_)) => event
+            .paths
+            .iter()
+            .map(|path| {
+                let change = if path.exists() {
+                    PathChange::Added
+                } else {
+                    PathChange::Removed
+                };
+                (path.clone(), change)
+            })
+            .collect(),
+        _ => all(PathChange::Modified),
+    }
+}
+
+fn classify_path(root: &Path, git_dirs: &[PathBuf], path: &Path) -> PathClass {
+    for git_dir in git_dirs {
+        if let Ok(rest) = path.strip_prefix(git_dir) {
+            let rest: Vec<String> = rest
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            return if is_git_state_path(&rest) {
+                PathClass::GitState
+            } else {
+                PathClass::Skipped
+            };
+        }
+    }
+    let Ok(relative) = path.strip_prefix(root) else {
+        return PathClass::Skipped;
+    };
+    let components: Vec<String> = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    let Some((name, parents)) = components.split_last() else {
+        return PathClass::Skipped;
+    };
+    if components[0] == ".git" {
+        return if is_git_state_path(&components[1..]) {
+            PathClass::GitState
+        } else {
+            PathClass::Skipped
+        };
+    }
+    if parents
+        .iter()
+        .any(|parent| should_always_skip(parent) || is_heavy_directory(parent))
+    {
+        return PathClass::Skipped;
+    }
+    if should_always_skip(name) || name == ".DS_Store" {
+        return PathClass::Skipped;
+    }
+    PathClass::Workspace(normalize_git_path(&relative.to_string_lossy()))
+}
+
+/// Files under `.git` whose changes mean branch, HEAD, or staged state moved.
+fn is_git_state_path(rest: &[String]) -> bool {
+    match rest.first().map(String::as_str) {
+        Some("index") | Some("HEAD") | Some("packed-refs") => rest.len() == 1,
+        Some("refs") => true,
+        _ => false,
+    }
+}
+
+/// Git directories whose files track HEAD, the index and refs: the
+/// repository's own and, for a linked worktree (whose `.git` is a file), the
+/// common directory of the main checkout that holds `refs` and `packed-refs`.
+/// The common directory comes last.
+fn resolve_git_dirs(root: &Path) -> Vec<PathBuf> {
+    let Ok(repo) = Repository::open(root) else {
+        return Vec::new();
+    };
+    let git_dir = repo.path().to_path_buf();
+    let common_dir = repo.commondir().to_path_buf();
+    if common_dir.components().eq(git_dir.components()) {
+        vec![git_dir]
+    } else {
+        vec![git_dir, common_dir]
+    }
+}
+
+/// Watches the git directories themselves for HEAD, index and packed-refs
+/// changes, and `refs` recursively so branch updates under `refs/heads/…`
+/// are seen.
+fn watch_git_dirs(watcher: &mut RecommendedWatcher, git_dirs: &[PathBuf]) {
+    let refs = git_dirs.last().map(|common_dir| common_dir.join("refs"));
+    let watches = git_dirs
+        .iter()
+        .map(|dir| (dir.clone(), RecursiveMode::NonRecursive))
+        .chain(refs.map(|refs| (refs, RecursiveMode::Recursive)));
+    for (path, mode) in watches {
+        if let Err(err) = watcher.watch(&path, mode) {
+            log::debug!("[watcher] failed to watch {}: {err}", path.display());
+        }
+    }
+}
+
+/// Entries below `dir` that the watcher cares about, using the same skip
+/// rules as the file tree plus `.gitignore`. Returns `(path, is_dir)` pairs;
+/// `dir` itself is included first.
+fn watchable_entries(dir: &Path) -> Vec<(PathBuf, bool)> {
+    let walker = WalkBuilder::new(dir)
+        .hidden(false)
+        .follow_links(false)
+        .filter_entry(|entry| {
+            if entry.depth() == 0 {
+                return true;
+            }
+            let name = entry.file_name().to_string_lossy();
+            if entry.file_type().is_some_and(|ft| ft.is_dir()) {
+                return !should_always_skip(&name) && !is_heavy_directory(&name);
+            }
+            name != ".DS_Store"
+        })
+        .build();
+    walker
+        .flatten()
+        .map(|entry| {
+            let is_dir = entry.file_type().is_some_and(|ft| ft.is_dir());
+            (entry.into_path(), is_dir)
+        })
+        .collect()
+}
+
+/// Adds a non-recursive watch for every watchable directory under `dir` and
+/// returns the workspace-relative paths found beneath it.
+fn watch_directory_tree(watcher: &mut RecommendedWatcher, dir: &Path, root: &Path) -> Vec<String> {
+    let mut found = Vec::new();
+    for (path, is_dir) in watchable_entries(dir) {
+        if is_dir {
+            if let Err(err) = watcher.watch(&path, RecursiveMode::NonRecursive) {
+                log::debug!("[watcher] failed to watch {}: {err}", path.display());
+            }
+        }
+        if path == dir {
+            continue;
+        }
+        if let Ok(relative) = path.strip_prefix(root) {
+            found.push(normalize_git_path(&relative.to_string_lossy()));
+        }
+    }
+    found
+}
+
+fn is_ignored(repo: Option<&Repository>, relative: &str) -> bool {
+    repo.and_then(|repo| repo.status_should_ignore(Path::new(relative)).ok())
+        .unwrap_or(false)
+}
+
+fn git_path_status(repo: &Repository, relative: &str) -> Option<String> {
+    let status = repo.status_file(Path::new(relative)).ok()?;
+    status_for_workdir(status)
+        .or_else(|| status_for_index(status))
+        .map(str::to_string)
+}
+
+fn flush_batch(
+    workspace_id: &str,
+    root: &Path,
+    batch: PendingBatch,
+    watcher: &Weak<StdMutex<RecommendedWatcher>>,
+) -> Option<WorkspaceFilesChangedEvent> {
+    if batch.is_empty() {
+        return None;
+    }
+    let repo = Repository::open(root).ok();
+    let mut changes = batch.changes;
+
+    // Directories created in this batch need their own watches, and anything
+    // written into them before the watch existed would otherwise be missed.
+    let new_directories: Vec<String> = changes
+        .iter()
+        .filter(|(path, change)| **change == PathChange::Added && root.join(path).is_dir())
+        .map(|(path, _)| path.clone())
+        .collect();
+    for directory in new_directories {
+        if is_ignored(repo.as_ref(), &directory) {
+            continue;
+        }
+        let watcher = watcher.upgrade()?;
+        let Ok(mut watcher) = watcher.lock() else {
+            continue;
+        };
+        for path in watch_directory_tree(&mut watcher, &root.join(&directory), root) {
+            changes.entry(path).or_insert(PathChange::Added);
+        }
+    }
+
+    let mut event = WorkspaceFilesChangedEvent {
+        workspace_id: workspace_id.to_string(),
+        git_changed: batch.git_changed,
+        rescan: batch.rescan,
+        ..Default::default()
+    };
+    let mut status_paths = Vec::new();
+    for (path, change) in changes {
+        if is_ignored(repo.as_ref(), &path) {
+            continue;
+        }
+        let is_dir = change != PathChange::Removed && root.join(&path).is_dir();
+        if !is_dir {
+            status_paths.push(path.clone());
+        }
+        match change {
+            PathChange::Added => event.added.push(path),
+            PathChange::Removed => event.removed.push(path),
+            PathChange::Modified => event.modified.push(path),
+        }
+    }
+    event.added.sort();
+    event.removed.sort();
+    event.modified.sort();
+
+    if let Some(repo) = repo.as_ref() {
+        if status_paths.len() > MAX_INCREMENTAL_GIT_PATHS {
+            event.git_changed = true;
+        } else {
+            status_paths.sort();
+            event.git_status = status_paths
+                .into_iter()
+                .map(|path| WorkspaceGitPathStatus {
+                    status: git_path_status(repo, &path),
+                    path,
+                })
+                .collect();
+        }
+    }
+
+    let has_changes = !event.added.is_empty()
+        || !event.removed.is_empty()
+        || !event.modified.is_empty()
+        || event.git_changed
+        || event.rescan;
+    has_changes.then_some(event)
+}
+
+async fn run_watch_loop(
+    app: AppHandle,
+    workspace_id: String,
+    root: PathBuf,
+    git_dirs: Vec<PathBuf>,
+    watcher: Weak<StdMutex<RecommendedWatcher>>,
+    mut rx: mpsc::UnboundedReceiver<notify::Result<Event>>,
+) {
+    while let Some(first) = rx.recv().await {
+        let mut batch = PendingBatch::default();
+        batch.push(&root, &git_dirs, first);
+        let deadline = tokio::time::Instant::now() + DEBOUNCE_MAX;
+        let mut closed = false;
+        loop {
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                break;
+            }
+            let wait = DEBOUNCE_QUIET.min(deadline - now);
+            match tokio::time::timeout(wait, rx.recv()).await {
+                Ok(Some(result)) => batch.push(&root, &git_dirs, result),
+                Ok(None) => {
+                    closed = true;
+                    break;
+                }
+                Err(_) => break,
+            }
+        }
+        if closed {
+            break;
+        }
+
+        let flush_id = workspace_id.clone();
+        let flush_root = root.clone();
+        let flush_watcher = watcher.clone();
+        let event = tokio::task::spawn_blocking(move || {
+            flush_batch(&flush_id, &flush_root, batch, &flush_watcher)
+        })
+        .await
+        .ok()
+        .flatten();
+        if let Some(event) = event {
+            crate::code_intel::refresh_symbol_index_for_changes(&app, &event).await;
+            let _ = app.emit(FILES_CHANGED_EVENT, event);
+        }
+    }
+}
+
+fn start_file_watcher(
+    app: AppHandle,
+    workspace_id: String,
+    root: PathBuf,
+) -> Result<WorkspaceFileWatcher, String> {
+    if !root.is_dir() {
+        return Err(format!("Workspace path does not exist: {}", root.display()));
+    }
+    let (tx, rx) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |result| {
+        let _ = tx.send(result);
+    })
+    .map_err(|err| format!("Failed to start file watcher: {err}"))?;
+
+    watch_directory_tree(&mut watcher, &root, &root);
+    let git_dirs = resolve_git_dirs(&root);
+    watch_git_dirs(&mut watcher, &git_dirs);
+
+    let watcher = Arc::new(StdMutex::new(watcher));
+    tauri::async_runtime::spawn(run_watch_loop(
+        app,
+        workspace_id,
+        root.clone(),
+        git_dirs,
+        Arc::downgrade(&watcher),
+        rx,
+    ));
+    Ok(WorkspaceFileWatcher {
+        root,
+        _watcher: watcher,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn watch_workspace_files(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return Err("File watching is not supported in remote mode yet.".to_string());
+    }
+    let root = {
+        let workspaces = state.workspaces.lock().await;
+        let entry = workspaces
+            .get(&workspace_id)
+            .ok_or_else(|| "workspace not found".to_string())?;
+        PathBuf::from(&entry.path)
+    };
+    {
+        let watchers = state.file_watchers.lock().await;
+        if watchers
+            .get(&workspace_id)
+            .is_some_and(|existing| existing.root == root)
+        {
+            return Ok(());
+        }
+    }
+
+    let watcher_id = workspace_id.clone();
+    let watcher = tokio::task::spawn_blocking(move || start_file_watcher(app, watcher_id, root))
+        .await
+        .map_err(|err| err.to_string())??;
+    state
+        .file_watchers
+        .lock()
+        .await
+        .insert(workspace_id, watcher);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn unwatch_workspace_files(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.file_watchers.lock().await.remove(&workspace_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, DataChange, RemoveKind};
+
+    fn event(kind: EventKind, paths: &[&Path]) -> Event {
+        paths.iter().fold(Event::new(kind), |event, path| {
+            event.add_path(path.to_path_buf())
+        })
+    }
+
+    #[test]
+    fn merge_change_collapses_transient_files() {
+        assert_eq!(
+            merge_change(Some(PathChange::Added), PathChange::Removed),
+            None
+        );
+        assert_eq!(
+            merge_change(Some(PathChange::Added), PathChange::Modified),
+            Some(PathChange::Added)
+        );
+        assert_eq!(
+            merge_change(Some(PathChange::Removed), PathChange::Added),
+            Some(PathChange::Modified)
+        );
+        assert_eq!(
+            merge_change(Some(PathChange::Modified), PathChange::Removed),
+            Some(PathChange::Removed)
+        );
+    }
+
+    #[test]
+    fn classify_path_skips_heavy_and_git_internals() {
+        let root = Path::new("/ws");
+        assert_eq!(
+            classify_path(root, &[], Path::new("/ws/src/main.rs")),
+            PathClass::Workspace("src/main.rs".to_string())
+        );
+        assert_eq!(
+            classify_path(root, &[], Path::new("/ws/target/debug/app")),
+            PathClass::Skipped
+        );
+        assert_eq!(
+            classify_path(root, &[], Path::new("/ws/web/node_modules/x/index.js")),
+            PathClass::Skipped
+        );
+        assert_eq!(
+            classify_path(root, &[], Path::new("/ws/.DS_Store")),
+            PathClass::Skipped
+        );
+        assert_eq!(
+            classify_path(root, &[], Path::new("/ws/.git/index")),
+            PathClass::GitState
+        );
+        assert_eq!(
+            classify_path(root, &[], Path::new("/ws/.git/refs/heads/main")),
+            PathClass::GitState
+        );
+        assert_eq!(
+            classify_path(root, &[], Path::new("/ws/.git/index.lock")),
+            PathClass::Skipped
+        );
+        assert_eq!(
+            classify_path(root, &[], Path::new("/elsewhere/file")),
+            PathClass::Skipped
+        );
+    }
+
+    #[test]
+    fn linked_worktrees_watch_the_common_git_dir() {
+        let base = std::env::temp_dir().join(format!("moss-watcher-{}", uuid::Uuid::new_v4()));
+        let main = base.join("main");
+        std::fs::create_dir_all(&main).unwrap();
+        let repo = Repository::init(&main).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .unwrap();
+        let linked = base.join("linked");
+        repo.worktree("linked", &linked, None).unwrap();
+
+        let git_dirs = resolve_git_dirs(&linked);
+        assert_eq!(git_dirs.len(), 2);
+        let common_dir = git_dirs[1].clone();
+        assert_eq!(
+            classify_path(&linked, &git_dirs, &git_dirs[0].join("HEAD")),
+            PathClass::GitState
+        );
+        assert_eq!(
+            classify_path(&linked, &git_dirs, &common_dir.join("refs/heads/feature/x")),
+            PathClass::GitState
+        );
+        assert_eq!(
+            classify_path(&linked, &git_dirs, &common_dir.join("objects/ab/cdef")),
+            PathClass::Skipped
+        );
+        assert_eq!(resolve_git_dirs(&main).len(), 1);
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn pending_batch_folds_rename_and_write_bursts() {
+        let root = Path::new("/ws");
+        let mut batch = PendingBatch::default();
+        batch.push(
+            root,
+            &[],
+            Ok(event(
+                EventKind::Create(CreateKind::File),
+                &[Path::new("/ws/tmp.txt")],
+            )),
+        );
+        batch.push(
+            root,
+            &[],
+            Ok(event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+                &[Path::new("/ws/tmp.txt"), Path::new("/ws/notes.txt")],
+            )),
+        );
+        batch.push(
+            root,
+            &[],
+            Ok(event(
+                EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+                &[Path::new("/ws/src/lib.rs")],
+            )),
+        );
+        batch.push(
+            root,
+            &[],
+            Ok(event(
+                EventKind::Remove(RemoveKind::File),
+                &[Path::new("/ws/old.rs")],
+            )),
+        );
+        batch.push(
+            root,
+            &[],
+            Ok(event(
+                EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+                &[Path::new("/ws/.git/HEAD")],
+            )),
+        );
+
+        let mut changes: Vec<_> = batch.changes.clone().into_iter().collect();
+        changes.sort();
+        assert_eq!(
+            changes,
+            vec![
+                ("notes.txt".to_string(), PathChange::Added),
+                ("old.rs".to_string(), PathChange::Removed),
+                ("src/lib.rs".to_string(), PathChange::Modified),
+            ]
+        );
+        assert!(batch.git_changed);
+        assert!(!batch.rescan);
+    }
+
+    #[test]
+    fn watchable_entries_respects_heavy_dirs_and_gitignore() {
+        let root = std::env::temp_dir().join(format!("moss-watcher-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        std::fs::create_dir_all(root.join("logs")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(root.join(".gitignore"), "logs/\n").unwrap();
+        Repository::init(&root).unwrap();
+
+        let mut dirs: Vec<String> = watchable_entries(&root)
+            .into_iter()
+            .filter(|(_, is_dir)| *is_dir)
+            .filter_map(|(path, _)| {
+                path.strip_prefix(&root)
+                    .ok()
+                    .map(|rel| normalize_git_path(&rel.to_string_lossy()))
+            })
+            .collect();
+        dirs.sort();
+        assert_eq!(dirs, vec!["".to_string(), "src".to_string()]);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
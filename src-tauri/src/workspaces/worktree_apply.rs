@@ -0,0 +1,619 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Output, Stdio};
+
+use tauri::State;
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+use super::git::{run_git_command, run_git_command_bytes, run_git_diff};
+use super::worktree::null_device_path;
+
+use crate::git::{git_args_with_identity, identity_for_entry};
+use crate::git_utils::resolve_git_root;
+use crate::state::AppState;
+use crate::types::{
+    WorkspaceEntry, WorktreeApplyRecord, WorktreeApplySelection, WorktreeChangeHunk,
+    WorktreeChangePreview, WorktreeFileChange,
+};
+use crate::utils::{git_env_path, resolve_git_binary};
+
+const APPLY_RECORDS_FILE: &str = "worktree-applies.json";
+const MAX_APPLY_RECORDS: usize = 200;
+
+/// One file's section of a `git diff --binary` patch, split into the header
+/// lines and the individual `@@` hunks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FilePatch {
+    path: String,
+    old_path: Option<String>,
+    status: String,
+    binary: bool,
+    header: Vec<u8>,
+    hunks: Vec<Vec<u8>>,
+}
+
+impl FilePatch {
+    fn to_bytes(&self, hunks: Option<&[usize]>) -> Vec<u8> {
+        let mut bytes = self.header.clone();
+        for (index, hunk) in self.hunks.iter().enumerate() {
+            if hunks.is_none_or(|selected| selected.contains(&index)) {
+                bytes.extend_from_slice(hunk);
+            }
+        }
+        bytes
+    }
+}
+
+fn unquote_path(value: &str) -> String {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+        .to_string()
+}
+
+fn strip_side_prefix(value: &str) -> Option<String> {
+    let value = unquote_path(value);
+    if value == "/dev/null" {
+        return None;
+    }
+    Some(
+        value
+            .strip_prefix("a/")
+            .or_else(|| value.strip_prefix("b/"))
+            .unwrap_or(&value)
+            .to_string(),
+    )
+}
+
+fn finish_file_patch(header: Vec<u8>, hunks: Vec<Vec<u8>>) -> Option<FilePatch> {
+    let text = String::from_utf8_lossy(&header);
+    let mut old_path = None;
+    let mut new_path = None;
+    let mut status = "modified";
+    let mut binary = false;
+    for line in text.lines() {
+        if let Some(value) = line.strip_prefix("--- ") {
+            old_path = strip_side_prefix(value);
+        } else if let Some(value) = line.strip_prefix("+++ ") {
+            new_path = strip_side_prefix(value);
+        } else if let Some(value) = line.strip_prefix("rename from ") {
+            old_path = Some(unquote_path(value));
+            status = "renamed";
+        } else if let Some(value) = line.strip_prefix("rename to ") {
+            new_path = Some(unquote_path(value));
+        } else if line.starts_with("new file mode") {
+            status = "added";
+        } else if line.starts_with("deleted file mode") {
+            status = "deleted";
+        } else if line == "GIT binary patch" || line.starts_with("Binary files ") {
+            binary = true;
+        }
+    }
+    if old_path.is_none() && new_path.is_none() {
+        // Binary and mode-only changes carry the paths on the `diff --git` line only.
+        let first = text.lines().next()?.strip_prefix("diff --git ")?;
+        let (_, right) = first.split_once(" b/")?;
+        new_path = Some(unquote_path(right));
+    }
+    let path = new_path.clone().or_else(|| old_path.clone())?;
+    let old_path = if status == "renamed" { old_path } else { None };
+    Some(FilePatch {
+        path,
+        old_path,
+        status: status.to_string(),
+        binary,
+        header,
+        hunks,
+    })
+}
+
+fn split_patch_by_file(patch: &[u8]) -> Vec<FilePatch> {
+    let mut files = Vec::new();
+    let mut header: Vec<u8> = Vec::new();
+    let mut hunks: Vec<Vec<u8>> = Vec::new();
+    for line in patch.split_inclusive(|byte| *byte == b'\n') {
+        if line.starts_with(b"diff --git ") {
+            if !header.is_empty() {
+                files.extend(finish_file_patch(
+                    std::mem::take(&mut header),
+                    std::mem::take(&mut hunks),
+                ));
+            }
+            header.extend_from_slice(line);
+        } else if line.starts_with(b"@@") {
+            hunks.push(line.to_vec());
+        } else if let Some(hunk) = hunks.last_mut() {
+            hunk.extend_from_slice(line);
+        } else if !header.is_empty() {
+            header.extend_from_slice(line);
+        }
+    }
+    if !header.is_empty() {
+        files.extend(finish_file_patch(header, hunks));
+    }
+    files
+}
+
+fn count_changes(hunk: &[u8]) -> (usize, usize) {
+    hunk.split(|byte| *byte == b'\n')
+        .skip(1)
+        .fold((0, 0), |(added, removed), line| match line.first() {
+            Some(b'+') => (added + 1, removed),
+            Some(b'-') => (added, removed + 1),
+            _ => (added, removed),
+        })
+}
+
+pub(super) async fn resolve_worktree_and_parent(
+    state: &State<'_, AppState>,
+    workspace_id: &str,
+) -> Result<(WorkspaceEntry, WorkspaceEntry), String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(workspace_id)
+        .cloned()
+        .ok_or("workspace not found")?;
+    if !entry.kind.is_worktree() {
+        return Err("Not a worktree workspace.".to_string());
+    }
+    let parent_id = entry.parent_id.clone().ok_or("worktree parent not found")?;
+    let parent = workspaces
+        .get(&parent_id)
+        .cloned()
+        .ok_or("worktree parent not found")?;
+    Ok((entry, parent))
+}
+
+/// All uncommitted worktree changes relative to its HEAD, untracked files included.
+pub(super) async fn collect_worktree_patch(worktree_root: &PathBuf) -> Result<Vec<u8>, String> {
+    collect_worktree_patch_since(worktree_root, "HEAD").await
+}
+
+/// Working tree changes relative to `base`, so commits made in the worktree
+/// since `base` are part of the patch too.
+pub(super) async fn collect_worktree_patch_since(
+    worktree_root: &PathBuf,
+    base: &str,
+) -> Result<Vec<u8>, String> {
+    let mut patch = run_git_diff(worktree_root, &["diff", "--binary", "--no-color", base]).await?;
+    let untracked_output = run_git_command_bytes(
+        worktree_root,
+        &["ls-files", "--others", "--exclude-standard", "-z"],
+    )
+    .await?;
+    for raw_path in untracked_output.split(|byte| *byte == 0) {
+        if raw_path.is_empty() {
+            continue;
+        }
+        let path = String::from_utf8_lossy(raw_path).to_string();
+        let diff = run_git_diff(
+            worktree_root,
+            &[
+                "diff",
+                "--binary",
+                "--no-color",
+                "--no-index",
+                "--",
+                null_device_path(),
+                &path,
+            ],
+        )
+        .await?;
+        patch.extend_from_slice(&diff);
+    }
+    Ok(patch)
+}
+
+pub(super) async fn run_git_apply(
+    repo_root: &PathBuf,
+    args: &[&str],
+    patch: &[u8],
+    index_file: Option<&Path>,
+) -> Result<Output, String> {
+    let git_bin = resolve_git_binary().map_err(|e| format!("Failed to run git: {e}"))?;
+    let mut command = crate::utils::async_command(git_bin);
+    command
+        .arg("apply")
+        .args(args)
+        .arg("-")
+        .current_dir(repo_root)
+        .env("PATH", git_env_path())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(index_file) = index_file {
+        command.env("GIT_INDEX_FILE", index_file);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(patch)
+            .await
+            .map_err(|e| format!("Failed to write git apply input: {e}"))?;
+    }
+    child
+        .wait_with_output()
+        .await
+        .map_err(|e| format!("Failed to run git: {e}"))
+}
+
+pub(super) fn apply_output_detail(output: &Output) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.trim().is_empty() {
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    } else {
+        stderr.trim().to_string()
+    }
+}
+
+async fn parent_index_path(parent_root: &PathBuf) -> Result<PathBuf, String> {
+    let index = run_git_command(parent_root, &["rev-parse", "--git-path", "index"]).await?;
+    Ok(parent_root.join(index))
+}
+
+/// `git apply --check --3way` passes whenever a merge is possible, even one
+/// that conflicts, so the three-way attempt runs against a scratch copy of
+/// the parent's index instead.
+async fn predict_file_apply(
+    parent_root: &PathBuf,
+    parent_index: &Path,
+    patch: &[u8],
+) -> (String, Option<String>) {
+    let clean = match run_git_apply(parent_root, &["--check"], patch, None).await {
+        Ok(output) if output.status.success() => return ("clean".to_string(), None),
+        Ok(output) => apply_output_detail(&output),
+        Err(error) => return ("conflict".to_string(), Some(error)),
+    };
+    if let Ok(output) = run_git_apply(parent_root, &["--check", "--reverse"], patch, None).await {
+        if output.status.success() {
+            return ("already-applied".to_string(), None);
+        }
+    }
+    let scratch_index = std::env::temp_dir().join(format!("moss-apply-index-{}", Uuid::new_v4()));
+    if let Err(error) = fs::copy(parent_index, &scratch_index) {
+        return ("conflict".to_string(), Some(format!("{clean}\n{error}")));
+    }
+    let result = run_git_apply(
+        parent_root,
+        &["--3way", "--cached"],
+        patch,
+        Some(&scratch_index),
+    )
+    .await;
+    let _ = fs::remove_file(&scratch_index);
+    match result {
+        Ok(output) if output.status.success() => ("three-way".to_string(), Some(clean)),
+        Ok(output) => ("conflict".to_string(), Some(apply_output_detail(&output))),
+        Err(error) => ("conflict".to_string(), Some(error)),
+    }
+}
+
+pub(super) async fn is_repo_dirty(repo_root: &PathBuf) -> Result<bool, String> {
+    let status = run_git_command_bytes(repo_root, &["status", "--porcelain"]).await?;
+    Ok(!String::from_utf8_lossy(&status).trim().is_empty())
+}
+
+fn apply_records_path(state: &AppState) -> PathBuf {
+    state
+        .storage_path
+        .parent()
+        .map(|dir| dir.join(APPLY_RECORDS_FILE))
+        .unwrap_or_else(|| PathBuf::from(APPLY_RECORDS_FILE))
+}
+
+fn read_worktree_apply_records(path: &PathBuf) -> Result<Vec<WorktreeApplyRecord>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&data).map_err(|e| e.to_string())
+}
+
+fn write_worktree_apply_records(
+    path: &PathBuf,
+    records: &[WorktreeApplyRecord],
+) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let data = serde_json::to_string_pretty(records).map_err(|e| e.to_string())?;
+    fs::write(path, data).map_err(|e| e.to_string())
+}
+
+fn default_apply_commit_message(branch: Option<&str>, files: &[WorktreeApplySelection]) -> String {
+    let source = branch.unwrap_or("worktree");
+    let mut message = format!("Apply changes from {source}\n");
+    if !files.is_empty() {
+        message.push('\n');
+        for file in files {
+            match &file.hunks {
+                Some(hunks) => {
+                    message.push_str(&format!("- {} ({} of its hunks)\n", file.path, hunks.len()))
+                }
+                None => message.push_str(&format!("- {}\n", file.path)),
+            }
+        }
+    }
+    message
+}
+
+#[tauri::command]
+pub(crate) async fn preview_worktree_changes(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<WorktreeChangePreview, String> {
+    let (entry, parent) = resolve_worktree_and_parent(&state, &workspace_id).await?;
+    let worktree_root = resolve_git_root(&entry)?;
+    let parent_root = resolve_git_root(&parent)?;
+
+    let patch = collect_worktree_patch(&worktree_root).await?;
+    let parent_index = parent_index_path(&parent_root).await?;
+    let mut files = Vec::new();
+    for file in split_patch_by_file(&patch) {
+        let bytes = file.to_bytes(None);
+        let (prediction, prediction_detail) =
+            predict_file_apply(&parent_root, &parent_index, &bytes).await;
+        let hunks = file
+            .hunks
+            .iter()
+            .enumerate()
+            .map(|(index, hunk)| {
+                let (additions, deletions) = count_changes(hunk);
+                let header = hunk
+                    .split(|byte| *byte == b'\n')
+                    .next()
+                    .map(|line| String::from_utf8_lossy(line).to_string())
+                    .unwrap_or_default();
+                WorktreeChangeHunk {
+                    index,
+                    header,
+                    additions,
+                    deletions,
+                }
+            })
+            .collect::<Vec<_>>();
+        files.push(WorktreeFileChange {
+            additions: hunks.iter().map(|hunk| hunk.additions).sum(),
+            deletions: hunks.iter().map(|hunk| hunk.deletions).sum(),
+            path: file.path,
+            old_path: file.old_path,
+            status: file.status,
+            binary: file.binary,
+            hunks,
+            patch: String::from_utf8_lossy(&bytes).to_string(),
+            prediction,
+            prediction_detail,
+        });
+    }
+    Ok(WorktreeChangePreview {
+        workspace_id,
+        parent_id: parent.id,
+        parent_dirty: is_repo_dirty(&parent_root).await?,
+        files,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn apply_worktree_change_selection(
+    workspace_id: String,
+    files: Vec<WorktreeApplySelection>,
+    commit: Option<bool>,
+    commit_message: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<WorktreeApplyRecord, String> {
+    if files.is_empty() {
+        return Err("Select at least one file to apply.".to_string());
+    }
+    let (entry, parent) = resolve_worktree_and_parent(&state, &workspace_id).await?;
+    let worktree_root = resolve_git_root(&entry)?;
+    let parent_root = resolve_git_root(&parent)?;
+    if is_repo_dirty(&parent_root).await? {
+        return Err(
+            "Your current branch has uncommitted changes. Please commit, stash, or discard them before applying worktree changes."
+                .to_string(),
+        );
+    }
+
+    let available = split_patch_by_file(&collect_worktree_patch(&worktree_root).await?);
+    let mut patch = Vec::new();
+    for selection in &files {
+        let file = available
+            .iter()
+            .find(|file| file.path == selection.path)
+            .ok_or_else(|| format!("No pending change for `{}`.", selection.path))?;
+        if let Some(hunks) = selection.hunks.as_deref() {
+            if file.binary {
+                return Err(format!(
+                    "`{}` is binary and can only be applied whole.",
+                    file.path
+                ));
+            }
+            if hunks.is_empty() || hunks.iter().any(|index| *index >= file.hunks.len()) {
+                return Err(format!("Invalid hunk selection for `{}`.", file.path));
+            }
+        }
+        patch.extend_from_slice(&file.to_bytes(selection.hunks.as_deref()));
+    }
+
+    let output = run_git_apply(
+        &parent_root,
+        &["--3way", "--whitespace=nowarn"],
+        &patch,
+        None,
+    )
+    .await?;
+    if !output.status.success() {
+        let detail = apply_output_detail(&output);
+        if detail.contains("with conflicts") {
+            return Err(
+                "Applied with conflicts. Resolve conflicts in the parent repo before retrying."
+                    .to_string(),
+            );
+        }
+        return Err(if detail.is_empty() {
+            "Git apply failed.".to_string()
+        } else {
+            detail
+        });
+    }
+
+    let branch = entry.worktree.as_ref().map(|info| info.branch.clone());
+    let mut commit_sha = None;
+    let mut final_message = None;
+    if commit.unwrap_or(false) {
+        let message = commit_message
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| default_apply_commit_message(branch.as_deref(), &files));
+        let identity = {
+            let workspaces = state.workspaces.lock().await;
+            identity_for_entry(&workspaces, &parent)
+        };
+        let args = git_args_with_identity(identity.as_ref(), &["commit", "-m", &message])?;
+        let arg_refs = args.iter().map(String::as_str).collect::<Vec<_>>();
+        run_git_command(&parent_root, &arg_refs).await?;
+        commit_sha = Some(run_git_command(&parent_root, &["rev-parse", "HEAD"]).await?);
+        final_message = Some(message);
+    }
+
+    let record = WorktreeApplyRecord {
+        id: Uuid::new_v4().to_string(),
+        workspace_id,
+        parent_id: parent.id,
+        branch,
+        applied_at: chrono::Utc::now().timestamp_millis(),
+        files,
+        commit_sha,
+        commit_message: final_message,
+    };
+    let records_path = apply_records_path(&state);
+    let mut records = read_worktree_apply_records(&records_path).unwrap_or_default();
+    records.push(record.clone());
+    if records.len() > MAX_APPLY_RECORDS {
+        let excess = records.len() - MAX_APPLY_RECORDS;
+        records.drain(0..excess);
+    }
+    write_worktree_apply_records(&records_path, &records)?;
+    Ok(record)
+}
+
+#[tauri::command]
+pub(crate) async fn list_worktree_apply_records(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<WorktreeApplyRecord>, String> {
+    let records = read_worktree_apply_records(&apply_records_path(&state))?;
+    Ok(records
+        .into_iter()
+        .filter(|record| record.workspace_id == workspace_id || record.parent_id == workspace_id)
+        .rev()
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::git;
+
+    const SAMPLE_PATCH: &str = "diff --git a/src/lib.rs b/src/lib.rs\n\
+index 1111111..2222222 100644\n\
+--- a/src/lib.rs\n\
++++ b/src/lib.rs\n\
+@@ -1,2 +1,2 @@\n\
+-one\n\
++uno\n\
+ two\n\
+@@ -10,1 +10,2 @@\n\
+ ten\n\
++eleven\n\
+diff --git a/old.txt b/new.txt\n\
+similarity index 100%\n\
+rename from old.txt\n\
+rename to new.txt\n\
+diff --git a/logo.png b/logo.png\n\
+new file mode 100644\n\
+index 0000000..3333333\n\
+GIT binary patch\n\
+literal 4\n\
+LcmZ?wbhEq+0A;~3\n\
+\n\
+literal 0\n\
+HcmV?d00001\n\
+\n";
+
+    #[test]
+    fn splits_patch_into_files_and_hunks() {
+        let files = split_patch_by_file(SAMPLE_PATCH.as_bytes());
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!(files[0].hunks.len(), 2);
+        assert_eq!(count_changes(&files[0].hunks[0]), (1, 1));
+        assert_eq!(count_changes(&files[0].hunks[1]), (1, 0));
+        let second_only = String::from_utf8(files[0].to_bytes(Some(&[1]))).unwrap();
+        assert!(second_only.contains("+eleven") && !second_only.contains("+uno"));
+
+        assert_eq!(files[1].status, "renamed");
+        assert_eq!(files[1].path, "new.txt");
+        assert_eq!(files[1].old_path.as_deref(), Some("old.txt"));
+
+        assert_eq!(
+            (files[2].path.as_str(), files[2].status.as_str()),
+            ("logo.png", "added")
+        );
+        assert!(files[2].binary);
+        assert!(files[2].hunks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn predicts_clean_and_conflicting_files() {
+        let root = std::env::temp_dir().join(format!("moss-wt-apply-{}", Uuid::new_v4()));
+        let repo = root.join("repo");
+        fs::create_dir_all(&repo).expect("create repo");
+        git(&repo, &["init", "-q", "-b", "main"]);
+        fs::write(repo.join("a.txt"), "a\n").expect("write");
+        fs::write(repo.join("b.txt"), "b\n").expect("write");
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "-q", "-m", "base"]);
+        let worktree = root.join("wt");
+        git(
+            &repo,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "-b",
+                "feature",
+                &worktree.to_string_lossy(),
+            ],
+        );
+        fs::write(worktree.join("a.txt"), "a changed\n").expect("write");
+        fs::write(worktree.join("b.txt"), "b changed\n").expect("write");
+        fs::write(worktree.join("c.txt"), "new\n").expect("write");
+        fs::write(repo.join("b.txt"), "b upstream\n").expect("write");
+        git(&repo, &["commit", "-q", "-am", "parent edit"]);
+
+        let patch = collect_worktree_patch(&worktree).await.expect("patch");
+        let files = split_patch_by_file(&patch);
+        let parent_index = parent_index_path(&repo).await.expect("index path");
+        let mut predictions = Vec::new();
+        for file in &files {
+            let (prediction, _) =
+                predict_file_apply(&repo, &parent_index, &file.to_bytes(None)).await;
+            predictions.push((file.path.clone(), prediction));
+        }
+        assert_eq!(
+            predictions,
+            vec![
+                ("a.txt".to_string(), "clean".to_string()),
+                ("b.txt".to_string(), "conflict".to_string()),
+                ("c.txt".to_string(), "clean".to_string()),
+            ]
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -453,6 +453,7 @@ function MainApp() {
     addWorktreeAgent,
     connectWorkspace,
     markWorkspaceConnected,
+    markWorkspaceDisconnected,
     updateWorkspaceSettings,
     updateWorkspaceCodexBin,
     createWorkspaceGroup,
@@ -1190,6 +1191,7 @@ function MainApp() {
     activeWorkspace,
     onDebug: addDebugEntry,
     pollingEnabled: workspaceFilesPollingEnabled,
+    onGitChanged: queueGitStatusRefresh,
   });
   const { branches, checkoutBranch, createBranch } = useGitBranches({
     activeWorkspace,
@@ -1425,6 +1427,7 @@ function MainApp() {
   } = useThreads({
     activeWorkspace,
     onWorkspaceConnected: markWorkspaceConnected,
+    onWorkspaceDisconnected: markWorkspaceDisconnected,
     onDebug: addDebugEntry,
     model: resolvedModel,
     effort: resolvedEffort,
@@ -54,6 +54,7 @@ describe("useAppServerEvents", () => {
     const handlers: Handlers = {
       onAppServerEvent: vi.fn(),
       onWorkspaceConnected: vi.fn(),
+      onWorkspaceDisconnected: vi.fn(),
       onThreadStarted: vi.fn(),
       onBackgroundThreadAction: vi.fn(),
       onAgentMessageDelta: vi.fn(),
@@ -78,6 +79,11 @@ describe("useAppServerEvents", () => {
     });
     expect(handlers.onWorkspaceConnected).toHaveBeenCalledWith("ws-1");
 
+    act(() => {
+      listener?.({ workspace_id: "ws-1", message: { method: "codex/disconnected" } });
+    });
+    expect(handlers.onWorkspaceDisconnected).toHaveBeenCalledWith("ws-1");
+
     act(() => {
       listener?.({
         workspace_id: "ws-1",
@@ -30,6 +30,7 @@ type AgentCompleted = {
 
 type AppServerEventHandlers = {
   onWorkspaceConnected?: (workspaceId: string) => void;
+  onWorkspaceDisconnected?: (workspaceId: string) => void;
   onThreadStarted?: (workspaceId: string, thread: Record<string, unknown>) => void;
   onThreadSessionIdUpdated?: (
     workspaceId: string,
@@ -346,6 +347,11 @@ export function useAppServerEvents(
         return;
       }
 
+      if (method === "codex/disconnected") {
+        handlers.onWorkspaceDisconnected?.(workspace_id);
+        return;
+      }
+
       const requestId = message.id;
       const hasRequestId =
         typeof requestId === "number" || typeof requestId === "string";
@@ -566,6 +566,7 @@ export function FileViewPanel({
   const [definitionCandidates, setDefinitionCandidates] = useState<LspLocationLike[]>([]);
   const [referenceResults, setReferenceResults] = useState<LspLocationLike[] | null>(null);
   const savedContentRef = useRef("");
+  const savedEncodingRef = useRef<{ encoding?: string; bom?: boolean }>({});
   const cmRef = useRef<ReactCodeMirrorRef>(null);
   const requestIdRef = useRef(0);
   const lspRequestIdRef = useRef(0);
@@ -683,6 +684,7 @@ export function FileViewPanel({
       setError(null);
       setContent("");
       savedContentRef.current = "";
+      savedEncodingRef.current = {};
       setTruncated(false);
       return;
     }
@@ -698,6 +700,10 @@ export function FileViewPanel({
         if (cancelled || currentRequest !== requestIdRef.current) return;
         setContent(response.content ?? "");
         savedContentRef.current = response.content ?? "";
+        savedEncodingRef.current = {
+          encoding: response.encoding || undefined,
+          bom: Boolean(response.bom),
+        };
         setTruncated(Boolean(response.truncated));
       })
       .catch((err) => {
@@ -762,7 +768,7 @@ export function FileViewPanel({
     if (!isDirty || isSaving || truncated) return;
     setIsSaving(true);
     try {
-      await writeWorkspaceFile(workspaceId, filePath, content);
+      await writeWorkspaceFile(workspaceId, filePath, content, savedEncodingRef.current);
       savedContentRef.current = content;
     } catch (err) {
       pushErrorToast({
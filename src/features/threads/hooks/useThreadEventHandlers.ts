@@ -34,6 +34,7 @@ type ThreadEventHandlersOptions = {
   pushThreadErrorMessage: (threadId: string, message: string) => void;
   onDebug?: (entry: DebugEntry) => void;
   onWorkspaceConnected: (workspaceId: string) => void;
+  onWorkspaceDisconnected?: (workspaceId: string) => void;
   applyCollabThreadLinks: (
     threadId: string,
     item: Record<string, unknown>,
@@ -90,6 +91,7 @@ export function useThreadEventHandlers({
   pushThreadErrorMessage,
   onDebug,
   onWorkspaceConnected,
+  onWorkspaceDisconnected,
   applyCollabThreadLinks,
   approvalAllowlistRef,
   pendingInterruptsRef,
@@ -380,6 +382,7 @@ export function useThreadEventHandlers({
   const handlers = useMemo(
     () => ({
       onWorkspaceConnected,
+      onWorkspaceDisconnected,
       onApprovalRequest,
       onRequestUserInput,
       onModeBlocked,
@@ -412,6 +415,7 @@ export function useThreadEventHandlers({
     }),
     [
       onWorkspaceConnected,
+      onWorkspaceDisconnected,
       onApprovalRequest,
       onRequestUserInput,
       onModeBlocked,
@@ -307,6 +307,7 @@ function memoryDebugLog(message: string, payload?: Record<string, unknown>) {
 type UseThreadsOptions = {
   activeWorkspace: WorkspaceInfo | null;
   onWorkspaceConnected: (id: string) => void;
+  onWorkspaceDisconnected?: (id: string) => void;
   onDebug?: (entry: DebugEntry) => void;
   model?: string | null;
   effort?: string | null;
@@ -437,6 +438,7 @@ export function resolvePendingThreadIdForSession({
 export function useThreads({
   activeWorkspace,
   onWorkspaceConnected,
+  onWorkspaceDisconnected,
   onDebug,
   model,
   effort,
@@ -1580,6 +1582,7 @@ export function useThreads({
     pushThreadErrorMessage,
     onDebug,
     onWorkspaceConnected: handleWorkspaceConnected,
+    onWorkspaceDisconnected,
     applyCollabThreadLinks,
     approvalAllowlistRef,
     pendingInterruptsRef,
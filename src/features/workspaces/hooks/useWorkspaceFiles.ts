@@ -1,6 +1,11 @@
 import { useCallback, useEffect, useMemo, useRef, useState } from "react";
 import type { DebugEntry, WorkspaceInfo } from "../../../types";
-import { getWorkspaceFiles } from "../../../services/tauri";
+import {
+  getWorkspaceFiles,
+  unwatchWorkspaceFiles,
+  watchWorkspaceFiles,
+} from "../../../services/tauri";
+import { subscribeWorkspaceFilesChanged } from "../../../services/events";
 
 const WORKSPACE_FILES_DEBUG_KEY = "mossx.debug.workspace-files";
 const WORKSPACE_FILES_SLOW_REQUEST_MS = 800;
@@ -16,12 +21,14 @@ type UseWorkspaceFilesOptions = {
   activeWorkspace: WorkspaceInfo | null;
   onDebug?: (entry: DebugEntry) => void;
   pollingEnabled?: boolean;
+  onGitChanged?: () => void;
 };
 
 export function useWorkspaceFiles({
   activeWorkspace,
   onDebug,
   pollingEnabled = true,
+  onGitChanged,
 }: UseWorkspaceFilesOptions) {
   const [files, setFiles] = useState<string[]>([]);
   const [directories, setDirectories] = useState<string[]>([]);
@@ -30,13 +37,16 @@ export function useWorkspaceFiles({
   const hasLoadedWorkspaceId = useRef<string | null>(null);
   const inFlight = useRef<string | null>(null);
   const consecutiveFailures = useRef(0);
+  const [isWatching, setIsWatching] = useState(false);
 
   const BASE_REFRESH_INTERVAL_MS = 30_000;
   const MAX_REFRESH_INTERVAL_MS = 180_000;
   const workspaceId = activeWorkspace?.id ?? null;
   const isConnected = Boolean(activeWorkspace?.connected);
 
-  const refreshFiles = useCallback(async (reason: "initial" | "poll" | "manual" = "manual") => {
+  const refreshFiles = useCallback(async (
+    reason: "initial" | "poll" | "watch" | "manual" = "manual",
+  ) => {
     if (!workspaceId || !isConnected) {
       return;
     }
@@ -46,7 +56,7 @@ export function useWorkspaceFiles({
     inFlight.current = workspaceId;
     const requestWorkspaceId = workspaceId;
     const isFirstLoadForWorkspace = hasLoadedWorkspaceId.current !== workspaceId;
-    if (reason !== "poll" || isFirstLoadForWorkspace) {
+    if ((reason !== "poll" && reason !== "watch") || isFirstLoadForWorkspace) {
       setIsLoading(true);
     }
     const startedAt = Date.now();
@@ -155,8 +165,52 @@ export function useWorkspaceFiles({
     void refreshFiles("initial");
   }, [isConnected, refreshFiles, workspaceId]);
 
+  const refreshFilesRef = useRef(refreshFiles);
+  const onGitChangedRef = useRef(onGitChanged);
   useEffect(() => {
-    if (!workspaceId || !isConnected || !pollingEnabled) {
+    refreshFilesRef.current = refreshFiles;
+    onGitChangedRef.current = onGitChanged;
+  }, [onGitChanged, refreshFiles]);
+
+  // The backend watcher reports changes as they happen; polling is only the
+  // fallback for when it cannot run (remote mode, watch limits).
+  useEffect(() => {
+    if (!workspaceId || !isConnected) {
+      return;
+    }
+    let cancelled = false;
+    const unsubscribe = subscribeWorkspaceFilesChanged((event) => {
+      if (event.workspaceId !== workspaceId) {
+        return;
+      }
+      if (event.rescan || event.added.length > 0 || event.removed.length > 0) {
+        void refreshFilesRef.current("watch");
+      }
+      if (event.gitChanged || event.gitStatus.length > 0) {
+        onGitChangedRef.current?.();
+      }
+    });
+    watchWorkspaceFiles(workspaceId)
+      .then(() => {
+        if (!cancelled) {
+          setIsWatching(true);
+        }
+      })
+      .catch(() => {
+        if (!cancelled) {
+          setIsWatching(false);
+        }
+      });
+    return () => {
+      cancelled = true;
+      setIsWatching(false);
+      unsubscribe();
+      void unwatchWorkspaceFiles(workspaceId).catch(() => {});
+    };
+  }, [isConnected, workspaceId]);
+
+  useEffect(() => {
+    if (!workspaceId || !isConnected || !pollingEnabled || isWatching) {
       return;
     }
 
@@ -183,7 +237,7 @@ export function useWorkspaceFiles({
       cancelled = true;
       window.clearTimeout(timeoutId);
     };
-  }, [isConnected, pollingEnabled, refreshFiles, workspaceId]);
+  }, [isConnected, isWatching, pollingEnabled, refreshFiles, workspaceId]);
 
   const fileOptions = useMemo(() => files.filter(Boolean), [files]);
   const directoryOptions = useMemo(() => directories.filter(Boolean), [directories]);
@@ -431,6 +431,12 @@ export function useWorkspaces(options: UseWorkspacesOptions = {}) {
     );
   }
 
+  function markWorkspaceDisconnected(id: string) {
+    setWorkspaces((prev) =>
+      prev.map((entry) => (entry.id === id ? { ...entry, connected: false } : entry)),
+    );
+  }
+
   const updateWorkspaceSettings = useCallback(
     async (workspaceId: string, patch: Partial<WorkspaceSettings>) => {
       onDebug?.({
@@ -894,6 +900,7 @@ export function useWorkspaces(options: UseWorkspacesOptions = {}) {
     addWorktreeAgent,
     connectWorkspace,
     markWorkspaceConnected,
+    markWorkspaceDisconnected,
     updateWorkspaceSettings,
     updateWorkspaceCodexBin,
     createWorkspaceGroup,
@@ -1,5 +1,13 @@
 import { listen } from "@tauri-apps/api/event";
-import type { AppServerEvent, DictationEvent, DictationModelStatus } from "../types";
+import type {
+  AppServerEvent,
+  DictationEvent,
+  DictationModelStatus,
+  LspDiagnosticsEvent,
+  LspTurnDiagnosticsEvent,
+  WorkspaceFilesChangedEvent,
+  WorkspaceSearchMatchEvent,
+} from "../types";
 
 export type Unsubscribe = () => void;
 
@@ -106,6 +114,16 @@ const menuComposerCycleCollaborationHub = createEventHub<void>(
   "menu-composer-cycle-collaboration",
 );
 const openPathsHub = createEventHub<string[]>("open-paths");
+const workspaceFilesChangedHub = createEventHub<WorkspaceFilesChangedEvent>(
+  "workspace-files-changed",
+);
+const workspaceSearchMatchHub = createEventHub<WorkspaceSearchMatchEvent>(
+  "workspace-search-match",
+);
+const lspDiagnosticsHub = createEventHub<LspDiagnosticsEvent>("lsp-diagnostics");
+const lspTurnDiagnosticsHub = createEventHub<LspTurnDiagnosticsEvent>(
+  "lsp-turn-diagnostics",
+);
 
 export function subscribeAppServerEvents(
   onEvent: (event: AppServerEvent) => void,
@@ -348,3 +366,31 @@ export function subscribeOpenPaths(
 ): Unsubscribe {
   return openPathsHub.subscribe(onEvent, options);
 }
+
+export function subscribeWorkspaceFilesChanged(
+  onEvent: (event: WorkspaceFilesChangedEvent) => void,
+  options?: SubscriptionOptions,
+): Unsubscribe {
+  return workspaceFilesChangedHub.subscribe(onEvent, options);
+}
+
+export function subscribeWorkspaceSearchMatches(
+  onEvent: (event: WorkspaceSearchMatchEvent) => void,
+  options?: SubscriptionOptions,
+): Unsubscribe {
+  return workspaceSearchMatchHub.subscribe(onEvent, options);
+}
+
+export function subscribeLspDiagnostics(
+  onEvent: (event: LspDiagnosticsEvent) => void,
+  options?: SubscriptionOptions,
+): Unsubscribe {
+  return lspDiagnosticsHub.subscribe(onEvent, options);
+}
+
+export function subscribeLspTurnDiagnostics(
+  onEvent: (event: LspTurnDiagnosticsEvent) => void,
+  options?: SubscriptionOptions,
+): Unsubscribe {
+  return lspTurnDiagnosticsHub.subscribe(onEvent, options);
+}
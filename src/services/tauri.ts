@@ -8,8 +8,21 @@ import type {
   CodexDoctorResult,
   DictationModelStatus,
   DictationSessionState,
+  FanoutCandidateSpec,
+  FanoutRun,
   LocalUsageSnapshot,
+  WorkspaceContentSearchQuery,
   WorkspaceInfo,
+  WorkspaceReplacePreview,
+  WorkspaceReplaceResult,
+  WorkspaceSearchSummary,
+  WorktreeApplyRecord,
+  WorktreeArchive,
+  WorktreeApplySelection,
+  WorktreeChangePreview,
+  WorktreeLifecycleInfo,
+  WorktreePrunePolicy,
+  WorktreePruneResult,
   WorkspaceSettings,
   EngineStatus,
   EngineType,
@@ -19,18 +32,34 @@ import type {
   GitFileDiff,
   GitFileStatus,
   GitHistoryResponse,
+  GitHistorySearchResponse,
+  GitCommitGraphResponse,
   GitCommitDetails,
   GitCommitDiff,
   GitBranchCompareCommitSets,
+  GitBranchDeleteResult,
+  GitBranchHygieneReport,
   GitBranchListResponse,
   GitPrWorkflowDefaults,
   GitPrWorkflowResult,
+  GitForgeInfo,
   GitHubIssuesResponse,
+  GitBisectResult,
   GitHubPullRequestComment,
   GitHubPullRequestDiff,
+  GitHubPullRequestReviewComment,
+  GitHubPullRequestReviewEvent,
+  GitHubPullRequestReviewReport,
+  GitHubPullRequestReviewResult,
   GitHubPullRequestsResponse,
   GitLogResponse,
   GitPushPreviewResponse,
+  GitRebasePlan,
+  GitRebasePlanEntry,
+  GitRebaseResult,
+  GitSubmoduleStatus,
+  GitReleaseNotesResponse,
+  GitTagInfo,
   ReviewTarget,
 } from "../types";
 
@@ -237,6 +266,106 @@ export async function applyWorktreeChanges(workspaceId: string): Promise<void> {
   return invoke("apply_worktree_changes", { workspaceId });
 }
 
+export async function previewWorktreeChanges(
+  workspaceId: string,
+): Promise<WorktreeChangePreview> {
+  return invoke<WorktreeChangePreview>("preview_worktree_changes", { workspaceId });
+}
+
+export async function applyWorktreeChangeSelection(
+  workspaceId: string,
+  files: WorktreeApplySelection[],
+  options?: {
+    commit?: boolean;
+    commitMessage?: string | null;
+  },
+): Promise<WorktreeApplyRecord> {
+  return invoke<WorktreeApplyRecord>("apply_worktree_change_selection", {
+    workspaceId,
+    files,
+    commit: options?.commit ?? false,
+    commitMessage: options?.commitMessage ?? null,
+  });
+}
+
+export async function listWorktreeApplyRecords(
+  workspaceId: string,
+): Promise<WorktreeApplyRecord[]> {
+  return invoke<WorktreeApplyRecord[]>("list_worktree_apply_records", { workspaceId });
+}
+
+export async function listWorktreeLifecycle(): Promise<WorktreeLifecycleInfo[]> {
+  return invoke<WorktreeLifecycleInfo[]>("list_worktree_lifecycle");
+}
+
+export async function archiveWorktree(workspaceId: string): Promise<WorktreeArchive> {
+  return invoke<WorktreeArchive>("archive_worktree", { workspaceId });
+}
+
+export async function listWorktreeArchives(): Promise<WorktreeArchive[]> {
+  return invoke<WorktreeArchive[]>("list_worktree_archives");
+}
+
+export async function restoreWorktreeArchive(archiveId: string): Promise<WorkspaceInfo> {
+  return invoke<WorkspaceInfo>("restore_worktree_archive", { archiveId });
+}
+
+export async function pruneWorktrees(options?: {
+  policy?: WorktreePrunePolicy | null;
+  dryRun?: boolean;
+}): Promise<WorktreePruneResult[]> {
+  return invoke<WorktreePruneResult[]>("prune_worktrees", {
+    policy: options?.policy ?? null,
+    dryRun: options?.dryRun ?? false,
+  });
+}
+
+export async function startFanoutRun(
+  parentId: string,
+  prompt: string,
+  candidates: FanoutCandidateSpec[],
+  options?: {
+    baseRef?: string | null;
+    testCommand?: string[] | null;
+    accessMode?: "read-only" | "current" | "full-access";
+  },
+): Promise<FanoutRun> {
+  return invoke<FanoutRun>("start_fanout_run", {
+    parentId,
+    prompt,
+    candidates,
+    baseRef: options?.baseRef ?? null,
+    testCommand: options?.testCommand ?? null,
+    accessMode: options?.accessMode ?? null,
+  });
+}
+
+export async function getFanoutRun(runId: string): Promise<FanoutRun> {
+  return invoke<FanoutRun>("get_fanout_run", { runId });
+}
+
+export async function listFanoutRuns(parentId?: string | null): Promise<FanoutRun[]> {
+  return invoke<FanoutRun[]>("list_fanout_runs", { parentId: parentId ?? null });
+}
+
+export async function applyFanoutWinner(
+  runId: string,
+  workspaceId: string,
+  options?: {
+    commit?: boolean;
+    commitMessage?: string | null;
+    removeOthers?: boolean;
+  },
+): Promise<FanoutRun> {
+  return invoke<FanoutRun>("apply_fanout_winner", {
+    runId,
+    workspaceId,
+    commit: options?.commit ?? false,
+    commitMessage: options?.commitMessage ?? null,
+    removeOthers: options?.removeOthers ?? false,
+  });
+}
+
 export async function openWorkspaceIn(
   path: string,
   options: {
@@ -369,6 +498,7 @@ export async function getGitStatus(workspace_id: string): Promise<{
   unstagedFiles: GitFileStatus[];
   totalAdditions: number;
   totalDeletions: number;
+  submodules?: GitSubmoduleStatus[];
 }> {
   return invoke("get_git_status", { workspaceId: workspace_id });
 }
@@ -380,6 +510,40 @@ export async function listGitRoots(
   return invoke("list_git_roots", { workspaceId: workspace_id, depth });
 }
 
+export async function getGitSubmodules(
+  workspace_id: string,
+): Promise<GitSubmoduleStatus[]> {
+  return invoke("get_git_submodules", { workspaceId: workspace_id });
+}
+
+export async function updateGitSubmodules(
+  workspace_id: string,
+  options?: {
+    paths?: string[] | null;
+    init?: boolean;
+    recursive?: boolean;
+    remote?: boolean;
+  },
+): Promise<GitSubmoduleStatus[]> {
+  return invoke("update_git_submodules", {
+    workspaceId: workspace_id,
+    paths: options?.paths ?? null,
+    init: options?.init ?? null,
+    recursive: options?.recursive ?? null,
+    remote: options?.remote ?? null,
+  });
+}
+
+export async function syncGitSubmodules(
+  workspace_id: string,
+  options?: { recursive?: boolean },
+): Promise<GitSubmoduleStatus[]> {
+  return invoke("sync_git_submodules", {
+    workspaceId: workspace_id,
+    recursive: options?.recursive ?? null,
+  });
+}
+
 export async function getGitDiffs(
   workspace_id: string,
 ): Promise<GitFileDiff[]> {
@@ -426,6 +590,52 @@ export async function getGitCommitHistory(
   });
 }
 
+export async function getGitCommitGraph(
+  workspace_id: string,
+  options?: {
+    branches?: string[] | null;
+    offset?: number;
+    limit?: number;
+  },
+): Promise<GitCommitGraphResponse> {
+  return invoke("get_git_commit_graph", {
+    workspaceId: workspace_id,
+    branches: options?.branches ?? null,
+    offset: options?.offset ?? 0,
+    limit: options?.limit ?? 100,
+  });
+}
+
+export async function searchGitHistory(
+  workspace_id: string,
+  options?: {
+    branch?: string | null;
+    messagePattern?: string | null;
+    author?: string | null;
+    dateFrom?: number | null;
+    dateTo?: number | null;
+    path?: string | null;
+    pickaxe?: string | null;
+    pickaxeMode?: "string" | "regex" | null;
+    offset?: number;
+    limit?: number;
+  },
+): Promise<GitHistorySearchResponse> {
+  return invoke("search_git_history", {
+    workspaceId: workspace_id,
+    branch: options?.branch ?? null,
+    messagePattern: options?.messagePattern ?? null,
+    author: options?.author ?? null,
+    dateFrom: options?.dateFrom ?? null,
+    dateTo: options?.dateTo ?? null,
+    path: options?.path ?? null,
+    pickaxe: options?.pickaxe ?? null,
+    pickaxeMode: options?.pickaxeMode ?? null,
+    offset: options?.offset ?? 0,
+    limit: options?.limit ?? 50,
+  });
+}
+
 export async function getGitPushPreview(
   workspace_id: string,
   options: {
@@ -638,6 +848,12 @@ export async function resetGitCommit(
   return invoke("reset_git_commit", { workspaceId, commitHash, mode });
 }
 
+export async function getGitForgeInfo(
+  workspace_id: string,
+): Promise<GitForgeInfo> {
+  return invoke("get_git_forge_info", { workspaceId: workspace_id });
+}
+
 export async function getGitHubIssues(
   workspace_id: string,
 ): Promise<GitHubIssuesResponse> {
@@ -670,6 +886,60 @@ export async function getGitHubPullRequestComments(
   });
 }
 
+export async function postGitHubPullRequestComment(
+  workspace_id: string,
+  prNumber: number,
+  body: string,
+): Promise<GitHubPullRequestComment> {
+  return invoke("post_github_pull_request_comment", {
+    workspaceId: workspace_id,
+    prNumber,
+    body,
+  });
+}
+
+export async function postGitHubPullRequestInlineComment(
+  workspace_id: string,
+  prNumber: number,
+  comment: GitHubPullRequestReviewComment,
+): Promise<GitHubPullRequestComment> {
+  return invoke("post_github_pull_request_inline_comment", {
+    workspaceId: workspace_id,
+    prNumber,
+    comment,
+  });
+}
+
+export async function submitGitHubPullRequestReview(
+  workspace_id: string,
+  prNumber: number,
+  event: GitHubPullRequestReviewEvent,
+  options?: {
+    body?: string | null;
+    comments?: GitHubPullRequestReviewComment[] | null;
+  },
+): Promise<GitHubPullRequestReviewResult> {
+  return invoke("submit_github_pull_request_review", {
+    workspaceId: workspace_id,
+    prNumber,
+    event,
+    body: options?.body ?? null,
+    comments: options?.comments ?? null,
+  });
+}
+
+export async function reviewPullRequest(
+  workspace_id: string,
+  prNumber: number,
+  engine?: EngineType | null,
+): Promise<GitHubPullRequestReviewReport> {
+  return invoke("review_pull_request", {
+    workspaceId: workspace_id,
+    prNumber,
+    engine: engine ?? null,
+  });
+}
+
 export async function localUsageSnapshot(
   days?: number,
   workspacePath?: string | null,
@@ -959,6 +1229,84 @@ export async function getCodeIntelReferences(
   });
 }
 
+export async function getCodeIntelWorkspaceSymbols(
+  workspaceId: string,
+  query: string,
+  limit?: number,
+) {
+  return invoke<{ query: string; result: unknown }>("code_intel_workspace_symbols", {
+    workspaceId,
+    query,
+    limit: limit ?? null,
+  });
+}
+
+export async function getCodeIntelDocumentSymbols(workspaceId: string, filePath: string) {
+  return invoke<{ filePath: string; language: string; result: unknown }>(
+    "code_intel_document_symbols",
+    { workspaceId, filePath },
+  );
+}
+
+export type CodeIntelTextEdit = {
+  range: {
+    start: { line: number; character: number };
+    end: { line: number; character: number };
+  };
+  newText: string;
+};
+
+export type CodeIntelFileEdit = {
+  path: string;
+  originalHash: string;
+  edits: CodeIntelTextEdit[];
+  diff: string;
+};
+
+export type CodeIntelWorkspaceEdit = {
+  files: CodeIntelFileEdit[];
+  totalEdits: number;
+  source: "lsp" | "index";
+  skippedFiles?: string[];
+};
+
+export type CodeIntelEditResult = {
+  snapshotId: string;
+  filesChanged: string[];
+};
+
+export async function getCodeIntelRename(
+  workspaceId: string,
+  input: {
+    filePath: string;
+    line: number;
+    character: number;
+    newName: string;
+  },
+) {
+  return invoke<{
+    filePath: string;
+    line: number;
+    character: number;
+    newName: string;
+    result: CodeIntelWorkspaceEdit;
+  }>("code_intel_rename", {
+    workspaceId,
+    filePath: input.filePath,
+    line: input.line,
+    character: input.character,
+    newName: input.newName,
+  });
+}
+
+export async function applyCodeIntelEdit(workspaceId: string, edit: CodeIntelWorkspaceEdit) {
+  return invoke<CodeIntelEditResult>("code_intel_apply_edit", { workspaceId, edit });
+}
+
+export async function undoCodeIntelEdit(workspaceId: string, snapshotId: string) {
+  return invoke<CodeIntelEditResult>("code_intel_undo_edit", { workspaceId, snapshotId });
+}
+
 export type LspPosition = {
   line: number;
   character: number;
@@ -1019,6 +1367,132 @@ export async function getOpenCodeLspReferences(
   });
 }
 
+export type LspServerStatus = {
+  id: string;
+  command: string;
+  args: string[];
+  extensions: string[];
+  binaryPath: string | null;
+  available: boolean;
+  running: boolean;
+};
+
+export async function listLspServers(workspaceId: string) {
+  return invoke<{ workspaceId: string; result: LspServerStatus[] }>(
+    "lsp_list_servers",
+    { workspaceId },
+  );
+}
+
+export async function stopLspServers(workspaceId: string) {
+  return invoke<void>("lsp_stop_servers", { workspaceId });
+}
+
+export async function getLspDiagnostics(workspaceId: string, filePath: string) {
+  return invoke<{ filePath: string; serverId: string; result: unknown[] }>(
+    "lsp_diagnostics",
+    { workspaceId, filePath },
+  );
+}
+
+export async function getLspHover(
+  workspaceId: string,
+  input: { filePath: string; line: number; character: number },
+) {
+  return invoke<{
+    filePath: string;
+    line: number;
+    character: number;
+    serverId: string;
+    result: unknown;
+  }>("lsp_hover", {
+    workspaceId,
+    filePath: input.filePath,
+    line: input.line,
+    character: input.character,
+  });
+}
+
+export async function getLspDefinition(
+  workspaceId: string,
+  input: { filePath: string; line: number; character: number },
+) {
+  return invoke<{
+    filePath: string;
+    line: number;
+    character: number;
+    serverId: string;
+    result: unknown;
+  }>("lsp_definition", {
+    workspaceId,
+    filePath: input.filePath,
+    line: input.line,
+    character: input.character,
+  });
+}
+
+export async function getLspReferences(
+  workspaceId: string,
+  input: {
+    filePath: string;
+    line: number;
+    character: number;
+    includeDeclaration?: boolean;
+  },
+) {
+  return invoke<{
+    filePath: string;
+    line: number;
+    character: number;
+    includeDeclaration: boolean;
+    serverId: string;
+    result: unknown;
+  }>("lsp_references", {
+    workspaceId,
+    filePath: input.filePath,
+    line: input.line,
+    character: input.character,
+    includeDeclaration: input.includeDeclaration ?? false,
+  });
+}
+
+export async function getLspRename(
+  workspaceId: string,
+  input: { filePath: string; line: number; character: number; newName: string },
+) {
+  return invoke<{
+    filePath: string;
+    line: number;
+    character: number;
+    newName: string;
+    serverId: string;
+    result: unknown;
+  }>("lsp_rename", {
+    workspaceId,
+    filePath: input.filePath,
+    line: input.line,
+    character: input.character,
+    newName: input.newName,
+  });
+}
+
+export async function getLspCodeActions(
+  workspaceId: string,
+  input: { filePath: string; range: LspRange },
+) {
+  return invoke<{ filePath: string; serverId: string; result: unknown }>(
+    "lsp_code_actions",
+    {
+      workspaceId,
+      filePath: input.filePath,
+      startLine: input.range.start.line,
+      startCharacter: input.range.start.character,
+      endLine: input.range.end.line,
+      endCharacter: input.range.end.character,
+    },
+  );
+}
+
 export async function getPromptsList(workspaceId: string) {
   return invoke<any>("prompts_list", { workspaceId });
 }
@@ -1136,10 +1610,88 @@ export type ExternalSpecFileResponse = {
   truncated: boolean;
 };
 
+export type WorkspaceFileReadOptions = {
+  offset?: number;
+  length?: number;
+  startLine?: number;
+  lineCount?: number;
+};
+
+export type WorkspaceFileResponse = {
+  content: string;
+  truncated: boolean;
+  encoding: string;
+  bom: boolean;
+  binary: boolean;
+  size: number;
+  offset: number;
+  length: number;
+  startLine?: number | null;
+  mimeType?: string | null;
+  hexPreview?: string | null;
+};
+
+export type WorkspaceFileWriteOptions = {
+  encoding?: string;
+  bom?: boolean;
+};
+
 export async function getWorkspaceFiles(workspaceId: string) {
   return invoke<WorkspaceFilesResponse>("list_workspace_files", { workspaceId });
 }
 
+export async function watchWorkspaceFiles(workspaceId: string) {
+  return invoke<void>("watch_workspace_files", { workspaceId });
+}
+
+export async function unwatchWorkspaceFiles(workspaceId: string) {
+  return invoke<void>("unwatch_workspace_files", { workspaceId });
+}
+
+export async function searchWorkspaceContent(
+  workspaceId: string,
+  query: WorkspaceContentSearchQuery,
+  searchId?: string,
+) {
+  return invoke<WorkspaceSearchSummary>("search_workspace_content", {
+    workspaceId,
+    query,
+    searchId: searchId ?? null,
+  });
+}
+
+export async function cancelWorkspaceSearch(searchId: string) {
+  return invoke<boolean>("cancel_workspace_search", { searchId });
+}
+
+export async function previewWorkspaceReplace(
+  workspaceId: string,
+  query: WorkspaceContentSearchQuery,
+  replacement: string,
+) {
+  return invoke<WorkspaceReplacePreview>("preview_workspace_replace", {
+    workspaceId,
+    query,
+    replacement,
+  });
+}
+
+export async function applyWorkspaceReplace(
+  workspaceId: string,
+  query: WorkspaceContentSearchQuery,
+  replacement: string,
+  expectedHashes: Record<string, string>,
+  paths?: string[] | null,
+) {
+  return invoke<WorkspaceReplaceResult>("apply_workspace_replace", {
+    workspaceId,
+    query,
+    replacement,
+    paths: paths ?? null,
+    expectedHashes,
+  });
+}
+
 export async function listExternalSpecTree(workspaceId: string, specRoot: string) {
   return invoke<WorkspaceFilesResponse>("list_external_spec_tree", { workspaceId, specRoot });
 }
@@ -1147,10 +1699,12 @@ export async function listExternalSpecTree(workspaceId: string, specRoot: string
 export async function readWorkspaceFile(
   workspaceId: string,
   path: string,
-): Promise<{ content: string; truncated: boolean }> {
-  return invoke<{ content: string; truncated: boolean }>("read_workspace_file", {
+  options?: WorkspaceFileReadOptions,
+): Promise<WorkspaceFileResponse> {
+  return invoke<WorkspaceFileResponse>("read_workspace_file", {
     workspaceId,
     path,
+    options: options ?? null,
   });
 }
 
@@ -1170,8 +1724,68 @@ export async function writeWorkspaceFile(
   workspaceId: string,
   path: string,
   content: string,
+  options?: WorkspaceFileWriteOptions,
 ): Promise<void> {
-  return invoke("write_workspace_file", { workspaceId, path, content });
+  return invoke("write_workspace_file", {
+    workspaceId,
+    path,
+    content,
+    options: options ?? null,
+  });
+}
+
+export type FileHistoryEntry = {
+  id: number;
+  path: string;
+  hash: string | null;
+  size: number;
+  source: "agent" | "editor" | "refactor" | "restore";
+  threadId: string | null;
+  turnId: string | null;
+  tool: string | null;
+  createdAt: number;
+};
+
+export type FileHistoryDiff = {
+  path: string;
+  fromId: number;
+  toId: number | null;
+  diff: string;
+  binary: boolean;
+};
+
+export async function listFileHistory(
+  workspaceId: string,
+  options: { path?: string; threadId?: string; limit?: number } = {},
+): Promise<FileHistoryEntry[]> {
+  return invoke<FileHistoryEntry[]>("file_history_list", {
+    workspaceId,
+    path: options.path ?? null,
+    threadId: options.threadId ?? null,
+    limit: options.limit ?? null,
+  });
+}
+
+export async function getFileHistoryDiff(
+  workspaceId: string,
+  fromId: number,
+  toId?: number | null,
+): Promise<FileHistoryDiff> {
+  return invoke<FileHistoryDiff>("file_history_diff", {
+    workspaceId,
+    fromId,
+    toId: toId ?? null,
+  });
+}
+
+export async function restoreFileHistory(
+  workspaceId: string,
+  snapshotId: number,
+): Promise<FileHistoryEntry | null> {
+  return invoke<FileHistoryEntry | null>("file_history_restore", {
+    workspaceId,
+    snapshotId,
+  });
 }
 
 export async function writeExternalSpecFile(
@@ -1297,6 +1911,38 @@ export async function deleteGitBranch(
   });
 }
 
+export async function classifyGitBranches(
+  workspaceId: string,
+  options?: {
+    baseBranch?: string | null;
+    staleDays?: number | null;
+  },
+): Promise<GitBranchHygieneReport> {
+  return invoke<GitBranchHygieneReport>("classify_git_branches", {
+    workspaceId,
+    baseBranch: options?.baseBranch ?? null,
+    staleDays: options?.staleDays ?? null,
+  });
+}
+
+export async function deleteGitBranches(
+  workspaceId: string,
+  branches: string[],
+  options?: {
+    remoteBranches?: string[];
+    force?: boolean;
+    removeOccupiedWorktree?: boolean;
+  },
+): Promise<GitBranchDeleteResult[]> {
+  return invoke<GitBranchDeleteResult[]>("delete_git_branches", {
+    workspaceId,
+    branches,
+    remoteBranches: options?.remoteBranches ?? null,
+    force: options?.force ?? false,
+    removeOccupiedWorktree: options?.removeOccupiedWorktree ?? false,
+  });
+}
+
 export async function renameGitBranch(
   workspaceId: string,
   oldName: string,
@@ -1313,6 +1959,105 @@ export async function rebaseGitBranch(workspaceId: string, ontoBranch: string) {
   return invoke("rebase_git_branch", { workspaceId, ontoBranch });
 }
 
+export async function getGitRebasePlan(
+  workspaceId: string,
+  onto: string,
+): Promise<GitRebasePlan> {
+  return invoke<GitRebasePlan>("get_git_rebase_plan", { workspaceId, onto });
+}
+
+export async function executeGitRebasePlan(
+  workspaceId: string,
+  onto: string,
+  entries: GitRebasePlanEntry[],
+): Promise<GitRebaseResult> {
+  return invoke<GitRebaseResult>("execute_git_rebase_plan", {
+    workspaceId,
+    onto,
+    entries,
+  });
+}
+
+export async function continueGitRebase(
+  workspaceId: string,
+): Promise<GitRebaseResult> {
+  return invoke<GitRebaseResult>("continue_git_rebase", { workspaceId });
+}
+
+export async function abortGitRebase(workspaceId: string): Promise<void> {
+  return invoke("abort_git_rebase", { workspaceId });
+}
+
+export async function runGitBisect(
+  workspaceId: string,
+  goodRef: string,
+  badRef: string,
+  command: string[],
+  options?: { timeoutMs?: number },
+): Promise<GitBisectResult> {
+  return invoke<GitBisectResult>("run_git_bisect", {
+    workspaceId,
+    goodRef,
+    badRef,
+    command,
+    timeoutMs: options?.timeoutMs ?? null,
+  });
+}
+
+export async function listGitTags(workspaceId: string): Promise<GitTagInfo[]> {
+  return invoke<GitTagInfo[]>("list_git_tags", { workspaceId });
+}
+
+export async function createGitTag(
+  workspaceId: string,
+  name: string,
+  options?: {
+    target?: string | null;
+    message?: string | null;
+    sign?: boolean;
+  },
+) {
+  return invoke("create_git_tag", {
+    workspaceId,
+    name,
+    target: options?.target ?? null,
+    message: options?.message ?? null,
+    sign: options?.sign ?? false,
+  });
+}
+
+export async function deleteGitTag(
+  workspaceId: string,
+  name: string,
+  remote?: string | null,
+) {
+  return invoke("delete_git_tag", { workspaceId, name, remote: remote ?? null });
+}
+
+export async function pushGitTag(
+  workspaceId: string,
+  name?: string | null,
+  remote?: string | null,
+) {
+  return invoke("push_git_tag", {
+    workspaceId,
+    name: name ?? null,
+    remote: remote ?? null,
+  });
+}
+
+export async function generateReleaseNotes(
+  workspaceId: string,
+  fromTag: string,
+  toRef?: string | null,
+): Promise<GitReleaseNotesResponse> {
+  return invoke<GitReleaseNotesResponse>("generate_release_notes", {
+    workspaceId,
+    fromTag,
+    toRef: toRef ?? null,
+  });
+}
+
 export async function getGitBranchCompareCommits(
   workspaceId: string,
   targetBranch: string,
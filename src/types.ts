@@ -8,6 +8,34 @@ export type WorkspaceSettings = {
   launchScript?: string | null;
   launchScripts?: LaunchScriptEntry[] | null;
   worktreeSetupScript?: string | null;
+  forgeKind?: GitForgeKind | null;
+  gitIdentity?: GitIdentityProfile | null;
+  sandbox?: WorkspaceSandbox | null;
+};
+
+export type GitForgeKind = "github" | "gitlab" | "gitea";
+
+export type WorkspaceSandboxRunner = "bubblewrap" | "podman" | "docker";
+
+export type WorkspaceSandbox = {
+  enabled: boolean;
+  runner: WorkspaceSandboxRunner;
+  runnerPath?: string | null;
+  image?: string | null;
+  allowNetwork: boolean;
+  memoryMb?: number | null;
+  cpus?: number | null;
+  pidsLimit?: number | null;
+};
+
+export type GitSigningFormat = "gpg" | "ssh" | "x509";
+
+export type GitIdentityProfile = {
+  name?: string | null;
+  email?: string | null;
+  signingKey?: string | null;
+  signingFormat?: GitSigningFormat | null;
+  signCommits?: boolean | null;
 };
 
 export type LaunchScriptIconId =
@@ -52,6 +80,237 @@ export type WorktreeInfo = {
   publishRetryCommand?: string | null;
 };
 
+export type WorktreeChangeHunk = {
+  index: number;
+  header: string;
+  additions: number;
+  deletions: number;
+};
+
+export type WorktreeApplyPrediction =
+  | "clean"
+  | "three-way"
+  | "already-applied"
+  | "conflict";
+
+export type WorktreeFileChange = {
+  path: string;
+  oldPath: string | null;
+  status: "added" | "deleted" | "renamed" | "modified";
+  binary: boolean;
+  additions: number;
+  deletions: number;
+  hunks: WorktreeChangeHunk[];
+  patch: string;
+  prediction: WorktreeApplyPrediction;
+  predictionDetail: string | null;
+};
+
+export type WorktreeChangePreview = {
+  workspaceId: string;
+  parentId: string;
+  parentDirty: boolean;
+  files: WorktreeFileChange[];
+};
+
+export type WorktreeApplySelection = {
+  path: string;
+  hunks?: number[] | null;
+};
+
+export type WorktreeApplyRecord = {
+  id: string;
+  workspaceId: string;
+  parentId: string;
+  branch: string | null;
+  appliedAt: number;
+  files: WorktreeApplySelection[];
+  commitSha: string | null;
+  commitMessage: string | null;
+};
+
+export type WorktreeLifecycleInfo = {
+  workspaceId: string;
+  parentId: string | null;
+  name: string;
+  branch: string | null;
+  path: string;
+  exists: boolean;
+  sizeBytes: number;
+  lastActivityAt: number | null;
+  merged: boolean | null;
+  dirty: boolean;
+};
+
+export type WorktreeArchive = {
+  id: string;
+  workspaceId: string;
+  parentId: string;
+  name: string;
+  branch: string;
+  baseRef: string | null;
+  headSha: string;
+  archivedAt: number;
+  patchBytes: number;
+};
+
+export type WorktreePrunePolicy = {
+  runOnStartup: boolean;
+  mergedAfterDays: number;
+  archiveDirty: boolean;
+};
+
+export type WorktreePruneResult = {
+  workspaceId: string;
+  name: string;
+  action: "removed" | "archived" | "would-remove" | "would-archive" | "skipped";
+  reason: string | null;
+  error: string | null;
+};
+
+export type FanoutCandidateSpec = {
+  engine?: EngineType | null;
+  model?: string | null;
+  effort?: string | null;
+};
+
+export type FanoutDiffStats = {
+  files: number;
+  additions: number;
+  deletions: number;
+};
+
+export type FanoutTestResult = {
+  exitCode: number;
+  success: boolean;
+  output: string;
+};
+
+export type FanoutCandidate = {
+  workspaceId: string;
+  branch: string;
+  engine: string | null;
+  model: string | null;
+  effort: string | null;
+  threadId: string | null;
+  status: "running" | "completed" | "failed";
+  error: string | null;
+  tokenUsage: number | null;
+  diffStats: FanoutDiffStats | null;
+  testResult: FanoutTestResult | null;
+};
+
+export type FanoutRun = {
+  id: string;
+  parentId: string;
+  prompt: string;
+  title: string | null;
+  baseSha: string;
+  testCommand: string[] | null;
+  createdAt: number;
+  status: "running" | "evaluating" | "completed";
+  candidates: FanoutCandidate[];
+  winnerWorkspaceId: string | null;
+};
+
+export type WorkspaceGitPathStatus = {
+  path: string;
+  status: string | null;
+};
+
+export type WorkspaceFilesChangedEvent = {
+  workspaceId: string;
+  added: string[];
+  removed: string[];
+  modified: string[];
+  gitStatus: WorkspaceGitPathStatus[];
+  gitChanged: boolean;
+  rescan: boolean;
+};
+
+export type LspDiagnosticsEvent = {
+  workspaceId: string;
+  serverId: string;
+  uri: string;
+  path: string | null;
+  diagnostics: unknown[];
+};
+
+export type LspTurnDiagnosticsFile = {
+  path: string;
+  serverId: string;
+  errors: unknown[];
+};
+
+export type LspTurnDiagnosticsEvent = {
+  workspaceId: string;
+  threadId: string;
+  files: LspTurnDiagnosticsFile[];
+  followUps: number;
+  followUpSent: boolean;
+};
+
+export type WorkspaceContentSearchQuery = {
+  pattern: string;
+  isRegex?: boolean;
+  caseSensitive?: boolean;
+  wholeWord?: boolean;
+  include?: string[];
+  exclude?: string[];
+  contextLines?: number;
+  maxResults?: number;
+};
+
+export type WorkspaceSearchMatchRange = {
+  start: number;
+  end: number;
+};
+
+export type WorkspaceSearchLineMatch = {
+  lineNumber: number;
+  lineText: string;
+  ranges: WorkspaceSearchMatchRange[];
+  contextBefore: string[];
+  contextAfter: string[];
+};
+
+export type WorkspaceSearchFileResult = {
+  path: string;
+  matches: WorkspaceSearchLineMatch[];
+};
+
+export type WorkspaceSearchMatchEvent = {
+  searchId: string;
+  workspaceId: string;
+  file: WorkspaceSearchFileResult;
+};
+
+export type WorkspaceSearchSummary = {
+  searchId: string;
+  totalMatches: number;
+  filesMatched: number;
+  truncated: boolean;
+  cancelled: boolean;
+};
+
+export type WorkspaceReplaceFilePreview = {
+  path: string;
+  replacements: number;
+  originalHash: string;
+  diff: string;
+};
+
+export type WorkspaceReplacePreview = {
+  files: WorkspaceReplaceFilePreview[];
+  totalReplacements: number;
+};
+
+export type WorkspaceReplaceResult = {
+  filesChanged: string[];
+  totalReplacements: number;
+  skipped: { path: string; reason: string }[];
+};
+
 export type WorkspaceInfo = {
   id: string;
   name: string;
@@ -218,6 +477,23 @@ export type AppSettings = {
   diffExpandedByDefault?: boolean;
   commitPrompt?: string;
   sendShortcut?: "enter" | "cmdEnter";
+  worktreePrunePolicy?: WorktreePrunePolicy | null;
+  engineSessionIdleMinutes?: number;
+  lspServers?: LspServerConfig[];
+  lspFeedbackEnabled?: boolean;
+  lspFeedbackAutoFix?: boolean;
+  lspFeedbackMaxIterations?: number;
+  fileHistoryEnabled?: boolean;
+  fileHistoryMaxSnapshots?: number;
+};
+
+export type LspServerConfig = {
+  id: string;
+  enabled?: boolean;
+  command?: string | null;
+  args?: string[] | null;
+  extensions?: string[] | null;
+  initializationOptions?: unknown;
 };
 
 export type CodexDoctorResult = {
@@ -328,6 +604,23 @@ export type GitFileStatus = {
   deletions: number;
 };
 
+export type GitSubmoduleDrift = "none" | "ahead" | "behind" | "diverged" | "unknown";
+
+export type GitSubmoduleStatus = {
+  name: string;
+  path: string;
+  url: string | null;
+  branch: string | null;
+  headSha: string | null;
+  indexSha: string | null;
+  workdirSha: string | null;
+  initialized: boolean;
+  dirty: boolean;
+  drift: GitSubmoduleDrift;
+  ahead: number;
+  behind: number;
+};
+
 export type GitFileDiff = {
   path: string;
   diff: string;
@@ -380,6 +673,34 @@ export type GitHistoryCommit = {
   refs: string[];
 };
 
+export type GitCommitGraphEdge = {
+  kind: "pass" | "in" | "out";
+  fromColumn: number;
+  toColumn: number;
+  color: number;
+};
+
+export type GitCommitGraphRow = {
+  commit: GitHistoryCommit;
+  column: number;
+  color: number;
+  edges: GitCommitGraphEdge[];
+  isMerge: boolean;
+  isFork: boolean;
+  isTip: boolean;
+  width: number;
+};
+
+export type GitCommitGraphResponse = {
+  cacheKey: string;
+  offset: number;
+  limit: number;
+  hasMore: boolean;
+  truncated: boolean;
+  maxColumns: number;
+  rows: GitCommitGraphRow[];
+};
+
 export type GitHistoryResponse = {
   snapshotId: string;
   total: number;
@@ -389,6 +710,27 @@ export type GitHistoryResponse = {
   commits: GitHistoryCommit[];
 };
 
+export type GitHistorySearchHighlight = {
+  field: "message" | "author" | "added" | "removed";
+  path: string | null;
+  text: string;
+  ranges: [number, number][];
+};
+
+export type GitHistorySearchResult = {
+  commit: GitHistoryCommit;
+  highlights: GitHistorySearchHighlight[];
+};
+
+export type GitHistorySearchResponse = {
+  total: number;
+  offset: number;
+  limit: number;
+  hasMore: boolean;
+  truncated: boolean;
+  results: GitHistorySearchResult[];
+};
+
 export type GitPushPreviewResponse = {
   sourceBranch: string;
   targetRemote: string;
@@ -481,6 +823,23 @@ export type GitCommitDetails = {
   files: GitCommitFileChange[];
   totalAdditions: number;
   totalDeletions: number;
+  signature?: GitCommitSignature | null;
+};
+
+export type GitCommitSignatureStatus =
+  | "good"
+  | "bad"
+  | "untrusted"
+  | "expired"
+  | "expired-key"
+  | "revoked"
+  | "unverifiable";
+
+export type GitCommitSignature = {
+  status: GitCommitSignatureStatus;
+  format: GitSigningFormat | null;
+  signer: string | null;
+  key: string | null;
 };
 
 export type GitBranchListItem = {
@@ -502,6 +861,103 @@ export type GitBranchListResponse = {
   currentBranch?: string | null;
 };
 
+export type GitBranchHygieneCategory = "merged" | "gone-upstream" | "stale" | "worktree";
+
+export type GitBranchHygieneEntry = {
+  branch: GitBranchListItem;
+  categories: GitBranchHygieneCategory[];
+  worktreePath: string | null;
+  safeToDelete: boolean;
+};
+
+export type GitBranchHygieneReport = {
+  baseBranch: string;
+  baseSha: string;
+  staleDays: number;
+  branches: GitBranchHygieneEntry[];
+};
+
+export type GitBranchDeleteResult = {
+  name: string;
+  isRemote: boolean;
+  deleted: boolean;
+  error: string | null;
+};
+
+export type GitTagInfo = {
+  name: string;
+  targetSha: string;
+  annotated: boolean;
+  message?: string | null;
+  tagger?: string | null;
+  taggerEmail?: string | null;
+  timestamp: number;
+};
+
+export type GitReleaseNotesResponse = {
+  fromRef: string;
+  toRef: string;
+  engine: string;
+  draft: string;
+  commits: GitHistoryCommit[];
+  truncated: boolean;
+};
+
+export type GitRebaseAction = "pick" | "reword" | "squash" | "fixup" | "drop";
+
+export type GitRebasePlanEntry = {
+  action: GitRebaseAction;
+  sha: string;
+  shortSha: string;
+  summary: string;
+  author: string;
+  timestamp: number;
+  message?: string | null;
+};
+
+export type GitRebasePlan = {
+  onto: string;
+  ontoSha: string;
+  headSha: string;
+  branch: string | null;
+  entries: GitRebasePlanEntry[];
+};
+
+export type GitRebaseResult = {
+  status: "completed" | "conflict" | "failed";
+  message: string;
+  currentStep: number | null;
+  totalSteps: number | null;
+  conflictedFiles: string[];
+  headSha: string | null;
+};
+
+export type GitBisectVerdict = "good" | "bad" | "skip";
+
+export type GitBisectStep = {
+  sha: string;
+  summary: string;
+  verdict: GitBisectVerdict;
+  exitCode: number;
+  remainingRevisions: number | null;
+};
+
+export type GitBisectResult = {
+  status: "found" | "inconclusive";
+  goodRef: string;
+  badRef: string;
+  steps: GitBisectStep[];
+  firstBadCommit: GitCommitDetails | null;
+  candidates: string[];
+};
+
+export type GitForgeInfo = {
+  kind: GitForgeKind;
+  host: string;
+  repo: string;
+  webUrl: string;
+};
+
 export type GitHubIssue = {
   number: number;
   title: string;
@@ -550,6 +1006,43 @@ export type GitHubPullRequestComment = {
   author: GitHubUser | null;
 };
 
+export type GitHubPullRequestReviewEvent =
+  | "approve"
+  | "request_changes"
+  | "comment";
+
+export type GitHubPullRequestReviewComment = {
+  path: string;
+  line: number;
+  side?: "left" | "right" | null;
+  startLine?: number | null;
+  body: string;
+};
+
+export type GitHubPullRequestReviewFinding = {
+  path: string;
+  line: number | null;
+  severity: "critical" | "major" | "minor" | "nit";
+  message: string;
+  inDiff: boolean;
+};
+
+export type GitHubPullRequestReviewReport = {
+  prNumber: number;
+  engine: string;
+  headSha: string;
+  summary: string;
+  findings: GitHubPullRequestReviewFinding[];
+  rawText: string;
+};
+
+export type GitHubPullRequestReviewResult = {
+  id: number | null;
+  url: string | null;
+  state: string;
+  commentCount: number;
+};
+
 export type TokenUsageBreakdown = {
   totalTokens: number;
   inputTokens: number;